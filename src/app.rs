@@ -1,4 +1,6 @@
+use chrono::Utc;
 use clap::Parser;
+use std::collections::HashMap;
 use std::env;
 use std::ffi::OsString;
 use std::fs;
@@ -9,38 +11,50 @@ use std::sync::Arc;
 use std::time::Instant;
 
 use crate::cli::{parse_manual_tasks, Cli, CliCommand};
-use crate::config::{load_config_with_profile, NotificationScope};
+use crate::config::{load_config_with_profile, Config, NotificationScope, PromptFormat};
 use crate::doctor::run_doctor_mode;
-use crate::logger::Logger;
+use crate::logger::{Logger, Transition};
+use crate::prompt_search::resolve_prompt;
+use crate::prompt_template::validate_template;
+use crate::run_journal::{read_run_journal, RunJournal};
 use crate::run_loop::{
-    dispatch_notification_hook, finish_current_task_context, quit, reset_task_on_exit, run_loop,
-    set_agent_invocation_context, validate_config, NotificationEvent, Quit, RuntimeState,
+    dispatch_notification_hook, finish_current_task_context, finish_run_journal, quit,
+    reset_task_on_exit, run_loop, run_watch_loop, validate_config, AgentInvocationContext,
+    NotificationEvent, Quit, RuntimeState,
 };
+use crate::status::run_status_mode;
+use crate::task_backend::task_backend;
 use crate::tmux::TmuxState;
 use crate::wizard::run_wizard_cli;
 
-const PROMPT_TRUDGE: &str = ".codex/prompts/trudge.md";
-const PROMPT_REVIEW: &str = ".codex/prompts/trudge_review.md";
 const DEFAULT_CONFIG_REL: &str = ".config/trudger.yml";
 
+// Set by the SIGHUP handler; `run_watch_loop` reloads config when it sees it.
+static RELOAD_REQUESTED: AtomicBool = AtomicBool::new(false);
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum AppMode {
     Run,
     Wizard,
     Doctor,
+    Resume,
+    Status { json: bool, all: bool },
 }
 
-fn home_dir() -> Result<PathBuf, String> {
+pub(crate) fn home_dir() -> Result<PathBuf, String> {
     env::var("HOME")
         .map(PathBuf::from)
         .map_err(|_| "Missing HOME environment variable".to_string())
 }
 
-pub(crate) fn require_file(path: &Path, label: &str) -> Result<(), String> {
-    if !path.is_file() {
-        return Err(format!("Missing {}: {}", label, path.display()));
-    }
-    Ok(())
+/// Names a run's transcript directory: its UTC start time and pid, so runs started in the same
+/// second (for example from two terminals) do not share one.
+pub(crate) fn run_id() -> String {
+    format!(
+        "{}-{}",
+        Utc::now().format("%Y%m%dT%H%M%SZ"),
+        std::process::id()
+    )
 }
 
 fn bootstrap_config_error(default_path: &Path) -> String {
@@ -88,6 +102,223 @@ pub(crate) fn render_prompt(path: &Path) -> Result<String, String> {
     Ok(out)
 }
 
+/// Reads the prompt for `phase` from its search path, checking its template syntax up front with
+/// `prompt_format: template`.
+fn load_prompt(
+    config: &Config,
+    phase: &str,
+    home: &Path,
+    cwd: &Path,
+    logger: &Logger,
+) -> Result<String, String> {
+    let found = resolve_prompt(config, phase, home, cwd)?;
+    logger.log_transition(
+        Transition::new("prompt_resolved")
+            .field("phase", phase)
+            .field("source", found.source)
+            .field("path", found.path.display().to_string()),
+    );
+    let prompt = render_prompt(&found.path)?;
+    if config.prompt_format == PromptFormat::Template {
+        validate_template(&prompt)
+            .map_err(|err| format!("Invalid prompt {}: {}", found.path.display(), err))?;
+    }
+    Ok(prompt)
+}
+
+/// Loads the prompt of every custom phase in the active profile's pipeline.
+fn load_phase_prompts(
+    config: &Config,
+    home: &Path,
+    cwd: &Path,
+    logger: &Logger,
+) -> Result<HashMap<String, String>, String> {
+    config
+        .agent_phase_names()
+        .into_iter()
+        .filter(|phase| config.custom_phase(phase).is_some())
+        .map(|phase| {
+            Ok((
+                phase.to_string(),
+                load_prompt(config, phase, home, cwd, logger)?,
+            ))
+        })
+        .collect()
+}
+
+/// The prompts a profile's agent phases run with.
+pub(crate) struct AgentPrompts {
+    pub(crate) trudge: String,
+    pub(crate) review: String,
+    pub(crate) phases: HashMap<String, String>,
+}
+
+/// Loads the solve, review, and custom phase prompts for `config`.
+pub(crate) fn load_agent_prompts(
+    config: &Config,
+    home: &Path,
+    cwd: &Path,
+    logger: &Logger,
+) -> Result<AgentPrompts, String> {
+    Ok(AgentPrompts {
+        trudge: load_prompt(config, "trudge", home, cwd, logger)?,
+        review: load_prompt(config, "trudge_review", home, cwd, logger)?,
+        phases: load_phase_prompts(config, home, cwd, logger)?,
+    })
+}
+
+/// Checks that the prompts of every `fallback` and `profile_rules` profile load, so switching to
+/// one mid-run cannot fail on them.
+fn check_switch_prompts(
+    config: &Config,
+    home: &Path,
+    cwd: &Path,
+    logger: &Logger,
+) -> Result<(), String> {
+    let routed = config
+        .task_routing
+        .rules
+        .iter()
+        .filter_map(|rule| config.task_routing.profiles.get(&rule.profile));
+    for profile in config.profile_fallback.profiles.iter().chain(routed) {
+        let mut switched = config.clone();
+        profile.apply(&mut switched);
+        load_agent_prompts(&switched, home, cwd, logger)
+            .map_err(|err| format!("profiles.{}: {}", profile.profile, err))?;
+    }
+    Ok(())
+}
+
+fn load_resume_journal(config: &Config, active_profile: &str) -> Result<RunJournal, Quit> {
+    let fail = |message: String| {
+        eprintln!("{}", message);
+        Quit {
+            code: 1,
+            reason: message,
+        }
+    };
+
+    let Some(path) = config.journal_path.as_deref() else {
+        return Err(fail(
+            "journal_path is not configured; nothing to resume.".to_string(),
+        ));
+    };
+    let journal = read_run_journal(path).map_err(fail)?.ok_or_else(|| {
+        fail(format!(
+            "No run journal found at {}; nothing to resume.",
+            path.display()
+        ))
+    })?;
+
+    // Workspace runs record the profile of whichever workspace was active.
+    let workspace_run = journal
+        .current_task
+        .as_ref()
+        .is_some_and(|task| task.workspace.is_some());
+    if let Some(profile) = journal.profile.as_deref().filter(|_| !workspace_run) {
+        if profile != active_profile {
+            eprintln!(
+                "Warning: run journal was recorded with profile {}; resuming with profile {} (pass -p {} to keep it).",
+                profile, active_profile, profile
+            );
+        }
+    }
+    Ok(journal)
+}
+
+fn warn_on_interrupted_journal(config: &Config) {
+    let Some(path) = config.journal_path.as_deref() else {
+        return;
+    };
+    if let Ok(Some(journal)) = read_run_journal(path) {
+        if let Some(task) = journal.current_task {
+            eprintln!(
+                "Warning: run journal {} records an interrupted run (task {}, {}); starting a fresh run replaces it. Use `trudger resume` to continue it instead.",
+                path.display(),
+                task.task_id,
+                task.phase
+            );
+        }
+    }
+}
+
+#[cfg(unix)]
+fn install_reload_handler() {
+    const SIGHUP: i32 = 1;
+    extern "C" {
+        fn signal(signum: i32, handler: extern "C" fn(i32)) -> usize;
+    }
+    extern "C" fn request_reload(_signum: i32) {
+        RELOAD_REQUESTED.store(true, Ordering::SeqCst);
+    }
+
+    // SAFETY: the handler only stores to an atomic, which is async-signal-safe.
+    unsafe {
+        signal(SIGHUP, request_reload);
+    }
+}
+
+#[cfg(not(unix))]
+fn install_reload_handler() {}
+
+/// Re-reads config and prompts for a watch-mode run after SIGHUP.
+///
+/// `log_path`, `log_format`, `journal_path`, and `agent_output_dir` stay as they were at startup.
+fn reload_watch_config(
+    state: &mut RuntimeState,
+    profile: Option<&str>,
+    home: &Path,
+) -> Result<(), String> {
+    let loaded = load_config_with_profile(&state.config_path, profile)?;
+    validate_config(&loaded.config, &[])?;
+    let cwd = PathBuf::from(&state.invocation_folder);
+    let prompts = load_agent_prompts(&loaded.config, home, &cwd, &state.logger)?;
+    check_switch_prompts(&loaded.config, home, &cwd, &state.logger)?;
+
+    let mut config = loaded.config;
+    if config.log_path != state.config.log_path
+        || config.log_format != state.config.log_format
+        || config.journal_path != state.config.journal_path
+        || config.agent_output_dir != state.config.agent_output_dir
+    {
+        eprintln!(
+            "Warning: log_path, log_format, journal_path, and agent_output_dir changes take effect after a restart."
+        );
+    }
+    config.log_path = state.config.log_path.clone();
+    config.log_format = state.config.log_format;
+    config.journal_path = state.config.journal_path.clone();
+    config.agent_output_dir = state.config.agent_output_dir.clone();
+
+    state.agent_context = AgentInvocationContext::new(
+        loaded.active_profile,
+        loaded.solve_invocation_id,
+        loaded.review_invocation_id,
+    );
+    let all_logs_hook = match config.hooks.effective_notification_scope() {
+        Some(NotificationScope::AllLogs) => config.hooks.on_notification.as_deref(),
+        _ => None,
+    };
+    state.logger.configure_all_logs_notification(
+        all_logs_hook,
+        &state.config_path,
+        state.invocation_folder.clone(),
+    );
+    if all_logs_hook.is_some() {
+        state
+            .logger
+            .mark_all_logs_run_started_at(state.run_started_at);
+    }
+
+    state.task_backend = task_backend(&config);
+    state.config = config;
+    state.prompt_trudge = prompts.trudge;
+    state.prompt_review = prompts.review;
+    state.phase_prompts = prompts.phases;
+    state.agent_failures = 0;
+    Ok(())
+}
+
 fn run_with_cli_impl<F>(cli: Cli, wizard_runner: F) -> Result<(), Quit>
 where
     F: FnOnce(&Path) -> Result<(), Quit>,
@@ -95,6 +326,8 @@ where
     let mode = match cli.command {
         Some(CliCommand::Doctor) => AppMode::Doctor,
         Some(CliCommand::Wizard) => AppMode::Wizard,
+        Some(CliCommand::Resume) => AppMode::Resume,
+        Some(CliCommand::Status { json, all }) => AppMode::Status { json, all },
         None => AppMode::Run,
     };
 
@@ -124,6 +357,24 @@ where
             reason: message,
         });
     }
+    if mode == AppMode::Resume && !manual_tasks.is_empty() {
+        let message =
+            "-t/--task is not supported in resume mode; the run journal records remaining tasks."
+                .to_string();
+        eprintln!("{}", message);
+        return Err(Quit {
+            code: 1,
+            reason: message,
+        });
+    }
+    if matches!(mode, AppMode::Status { .. }) && !manual_tasks.is_empty() {
+        let message = "-t/--task is not supported in status mode.".to_string();
+        eprintln!("{}", message);
+        return Err(Quit {
+            code: 1,
+            reason: message,
+        });
+    }
     if mode == AppMode::Run && !cli.positional.is_empty() {
         let message = format!(
             "Positional arguments are not supported.\nMigration: pass manual task ids via -t/--task (for example: trudger -t {}).",
@@ -135,6 +386,14 @@ where
             reason: "positional_args_not_supported".to_string(),
         });
     }
+    if mode == AppMode::Resume && !cli.positional.is_empty() {
+        let message = "Positional arguments are not supported in resume mode.".to_string();
+        eprintln!("{}", message);
+        return Err(Quit {
+            code: 1,
+            reason: message,
+        });
+    }
     if mode == AppMode::Wizard && !cli.positional.is_empty() {
         let message = "Positional arguments are not supported in wizard mode.".to_string();
         eprintln!("{}", message);
@@ -143,6 +402,27 @@ where
             reason: message,
         });
     }
+    if matches!(mode, AppMode::Status { .. }) && !cli.positional.is_empty() {
+        let message = "Positional arguments are not supported in status mode.".to_string();
+        eprintln!("{}", message);
+        return Err(Quit {
+            code: 1,
+            reason: message,
+        });
+    }
+    if cli.watch
+        && matches!(
+            mode,
+            AppMode::Doctor | AppMode::Wizard | AppMode::Status { .. }
+        )
+    {
+        let message = "--watch is only supported for runs and `trudger resume`.".to_string();
+        eprintln!("{}", message);
+        return Err(Quit {
+            code: 1,
+            reason: message,
+        });
+    }
     if mode == AppMode::Doctor && cli.profile.is_some() {
         let message = "-p/--profile is not supported in doctor mode.".to_string();
         eprintln!("{}", message);
@@ -192,11 +472,9 @@ where
             reason: message,
         })?;
 
-    set_agent_invocation_context(
-        loaded.active_profile.clone(),
-        loaded.solve_invocation_id.clone(),
-        loaded.review_invocation_id.clone(),
-    );
+    if let AppMode::Status { json, all } = mode {
+        return run_status_mode(&loaded.config, json, all);
+    }
 
     // Capture the absolute invocation working directory once for stable notification payloads.
     let invocation_folder = env::current_dir()
@@ -204,6 +482,7 @@ where
         .map(|path| path.display().to_string())
         .unwrap_or_default();
     let mut logger = Logger::new(loaded.config.log_path.clone());
+    logger.set_format(loaded.config.log_format);
 
     if mode == AppMode::Doctor {
         return run_doctor_mode(&loaded.config, &config_path, &logger);
@@ -220,26 +499,54 @@ where
         );
     }
 
-    if let Err(message) = validate_config(&loaded.config, &manual_tasks) {
-        eprintln!("{}", message);
-        return Err(quit(&logger, &message, 1));
-    }
+    let journal = match mode {
+        AppMode::Resume => Some(load_resume_journal(&loaded.config, &loaded.active_profile)?),
+        _ => {
+            warn_on_interrupted_journal(&loaded.config);
+            None
+        }
+    };
+    let manual_tasks = match &journal {
+        Some(journal) => journal.manual_tasks.clone(),
+        None => manual_tasks,
+    };
 
-    let prompt_trudge = home.join(PROMPT_TRUDGE);
-    let prompt_review = home.join(PROMPT_REVIEW);
-    if let Err(message) = require_file(&prompt_trudge, "prompt file") {
+    // `run_start`/`run_end` bracket each run in the log for `trudger status`, including runs
+    // that fail before the first task.
+    logger.log_transition(
+        Transition::new("run_start")
+            .field("pid", std::process::id())
+            .field("profile", &loaded.active_profile)
+            .field("watch", cli.watch),
+    );
+    let quit_before_run = |message: &str| {
+        let quit = quit(&logger, message, 1);
+        logger.log_transition(Transition::new("run_end").field("exit_code", 1));
+        quit
+    };
+
+    if let Err(message) = validate_config(&loaded.config, &manual_tasks) {
         eprintln!("{}", message);
-        return Err(quit(&logger, &message, 1));
+        return Err(quit_before_run(&message));
     }
-    if let Err(message) = require_file(&prompt_review, "prompt file") {
-        eprintln!("{}", message);
-        return Err(quit(&logger, &message, 1));
+    if cli.watch {
+        // Watch mode keeps asking for tasks after the manual queue drains.
+        if let Err(message) = validate_config(&loaded.config, &[]) {
+            eprintln!("{}", message);
+            return Err(quit_before_run(&message));
+        }
     }
 
-    let prompt_trudge_content =
-        render_prompt(&prompt_trudge).map_err(|message| quit(&logger, &message, 1))?;
-    let prompt_review_content =
-        render_prompt(&prompt_review).map_err(|message| quit(&logger, &message, 1))?;
+    let cwd = PathBuf::from(&invocation_folder);
+    let prompts = load_agent_prompts(&loaded.config, &home, &cwd, &logger)
+        .and_then(|prompts| {
+            check_switch_prompts(&loaded.config, &home, &cwd, &logger)?;
+            Ok(prompts)
+        })
+        .map_err(|message| {
+            eprintln!("{}", message);
+            quit_before_run(&message)
+        })?;
 
     let interrupt_flag = Arc::new(AtomicBool::new(false));
     if let Err(err) = ctrlc::set_handler({
@@ -251,24 +558,64 @@ where
         eprintln!("Failed to set interrupt handler: {}", err);
     }
 
+    // Each run gets its own transcript directory, resolved once like the invocation folder.
+    let agent_output_dir = loaded
+        .config
+        .agent_output_dir
+        .as_ref()
+        .map(|dir| Path::new(&invocation_folder).join(dir).join(run_id()));
+
+    let task_backend = task_backend(&loaded.config);
     let mut state = RuntimeState {
         config: loaded.config,
         config_path,
         invocation_folder,
-        prompt_trudge: prompt_trudge_content,
-        prompt_review: prompt_review_content,
+        prompt_trudge: prompts.trudge,
+        prompt_review: prompts.review,
+        phase_prompts: prompts.phases,
         logger,
         tmux: TmuxState::new(),
         interrupt_flag,
         manual_tasks,
-        completed_tasks: Vec::new(),
-        needs_human_tasks: Vec::new(),
+        completed_tasks: journal
+            .as_ref()
+            .map(|journal| journal.completed_tasks.clone())
+            .unwrap_or_default(),
+        needs_human_tasks: journal
+            .as_ref()
+            .map(|journal| journal.needs_human_tasks.clone())
+            .unwrap_or_default(),
+        skipped_tasks: journal
+            .as_ref()
+            .map(|journal| journal.skipped_tasks.clone())
+            .unwrap_or_default(),
+        declined_tasks: journal
+            .as_ref()
+            .map(|journal| journal.declined_tasks.clone())
+            .unwrap_or_default(),
+        workspace: None,
+        cwd: None,
         current_task_id: None,
         current_task_show: None,
         current_task_status: None,
         run_started_at: Instant::now(),
         current_task_started_at: None,
         run_exit_code: 0,
+        agent_output_dir,
+        current_agent_output: None,
+        current_review_result: None,
+        current_review_feedback: None,
+        current_review_follow_ups: Vec::new(),
+        current_review_loop: None,
+        agent_failures: 0,
+        routed_from: None,
+        agent_context: AgentInvocationContext::new(
+            loaded.active_profile,
+            loaded.solve_invocation_id,
+            loaded.review_invocation_id,
+        ),
+        resume: journal.and_then(|journal| journal.current_task),
+        task_backend,
     };
 
     if env::var("TRUDGER_TEST_FORCE_ERR").is_ok() {
@@ -285,11 +632,23 @@ where
     }
 
     dispatch_notification_hook(&state, None, NotificationEvent::RunStart);
-    let result = run_loop(&mut state);
+    let result = if cli.watch {
+        install_reload_handler();
+        let profile = cli.profile;
+        run_watch_loop(&mut state, &RELOAD_REQUESTED, |state| {
+            reload_watch_config(state, profile.as_deref(), &home)
+        })
+    } else {
+        run_loop(&mut state)
+    };
     reset_task_on_exit(&state, &result);
+    finish_run_journal(&state, &result);
     finish_current_task_context(&mut state);
     state.tmux.restore();
     state.run_exit_code = result.as_ref().err().map(|quit| quit.code).unwrap_or(0);
+    state
+        .logger
+        .log_transition(Transition::new("run_end").field("exit_code", state.run_exit_code));
     dispatch_notification_hook(&state, None, NotificationEvent::RunEnd);
     result
 }
@@ -0,0 +1,161 @@
+use serde_json::Value;
+use std::path::Path;
+
+use crate::logger::Logger;
+use crate::shell::run_program_capture;
+use crate::task_types::{TaskId, TaskStatus};
+
+const BR_PROGRAM: &str = "br";
+
+/// Task operations for `backend: br`. `br` runs directly in the task repository and its `--json`
+/// output is parsed here, so neither `bash` quoting nor `jq` is involved.
+pub(crate) struct Br<'a> {
+    /// Repository to run `br` in; `None` uses the current directory.
+    pub(crate) cwd: Option<&'a Path>,
+    pub(crate) logger: &'a Logger,
+}
+
+impl Br<'_> {
+    /// The id of the highest-priority ready issue labeled `label`, if any.
+    pub(crate) fn next_task(&self, label: &str, log_label: &str) -> Result<Option<String>, String> {
+        let stdout = self.run(
+            &[
+                "ready", "--json", "--label", label, "--sort", "priority", "--limit", "1",
+            ],
+            log_label,
+            "none",
+        )?;
+        let issues = parse_json("br ready", &stdout)?;
+        Ok(first_issue(&issues)
+            .and_then(|issue| issue.get("id"))
+            .and_then(Value::as_str)
+            .filter(|id| !id.trim().is_empty())
+            .map(str::to_string))
+    }
+
+    /// `br show` output, used as prompt context (`TRUDGER_TASK_SHOW`).
+    pub(crate) fn show(&self, task_id: &TaskId, log_label: &str) -> Result<String, String> {
+        self.run(&["show", task_id.as_str()], log_label, task_id.as_str())
+    }
+
+    pub(crate) fn status(
+        &self,
+        task_id: &TaskId,
+        log_label: &str,
+    ) -> Result<Option<TaskStatus>, String> {
+        let stdout = self.run(
+            &["show", task_id.as_str(), "--json"],
+            log_label,
+            task_id.as_str(),
+        )?;
+        let issues = parse_json("br show", &stdout)?;
+        Ok(first_issue(&issues)
+            .and_then(|issue| issue.get("status"))
+            .and_then(Value::as_str)
+            .and_then(TaskStatus::parse))
+    }
+
+    pub(crate) fn set_status(
+        &self,
+        task_id: &TaskId,
+        status: &TaskStatus,
+        log_label: &str,
+    ) -> Result<(), String> {
+        self.run(
+            &["update", task_id.as_str(), "--status", status_token(status)],
+            log_label,
+            task_id.as_str(),
+        )
+        .map(|_| ())
+    }
+
+    fn run(&self, args: &[&str], log_label: &str, task_token: &str) -> Result<String, String> {
+        let args: Vec<String> = args.iter().map(|arg| arg.to_string()).collect();
+        let output = run_program_capture(
+            BR_PROGRAM,
+            &args,
+            self.cwd,
+            log_label,
+            task_token,
+            self.logger,
+        )?;
+        if output.exit_code != 0 {
+            return Err(format!(
+                "br {} failed with exit code {}",
+                args[0], output.exit_code
+            ));
+        }
+        Ok(output.stdout)
+    }
+}
+
+fn parse_json(command: &str, stdout: &str) -> Result<Value, String> {
+    if stdout.trim().is_empty() {
+        return Ok(Value::Null);
+    }
+    serde_json::from_str(stdout)
+        .map_err(|err| format!("{} returned invalid JSON: {}", command, err))
+}
+
+/// `br` prints a list for `ready` and, depending on the version, a list or a single issue for
+/// `show --json`.
+fn first_issue(value: &Value) -> Option<&Value> {
+    match value {
+        Value::Array(issues) => issues.first(),
+        Value::Object(_) => Some(value),
+        _ => None,
+    }
+}
+
+/// The `br` status for `status`; `br` calls a set-aside issue `deferred`.
+fn status_token(status: &TaskStatus) -> &str {
+    match status {
+        TaskStatus::Skipped => "deferred",
+        status => status.as_str(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn first_issue_accepts_lists_and_single_issues() {
+        let list = parse_json("br ready", r#"[{"id":"tr-1"},{"id":"tr-2"}]"#).expect("json");
+        assert_eq!(
+            first_issue(&list).and_then(|issue| issue.get("id")),
+            Some(&"tr-1".into())
+        );
+
+        let single = parse_json("br show", r#"{"id":"tr-3","status":"open"}"#).expect("json");
+        assert_eq!(
+            first_issue(&single).and_then(|issue| issue.get("status")),
+            Some(&"open".into())
+        );
+
+        assert_eq!(
+            first_issue(&parse_json("br ready", "[]").expect("json")),
+            None
+        );
+        assert_eq!(
+            first_issue(&parse_json("br ready", "\n").expect("json")),
+            None
+        );
+    }
+
+    #[test]
+    fn skipped_is_written_as_deferred() {
+        assert_eq!(status_token(&TaskStatus::Skipped), "deferred");
+        assert_eq!(status_token(&TaskStatus::InProgress), "in_progress");
+        assert_eq!(TaskStatus::parse("deferred"), Some(TaskStatus::Skipped));
+    }
+
+    #[test]
+    fn parse_json_reports_the_command() {
+        let err = parse_json("br show", "not json").expect_err("invalid json");
+        assert!(
+            err.starts_with("br show returned invalid JSON"),
+            "got: {err}"
+        );
+    }
+}
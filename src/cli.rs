@@ -45,6 +45,14 @@ pub(crate) struct Cli {
     #[arg(value_name = "ARG", hide = true)]
     pub(crate) positional: Vec<String>,
 
+    /// Keep running when the task queue is empty and poll `commands.next_task` again.
+    #[arg(
+        long = "watch",
+        global = true,
+        help = "Keep running when the task queue is empty and poll for new tasks (SIGHUP reloads config)."
+    )]
+    pub(crate) watch: bool,
+
     #[command(subcommand)]
     pub(crate) command: Option<CliCommand>,
 }
@@ -60,6 +68,29 @@ pub(crate) enum CliCommand {
     #[command(about = "Run configuration/command validation against a temporary scratch task DB.")]
     /// Run configuration/command validation against a temporary scratch task DB.
     Doctor,
+
+    #[command(
+        about = "Resume an interrupted run from the run journal (requires journal_path in config)."
+    )]
+    /// Resume an interrupted run from the run journal.
+    Resume,
+
+    #[command(
+        about = "Summarize the current or latest run from the transition log and run journal."
+    )]
+    /// Summarize the current or latest run from the transition log and run journal.
+    Status {
+        /// Print the summary as JSON.
+        #[arg(long = "json", help = "Print the summary as JSON.")]
+        json: bool,
+
+        /// Include every run in the transition log, not just the latest.
+        #[arg(
+            long = "all",
+            help = "Include every run in the transition log, not just the latest."
+        )]
+        all: bool,
+    },
 }
 
 pub(crate) fn parse_manual_tasks(raw_values: &[String]) -> Result<Vec<TaskId>, String> {
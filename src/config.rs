@@ -1,10 +1,13 @@
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Deserializer};
 use serde_yaml::{Mapping, Value};
 use std::collections::HashMap;
 use std::fs;
+use std::num::{NonZeroU64, NonZeroUsize};
 use std::path::{Path, PathBuf};
+use std::time::Duration;
 
-use crate::task_types::ReviewLoopLimit;
+use crate::task_types::{ReviewLoopLimit, TaskStatus, TimeoutSeconds};
 
 fn deserialize_log_path<'de, D>(deserializer: D) -> Result<Option<PathBuf>, D::Error>
 where
@@ -21,19 +24,613 @@ where
 pub struct Config {
     pub agent_command: String,
     pub agent_review_command: String,
+    #[serde(default)]
+    pub backend: Backend,
+    #[serde(default)]
+    pub br: BrConfig,
+    #[serde(default)]
+    pub github: GithubConfig,
+    #[serde(default)]
+    pub markdown: MarkdownConfig,
+    #[serde(default)]
+    pub openspec: OpenspecConfig,
+    #[serde(default)]
+    pub statuses: StatusMap,
     pub commands: Commands,
     pub hooks: Hooks,
     pub review_loop_limit: ReviewLoopLimit,
     #[serde(default, deserialize_with = "deserialize_log_path")]
     pub log_path: Option<PathBuf>,
+    #[serde(default, deserialize_with = "deserialize_log_path")]
+    pub journal_path: Option<PathBuf>,
+    #[serde(default)]
+    pub agent_timeout: Option<AgentTimeout>,
+    #[serde(default)]
+    pub agent_review_timeout: Option<AgentTimeout>,
+    #[serde(default)]
+    pub agent_retry: Option<RetryPolicy>,
+    #[serde(default)]
+    pub agent_review_retry: Option<RetryPolicy>,
+    #[serde(default)]
+    pub on_task_error: TaskErrorAction,
+    #[serde(default)]
+    pub watch: WatchConfig,
+    #[serde(skip)]
+    pub workspaces: Vec<Workspace>,
+    #[serde(default)]
+    pub workspace_order: WorkspaceOrder,
+    /// How many tasks run at once; above 1 each task gets its own git worktree.
+    #[serde(default = "default_concurrency")]
+    pub concurrency: NonZeroUsize,
+    #[serde(default, deserialize_with = "deserialize_log_path")]
+    pub worktree_root: Option<PathBuf>,
+    #[serde(default)]
+    pub log_format: LogFormat,
+    /// Where solve/review transcripts are written, relative to the invocation folder.
+    #[serde(default, deserialize_with = "deserialize_log_path")]
+    pub agent_output_dir: Option<PathBuf>,
+    #[serde(default)]
+    pub prompt_format: PromptFormat,
+    /// Prompt files from `prompts`, with the active profile's entries taking precedence.
+    #[serde(default)]
+    pub prompts: PromptPaths,
+    /// The active profile's pipeline up to the review; just `Solve` unless it sets `phases`.
+    #[serde(skip, default = "default_phases")]
+    pub phases: Vec<PipelinePhase>,
+    #[serde(skip)]
+    pub profile_fallback: ProfileFallback,
+    #[serde(skip)]
+    pub task_routing: TaskRouting,
 }
 
-#[derive(Debug, Deserialize, Clone)]
+impl Config {
+    /// The custom phase named `name` in the active profile's pipeline.
+    pub fn custom_phase(&self, name: &str) -> Option<&AgentPhase> {
+        self.phases.iter().find_map(|phase| match phase {
+            PipelinePhase::Custom(phase) if phase.name == name => Some(phase),
+            _ => None,
+        })
+    }
+
+    /// Every agent phase name in pipeline order, ending with `trudge_review`.
+    pub fn agent_phase_names(&self) -> Vec<&str> {
+        self.phases
+            .iter()
+            .map(|phase| match phase {
+                PipelinePhase::Solve => "trudge",
+                PipelinePhase::Custom(phase) => phase.name.as_str(),
+            })
+            .chain(["trudge_review"])
+            .collect()
+    }
+}
+
+fn default_concurrency() -> NonZeroUsize {
+    NonZeroUsize::MIN
+}
+
+/// A repository trudged from the same run (`workspaces`), fully resolved against the top level.
+#[derive(Debug, Clone)]
+pub struct Workspace {
+    pub name: String,
+    /// Working directory for every command and hook run for this workspace's tasks.
+    pub path: PathBuf,
+    pub profile: String,
+    pub solve_invocation_id: String,
+    pub review_invocation_id: String,
+    /// Top-level config with this workspace's `commands`/`hooks` overrides and profile applied.
+    pub config: Config,
+}
+
+/// Line format of the transition log at `log_path` (`log_format`).
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum LogFormat {
+    /// `<timestamp> <message>` lines.
+    #[default]
+    Text,
+    /// One JSON object per transition, with the message's `key=value` fields as typed members.
+    Jsonl,
+}
+
+/// Prompt file for each agent phase (`prompts`, at the top level or per profile).
+#[derive(Debug, Deserialize, Clone, Default, PartialEq, Eq)]
+pub struct PromptPaths {
+    #[serde(default)]
+    pub trudge: Option<PathBuf>,
+    #[serde(default)]
+    pub trudge_review: Option<PathBuf>,
+}
+
+impl PromptPaths {
+    /// The configured prompt for `phase` (`trudge` or `trudge_review`).
+    pub fn get(&self, phase: &str) -> Option<&Path> {
+        match phase {
+            "trudge" => self.trudge.as_deref(),
+            "trudge_review" => self.trudge_review.as_deref(),
+            _ => None,
+        }
+    }
+
+    fn or(&self, fallback: &PromptPaths) -> PromptPaths {
+        PromptPaths {
+            trudge: self.trudge.clone().or_else(|| fallback.trudge.clone()),
+            trudge_review: self
+                .trudge_review
+                .clone()
+                .or_else(|| fallback.trudge_review.clone()),
+        }
+    }
+}
+
+/// How prompt files become `TRUDGER_AGENT_PROMPT` (`prompt_format`).
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum PromptFormat {
+    /// The prompt text is passed as written.
+    #[default]
+    Plain,
+    /// `{{task.id}}`-style variables and `{{#if}}` blocks are rendered per task and phase.
+    Template,
+}
+
+/// A step of a profile's pipeline that runs before the review (`profiles.<name>.phases`); the
+/// review (`trudge_review`) always comes last.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PipelinePhase {
+    /// The solve phase (`trudge`), run with the profile's `trudge` invocation.
+    Solve,
+    Custom(AgentPhase),
+}
+
+/// A phase a profile adds to its pipeline, such as `plan` or `test`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AgentPhase {
+    /// Used for `TRUDGER_AGENT_PHASE`, log lines, and the default prompt file name.
+    pub name: String,
+    pub invocation_id: String,
+    pub command: String,
+    pub timeout: Option<AgentTimeout>,
+    pub retry: Option<RetryPolicy>,
+    pub prompt: Option<PathBuf>,
+    /// `on_failure`, or else the one matching `on_task_error`.
+    pub on_failure: PhaseFailureAction,
+}
+
+/// What to do with a task when a custom phase's invocation fails.
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum PhaseFailureAction {
+    Escalate,
+    Skip,
+    Quit,
+    /// Carry on with the next phase as if the invocation had succeeded.
+    Continue,
+    /// Start the next solve/review loop, as a review that sends the task back would.
+    NextLoop,
+}
+
+impl PhaseFailureAction {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Escalate => "escalate",
+            Self::Skip => "skip",
+            Self::Quit => "quit",
+            Self::Continue => "continue",
+            Self::NextLoop => "next_loop",
+        }
+    }
+}
+
+impl From<TaskErrorAction> for PhaseFailureAction {
+    fn from(action: TaskErrorAction) -> Self {
+        match action {
+            TaskErrorAction::Escalate => Self::Escalate,
+            TaskErrorAction::Skip => Self::Skip,
+            TaskErrorAction::Quit => Self::Quit,
+        }
+    }
+}
+
+/// `profiles.<name>.fallback`: profiles to switch to, in order, when agent invocations keep
+/// failing.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ProfileFallback {
+    /// The profiles not switched to yet.
+    pub profiles: Vec<AgentProfile>,
+    pub after_failures: Option<NonZeroU64>,
+    pub exit_codes: Vec<i32>,
+}
+
+impl ProfileFallback {
+    /// Whether a failed invocation, the `failures`-th in a row, calls for the next profile.
+    /// Without `fallback_exit_codes` or `fallback_after_failures`, any failure does.
+    pub fn should_fall_back(&self, failures: u64, exit_code: Option<i32>) -> bool {
+        if self.profiles.is_empty() {
+            return false;
+        }
+        if exit_code.is_some_and(|code| self.exit_codes.contains(&code)) {
+            return true;
+        }
+        let after_failures = match self.after_failures {
+            Some(after_failures) => after_failures.get(),
+            None if self.exit_codes.is_empty() => 1,
+            None => return false,
+        };
+        failures >= after_failures
+    }
+}
+
+/// Per-task profile selection from `profile_rules` and `commands.task_profile`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TaskRouting {
+    /// `profile_rules`, in config order.
+    pub rules: Vec<ProfileRule>,
+    /// Every configured profile, resolved, for a task to be routed to.
+    pub profiles: HashMap<String, AgentProfile>,
+}
+
+impl TaskRouting {
+    /// The first rule whose label the task carries.
+    pub fn rule_for(&self, labels: &[String]) -> Option<&ProfileRule> {
+        self.rules.iter().find(|rule| labels.contains(&rule.label))
+    }
+}
+
+/// One `profile_rules` entry: tasks labeled `label` run with `profile`.
+#[derive(Debug, Deserialize, Clone, PartialEq, Eq)]
+pub struct ProfileRule {
+    pub label: String,
+    pub profile: String,
+}
+
+fn default_phases() -> Vec<PipelinePhase> {
+    vec![PipelinePhase::Solve]
+}
+
+/// Where tasks are selected, shown, and updated (`backend`).
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum Backend {
+    /// The `commands.*` shell commands.
+    #[default]
+    Shell,
+    /// `br` (beads), run directly with its JSON output parsed by trudger.
+    Br,
+    /// GitHub Issues, through the REST API.
+    Github,
+    /// A markdown checklist in the repository.
+    Markdown,
+    /// openspec change proposals and their `tasks.md` checklists.
+    Openspec,
+}
+
+impl Backend {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Shell => "shell",
+            Self::Br => "br",
+            Self::Github => "github",
+            Self::Markdown => "markdown",
+            Self::Openspec => "openspec",
+        }
+    }
+}
+
+/// Settings for `backend: br` (`br`).
+#[derive(Debug, Deserialize, Clone, PartialEq, Eq)]
+pub struct BrConfig {
+    /// Only ready issues with this label are selected.
+    #[serde(default = "default_task_label")]
+    pub label: String,
+}
+
+impl Default for BrConfig {
+    fn default() -> Self {
+        Self {
+            label: default_task_label(),
+        }
+    }
+}
+
+fn default_task_label() -> String {
+    "trudgeable".to_string()
+}
+
+/// Settings for `backend: github` (`github`).
+#[derive(Debug, Deserialize, Clone, PartialEq, Eq)]
+pub struct GithubConfig {
+    /// `owner/name` of the repository whose issues are the tasks.
+    #[serde(default)]
+    pub repo: String,
+    /// Only open issues with this label are selected.
+    #[serde(default = "default_task_label")]
+    pub label: String,
+    /// REST API root; point it at a stub server in tests or at a GitHub Enterprise instance.
+    #[serde(default = "default_github_api_url")]
+    pub api_url: String,
+    /// Environment variable holding the API token; requests are unauthenticated when it is unset.
+    #[serde(default = "default_github_token_env")]
+    pub token_env: String,
+}
+
+impl Default for GithubConfig {
+    fn default() -> Self {
+        Self {
+            repo: String::new(),
+            label: default_task_label(),
+            api_url: default_github_api_url(),
+            token_env: default_github_token_env(),
+        }
+    }
+}
+
+fn default_github_api_url() -> String {
+    "https://api.github.com".to_string()
+}
+
+fn default_github_token_env() -> String {
+    "GITHUB_TOKEN".to_string()
+}
+
+/// Settings for `backend: markdown` (`markdown`).
+#[derive(Debug, Deserialize, Clone, PartialEq, Eq)]
+pub struct MarkdownConfig {
+    /// The checklist file, relative to the repository being trudged.
+    #[serde(default = "default_markdown_path")]
+    pub path: PathBuf,
+}
+
+impl Default for MarkdownConfig {
+    fn default() -> Self {
+        Self {
+            path: default_markdown_path(),
+        }
+    }
+}
+
+fn default_markdown_path() -> PathBuf {
+    PathBuf::from("TODO.md")
+}
+
+/// Settings for `backend: openspec` (`openspec`).
+#[derive(Debug, Deserialize, Clone, PartialEq, Eq)]
+pub struct OpenspecConfig {
+    /// The openspec directory (holding `changes/`), relative to the repository being trudged.
+    #[serde(default = "default_openspec_dir")]
+    pub dir: PathBuf,
+}
+
+impl Default for OpenspecConfig {
+    fn default() -> Self {
+        Self {
+            dir: default_openspec_dir(),
+        }
+    }
+}
+
+fn default_openspec_dir() -> PathBuf {
+    PathBuf::from("openspec")
+}
+
+/// How the run loop picks the next workspace to take a task from (`workspace_order`).
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum WorkspaceOrder {
+    /// Take one task from each workspace in turn.
+    #[default]
+    RoundRobin,
+    /// Always take the next task from the first workspace that has one.
+    Priority,
+}
+
+/// What to do with a task whose solve or review invocation fails (`on_task_error`).
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum TaskErrorAction {
+    /// Mark the task blocked, run `hooks.on_task_error` (or `hooks.on_requires_human`), and continue.
+    Escalate,
+    /// Reset the task to open, leave it alone for the rest of the run, and continue.
+    Skip,
+    #[default]
+    Quit,
+}
+
+/// What to do when an agent invocation exceeds `invocations.<id>.timeout`.
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum TimeoutAction {
+    /// Run the invocation once more; a second timeout quits.
+    Retry,
+    /// Mark the task blocked, run `hooks.on_requires_human`, and continue with the next task.
+    Escalate,
+    #[default]
+    Quit,
+}
+
+impl TimeoutAction {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Retry => "retry",
+            Self::Escalate => "escalate",
+            Self::Quit => "quit",
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub struct AgentTimeout {
+    pub seconds: TimeoutSeconds,
+    pub on_timeout: TimeoutAction,
+}
+
+const DEFAULT_RETRY_BACKOFF_SECONDS: u64 = 5;
+const DEFAULT_RETRY_MAX_BACKOFF_SECONDS: u64 = 300;
+
+fn default_retry_backoff_seconds() -> u64 {
+    DEFAULT_RETRY_BACKOFF_SECONDS
+}
+
+fn default_retry_max_backoff_seconds() -> u64 {
+    DEFAULT_RETRY_MAX_BACKOFF_SECONDS
+}
+
+/// Re-runs an agent invocation that exits non-zero (`invocations.<id>.retry`).
+#[derive(Debug, Deserialize, Clone, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Total attempts, including the first one.
+    pub max_attempts: NonZeroU64,
+    /// Delay before the first retry; doubles on every further retry.
+    #[serde(default = "default_retry_backoff_seconds")]
+    pub backoff_seconds: u64,
+    #[serde(default = "default_retry_max_backoff_seconds")]
+    pub max_backoff_seconds: u64,
+    /// Exit codes worth retrying; empty means every non-zero exit code.
+    #[serde(default)]
+    pub exit_codes: Vec<i32>,
+}
+
+impl RetryPolicy {
+    pub fn retries_exit_code(&self, exit_code: i32) -> bool {
+        exit_code != 0 && (self.exit_codes.is_empty() || self.exit_codes.contains(&exit_code))
+    }
+
+    /// Delay before the attempt that follows `attempt` (1-based).
+    pub fn backoff_after(&self, attempt: u64) -> Duration {
+        let doublings = u32::try_from(attempt.saturating_sub(1)).unwrap_or(u32::MAX);
+        let factor = 1u64.checked_shl(doublings).unwrap_or(u64::MAX);
+        let seconds = self
+            .backoff_seconds
+            .saturating_mul(factor)
+            .min(self.max_backoff_seconds);
+        Duration::from_secs(seconds)
+    }
+}
+
+const DEFAULT_WATCH_POLL_INTERVAL_SECONDS: u64 = 60;
+const DEFAULT_WATCH_MAX_POLL_INTERVAL_SECONDS: u64 = 600;
+
+fn default_watch_poll_interval_seconds() -> NonZeroU64 {
+    NonZeroU64::new(DEFAULT_WATCH_POLL_INTERVAL_SECONDS).expect("non-zero default")
+}
+
+fn default_watch_max_poll_interval_seconds() -> NonZeroU64 {
+    NonZeroU64::new(DEFAULT_WATCH_MAX_POLL_INTERVAL_SECONDS).expect("non-zero default")
+}
+
+/// Polling cadence for `trudger --watch` while the task queue is empty (`watch`).
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub struct WatchConfig {
+    /// Delay before the first poll after the queue drains; doubles on every further empty poll.
+    #[serde(default = "default_watch_poll_interval_seconds")]
+    pub poll_interval_seconds: NonZeroU64,
+    #[serde(default = "default_watch_max_poll_interval_seconds")]
+    pub max_poll_interval_seconds: NonZeroU64,
+}
+
+impl Default for WatchConfig {
+    fn default() -> Self {
+        Self {
+            poll_interval_seconds: default_watch_poll_interval_seconds(),
+            max_poll_interval_seconds: default_watch_max_poll_interval_seconds(),
+        }
+    }
+}
+
+impl WatchConfig {
+    /// Delay before the next poll after `idle_polls` consecutive empty polls (0-based).
+    pub fn poll_delay(&self, idle_polls: u64) -> Duration {
+        let doublings = u32::try_from(idle_polls).unwrap_or(u32::MAX);
+        let factor = 1u64.checked_shl(doublings).unwrap_or(u64::MAX);
+        let base = self.poll_interval_seconds.get();
+        let seconds = base
+            .saturating_mul(factor)
+            .min(self.max_poll_interval_seconds.get().max(base));
+        Duration::from_secs(seconds)
+    }
+}
+
+/// Task commands for `backend: shell`; empty when another backend is configured.
+#[derive(Debug, Deserialize, Clone, Default)]
 pub struct Commands {
+    #[serde(default)]
     pub next_task: Option<String>,
+    #[serde(default)]
     pub task_show: String,
+    #[serde(default)]
     pub task_status: String,
+    #[serde(default)]
     pub task_update_status: String,
+    /// Prints the ids of the tasks that must be closed first, one per line.
+    #[serde(default)]
+    pub task_dependencies: Option<String>,
+    /// Prints the profile a task runs with; empty output leaves it to `profile_rules`.
+    #[serde(default)]
+    pub task_profile: Option<String>,
+}
+
+/// Tracker status tokens mapped to trudger's statuses (`statuses`), in config order.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StatusMap(Vec<(String, TaskStatus)>);
+
+const STATUS_STATES: &str = "ready|in_progress|done|needs_human|skip";
+
+fn status_state(value: &str) -> Option<TaskStatus> {
+    Some(match value {
+        "ready" => TaskStatus::Ready,
+        "in_progress" => TaskStatus::InProgress,
+        "done" => TaskStatus::Closed,
+        "needs_human" => TaskStatus::Blocked,
+        "skip" => TaskStatus::Skipped,
+        _ => return None,
+    })
+}
+
+impl StatusMap {
+    /// Parses a `commands.task_status` token: mapped tokens first, then the built-in names.
+    pub(crate) fn parse(&self, token: &str) -> Option<TaskStatus> {
+        let token = token.trim();
+        self.0
+            .iter()
+            .find(|(mapped, _)| mapped == token)
+            .map(|(_, status)| status.clone())
+            .or_else(|| TaskStatus::parse(token))
+    }
+
+    /// The `TRUDGER_TARGET_STATUS` token for `status`: the first one mapped to it, else its
+    /// built-in name. `ready` tokens stand for both `ready` and `open`.
+    pub(crate) fn token<'a>(&'a self, status: &'a TaskStatus) -> &'a str {
+        self.0
+            .iter()
+            .find(|(_, mapped)| mapped == status || (mapped.is_ready() && status.is_ready()))
+            .map_or(status.as_str(), |(token, _)| token.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for StatusMap {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        use serde::de::Error as _;
+        let mapping = Mapping::deserialize(deserializer)?;
+        mapping
+            .iter()
+            .map(|(token, state)| {
+                let token = token
+                    .as_str()
+                    .ok_or_else(|| D::Error::custom("statuses keys must be strings"))?;
+                let status = state.as_str().and_then(status_state).ok_or_else(|| {
+                    D::Error::custom(format!(
+                        "statuses.{} must be one of {}",
+                        token, STATUS_STATES
+                    ))
+                })?;
+                Ok((token.to_string(), status))
+            })
+            .collect::<Result<_, _>>()
+            .map(StatusMap)
+    }
 }
 
 #[derive(Debug, Deserialize, Clone)]
@@ -46,6 +643,11 @@ pub struct Hooks {
     pub on_notification: Option<String>,
     #[serde(default)]
     pub on_notification_scope: Option<NotificationScope>,
+    #[serde(default)]
+    pub on_task_error: Option<String>,
+    /// Runs for tasks the review leaves `skipped` (declined as out of scope).
+    #[serde(default)]
+    pub on_skipped: Option<String>,
 }
 
 #[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq)]
@@ -87,11 +689,66 @@ pub struct LoadedConfig {
 struct ParsedProfile {
     trudge: String,
     trudge_review: String,
+    #[serde(default)]
+    prompts: PromptPaths,
+    #[serde(default)]
+    phases: Option<Vec<ParsedPhase>>,
+    #[serde(default)]
+    fallback: Vec<String>,
+    #[serde(default)]
+    fallback_after_failures: Option<NonZeroU64>,
+    #[serde(default)]
+    fallback_exit_codes: Vec<i32>,
+}
+
+/// A `profiles.<name>.phases` entry: `trudge`/`trudge_review`, or a custom phase mapping.
+#[derive(Debug, Deserialize, Clone)]
+#[serde(untagged)]
+enum ParsedPhase {
+    Builtin(String),
+    Custom(ParsedCustomPhase),
+}
+
+#[derive(Debug, Deserialize, Clone)]
+struct ParsedCustomPhase {
+    name: String,
+    invocation: String,
+    #[serde(default)]
+    prompt: Option<PathBuf>,
+    #[serde(default)]
+    on_failure: Option<PhaseFailureAction>,
 }
 
 #[derive(Debug, Deserialize, Clone)]
 struct ParsedInvocation {
     command: String,
+    #[serde(default)]
+    timeout: Option<TimeoutSeconds>,
+    #[serde(default)]
+    on_timeout: Option<TimeoutAction>,
+    #[serde(default)]
+    retry: Option<RetryPolicy>,
+}
+
+impl ParsedInvocation {
+    fn agent_timeout(&self) -> Option<AgentTimeout> {
+        self.timeout.map(|seconds| AgentTimeout {
+            seconds,
+            on_timeout: self.on_timeout.unwrap_or_default(),
+        })
+    }
+}
+
+#[derive(Debug, Deserialize, Clone)]
+struct ParsedWorkspace {
+    name: String,
+    path: PathBuf,
+    #[serde(default)]
+    profile: Option<String>,
+    #[serde(default)]
+    commands: Option<Mapping>,
+    #[serde(default)]
+    hooks: Option<Mapping>,
 }
 
 #[derive(Debug, Deserialize, Clone)]
@@ -99,11 +756,48 @@ struct ParsedConfig {
     default_profile: String,
     profiles: HashMap<String, ParsedProfile>,
     invocations: HashMap<String, ParsedInvocation>,
+    #[serde(default)]
+    backend: Backend,
+    #[serde(default)]
+    br: BrConfig,
+    #[serde(default)]
+    github: GithubConfig,
+    #[serde(default)]
+    markdown: MarkdownConfig,
+    #[serde(default)]
+    openspec: OpenspecConfig,
+    #[serde(default)]
+    statuses: StatusMap,
+    #[serde(default)]
     commands: Commands,
     hooks: Hooks,
     review_loop_limit: ReviewLoopLimit,
     #[serde(default, deserialize_with = "deserialize_log_path")]
     log_path: Option<PathBuf>,
+    #[serde(default, deserialize_with = "deserialize_log_path")]
+    journal_path: Option<PathBuf>,
+    #[serde(default)]
+    on_task_error: TaskErrorAction,
+    #[serde(default)]
+    watch: WatchConfig,
+    #[serde(default)]
+    workspaces: Vec<ParsedWorkspace>,
+    #[serde(default)]
+    workspace_order: WorkspaceOrder,
+    #[serde(default = "default_concurrency")]
+    concurrency: NonZeroUsize,
+    #[serde(default, deserialize_with = "deserialize_log_path")]
+    worktree_root: Option<PathBuf>,
+    #[serde(default)]
+    log_format: LogFormat,
+    #[serde(default, deserialize_with = "deserialize_log_path")]
+    agent_output_dir: Option<PathBuf>,
+    #[serde(default)]
+    prompt_format: PromptFormat,
+    #[serde(default)]
+    prompts: PromptPaths,
+    #[serde(default)]
+    profile_rules: Vec<ProfileRule>,
 }
 
 #[allow(dead_code)]
@@ -142,6 +836,10 @@ pub(crate) fn load_config_from_str_with_profile(
         eprintln!("Warning: {}", warning);
         warnings.push(warning);
     }
+    for warning in on_timeout_without_timeout_warnings(&mapping) {
+        eprintln!("Warning: {}", warning);
+        warnings.push(warning);
+    }
     validate_required_fields(&mapping)?;
 
     // `serde_yaml` doesn't reliably include the failing key path for custom
@@ -152,14 +850,40 @@ pub(crate) fn load_config_from_str_with_profile(
         .map_err(|err| format!("Failed to parse config {}: {}", label, err))?;
 
     let resolved_commands = resolve_profile_commands(&config, profile)?;
-    let config = Config {
+    let base = Config {
         agent_command: resolved_commands.solve_command,
         agent_review_command: resolved_commands.review_command,
-        commands: config.commands,
-        hooks: config.hooks,
+        backend: config.backend,
+        br: config.br.clone(),
+        github: config.github.clone(),
+        markdown: config.markdown.clone(),
+        openspec: config.openspec.clone(),
+        statuses: config.statuses.clone(),
+        commands: config.commands.clone(),
+        hooks: config.hooks.clone(),
         review_loop_limit: config.review_loop_limit,
-        log_path: config.log_path,
+        log_path: config.log_path.clone(),
+        journal_path: config.journal_path.clone(),
+        agent_timeout: resolved_commands.solve_timeout,
+        agent_review_timeout: resolved_commands.review_timeout,
+        agent_retry: resolved_commands.solve_retry,
+        agent_review_retry: resolved_commands.review_retry,
+        on_task_error: config.on_task_error,
+        watch: config.watch,
+        workspaces: Vec::new(),
+        workspace_order: config.workspace_order,
+        concurrency: config.concurrency,
+        worktree_root: config.worktree_root.clone(),
+        log_format: config.log_format,
+        agent_output_dir: config.agent_output_dir.clone(),
+        prompt_format: config.prompt_format,
+        prompts: resolved_commands.prompts,
+        phases: resolved_commands.phases,
+        profile_fallback: resolve_profile_fallback(&config, &resolved_commands.profile)?,
+        task_routing: resolve_task_routing(&config)?,
     };
+    let workspaces = resolve_workspaces(&mapping, &config, profile, &base)?;
+    let config = Config { workspaces, ..base };
 
     Ok(LoadedConfig {
         config,
@@ -170,63 +894,222 @@ pub(crate) fn load_config_from_str_with_profile(
     })
 }
 
-struct ResolvedAgentCommands {
-    profile: String,
-    solve_invocation_id: String,
-    review_invocation_id: String,
-    solve_command: String,
-    review_command: String,
+fn resolve_workspaces(
+    mapping: &Mapping,
+    parsed: &ParsedConfig,
+    profile_override: Option<&str>,
+    base: &Config,
+) -> Result<Vec<Workspace>, String> {
+    parsed
+        .workspaces
+        .iter()
+        .map(|workspace| {
+            let profile = workspace.profile.as_deref().or(profile_override);
+            let resolved = resolve_profile_commands(parsed, profile)
+                .map_err(|err| format!("workspaces.{}: {}", workspace.name, err))?;
+            let profile_fallback = resolve_profile_fallback(parsed, &resolved.profile)
+                .map_err(|err| format!("workspaces.{}: {}", workspace.name, err))?;
+            let commands: Commands =
+                merge_section_override(mapping, "commands", workspace.commands.as_ref())
+                    .map_err(|err| format!("workspaces.{}.commands: {}", workspace.name, err))?;
+            let hooks: Hooks =
+                merge_section_override(mapping, "hooks", workspace.hooks.as_ref())
+                    .map_err(|err| format!("workspaces.{}.hooks: {}", workspace.name, err))?;
+
+            Ok(Workspace {
+                name: workspace.name.clone(),
+                path: workspace.path.clone(),
+                profile: resolved.profile,
+                solve_invocation_id: resolved.solve_invocation_id,
+                review_invocation_id: resolved.review_invocation_id,
+                config: Config {
+                    agent_command: resolved.solve_command,
+                    agent_review_command: resolved.review_command,
+                    commands,
+                    hooks,
+                    agent_timeout: resolved.solve_timeout,
+                    agent_review_timeout: resolved.review_timeout,
+                    agent_retry: resolved.solve_retry,
+                    agent_review_retry: resolved.review_retry,
+                    prompts: resolved.prompts,
+                    phases: resolved.phases,
+                    profile_fallback,
+                    workspaces: Vec::new(),
+                    ..base.clone()
+                },
+            })
+        })
+        .collect()
 }
 
-fn resolve_profile_commands(
-    config: &ParsedConfig,
-    profile_override: Option<&str>,
-) -> Result<ResolvedAgentCommands, String> {
-    let profile_name = profile_override.unwrap_or(config.default_profile.as_str());
-    let profile = config.profiles.get(profile_name).ok_or_else(|| {
-        if profile_override.is_some() {
-            format!("Unknown profile: {}", profile_name)
-        } else {
-            format!(
+// Overlays a workspace's `commands`/`hooks` keys on the top-level section of the same name.
+fn merge_section_override<T: DeserializeOwned>(
+    mapping: &Mapping,
+    section: &str,
+    overrides: Option<&Mapping>,
+) -> Result<T, String> {
+    let mut merged = match mapping.get(Value::String(section.to_string())) {
+        Some(Value::Mapping(base)) => base.clone(),
+        _ => Mapping::new(),
+    };
+    for (key, value) in overrides.into_iter().flatten() {
+        merged.insert(key.clone(), value.clone());
+    }
+    serde_yaml::from_value(Value::Mapping(merged)).map_err(|err| err.to_string())
+}
+
+/// One profile's agent settings (`profiles.<name>`), resolved against `invocations`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AgentProfile {
+    pub profile: String,
+    pub solve_invocation_id: String,
+    pub review_invocation_id: String,
+    pub solve_command: String,
+    pub review_command: String,
+    pub solve_timeout: Option<AgentTimeout>,
+    pub review_timeout: Option<AgentTimeout>,
+    pub solve_retry: Option<RetryPolicy>,
+    pub review_retry: Option<RetryPolicy>,
+    pub prompts: PromptPaths,
+    pub phases: Vec<PipelinePhase>,
+}
+
+impl AgentProfile {
+    /// Points `config`'s agent commands, timeouts, retries, prompts, and phases at this profile.
+    pub(crate) fn apply(&self, config: &mut Config) {
+        config.agent_command = self.solve_command.clone();
+        config.agent_review_command = self.review_command.clone();
+        config.agent_timeout = self.solve_timeout;
+        config.agent_review_timeout = self.review_timeout;
+        config.agent_retry = self.solve_retry.clone();
+        config.agent_review_retry = self.review_retry.clone();
+        config.prompts = self.prompts.clone();
+        config.phases = self.phases.clone();
+    }
+}
+
+fn resolve_profile_commands(
+    config: &ParsedConfig,
+    profile_override: Option<&str>,
+) -> Result<AgentProfile, String> {
+    let profile_name = profile_override.unwrap_or(config.default_profile.as_str());
+    let profile = config.profiles.get(profile_name).ok_or_else(|| {
+        if profile_override.is_some() {
+            format!("Unknown profile: {}", profile_name)
+        } else {
+            format!(
                 "default_profile references missing profile: {}",
                 config.default_profile
             )
         }
     })?;
 
-    let agent_command = config
-        .invocations
-        .get(&profile.trudge)
-        .ok_or_else(|| {
-            format!(
-                "profiles.{}.trudge references missing invocation: {}",
-                profile_name, profile.trudge
-            )
-        })?
-        .command
-        .clone();
-
-    let agent_review_command = config
-        .invocations
-        .get(&profile.trudge_review)
-        .ok_or_else(|| {
-            format!(
-                "profiles.{}.trudge_review references missing invocation: {}",
-                profile_name, profile.trudge_review
-            )
-        })?
-        .command
-        .clone();
+    let agent_invocation = config.invocations.get(&profile.trudge).ok_or_else(|| {
+        format!(
+            "profiles.{}.trudge references missing invocation: {}",
+            profile_name, profile.trudge
+        )
+    })?;
+
+    let agent_review_invocation =
+        config
+            .invocations
+            .get(&profile.trudge_review)
+            .ok_or_else(|| {
+                format!(
+                    "profiles.{}.trudge_review references missing invocation: {}",
+                    profile_name, profile.trudge_review
+                )
+            })?;
 
-    Ok(ResolvedAgentCommands {
+    Ok(AgentProfile {
         profile: profile_name.to_string(),
         solve_invocation_id: profile.trudge.clone(),
         review_invocation_id: profile.trudge_review.clone(),
-        solve_command: agent_command,
-        review_command: agent_review_command,
+        solve_command: agent_invocation.command.clone(),
+        review_command: agent_review_invocation.command.clone(),
+        solve_timeout: agent_invocation.agent_timeout(),
+        review_timeout: agent_review_invocation.agent_timeout(),
+        solve_retry: agent_invocation.retry.clone(),
+        review_retry: agent_review_invocation.retry.clone(),
+        prompts: profile.prompts.or(&config.prompts),
+        phases: resolve_profile_phases(config, profile_name, profile)?,
+    })
+}
+
+fn resolve_profile_fallback(
+    config: &ParsedConfig,
+    profile_name: &str,
+) -> Result<ProfileFallback, String> {
+    let Some(profile) = config.profiles.get(profile_name) else {
+        return Ok(ProfileFallback::default());
+    };
+    let profiles = profile
+        .fallback
+        .iter()
+        .map(|name| {
+            resolve_profile_commands(config, Some(name))
+                .map_err(|err| format!("profiles.{}.fallback: {}", profile_name, err))
+        })
+        .collect::<Result<_, _>>()?;
+    Ok(ProfileFallback {
+        profiles,
+        after_failures: profile.fallback_after_failures,
+        exit_codes: profile.fallback_exit_codes.clone(),
+    })
+}
+
+fn resolve_task_routing(config: &ParsedConfig) -> Result<TaskRouting, String> {
+    let profiles = config
+        .profiles
+        .keys()
+        .map(|name| Ok((name.clone(), resolve_profile_commands(config, Some(name))?)))
+        .collect::<Result<_, String>>()?;
+    Ok(TaskRouting {
+        rules: config.profile_rules.clone(),
+        profiles,
     })
 }
 
+fn resolve_profile_phases(
+    config: &ParsedConfig,
+    profile_name: &str,
+    profile: &ParsedProfile,
+) -> Result<Vec<PipelinePhase>, String> {
+    let Some(phases) = profile.phases.as_ref() else {
+        return Ok(default_phases());
+    };
+    let mut resolved = Vec::new();
+    for (index, phase) in phases.iter().enumerate() {
+        let phase = match phase {
+            ParsedPhase::Builtin(name) if name == "trudge" => PipelinePhase::Solve,
+            // Validation keeps `trudge_review` as the last entry; the review is not a pipeline step.
+            ParsedPhase::Builtin(_) => continue,
+            ParsedPhase::Custom(phase) => {
+                let invocation = config.invocations.get(&phase.invocation).ok_or_else(|| {
+                    format!(
+                        "profiles.{}.phases[{}].invocation references missing invocation: {}",
+                        profile_name, index, phase.invocation
+                    )
+                })?;
+                PipelinePhase::Custom(AgentPhase {
+                    name: phase.name.clone(),
+                    invocation_id: phase.invocation.clone(),
+                    command: invocation.command.clone(),
+                    timeout: invocation.agent_timeout(),
+                    retry: invocation.retry.clone(),
+                    prompt: phase.prompt.clone(),
+                    on_failure: phase
+                        .on_failure
+                        .unwrap_or_else(|| config.on_task_error.into()),
+                })
+            }
+        };
+        resolved.push(phase);
+    }
+    Ok(resolved)
+}
+
 fn emit_unknown_key_warnings(keys: &[String]) {
     for key in keys {
         eprintln!("Warning: Unknown config key: {}", key);
@@ -238,10 +1121,28 @@ fn unknown_top_level_keys(mapping: &Mapping) -> Vec<String> {
         "default_profile",
         "profiles",
         "invocations",
+        "backend",
+        "br",
+        "github",
+        "markdown",
+        "openspec",
+        "statuses",
         "commands",
         "hooks",
         "review_loop_limit",
         "log_path",
+        "journal_path",
+        "on_task_error",
+        "watch",
+        "workspaces",
+        "workspace_order",
+        "concurrency",
+        "worktree_root",
+        "log_format",
+        "agent_output_dir",
+        "prompt_format",
+        "prompts",
+        "profile_rules",
     ];
 
     mapping
@@ -251,42 +1152,206 @@ fn unknown_top_level_keys(mapping: &Mapping) -> Vec<String> {
         .collect()
 }
 
+const PROMPT_KEYS: &[&str] = &["trudge", "trudge_review"];
+
+const COMMAND_KEYS: &[&str] = &[
+    "next_task",
+    "task_show",
+    "task_status",
+    "task_update_status",
+    "task_dependencies",
+    "task_profile",
+];
+
+const HOOK_KEYS: &[&str] = &[
+    "on_completed",
+    "on_requires_human",
+    "on_doctor_setup",
+    "on_notification",
+    "on_notification_scope",
+    "on_task_error",
+    "on_skipped",
+];
+
 fn unknown_config_keys(mapping: &Mapping) -> Vec<String> {
     let mut keys = unknown_top_level_keys(mapping);
+    keys.extend(unknown_nested_keys(mapping, "commands", COMMAND_KEYS));
+    keys.extend(unknown_nested_keys(mapping, "hooks", HOOK_KEYS));
+    keys.extend(unknown_nested_keys(mapping, "br", &["label"]));
     keys.extend(unknown_nested_keys(
         mapping,
-        "commands",
-        &[
-            "next_task",
-            "task_show",
-            "task_status",
-            "task_update_status",
-        ],
+        "github",
+        &["repo", "label", "api_url", "token_env"],
     ));
+    keys.extend(unknown_nested_keys(mapping, "markdown", &["path"]));
+    keys.extend(unknown_nested_keys(mapping, "openspec", &["dir"]));
     keys.extend(unknown_nested_keys(
         mapping,
-        "hooks",
-        &[
-            "on_completed",
-            "on_requires_human",
-            "on_doctor_setup",
-            "on_notification",
-            "on_notification_scope",
-        ],
+        "watch",
+        &["poll_interval_seconds", "max_poll_interval_seconds"],
     ));
+    keys.extend(unknown_nested_keys(mapping, "prompts", PROMPT_KEYS));
     keys.extend(unknown_nested_profile_or_invocation_keys(
         mapping,
         "profiles",
-        &["trudge", "trudge_review"],
+        &[
+            "trudge",
+            "trudge_review",
+            "prompts",
+            "phases",
+            "fallback",
+            "fallback_after_failures",
+            "fallback_exit_codes",
+        ],
     ));
+    keys.extend(unknown_profile_prompt_keys(mapping));
+    keys.extend(unknown_profile_phase_keys(mapping));
     keys.extend(unknown_nested_profile_or_invocation_keys(
         mapping,
         "invocations",
-        &["command"],
+        &["command", "timeout", "on_timeout", "retry"],
     ));
+    keys.extend(unknown_invocation_retry_keys(mapping));
+    keys.extend(unknown_workspace_keys(mapping));
+    keys.extend(unknown_profile_rule_keys(mapping));
+    keys
+}
+
+fn unknown_profile_rule_keys(mapping: &Mapping) -> Vec<String> {
+    let allowed = ["label", "profile"];
+    let Some(Value::Sequence(rules)) = mapping.get(Value::String("profile_rules".to_string()))
+    else {
+        return Vec::new();
+    };
+
+    rules
+        .iter()
+        .enumerate()
+        .filter_map(|(index, rule)| match rule {
+            Value::Mapping(rule) => Some((index, rule)),
+            _ => None,
+        })
+        .flat_map(|(index, rule)| {
+            rule.keys()
+                .filter_map(|key| key.as_str())
+                .filter(|key| !allowed.contains(key))
+                .map(move |key| format!("profile_rules[{}].{}", index, key))
+        })
+        .collect()
+}
+
+fn unknown_workspace_keys(mapping: &Mapping) -> Vec<String> {
+    let allowed = ["name", "path", "profile", "commands", "hooks"];
+    let Some(Value::Sequence(workspaces)) = mapping.get(Value::String("workspaces".to_string()))
+    else {
+        return Vec::new();
+    };
+
+    let mut keys = Vec::new();
+    for (index, workspace) in workspaces.iter().enumerate() {
+        let Value::Mapping(workspace) = workspace else {
+            continue;
+        };
+        let prefix = format!("workspaces[{}]", index);
+        keys.extend(
+            workspace
+                .keys()
+                .filter_map(|key| key.as_str())
+                .filter(|key| !allowed.contains(key))
+                .map(|key| format!("{}.{}", prefix, key)),
+        );
+        for (section, section_keys) in [("commands", COMMAND_KEYS), ("hooks", HOOK_KEYS)] {
+            keys.extend(
+                unknown_nested_keys(workspace, section, section_keys)
+                    .into_iter()
+                    .map(|key| format!("{}.{}", prefix, key)),
+            );
+        }
+    }
+    keys
+}
+
+fn unknown_profile_prompt_keys(mapping: &Mapping) -> Vec<String> {
+    let Some(Value::Mapping(profiles)) = mapping.get(Value::String("profiles".to_string())) else {
+        return Vec::new();
+    };
+
+    profiles
+        .iter()
+        .filter_map(|(profile_id, profile)| {
+            let profile_id = profile_id.as_str()?;
+            let Value::Mapping(profile) = profile else {
+                return None;
+            };
+            Some(
+                unknown_nested_keys(profile, "prompts", PROMPT_KEYS)
+                    .into_iter()
+                    .map(move |key| format!("profiles.{}.{}", profile_id, key)),
+            )
+        })
+        .flatten()
+        .collect()
+}
+
+fn unknown_profile_phase_keys(mapping: &Mapping) -> Vec<String> {
+    let allowed = ["name", "invocation", "prompt", "on_failure"];
+    let Some(Value::Mapping(profiles)) = mapping.get(Value::String("profiles".to_string())) else {
+        return Vec::new();
+    };
+
+    let mut keys = Vec::new();
+    for (profile_id, profile) in profiles {
+        let (Some(profile_id), Value::Mapping(profile)) = (profile_id.as_str(), profile) else {
+            continue;
+        };
+        let Some(Value::Sequence(phases)) = profile.get(Value::String("phases".to_string())) else {
+            continue;
+        };
+        for (index, phase) in phases.iter().enumerate() {
+            let Value::Mapping(phase) = phase else {
+                continue;
+            };
+            keys.extend(
+                phase
+                    .keys()
+                    .filter_map(|key| key.as_str())
+                    .filter(|key| !allowed.contains(key))
+                    .map(|key| format!("profiles.{}.phases[{}].{}", profile_id, index, key)),
+            );
+        }
+    }
     keys
 }
 
+fn unknown_invocation_retry_keys(mapping: &Mapping) -> Vec<String> {
+    let allowed = [
+        "max_attempts",
+        "backoff_seconds",
+        "max_backoff_seconds",
+        "exit_codes",
+    ];
+    let invocations_key = Value::String("invocations".to_string());
+    let Some(Value::Mapping(invocations)) = mapping.get(&invocations_key) else {
+        return Vec::new();
+    };
+
+    invocations
+        .iter()
+        .filter_map(|(invocation_id, invocation)| {
+            let invocation_id = invocation_id.as_str()?;
+            let Value::Mapping(invocation) = invocation else {
+                return None;
+            };
+            Some(
+                unknown_nested_keys(invocation, "retry", &allowed)
+                    .into_iter()
+                    .map(move |key| format!("invocations.{}.{}", invocation_id, key)),
+            )
+        })
+        .flatten()
+        .collect()
+}
+
 #[allow(dead_code)]
 fn unknown_nested_profile_or_invocation_keys_legacy(
     mapping: &Mapping,
@@ -397,6 +1462,21 @@ fn validate_required_fields(mapping: &Mapping) -> Result<(), String> {
             "command",
             &format!("invocations.{}.command", invocation_id),
         )?;
+        validate_optional_timeout_seconds(
+            invocation,
+            "timeout",
+            &format!("invocations.{}.timeout", invocation_id),
+        )?;
+        validate_optional_timeout_action(
+            invocation,
+            "on_timeout",
+            &format!("invocations.{}.on_timeout", invocation_id),
+        )?;
+        validate_optional_retry_policy(
+            invocation,
+            "retry",
+            &format!("invocations.{}.retry", invocation_id),
+        )?;
     }
 
     for (profile_id, profile) in profiles {
@@ -418,6 +1498,9 @@ fn validate_required_fields(mapping: &Mapping) -> Result<(), String> {
             "trudge_review",
             &format!("profiles.{}.trudge_review", profile_id),
         )?;
+        validate_optional_prompts(profile, &format!("profiles.{}.prompts", profile_id))?;
+        validate_optional_phases(profile, profile_id, invocations)?;
+        validate_optional_fallback(profile, profile_id, profiles)?;
 
         if !invocations.contains_key(Value::String(trudge.clone())) {
             return Err(format!(
@@ -442,15 +1525,30 @@ fn validate_required_fields(mapping: &Mapping) -> Result<(), String> {
 
     require_non_null(mapping, "review_loop_limit", "review_loop_limit")?;
     validate_optional_string(mapping, "log_path", "log_path")?;
+    validate_optional_string(mapping, "journal_path", "journal_path")?;
+    validate_optional_log_format(mapping)?;
+    validate_optional_string(mapping, "agent_output_dir", "agent_output_dir")?;
+    validate_optional_prompt_format(mapping)?;
+    validate_optional_prompts(mapping, "prompts")?;
+    validate_optional_task_error_action(mapping, "on_task_error", "on_task_error")?;
+    validate_optional_watch(mapping)?;
+    validate_optional_workspace_order(mapping)?;
+    validate_optional_workspaces(mapping, profiles)?;
+    validate_optional_profile_rules(mapping, profiles)?;
+    validate_optional_statuses(mapping)?;
+    validate_optional_concurrency(mapping)?;
+    validate_optional_string(mapping, "worktree_root", "worktree_root")?;
 
-    let commands = require_mapping(mapping, "commands", "commands")?;
-    let _ = require_non_empty_string(commands, "task_show", "commands.task_show")?;
-    let _ = require_non_empty_string(commands, "task_status", "commands.task_status")?;
-    let _ = require_non_empty_string(
-        commands,
-        "task_update_status",
-        "commands.task_update_status",
-    )?;
+    if validate_optional_backend(mapping)? == Backend::Shell {
+        let commands = require_mapping(mapping, "commands", "commands")?;
+        let _ = require_non_empty_string(commands, "task_show", "commands.task_show")?;
+        let _ = require_non_empty_string(commands, "task_status", "commands.task_status")?;
+        let _ = require_non_empty_string(
+            commands,
+            "task_update_status",
+            "commands.task_update_status",
+        )?;
+    }
 
     let hooks = require_mapping(mapping, "hooks", "hooks")?;
     let _ = require_non_empty_string(hooks, "on_completed", "hooks.on_completed")?;
@@ -462,6 +1560,8 @@ fn validate_required_fields(mapping: &Mapping) -> Result<(), String> {
         "on_notification_scope",
         "hooks.on_notification_scope",
     )?;
+    validate_optional_non_empty_string(hooks, "on_task_error", "hooks.on_task_error")?;
+    validate_optional_non_empty_string(hooks, "on_skipped", "hooks.on_skipped")?;
 
     Ok(())
 }
@@ -484,6 +1584,32 @@ fn notification_scope_without_hook_warning(mapping: &Mapping) -> Option<String>
     None
 }
 
+fn on_timeout_without_timeout_warnings(mapping: &Mapping) -> Vec<String> {
+    let invocations_key = Value::String("invocations".to_string());
+    let Some(Value::Mapping(invocations)) = mapping.get(&invocations_key) else {
+        return Vec::new();
+    };
+
+    let timeout_key = Value::String("timeout".to_string());
+    let on_timeout_key = Value::String("on_timeout".to_string());
+    invocations
+        .iter()
+        .filter_map(|(invocation_id, invocation)| {
+            let invocation_id = invocation_id.as_str()?;
+            let Value::Mapping(invocation) = invocation else {
+                return None;
+            };
+            if invocation.contains_key(&on_timeout_key) && !invocation.contains_key(&timeout_key) {
+                return Some(format!(
+                    "invocations.{}.on_timeout is ignored because invocations.{}.timeout is not configured.",
+                    invocation_id, invocation_id
+                ));
+            }
+            None
+        })
+        .collect()
+}
+
 fn reject_deprecated_keys(mapping: &Mapping) -> Result<(), String> {
     let legacy_keys = ["agent_command", "agent_review_command", "codex_command"];
     for key in legacy_keys {
@@ -599,93 +1725,693 @@ fn validate_optional_notification_scope(
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use serde::de::IntoDeserializer;
-    use std::fs;
-    use tempfile::NamedTempFile;
-    use tempfile::TempDir;
+fn validate_optional_timeout_seconds(
+    mapping: &Mapping,
+    key_name: &str,
+    label: &str,
+) -> Result<(), String> {
+    let key = Value::String(key_name.to_string());
+    match mapping.get(&key) {
+        None => Ok(()),
+        Some(Value::Null) => Err(format!("{} must not be null", label)),
+        Some(Value::Number(value)) if value.as_u64().is_some_and(|value| value > 0) => Ok(()),
+        Some(_) => Err(format!(
+            "{} must be a positive integer number of seconds",
+            label
+        )),
+    }
+}
 
-    fn write_temp_config(contents: &str) -> NamedTempFile {
-        let file = NamedTempFile::new().expect("create temp file");
-        fs::write(file.path(), contents).expect("write temp config");
-        file
+fn validate_optional_retry_policy(
+    mapping: &Mapping,
+    key_name: &str,
+    label: &str,
+) -> Result<(), String> {
+    let key = Value::String(key_name.to_string());
+    let retry = match mapping.get(&key) {
+        None => return Ok(()),
+        Some(Value::Null) => return Err(format!("{} must not be null", label)),
+        Some(Value::Mapping(retry)) => retry,
+        Some(_) => return Err(format!("{} must be a mapping", label)),
+    };
+
+    let max_attempts_label = format!("{}.max_attempts", label);
+    match retry.get(Value::String("max_attempts".to_string())) {
+        None => {
+            return Err(format!(
+                "Missing required config value: {}",
+                max_attempts_label
+            ))
+        }
+        Some(Value::Number(value)) if value.as_u64().is_some_and(|value| value > 0) => {}
+        Some(_) => return Err(format!("{} must be a positive integer", max_attempts_label)),
     }
 
-    #[test]
-    fn config_must_be_yaml_mapping() {
-        let file = write_temp_config("[]");
-        let err = load_config(file.path()).expect_err("expected mapping error");
-        assert!(err.contains("must be a YAML mapping"));
+    for seconds_key in ["backoff_seconds", "max_backoff_seconds"] {
+        match retry.get(Value::String(seconds_key.to_string())) {
+            None => {}
+            Some(Value::Number(value)) if value.as_u64().is_some() => {}
+            Some(_) => {
+                return Err(format!(
+                    "{}.{} must be a non-negative integer number of seconds",
+                    label, seconds_key
+                ))
+            }
+        }
     }
 
-    #[test]
-    fn deserialize_log_path_rejects_non_string_values() {
-        let value: serde_yaml::Value = serde_yaml::from_str("123").expect("value");
-        let err = deserialize_log_path(value.into_deserializer()).expect_err("expected error");
-        assert!(err.to_string().contains("string"));
+    let exit_codes_label = format!("{}.exit_codes", label);
+    match retry.get(Value::String("exit_codes".to_string())) {
+        None => {}
+        Some(Value::Sequence(codes)) => {
+            let valid = codes.iter().all(|code| {
+                code.as_i64()
+                    .and_then(|code| i32::try_from(code).ok())
+                    .is_some_and(|code| code != 0)
+            });
+            if !valid {
+                return Err(format!(
+                    "{} must only contain non-zero integer exit codes",
+                    exit_codes_label
+                ));
+            }
+        }
+        Some(_) => return Err(format!("{} must be a list", exit_codes_label)),
     }
 
-    #[test]
-    fn deserialize_log_path_error_path_is_covered_for_config_deserializer() {
-        // Exercise the `String::deserialize(..)?` error path for the specific deserializer
-        // instantiation used by `load_config_from_str` (via `serde_path_to_error`).
-        let config = r#"
-default_profile: codex
-profiles:
-  codex:
-    trudge: codex
-    trudge_review: codex-review
-invocations:
-  codex:
-    command: "agent"
-  codex-review:
-    command: "review"
-commands:
-  next_task: "next"
-  task_show: "show"
-  task_status: "status"
-  task_update_status: "update"
-review_loop_limit: 3
-log_path: [123]
-hooks:
-  on_completed: "done"
-  on_requires_human: "human"
-"#;
+    Ok(())
+}
 
-        let deserializer = serde_yaml::Deserializer::from_str(config);
-        let err = serde_path_to_error::deserialize::<_, ParsedConfig>(deserializer)
-            .expect_err("expected log_path deserialization error");
-        assert!(err.to_string().contains("log_path"));
+fn validate_optional_watch(mapping: &Mapping) -> Result<(), String> {
+    let watch = match mapping.get(Value::String("watch".to_string())) {
+        None => return Ok(()),
+        Some(Value::Null) => return Err("watch must not be null".to_string()),
+        Some(Value::Mapping(watch)) => watch,
+        Some(_) => return Err("watch must be a mapping".to_string()),
+    };
+
+    let mut seconds = Vec::new();
+    for seconds_key in ["poll_interval_seconds", "max_poll_interval_seconds"] {
+        match watch.get(Value::String(seconds_key.to_string())) {
+            None => seconds.push(None),
+            Some(Value::Number(value)) if value.as_u64().is_some_and(|value| value > 0) => {
+                seconds.push(value.as_u64());
+            }
+            Some(_) => {
+                return Err(format!(
+                    "watch.{} must be a positive integer number of seconds",
+                    seconds_key
+                ))
+            }
+        }
+    }
+    if let [Some(poll), Some(max_poll)] = seconds[..] {
+        if max_poll < poll {
+            return Err(
+                "watch.max_poll_interval_seconds must not be less than watch.poll_interval_seconds"
+                    .to_string(),
+            );
+        }
     }
 
-    #[test]
-    fn missing_required_profile_schema_keys() {
-        let config = r#"
-default_profile: codex
-commands:
-  next_task: "next"
-  task_show: "show"
-  task_status: "status"
-  task_update_status: "update"
-review_loop_limit: 3
-hooks:
-  on_completed: "done"
-  on_requires_human: "human"
-"#;
-        let file = write_temp_config(config);
-        let err = load_config(file.path()).expect_err("expected missing required profile");
-        assert!(err.contains("profiles"));
+    Ok(())
+}
 
-        let config = r#"
-default_profile: codex
-commands:
-  next_task: "next"
-  task_show: "show"
-  task_status: "status"
-  task_update_status: "update"
-review_loop_limit: 3
+/// Validates `backend` and the per-backend sections, returning the configured backend. Every
+/// backend other than `shell` replaces the `commands` section, so setting both (top-level or per workspace) is
+/// rejected.
+fn validate_optional_backend(mapping: &Mapping) -> Result<Backend, String> {
+    let backend = match mapping.get(Value::String("backend".to_string())) {
+        None => Backend::Shell,
+        Some(Value::Null) => return Err("backend must not be null".to_string()),
+        Some(Value::String(value)) if value == "shell" => Backend::Shell,
+        Some(Value::String(value)) if value == "br" => Backend::Br,
+        Some(Value::String(value)) if value == "github" => Backend::Github,
+        Some(Value::String(value)) if value == "markdown" => Backend::Markdown,
+        Some(Value::String(value)) if value == "openspec" => Backend::Openspec,
+        Some(_) => {
+            return Err("backend must be one of shell|br|github|markdown|openspec".to_string());
+        }
+    };
+
+    match mapping.get(Value::String("br".to_string())) {
+        None => {}
+        Some(Value::Mapping(br)) => {
+            validate_optional_non_empty_string(br, "label", "br.label")?;
+        }
+        Some(Value::Null) => return Err("br must not be null".to_string()),
+        Some(_) => return Err("br must be a mapping".to_string()),
+    }
+
+    match mapping.get(Value::String("github".to_string())) {
+        None if backend == Backend::Github => {
+            return Err("github.repo is required with backend: github".to_string());
+        }
+        None => {}
+        Some(Value::Mapping(github)) => {
+            match github.get(Value::String("repo".to_string())) {
+                None if backend == Backend::Github => {
+                    return Err("github.repo is required with backend: github".to_string());
+                }
+                None => {}
+                Some(Value::String(repo)) if is_github_repo(repo) => {}
+                Some(_) => return Err("github.repo must be owner/name".to_string()),
+            }
+            validate_optional_non_empty_string(github, "label", "github.label")?;
+            validate_optional_non_empty_string(github, "api_url", "github.api_url")?;
+            validate_optional_non_empty_string(github, "token_env", "github.token_env")?;
+        }
+        Some(Value::Null) => return Err("github must not be null".to_string()),
+        Some(_) => return Err("github must be a mapping".to_string()),
+    }
+
+    match mapping.get(Value::String("markdown".to_string())) {
+        None => {}
+        Some(Value::Mapping(markdown)) => {
+            validate_optional_non_empty_string(markdown, "path", "markdown.path")?;
+        }
+        Some(Value::Null) => return Err("markdown must not be null".to_string()),
+        Some(_) => return Err("markdown must be a mapping".to_string()),
+    }
+
+    match mapping.get(Value::String("openspec".to_string())) {
+        None => {}
+        Some(Value::Mapping(openspec)) => {
+            validate_optional_non_empty_string(openspec, "dir", "openspec.dir")?;
+        }
+        Some(Value::Null) => return Err("openspec must not be null".to_string()),
+        Some(_) => return Err("openspec must be a mapping".to_string()),
+    }
+
+    if backend != Backend::Shell {
+        let commands_key = Value::String("commands".to_string());
+        for key in ["commands", "statuses"] {
+            if mapping.contains_key(Value::String(key.to_string())) {
+                return Err(format!(
+                    "{} is not used with backend: {}; remove it.",
+                    key,
+                    backend.as_str()
+                ));
+            }
+        }
+        if let Some(Value::Sequence(workspaces)) =
+            mapping.get(Value::String("workspaces".to_string()))
+        {
+            for workspace in workspaces {
+                let Value::Mapping(workspace) = workspace else {
+                    continue;
+                };
+                if workspace.contains_key(&commands_key) {
+                    let name = workspace
+                        .get(Value::String("name".to_string()))
+                        .and_then(Value::as_str)
+                        .unwrap_or("?");
+                    return Err(format!(
+                        "workspaces.{}.commands is not used with backend: {}.",
+                        name,
+                        backend.as_str()
+                    ));
+                }
+            }
+        }
+    }
+
+    Ok(backend)
+}
+
+fn is_github_repo(value: &str) -> bool {
+    let mut parts = value.split('/');
+    matches!(
+        (parts.next(), parts.next(), parts.next()),
+        (Some(owner), Some(name), None) if !owner.trim().is_empty() && !name.trim().is_empty()
+    )
+}
+
+fn validate_optional_statuses(mapping: &Mapping) -> Result<(), String> {
+    let statuses = match mapping.get(Value::String("statuses".to_string())) {
+        None => return Ok(()),
+        Some(Value::Null) => return Err("statuses must not be null".to_string()),
+        Some(Value::Mapping(statuses)) => statuses,
+        Some(_) => return Err("statuses must be a mapping".to_string()),
+    };
+    for (token, state) in statuses {
+        let Some(token) = token.as_str().filter(|token| !token.trim().is_empty()) else {
+            return Err("statuses keys must be non-empty strings".to_string());
+        };
+        if token.split_whitespace().count() != 1 {
+            return Err(format!("statuses.{} must be a single word", token));
+        }
+        if state.as_str().and_then(status_state).is_none() {
+            return Err(format!(
+                "statuses.{} must be one of {}",
+                token, STATUS_STATES
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn validate_optional_log_format(mapping: &Mapping) -> Result<(), String> {
+    let allowed = ["text", "jsonl"];
+    match mapping.get(Value::String("log_format".to_string())) {
+        None => Ok(()),
+        Some(Value::Null) => Err("log_format must not be null".to_string()),
+        Some(Value::String(value)) if allowed.contains(&value.as_str()) => Ok(()),
+        Some(_) => Err("log_format must be one of text|jsonl".to_string()),
+    }
+}
+
+fn validate_optional_prompts(mapping: &Mapping, label: &str) -> Result<(), String> {
+    let prompts = match mapping.get(Value::String("prompts".to_string())) {
+        None => return Ok(()),
+        Some(Value::Mapping(prompts)) => prompts,
+        Some(_) => return Err(format!("{} must be a mapping", label)),
+    };
+    for phase in PROMPT_KEYS {
+        validate_optional_non_empty_string(prompts, phase, &format!("{}.{}", label, phase))?;
+    }
+    Ok(())
+}
+
+fn validate_optional_fallback(
+    profile: &Mapping,
+    profile_id: &str,
+    profiles: &Mapping,
+) -> Result<(), String> {
+    let label = format!("profiles.{}.fallback", profile_id);
+    match profile.get(Value::String("fallback".to_string())) {
+        None => {}
+        Some(Value::Sequence(names)) => {
+            let mut seen = Vec::new();
+            for name in names {
+                let Some(name) = name.as_str().filter(|name| !name.trim().is_empty()) else {
+                    return Err(format!("{} must be a list of profile names", label));
+                };
+                if name == profile_id {
+                    return Err(format!("{} must not list the profile itself", label));
+                }
+                if !profiles.contains_key(Value::String(name.to_string())) {
+                    return Err(format!("{} references missing profile: {}", label, name));
+                }
+                if seen.contains(&name) {
+                    return Err(format!("{} lists {} more than once", label, name));
+                }
+                seen.push(name);
+            }
+        }
+        Some(_) => return Err(format!("{} must be a list of profile names", label)),
+    }
+
+    match profile.get(Value::String("fallback_after_failures".to_string())) {
+        None => {}
+        Some(Value::Number(value)) if value.as_u64().is_some_and(|value| value > 0) => {}
+        Some(_) => {
+            return Err(format!(
+                "{}_after_failures must be a positive integer",
+                label
+            ))
+        }
+    }
+
+    match profile.get(Value::String("fallback_exit_codes".to_string())) {
+        None => Ok(()),
+        Some(Value::Sequence(codes)) => {
+            let valid = codes.iter().all(|code| {
+                code.as_i64()
+                    .and_then(|code| i32::try_from(code).ok())
+                    .is_some_and(|code| code != 0)
+            });
+            if valid {
+                Ok(())
+            } else {
+                Err(format!(
+                    "{}_exit_codes must only contain non-zero integer exit codes",
+                    label
+                ))
+            }
+        }
+        Some(_) => Err(format!("{}_exit_codes must be a list", label)),
+    }
+}
+
+/// Custom phase names that would be confused with the built-in phases or their log labels.
+const RESERVED_PHASE_NAMES: &[&str] = &[
+    "trudge",
+    "trudge_review",
+    "solve",
+    "review",
+    "solving",
+    "reviewing",
+];
+
+fn validate_optional_phases(
+    profile: &Mapping,
+    profile_id: &str,
+    invocations: &Mapping,
+) -> Result<(), String> {
+    let label = format!("profiles.{}.phases", profile_id);
+    let phases = match profile.get(Value::String("phases".to_string())) {
+        None => return Ok(()),
+        Some(Value::Sequence(phases)) => phases,
+        Some(_) => return Err(format!("{} must be a list", label)),
+    };
+
+    let mut names: Vec<String> = Vec::new();
+    for (index, phase) in phases.iter().enumerate() {
+        let entry_label = format!("{}[{}]", label, index);
+        let name = match phase {
+            Value::String(name) if name == "trudge" || name == "trudge_review" => name.clone(),
+            Value::Mapping(phase) => {
+                let name =
+                    require_non_empty_string(phase, "name", &format!("{}.name", entry_label))?;
+                if RESERVED_PHASE_NAMES.contains(&name.as_str()) {
+                    return Err(format!(
+                        "{}.name {} is reserved for the built-in phases",
+                        entry_label, name
+                    ));
+                }
+                if !name
+                    .chars()
+                    .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+                {
+                    return Err(format!(
+                        "{}.name must use only lowercase letters, digits, and _ (got {})",
+                        entry_label, name
+                    ));
+                }
+                let invocation = require_non_empty_string(
+                    phase,
+                    "invocation",
+                    &format!("{}.invocation", entry_label),
+                )?;
+                if !invocations.contains_key(Value::String(invocation.clone())) {
+                    return Err(format!(
+                        "{}.invocation references missing invocation: {}",
+                        entry_label, invocation
+                    ));
+                }
+                validate_optional_non_empty_string(
+                    phase,
+                    "prompt",
+                    &format!("{}.prompt", entry_label),
+                )?;
+                validate_optional_phase_failure_action(
+                    phase,
+                    &format!("{}.on_failure", entry_label),
+                )?;
+                name
+            }
+            _ => {
+                return Err(format!(
+                    "{} must be trudge, trudge_review, or a mapping with name and invocation",
+                    entry_label
+                ))
+            }
+        };
+        if names.contains(&name) {
+            return Err(format!("{} lists {} more than once", label, name));
+        }
+        names.push(name);
+    }
+
+    if !names.iter().any(|name| name == "trudge") {
+        return Err(format!("{} must include trudge", label));
+    }
+    if names.last().map(String::as_str) != Some("trudge_review") {
+        return Err(format!("{} must end with trudge_review", label));
+    }
+    Ok(())
+}
+
+fn validate_optional_phase_failure_action(phase: &Mapping, label: &str) -> Result<(), String> {
+    let allowed = ["escalate", "skip", "quit", "continue", "next_loop"];
+    match phase.get(Value::String("on_failure".to_string())) {
+        None => Ok(()),
+        Some(Value::Null) => Err(format!("{} must not be null", label)),
+        Some(Value::String(value)) if allowed.contains(&value.as_str()) => Ok(()),
+        Some(_) => Err(format!(
+            "{} must be one of escalate|skip|quit|continue|next_loop",
+            label
+        )),
+    }
+}
+
+fn validate_optional_prompt_format(mapping: &Mapping) -> Result<(), String> {
+    let allowed = ["plain", "template"];
+    match mapping.get(Value::String("prompt_format".to_string())) {
+        None => Ok(()),
+        Some(Value::Null) => Err("prompt_format must not be null".to_string()),
+        Some(Value::String(value)) if allowed.contains(&value.as_str()) => Ok(()),
+        Some(_) => Err("prompt_format must be one of plain|template".to_string()),
+    }
+}
+
+fn validate_optional_workspace_order(mapping: &Mapping) -> Result<(), String> {
+    let allowed = ["round_robin", "priority"];
+    match mapping.get(Value::String("workspace_order".to_string())) {
+        None => Ok(()),
+        Some(Value::Null) => Err("workspace_order must not be null".to_string()),
+        Some(Value::String(value)) if allowed.contains(&value.as_str()) => Ok(()),
+        Some(_) => Err("workspace_order must be one of round_robin|priority".to_string()),
+    }
+}
+
+fn validate_optional_concurrency(mapping: &Mapping) -> Result<(), String> {
+    let concurrency = match mapping.get(Value::String("concurrency".to_string())) {
+        None => return Ok(()),
+        Some(Value::Number(value)) => value.as_u64().filter(|value| *value > 0),
+        Some(_) => None,
+    }
+    .ok_or_else(|| "concurrency must be a positive integer".to_string())?;
+    if concurrency > 1 && mapping.contains_key(Value::String("workspaces".to_string())) {
+        return Err("concurrency above 1 is not supported with workspaces".to_string());
+    }
+    // Workers run in their own worktrees, so each would edit its own copy of the task files.
+    if concurrency > 1 {
+        if let Some(Value::String(backend)) = mapping.get(Value::String("backend".to_string())) {
+            if backend == "markdown" || backend == "openspec" {
+                return Err(format!(
+                    "concurrency above 1 is not supported with backend: {}",
+                    backend
+                ));
+            }
+        }
+    }
+    let task_profile = match mapping.get(Value::String("commands".to_string())) {
+        Some(Value::Mapping(commands)) => {
+            commands.contains_key(Value::String("task_profile".to_string()))
+        }
+        _ => false,
+    };
+    if concurrency > 1
+        && (task_profile || mapping.contains_key(Value::String("profile_rules".to_string())))
+    {
+        return Err(
+            "concurrency above 1 is not supported with profile_rules or commands.task_profile"
+                .to_string(),
+        );
+    }
+    Ok(())
+}
+
+fn validate_optional_profile_rules(mapping: &Mapping, profiles: &Mapping) -> Result<(), String> {
+    let rules = match mapping.get(Value::String("profile_rules".to_string())) {
+        None => return Ok(()),
+        Some(Value::Sequence(rules)) => rules,
+        Some(_) => return Err("profile_rules must be a list".to_string()),
+    };
+    for (index, rule) in rules.iter().enumerate() {
+        let label = format!("profile_rules[{}]", index);
+        let Value::Mapping(rule) = rule else {
+            return Err(format!(
+                "{} must be a mapping with label and profile",
+                label
+            ));
+        };
+        require_non_empty_string(rule, "label", &format!("{}.label", label))?;
+        let profile = require_non_empty_string(rule, "profile", &format!("{}.profile", label))?;
+        if !profiles.contains_key(Value::String(profile.clone())) {
+            return Err(format!(
+                "{}.profile references missing profile: {}",
+                label, profile
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn validate_optional_workspaces(mapping: &Mapping, profiles: &Mapping) -> Result<(), String> {
+    let workspaces = match mapping.get(Value::String("workspaces".to_string())) {
+        None => return Ok(()),
+        Some(Value::Null) => return Err("workspaces must not be null".to_string()),
+        Some(Value::Sequence(workspaces)) => workspaces,
+        Some(_) => return Err("workspaces must be a list".to_string()),
+    };
+    if workspaces.is_empty() {
+        return Err("workspaces must not be empty".to_string());
+    }
+
+    let mut names: Vec<String> = Vec::new();
+    for (index, workspace) in workspaces.iter().enumerate() {
+        let label = format!("workspaces[{}]", index);
+        let Value::Mapping(workspace) = workspace else {
+            return Err(format!("{} must be a mapping", label));
+        };
+
+        let name = require_non_empty_string(workspace, "name", &format!("{}.name", label))?;
+        if names.contains(&name) {
+            return Err(format!("{}.name duplicates workspace: {}", label, name));
+        }
+        names.push(name);
+        let _ = require_non_empty_string(workspace, "path", &format!("{}.path", label))?;
+        validate_optional_non_empty_string(workspace, "profile", &format!("{}.profile", label))?;
+        if let Some(Value::String(profile)) = workspace.get(Value::String("profile".to_string())) {
+            if !profiles.contains_key(Value::String(profile.clone())) {
+                return Err(format!(
+                    "{}.profile references missing profile: {}",
+                    label, profile
+                ));
+            }
+        }
+
+        for section in ["commands", "hooks"] {
+            let section_label = format!("{}.{}", label, section);
+            let overrides = match workspace.get(Value::String(section.to_string())) {
+                None => continue,
+                Some(Value::Mapping(overrides)) => overrides,
+                Some(_) => return Err(format!("{} must be a mapping", section_label)),
+            };
+            for key in overrides.keys().filter_map(Value::as_str) {
+                let key_label = format!("{}.{}", section_label, key);
+                if key == "on_notification_scope" {
+                    validate_optional_notification_scope(overrides, key, &key_label)?;
+                } else if COMMAND_KEYS.contains(&key) || HOOK_KEYS.contains(&key) {
+                    validate_optional_non_empty_string(overrides, key, &key_label)?;
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn validate_optional_task_error_action(
+    mapping: &Mapping,
+    key_name: &str,
+    label: &str,
+) -> Result<(), String> {
+    let key = Value::String(key_name.to_string());
+    let allowed = ["escalate", "skip", "quit"];
+    match mapping.get(&key) {
+        None => Ok(()),
+        Some(Value::Null) => Err(format!("{} must not be null", label)),
+        Some(Value::String(value)) if allowed.contains(&value.as_str()) => Ok(()),
+        Some(_) => Err(format!("{} must be one of escalate|skip|quit", label)),
+    }
+}
+
+fn validate_optional_timeout_action(
+    mapping: &Mapping,
+    key_name: &str,
+    label: &str,
+) -> Result<(), String> {
+    let key = Value::String(key_name.to_string());
+    let allowed = ["retry", "escalate", "quit"];
+    match mapping.get(&key) {
+        None => Ok(()),
+        Some(Value::Null) => Err(format!("{} must not be null", label)),
+        Some(Value::String(value)) if allowed.contains(&value.as_str()) => Ok(()),
+        Some(_) => Err(format!("{} must be one of retry|escalate|quit", label)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::de::IntoDeserializer;
+    use std::fs;
+    use tempfile::NamedTempFile;
+    use tempfile::TempDir;
+
+    fn write_temp_config(contents: &str) -> NamedTempFile {
+        let file = NamedTempFile::new().expect("create temp file");
+        fs::write(file.path(), contents).expect("write temp config");
+        file
+    }
+
+    #[test]
+    fn config_must_be_yaml_mapping() {
+        let file = write_temp_config("[]");
+        let err = load_config(file.path()).expect_err("expected mapping error");
+        assert!(err.contains("must be a YAML mapping"));
+    }
+
+    #[test]
+    fn deserialize_log_path_rejects_non_string_values() {
+        let value: serde_yaml::Value = serde_yaml::from_str("123").expect("value");
+        let err = deserialize_log_path(value.into_deserializer()).expect_err("expected error");
+        assert!(err.to_string().contains("string"));
+    }
+
+    #[test]
+    fn deserialize_log_path_error_path_is_covered_for_config_deserializer() {
+        // Exercise the `String::deserialize(..)?` error path for the specific deserializer
+        // instantiation used by `load_config_from_str` (via `serde_path_to_error`).
+        let config = r#"
+default_profile: codex
+profiles:
+  codex:
+    trudge: codex
+    trudge_review: codex-review
+invocations:
+  codex:
+    command: "agent"
+  codex-review:
+    command: "review"
+commands:
+  next_task: "next"
+  task_show: "show"
+  task_status: "status"
+  task_update_status: "update"
+review_loop_limit: 3
+log_path: [123]
+hooks:
+  on_completed: "done"
+  on_requires_human: "human"
+"#;
+
+        let deserializer = serde_yaml::Deserializer::from_str(config);
+        let err = serde_path_to_error::deserialize::<_, ParsedConfig>(deserializer)
+            .expect_err("expected log_path deserialization error");
+        assert!(err.to_string().contains("log_path"));
+    }
+
+    #[test]
+    fn missing_required_profile_schema_keys() {
+        let config = r#"
+default_profile: codex
+commands:
+  next_task: "next"
+  task_show: "show"
+  task_status: "status"
+  task_update_status: "update"
+review_loop_limit: 3
+hooks:
+  on_completed: "done"
+  on_requires_human: "human"
+"#;
+        let file = write_temp_config(config);
+        let err = load_config(file.path()).expect_err("expected missing required profile");
+        assert!(err.contains("profiles"));
+
+        let config = r#"
+default_profile: codex
+commands:
+  next_task: "next"
+  task_show: "show"
+  task_status: "status"
+  task_update_status: "update"
+review_loop_limit: 3
 log_path: "./log"
 hooks:
   on_completed: "done"
@@ -811,57 +2537,569 @@ invocations:
   review-review:
     command: "review-review"
 commands:
-  next_task: "next-task"
-  task_show: "task-show"
-  task_status: "task-status"
-  task_update_status: "task-update"
+  next_task: "next-task"
+  task_show: "task-show"
+  task_status: "task-status"
+  task_update_status: "task-update"
+review_loop_limit: 3
+hooks:
+  on_completed: "done"
+  on_requires_human: "human"
+"#;
+        let file = write_temp_config(config);
+        let loaded = load_config_with_profile(file.path(), Some("review")).expect("load profile");
+        assert_eq!(loaded.config.agent_command, "agent-review");
+        assert_eq!(loaded.config.agent_review_command, "review-review");
+    }
+
+    #[test]
+    fn load_config_with_unknown_profile_is_rejected() {
+        let config = r#"
+default_profile: fast
+profiles:
+  fast:
+    trudge: fast-agent
+    trudge_review: fast-review
+invocations:
+  fast-agent:
+    command: "agent-fast"
+  fast-review:
+    command: "review-fast"
+commands:
+  next_task: "next-task"
+  task_show: "task-show"
+  task_status: "task-status"
+  task_update_status: "task-update"
+review_loop_limit: 3
+hooks:
+  on_completed: "done"
+  on_requires_human: "human"
+"#;
+        let file = write_temp_config(config);
+        let err = load_config_with_profile(file.path(), Some("missing")).expect_err("load profile");
+        assert!(err.contains("Unknown profile: missing"));
+    }
+
+    #[test]
+    fn profile_missing_invocation_reference_is_rejected() {
+        let config = r#"
+default_profile: codex
+profiles:
+  codex:
+    trudge: codex
+    trudge_review: codex-review-missing
+invocations:
+  codex:
+    command: "agent"
+  codex-review:
+    command: "review"
+commands:
+  next_task: "next"
+  task_show: "show"
+  task_status: "status"
+  task_update_status: "update"
+review_loop_limit: 3
+hooks:
+  on_completed: "done"
+  on_requires_human: "human"
+"#;
+        let file = write_temp_config(config);
+        let err = load_config(file.path()).expect_err("expected missing invocation reference");
+        assert!(err.contains("profiles.codex.trudge_review references missing invocation"));
+    }
+
+    #[test]
+    fn missing_required_mapping_errors() {
+        let config = r#"
+default_profile: codex
+profiles:
+  codex:
+    trudge: codex
+    trudge_review: codex-review
+invocations:
+  codex:
+    command: "agent"
+  codex-review:
+    command: "review"
+review_loop_limit: 3
+hooks:
+  on_completed: "done"
+  on_requires_human: "human"
+"#;
+        let file = write_temp_config(config);
+        let err = load_config(file.path()).expect_err("expected missing commands mapping");
+        assert!(err.contains("commands"));
+
+        let config = r#"
+default_profile: codex
+profiles:
+  codex:
+    trudge: codex
+    trudge_review: codex-review
+invocations:
+  codex:
+    command: "agent"
+  codex-review:
+    command: "review"
+review_loop_limit: 3
+commands:
+  task_show: "show"
+  task_status: "status"
+  task_update_status: "update"
+"#;
+        let file = write_temp_config(config);
+        let err = load_config(file.path()).expect_err("expected missing hooks mapping");
+        assert!(err.contains("hooks"));
+    }
+
+    #[test]
+    fn null_required_value_errors() {
+        let config = r#"
+default_profile: codex
+profiles:
+  codex:
+    trudge: codex
+    trudge_review: codex-review
+invocations:
+  codex:
+    command: null
+  codex-review:
+    command: "review"
+commands:
+  next_task: "next"
+  task_show: "show"
+  task_status: "status"
+  task_update_status: "update"
+review_loop_limit: 3
+log_path: "./log"
+hooks:
+  on_completed: "done"
+  on_requires_human: "human"
+"#;
+        let file = write_temp_config(config);
+        let err = load_config(file.path()).expect_err("expected null invocation command");
+        assert!(err.contains("invocations.codex.command"));
+    }
+
+    #[test]
+    fn empty_and_wrong_type_required_value_errors() {
+        let config = r#"
+default_profile: codex
+profiles:
+  codex:
+    trudge: codex
+    trudge_review: codex-review
+invocations:
+  codex:
+    command: ""
+  codex-review:
+    command: "review"
+commands:
+  next_task: "next"
+  task_show: "show"
+  task_status: "status"
+  task_update_status: "update"
+review_loop_limit: 3
+hooks:
+  on_completed: "done"
+  on_requires_human: "human"
+"#;
+        let file = write_temp_config(config);
+        let err = load_config(file.path()).expect_err("expected empty invocation command");
+        assert!(err.contains("invocations.codex.command"));
+
+        let config = r#"
+default_profile: codex
+profiles:
+  codex:
+    trudge: codex
+    trudge_review: codex-review
+invocations:
+  codex:
+    command: ["agent"]
+  codex-review:
+    command: "review"
+commands:
+  next_task: "next"
+  task_show: "show"
+  task_status: "status"
+  task_update_status: "update"
+review_loop_limit: 3
+hooks:
+  on_completed: "done"
+  on_requires_human: "human"
+"#;
+        let file = write_temp_config(config);
+        let err = load_config(file.path()).expect_err("expected invocation command type error");
+        assert!(err.contains("invocations.codex.command"));
+        assert!(err.contains("string"));
+    }
+
+    #[test]
+    fn missing_review_loop_limit_errors() {
+        let config = r#"
+default_profile: codex
+profiles:
+  codex:
+    trudge: codex
+    trudge_review: codex-review
+invocations:
+  codex:
+    command: "agent"
+  codex-review:
+    command: "review"
+commands:
+  next_task: "next"
+  task_show: "show"
+  task_status: "status"
+  task_update_status: "update"
+hooks:
+  on_completed: "done"
+  on_requires_human: "human"
+"#;
+        let file = write_temp_config(config);
+        let err = load_config(file.path()).expect_err("expected missing review_loop_limit");
+        assert!(err.contains("review_loop_limit"));
+    }
+
+    #[test]
+    fn null_review_loop_limit_errors() {
+        let config = r#"
+default_profile: codex
+profiles:
+  codex:
+    trudge: codex
+    trudge_review: codex-review
+invocations:
+  codex:
+    command: "agent"
+  codex-review:
+    command: "review"
+commands:
+  next_task: "next"
+  task_show: "show"
+  task_status: "status"
+  task_update_status: "update"
+review_loop_limit: null
+log_path: "./log"
+hooks:
+  on_completed: "done"
+  on_requires_human: "human"
+"#;
+        let file = write_temp_config(config);
+        let err = load_config(file.path()).expect_err("expected null review_loop_limit");
+        assert!(err.contains("review_loop_limit"));
+    }
+
+    #[test]
+    fn zero_review_loop_limit_is_rejected_with_actionable_error() {
+        let config = r#"
+default_profile: codex
+profiles:
+  codex:
+    trudge: codex
+    trudge_review: codex-review
+invocations:
+  codex:
+    command: "agent"
+  codex-review:
+    command: "review"
+commands:
+  next_task: "next"
+  task_show: "show"
+  task_status: "status"
+  task_update_status: "update"
+review_loop_limit: 0
+hooks:
+  on_completed: "done"
+  on_requires_human: "human"
+"#;
+        let file = write_temp_config(config);
+        let err = load_config(file.path()).expect_err("expected zero review_loop_limit");
+        assert!(err.contains("review_loop_limit"));
+        assert!(err.contains("positive integer") | err.contains("got 0"));
+        assert!(err.contains(&file.path().display().to_string()));
+    }
+
+    #[test]
+    fn null_commands_mapping_errors() {
+        let config = r#"
+default_profile: codex
+profiles:
+  codex:
+    trudge: codex
+    trudge_review: codex-review
+invocations:
+  codex:
+    command: "agent"
+  codex-review:
+    command: "review"
+commands: null
+review_loop_limit: 3
+log_path: "./log"
+hooks:
+  on_completed: "done"
+  on_requires_human: "human"
+"#;
+        let file = write_temp_config(config);
+        let err = load_config(file.path()).expect_err("expected null commands");
+        assert!(err.contains("commands"));
+    }
+
+    #[test]
+    fn commands_mapping_must_be_mapping() {
+        let config = r#"
+default_profile: codex
+profiles:
+  codex:
+    trudge: codex
+    trudge_review: codex-review
+invocations:
+  codex:
+    command: "agent"
+  codex-review:
+    command: "review"
+commands: "not-a-mapping"
+review_loop_limit: 3
+hooks:
+  on_completed: "done"
+  on_requires_human: "human"
+"#;
+        let file = write_temp_config(config);
+        let err = load_config(file.path()).expect_err("expected commands mapping type error");
+        assert!(err.contains("commands"));
+        assert!(err.contains("mapping"));
+    }
+
+    #[test]
+    fn missing_task_update_status_errors() {
+        let config = r#"
+default_profile: codex
+profiles:
+  codex:
+    trudge: codex
+    trudge_review: codex-review
+invocations:
+  codex:
+    command: "agent"
+  codex-review:
+    command: "review"
+commands:
+  next_task: "next"
+  task_show: "show"
+  task_status: "status"
+review_loop_limit: 3
+hooks:
+  on_completed: "done"
+  on_requires_human: "human"
+"#;
+        let file = write_temp_config(config);
+        let err = load_config(file.path()).expect_err("expected missing task_update_status");
+        assert!(err.contains("commands.task_update_status"));
+    }
+
+    #[test]
+    fn invalid_yaml_includes_path() {
+        let file = write_temp_config("agent_command: [");
+        let err = load_config(file.path()).expect_err("expected parse error");
+        let path = file.path().display().to_string();
+        assert!(err.contains(&path));
+    }
+
+    #[test]
+    fn unknown_keys_reported() {
+        let config = r#"
+default_profile: codex
+profiles:
+  codex:
+    trudge: codex
+    trudge_review: codex-review
+invocations:
+  codex:
+    command: "agent"
+  codex-review:
+    command: "review"
+commands:
+  next_task: "next"
+  task_show: "show"
+  task_status: "status"
+  task_update_status: "update"
+review_loop_limit: 3
+log_path: "./log"
+hooks:
+  on_completed: "done"
+  on_requires_human: "human"
+extra_key: true
+"#;
+        let file = write_temp_config(config);
+        let loaded = load_config(file.path()).expect("config should load");
+        assert_eq!(loaded.warnings, vec!["extra_key".to_string()]);
+    }
+
+    #[test]
+    fn unknown_nested_keys_reported() {
+        let config = r#"
+default_profile: codex
+profiles:
+  codex:
+    trudge: codex
+    trudge_review: codex-review
+    extra_profile_key: "mystery"
+invocations:
+  codex:
+    command: "agent"
+    extra_invocation_key: "mystery"
+  codex-review:
+    command: "review"
+commands:
+  next_task: "next"
+  task_show: "show"
+  task_status: "status"
+  task_update_status: "update"
+  extra_command_key: "mystery"
+review_loop_limit: 3
+hooks:
+  on_completed: "done"
+  on_requires_human: "human"
+  extra_hook_key: "mystery"
+"#;
+        let file = write_temp_config(config);
+        let loaded = load_config(file.path()).expect("config should load");
+        assert_eq!(
+            loaded.warnings,
+            vec![
+                "commands.extra_command_key".to_string(),
+                "hooks.extra_hook_key".to_string(),
+                "profiles.codex.extra_profile_key".to_string(),
+                "invocations.codex.extra_invocation_key".to_string()
+            ]
+        );
+    }
+
+    #[test]
+    fn codex_command_is_rejected() {
+        let config = r#"
+default_profile: codex
+profiles:
+  codex:
+    trudge: codex
+    trudge_review: codex-review
+invocations:
+  codex:
+    command: "agent"
+  codex-review:
+    command: "review"
+codex_command: "codex"
+commands:
+  next_task: "next"
+  task_show: "show"
+  task_status: "status"
+  task_update_status: "update"
+review_loop_limit: 3
+log_path: "./log"
+hooks:
+  on_completed: "done"
+  on_requires_human: "human"
+"#;
+        let file = write_temp_config(config);
+        let err = load_config(file.path()).expect_err("expected codex_command error");
+        assert!(err.contains("codex_command"));
+        assert!(err.contains("default_profile"));
+        assert!(err.contains("profiles"));
+        assert!(err.contains("invocations"));
+        assert!(err.contains("Migration"));
+    }
+
+    #[test]
+    fn optional_doctor_setup_value_errors() {
+        let config = r#"
+default_profile: codex
+profiles:
+  codex:
+    trudge: codex
+    trudge_review: codex-review
+invocations:
+  codex:
+    command: "agent"
+  codex-review:
+    command: "review"
+commands:
+  next_task: "next"
+  task_show: "show"
+  task_status: "status"
+  task_update_status: "update"
+review_loop_limit: 3
+hooks:
+  on_completed: "done"
+  on_requires_human: "human"
+  on_doctor_setup: null
+"#;
+        let file = write_temp_config(config);
+        let err = load_config(file.path()).expect_err("expected null on_doctor_setup error");
+        assert!(err.contains("hooks.on_doctor_setup"));
+
+        let config = r#"
+default_profile: codex
+profiles:
+  codex:
+    trudge: codex
+    trudge_review: codex-review
+invocations:
+  codex:
+    command: "agent"
+  codex-review:
+    command: "review"
+commands:
+  next_task: "next"
+  task_show: "show"
+  task_status: "status"
+  task_update_status: "update"
 review_loop_limit: 3
 hooks:
   on_completed: "done"
   on_requires_human: "human"
+  on_doctor_setup: ""
 "#;
         let file = write_temp_config(config);
-        let loaded = load_config_with_profile(file.path(), Some("review")).expect("load profile");
-        assert_eq!(loaded.config.agent_command, "agent-review");
-        assert_eq!(loaded.config.agent_review_command, "review-review");
-    }
+        let err = load_config(file.path()).expect_err("expected empty on_doctor_setup error");
+        assert!(err.contains("hooks.on_doctor_setup"));
 
-    #[test]
-    fn load_config_with_unknown_profile_is_rejected() {
         let config = r#"
-default_profile: fast
+default_profile: codex
 profiles:
-  fast:
-    trudge: fast-agent
-    trudge_review: fast-review
+  codex:
+    trudge: codex
+    trudge_review: codex-review
 invocations:
-  fast-agent:
-    command: "agent-fast"
-  fast-review:
-    command: "review-fast"
+  codex:
+    command: "agent"
+  codex-review:
+    command: "review"
 commands:
-  next_task: "next-task"
-  task_show: "task-show"
-  task_status: "task-status"
-  task_update_status: "task-update"
+  next_task: "next"
+  task_show: "show"
+  task_status: "status"
+  task_update_status: "update"
 review_loop_limit: 3
 hooks:
   on_completed: "done"
   on_requires_human: "human"
+  on_doctor_setup: 123
 "#;
         let file = write_temp_config(config);
-        let err = load_config_with_profile(file.path(), Some("missing")).expect_err("load profile");
-        assert!(err.contains("Unknown profile: missing"));
+        let err = load_config(file.path()).expect_err("expected non-string on_doctor_setup error");
+        assert!(err.contains("hooks.on_doctor_setup"));
+        assert!(err.contains("string"));
     }
 
     #[test]
-    fn profile_missing_invocation_reference_is_rejected() {
+    fn optional_notification_value_errors() {
         let config = r#"
 default_profile: codex
 profiles:
   codex:
     trudge: codex
-    trudge_review: codex-review-missing
+    trudge_review: codex-review
 invocations:
   codex:
     command: "agent"
@@ -876,14 +3114,12 @@ review_loop_limit: 3
 hooks:
   on_completed: "done"
   on_requires_human: "human"
+  on_notification: null
 "#;
         let file = write_temp_config(config);
-        let err = load_config(file.path()).expect_err("expected missing invocation reference");
-        assert!(err.contains("profiles.codex.trudge_review references missing invocation"));
-    }
+        let err = load_config(file.path()).expect_err("expected null on_notification error");
+        assert!(err.contains("hooks.on_notification"));
 
-    #[test]
-    fn missing_required_mapping_errors() {
         let config = r#"
 default_profile: codex
 profiles:
@@ -895,15 +3131,24 @@ invocations:
     command: "agent"
   codex-review:
     command: "review"
+commands:
+  next_task: "next"
+  task_show: "show"
+  task_status: "status"
+  task_update_status: "update"
 review_loop_limit: 3
 hooks:
   on_completed: "done"
   on_requires_human: "human"
+  on_notification: ""
 "#;
         let file = write_temp_config(config);
-        let err = load_config(file.path()).expect_err("expected missing commands mapping");
-        assert!(err.contains("commands"));
+        let err = load_config(file.path()).expect_err("expected empty on_notification error");
+        assert!(err.contains("hooks.on_notification"));
+    }
 
+    #[test]
+    fn invalid_notification_scope_errors() {
         let config = r#"
 default_profile: codex
 profiles:
@@ -915,19 +3160,27 @@ invocations:
     command: "agent"
   codex-review:
     command: "review"
-review_loop_limit: 3
 commands:
+  next_task: "next"
   task_show: "show"
   task_status: "status"
   task_update_status: "update"
+review_loop_limit: 3
+hooks:
+  on_completed: "done"
+  on_requires_human: "human"
+  on_notification: "notify"
+  on_notification_scope: "bad_scope"
 "#;
         let file = write_temp_config(config);
-        let err = load_config(file.path()).expect_err("expected missing hooks mapping");
-        assert!(err.contains("hooks"));
+        let err =
+            load_config(file.path()).expect_err("expected invalid on_notification_scope error");
+        assert!(err.contains("hooks.on_notification_scope"));
+        assert!(err.contains("all_logs|task_boundaries|run_boundaries"));
     }
 
     #[test]
-    fn null_required_value_errors() {
+    fn notification_scope_defaults_and_scope_without_hook_warns() {
         let config = r#"
 default_profile: codex
 profiles:
@@ -936,7 +3189,7 @@ profiles:
     trudge_review: codex-review
 invocations:
   codex:
-    command: null
+    command: "agent"
   codex-review:
     command: "review"
 commands:
@@ -945,18 +3198,18 @@ commands:
   task_status: "status"
   task_update_status: "update"
 review_loop_limit: 3
-log_path: "./log"
 hooks:
   on_completed: "done"
   on_requires_human: "human"
+  on_notification: "notify"
 "#;
         let file = write_temp_config(config);
-        let err = load_config(file.path()).expect_err("expected null invocation command");
-        assert!(err.contains("invocations.codex.command"));
-    }
+        let loaded = load_config(file.path()).expect("config should load");
+        assert_eq!(
+            loaded.config.hooks.effective_notification_scope(),
+            Some(NotificationScope::TaskBoundaries)
+        );
 
-    #[test]
-    fn empty_and_wrong_type_required_value_errors() {
         let config = r#"
 default_profile: codex
 profiles:
@@ -965,7 +3218,7 @@ profiles:
     trudge_review: codex-review
 invocations:
   codex:
-    command: ""
+    command: "agent"
   codex-review:
     command: "review"
 commands:
@@ -977,11 +3230,21 @@ review_loop_limit: 3
 hooks:
   on_completed: "done"
   on_requires_human: "human"
+  on_notification_scope: "run_boundaries"
 "#;
         let file = write_temp_config(config);
-        let err = load_config(file.path()).expect_err("expected empty invocation command");
-        assert!(err.contains("invocations.codex.command"));
+        let loaded = load_config(file.path()).expect("config should load");
+        let warning = loaded
+            .warnings
+            .iter()
+            .find(|warning| warning.contains("hooks.on_notification_scope"))
+            .expect("scope-without-hook warning");
+        assert!(warning.contains("ignored"));
+        assert_eq!(loaded.config.hooks.effective_notification_scope(), None);
+    }
 
+    #[test]
+    fn optional_log_path_value_errors() {
         let config = r#"
 default_profile: codex
 profiles:
@@ -990,7 +3253,7 @@ profiles:
     trudge_review: codex-review
 invocations:
   codex:
-    command: ["agent"]
+    command: "agent"
   codex-review:
     command: "review"
 commands:
@@ -999,18 +3262,19 @@ commands:
   task_status: "status"
   task_update_status: "update"
 review_loop_limit: 3
+log_path: 123
 hooks:
   on_completed: "done"
   on_requires_human: "human"
 "#;
         let file = write_temp_config(config);
-        let err = load_config(file.path()).expect_err("expected invocation command type error");
-        assert!(err.contains("invocations.codex.command"));
+        let err = load_config(file.path()).expect_err("expected non-string log_path error");
+        assert!(err.contains("log_path"));
         assert!(err.contains("string"));
     }
 
     #[test]
-    fn missing_review_loop_limit_errors() {
+    fn missing_log_path_is_allowed() {
         let config = r#"
 default_profile: codex
 profiles:
@@ -1027,17 +3291,18 @@ commands:
   task_show: "show"
   task_status: "status"
   task_update_status: "update"
+review_loop_limit: 3
 hooks:
   on_completed: "done"
   on_requires_human: "human"
 "#;
         let file = write_temp_config(config);
-        let err = load_config(file.path()).expect_err("expected missing review_loop_limit");
-        assert!(err.contains("review_loop_limit"));
+        let loaded = load_config(file.path()).expect("config should load");
+        assert_eq!(loaded.config.log_path, None);
     }
 
     #[test]
-    fn null_review_loop_limit_errors() {
+    fn empty_log_path_is_allowed() {
         let config = r#"
 default_profile: codex
 profiles:
@@ -1054,19 +3319,19 @@ commands:
   task_show: "show"
   task_status: "status"
   task_update_status: "update"
-review_loop_limit: null
-log_path: "./log"
+review_loop_limit: 3
+log_path: ""
 hooks:
   on_completed: "done"
   on_requires_human: "human"
 "#;
         let file = write_temp_config(config);
-        let err = load_config(file.path()).expect_err("expected null review_loop_limit");
-        assert!(err.contains("review_loop_limit"));
+        let loaded = load_config(file.path()).expect("config should load");
+        assert_eq!(loaded.config.log_path, None);
     }
 
     #[test]
-    fn zero_review_loop_limit_is_rejected_with_actionable_error() {
+    fn null_log_path_errors() {
         let config = r#"
 default_profile: codex
 profiles:
@@ -1083,21 +3348,20 @@ commands:
   task_show: "show"
   task_status: "status"
   task_update_status: "update"
-review_loop_limit: 0
+review_loop_limit: 3
+log_path: null
 hooks:
   on_completed: "done"
   on_requires_human: "human"
 "#;
         let file = write_temp_config(config);
-        let err = load_config(file.path()).expect_err("expected zero review_loop_limit");
-        assert!(err.contains("review_loop_limit"));
-        assert!(err.contains("positive integer") | err.contains("got 0"));
-        assert!(err.contains(&file.path().display().to_string()));
+        let err = load_config(file.path()).expect_err("expected null log_path");
+        assert!(err.contains("log_path"));
     }
 
     #[test]
-    fn null_commands_mapping_errors() {
-        let config = r#"
+    fn journal_path_is_optional_and_empty_disables_it() {
+        let base = r#"
 default_profile: codex
 profiles:
   codex:
@@ -1108,21 +3372,39 @@ invocations:
     command: "agent"
   codex-review:
     command: "review"
-commands: null
+commands:
+  next_task: "next"
+  task_show: "show"
+  task_status: "status"
+  task_update_status: "update"
 review_loop_limit: 3
-log_path: "./log"
 hooks:
   on_completed: "done"
   on_requires_human: "human"
 "#;
-        let file = write_temp_config(config);
-        let err = load_config(file.path()).expect_err("expected null commands");
-        assert!(err.contains("commands"));
+        let loaded = load_config_from_str("test", base).expect("config should load");
+        assert_eq!(loaded.config.journal_path, None);
+        assert!(loaded.warnings.is_empty());
+
+        let configured = format!("{}journal_path: \"./.trudger/journal.json\"\n", base);
+        let loaded = load_config_from_str("test", &configured).expect("config should load");
+        assert_eq!(
+            loaded.config.journal_path,
+            Some(PathBuf::from("./.trudger/journal.json"))
+        );
+
+        let empty = format!("{}journal_path: \"\"\n", base);
+        let loaded = load_config_from_str("test", &empty).expect("config should load");
+        assert_eq!(loaded.config.journal_path, None);
+
+        let null = format!("{}journal_path: null\n", base);
+        let err = load_config_from_str("test", &null).expect_err("expected null journal_path");
+        assert!(err.contains("journal_path must not be null"));
     }
 
     #[test]
-    fn commands_mapping_must_be_mapping() {
-        let config = r#"
+    fn on_task_error_defaults_to_quit_and_accepts_a_dedicated_hook() {
+        let base = r#"
 default_profile: codex
 profiles:
   codex:
@@ -1133,21 +3415,44 @@ invocations:
     command: "agent"
   codex-review:
     command: "review"
-commands: "not-a-mapping"
+commands:
+  next_task: "next"
+  task_show: "show"
+  task_status: "status"
+  task_update_status: "update"
 review_loop_limit: 3
 hooks:
   on_completed: "done"
   on_requires_human: "human"
 "#;
-        let file = write_temp_config(config);
-        let err = load_config(file.path()).expect_err("expected commands mapping type error");
-        assert!(err.contains("commands"));
-        assert!(err.contains("mapping"));
+        let loaded = load_config_from_str("test", base).expect("config should load");
+        assert_eq!(loaded.config.on_task_error, TaskErrorAction::Quit);
+        assert_eq!(loaded.config.hooks.on_task_error, None);
+
+        let configured = format!(
+            "{}  on_task_error: \"task-error\"\non_task_error: skip\n",
+            base
+        );
+        let loaded = load_config_from_str("test", &configured).expect("config should load");
+        assert_eq!(loaded.config.on_task_error, TaskErrorAction::Skip);
+        assert_eq!(
+            loaded.config.hooks.on_task_error.as_deref(),
+            Some("task-error")
+        );
+        assert!(loaded.warnings.is_empty());
+
+        let invalid = format!("{}on_task_error: retry\n", base);
+        let err = load_config_from_str("test", &invalid).expect_err("expected invalid action");
+        assert!(err.contains("on_task_error must be one of escalate|skip|quit"));
+
+        let empty_hook = format!("{}  on_task_error: \"\"\n", base);
+        let err = load_config_from_str("test", &empty_hook).expect_err("expected empty hook");
+        assert!(err.contains("hooks.on_task_error"));
     }
 
     #[test]
-    fn missing_task_update_status_errors() {
-        let config = r#"
+    fn watch_settings_default_and_validate() {
+        let base = r#"
 default_profile: codex
 profiles:
   codex:
@@ -1162,67 +3467,148 @@ commands:
   next_task: "next"
   task_show: "show"
   task_status: "status"
+  task_update_status: "update"
 review_loop_limit: 3
 hooks:
   on_completed: "done"
   on_requires_human: "human"
 "#;
-        let file = write_temp_config(config);
-        let err = load_config(file.path()).expect_err("expected missing task_update_status");
-        assert!(err.contains("commands.task_update_status"));
+        let loaded = load_config_from_str("test", base).expect("config should load");
+        assert_eq!(loaded.config.watch, WatchConfig::default());
+        assert_eq!(loaded.config.watch.poll_interval_seconds.get(), 60);
+        assert_eq!(loaded.config.watch.max_poll_interval_seconds.get(), 600);
+
+        let configured = format!(
+            "{}watch:\n  poll_interval_seconds: 10\n  max_poll_interval_seconds: 45\n",
+            base
+        );
+        let loaded = load_config_from_str("test", &configured).expect("config should load");
+        assert_eq!(loaded.config.watch.poll_interval_seconds.get(), 10);
+        assert_eq!(loaded.config.watch.max_poll_interval_seconds.get(), 45);
+        assert!(loaded.warnings.is_empty());
+
+        let unknown = format!("{}watch:\n  jitter: 3\n", base);
+        let loaded = load_config_from_str("test", &unknown).expect("config should load");
+        assert_eq!(loaded.warnings, vec!["watch.jitter".to_string()]);
+
+        let zero = format!("{}watch:\n  poll_interval_seconds: 0\n", base);
+        let err = load_config_from_str("test", &zero).expect_err("expected zero interval");
+        assert!(err.contains("watch.poll_interval_seconds must be a positive integer"));
+
+        let inverted = format!(
+            "{}watch:\n  poll_interval_seconds: 30\n  max_poll_interval_seconds: 10\n",
+            base
+        );
+        let err = load_config_from_str("test", &inverted).expect_err("expected inverted bounds");
+        assert!(err.contains("must not be less than watch.poll_interval_seconds"));
+
+        let null = format!("{}watch: null\n", base);
+        let err = load_config_from_str("test", &null).expect_err("expected null watch");
+        assert!(err.contains("watch must not be null"));
     }
 
     #[test]
-    fn invalid_yaml_includes_path() {
-        let file = write_temp_config("agent_command: [");
-        let err = load_config(file.path()).expect_err("expected parse error");
-        let path = file.path().display().to_string();
-        assert!(err.contains(&path));
+    fn watch_poll_delay_doubles_up_to_cap() {
+        let watch = WatchConfig {
+            poll_interval_seconds: NonZeroU64::new(10).expect("non-zero"),
+            max_poll_interval_seconds: NonZeroU64::new(45).expect("non-zero"),
+        };
+        assert_eq!(watch.poll_delay(0), Duration::from_secs(10));
+        assert_eq!(watch.poll_delay(1), Duration::from_secs(20));
+        assert_eq!(watch.poll_delay(2), Duration::from_secs(40));
+        assert_eq!(watch.poll_delay(3), Duration::from_secs(45));
+        assert_eq!(watch.poll_delay(u64::MAX), Duration::from_secs(45));
+
+        // A cap below the interval never shortens the base delay.
+        let watch = WatchConfig {
+            poll_interval_seconds: NonZeroU64::new(900).expect("non-zero"),
+            ..WatchConfig::default()
+        };
+        assert_eq!(watch.poll_delay(4), Duration::from_secs(900));
     }
 
     #[test]
-    fn unknown_keys_reported() {
-        let config = r#"
+    fn workspaces_resolve_overrides_and_profiles() {
+        let content = r#"
 default_profile: codex
 profiles:
   codex:
     trudge: codex
     trudge_review: codex-review
+  z.ai:
+    trudge: z.ai
+    trudge_review: z.ai
 invocations:
   codex:
     command: "agent"
   codex-review:
     command: "review"
+  z.ai:
+    command: "pi_trudge"
+    timeout: 30
 commands:
   next_task: "next"
   task_show: "show"
   task_status: "status"
   task_update_status: "update"
 review_loop_limit: 3
-log_path: "./log"
 hooks:
   on_completed: "done"
   on_requires_human: "human"
-extra_key: true
+workspace_order: priority
+workspaces:
+  - name: api
+    path: "./api"
+  - name: web
+    path: "/src/web"
+    profile: z.ai
+    commands:
+      next_task: "web-next"
+    hooks:
+      on_completed: "web-done"
 "#;
-        let file = write_temp_config(config);
-        let loaded = load_config(file.path()).expect("config should load");
-        assert_eq!(loaded.warnings, vec!["extra_key".to_string()]);
+        let loaded = load_config_from_str("test", content).expect("config should load");
+        assert!(loaded.warnings.is_empty());
+        let config = loaded.config;
+        assert_eq!(config.workspace_order, WorkspaceOrder::Priority);
+        assert_eq!(config.workspaces.len(), 2);
+
+        let api = &config.workspaces[0];
+        assert_eq!(api.name, "api");
+        assert_eq!(api.path, PathBuf::from("./api"));
+        assert_eq!(api.profile, "codex");
+        assert_eq!(api.config.agent_command, "agent");
+        assert_eq!(api.config.commands.next_task.as_deref(), Some("next"));
+        assert!(api.config.workspaces.is_empty());
+
+        let web = &config.workspaces[1];
+        assert_eq!(web.profile, "z.ai");
+        assert_eq!(web.solve_invocation_id, "z.ai");
+        assert_eq!(web.config.agent_command, "pi_trudge");
+        assert_eq!(
+            web.config
+                .agent_timeout
+                .map(|timeout| timeout.seconds.get()),
+            Some(30)
+        );
+        assert_eq!(web.config.commands.next_task.as_deref(), Some("web-next"));
+        assert_eq!(web.config.commands.task_show, "show");
+        assert_eq!(web.config.hooks.on_completed, "web-done");
+        assert_eq!(web.config.hooks.on_requires_human, "human");
+        assert_eq!(web.config.review_loop_limit.get(), 3);
     }
 
     #[test]
-    fn unknown_nested_keys_reported() {
-        let config = r#"
+    fn workspaces_are_validated() {
+        let base = r#"
 default_profile: codex
 profiles:
   codex:
     trudge: codex
     trudge_review: codex-review
-    extra_profile_key: "mystery"
 invocations:
   codex:
     command: "agent"
-    extra_invocation_key: "mystery"
   codex-review:
     command: "review"
 commands:
@@ -1230,29 +3616,64 @@ commands:
   task_show: "show"
   task_status: "status"
   task_update_status: "update"
-  extra_command_key: "mystery"
 review_loop_limit: 3
 hooks:
   on_completed: "done"
   on_requires_human: "human"
-  extra_hook_key: "mystery"
 "#;
-        let file = write_temp_config(config);
-        let loaded = load_config(file.path()).expect("config should load");
+        let cases = [
+            ("workspaces: []\n", "workspaces must not be empty"),
+            ("workspaces: api\n", "workspaces must be a list"),
+            (
+                "workspaces:\n  - path: ./api\n",
+                "Missing required config value: workspaces[0].name",
+            ),
+            (
+                "workspaces:\n  - name: api\n",
+                "Missing required config value: workspaces[0].path",
+            ),
+            (
+                "workspaces:\n  - name: api\n    path: ./a\n  - name: api\n    path: ./b\n",
+                "workspaces[1].name duplicates workspace: api",
+            ),
+            (
+                "workspaces:\n  - name: api\n    path: ./api\n    profile: missing\n",
+                "workspaces[0].profile references missing profile: missing",
+            ),
+            (
+                "workspaces:\n  - name: api\n    path: ./api\n    commands: next\n",
+                "workspaces[0].commands must be a mapping",
+            ),
+            (
+                "workspaces:\n  - name: api\n    path: ./api\n    hooks:\n      on_completed: \"\"\n",
+                "workspaces[0].hooks.on_completed",
+            ),
+            ("workspace_order: random\n", "workspace_order must be one of round_robin|priority"),
+        ];
+        for (extra, expected) in cases {
+            let content = format!("{}{}", base, extra);
+            let err = load_config_from_str("test", &content)
+                .expect_err(&format!("expected error for {extra:?}"));
+            assert!(err.contains(expected), "expected {expected:?}, got: {err}");
+        }
+
+        let unknown = format!(
+            "{}workspaces:\n  - name: api\n    path: ./api\n    branch: main\n    commands:\n      next: x\n",
+            base
+        );
+        let loaded = load_config_from_str("test", &unknown).expect("config should load");
         assert_eq!(
             loaded.warnings,
             vec![
-                "commands.extra_command_key".to_string(),
-                "hooks.extra_hook_key".to_string(),
-                "profiles.codex.extra_profile_key".to_string(),
-                "invocations.codex.extra_invocation_key".to_string()
+                "workspaces[0].branch".to_string(),
+                "workspaces[0].commands.next".to_string(),
             ]
         );
     }
 
     #[test]
-    fn codex_command_is_rejected() {
-        let config = r#"
+    fn concurrency_and_log_format_default_and_are_validated() {
+        let base = r#"
 default_profile: codex
 profiles:
   codex:
@@ -1263,30 +3684,61 @@ invocations:
     command: "agent"
   codex-review:
     command: "review"
-codex_command: "codex"
 commands:
   next_task: "next"
   task_show: "show"
   task_status: "status"
   task_update_status: "update"
 review_loop_limit: 3
-log_path: "./log"
 hooks:
   on_completed: "done"
   on_requires_human: "human"
 "#;
-        let file = write_temp_config(config);
-        let err = load_config(file.path()).expect_err("expected codex_command error");
-        assert!(err.contains("codex_command"));
-        assert!(err.contains("default_profile"));
-        assert!(err.contains("profiles"));
-        assert!(err.contains("invocations"));
-        assert!(err.contains("Migration"));
+        let loaded = load_config_from_str("test", base).expect("config should load");
+        assert_eq!(loaded.config.concurrency.get(), 1);
+        assert_eq!(loaded.config.worktree_root, None);
+        assert_eq!(loaded.config.log_format, LogFormat::Text);
+        assert_eq!(loaded.config.prompt_format, PromptFormat::Plain);
+
+        let content = format!(
+            "{}concurrency: 3\nworktree_root: ../trees\nlog_format: jsonl\nprompt_format: template\n",
+            base
+        );
+        let loaded = load_config_from_str("test", &content).expect("config should load");
+        assert!(loaded.warnings.is_empty());
+        assert_eq!(loaded.config.concurrency.get(), 3);
+        assert_eq!(loaded.config.worktree_root, Some(PathBuf::from("../trees")));
+        assert_eq!(loaded.config.log_format, LogFormat::Jsonl);
+        assert_eq!(loaded.config.prompt_format, PromptFormat::Template);
+
+        let cases = [
+            ("concurrency: 0\n", "concurrency must be a positive integer"),
+            (
+                "concurrency: two\n",
+                "concurrency must be a positive integer",
+            ),
+            ("worktree_root: null\n", "worktree_root must not be null"),
+            ("log_format: xml\n", "log_format must be one of text|jsonl"),
+            (
+                "prompt_format: jinja\n",
+                "prompt_format must be one of plain|template",
+            ),
+            (
+                "concurrency: 2\nworkspaces:\n  - name: api\n    path: ./api\n",
+                "concurrency above 1 is not supported with workspaces",
+            ),
+        ];
+        for (extra, expected) in cases {
+            let content = format!("{}{}", base, extra);
+            let err = load_config_from_str("test", &content)
+                .expect_err(&format!("expected error for {extra:?}"));
+            assert!(err.contains(expected), "expected {expected:?}, got: {err}");
+        }
     }
 
     #[test]
-    fn optional_doctor_setup_value_errors() {
-        let config = r#"
+    fn backend_br_replaces_commands() {
+        let base = r#"
 default_profile: codex
 profiles:
   codex:
@@ -1297,22 +3749,45 @@ invocations:
     command: "agent"
   codex-review:
     command: "review"
-commands:
-  next_task: "next"
-  task_show: "show"
-  task_status: "status"
-  task_update_status: "update"
 review_loop_limit: 3
 hooks:
   on_completed: "done"
   on_requires_human: "human"
-  on_doctor_setup: null
 "#;
-        let file = write_temp_config(config);
-        let err = load_config(file.path()).expect_err("expected null on_doctor_setup error");
-        assert!(err.contains("hooks.on_doctor_setup"));
+        let err = load_config_from_str("test", base).expect_err("shell needs commands");
+        assert!(err.contains("commands"), "got: {err}");
 
-        let config = r#"
+        let content = format!("{}backend: br\n", base);
+        let loaded = load_config_from_str("test", &content).expect("config should load");
+        assert_eq!(loaded.config.backend, Backend::Br);
+        assert_eq!(loaded.config.br.label, "trudgeable");
+
+        let content = format!("{}backend: br\nbr:\n  label: agent-ready\n", base);
+        let loaded = load_config_from_str("test", &content).expect("config should load");
+        assert_eq!(loaded.config.br.label, "agent-ready");
+
+        let content = format!("{}backend: br\ncommands:\n  task_show: \"show\"\n", base);
+        let err = load_config_from_str("test", &content).expect_err("commands are rejected");
+        assert!(
+            err.contains("commands is not used with backend: br"),
+            "got: {err}"
+        );
+
+        let content = format!("{}backend: br\nbr:\n  label: \"\"\n", base);
+        let err = load_config_from_str("test", &content).expect_err("empty label");
+        assert!(err.contains("br.label"), "got: {err}");
+
+        let content = format!("{}backend: jira\n", base);
+        let err = load_config_from_str("test", &content).expect_err("unknown backend");
+        assert!(
+            err.contains("backend must be one of shell|br|github|markdown|openspec"),
+            "got: {err}"
+        );
+    }
+
+    #[test]
+    fn backend_github_requires_a_repo() {
+        let base = r#"
 default_profile: codex
 profiles:
   codex:
@@ -1323,22 +3798,55 @@ invocations:
     command: "agent"
   codex-review:
     command: "review"
-commands:
-  next_task: "next"
-  task_show: "show"
-  task_status: "status"
-  task_update_status: "update"
 review_loop_limit: 3
 hooks:
   on_completed: "done"
   on_requires_human: "human"
-  on_doctor_setup: ""
+backend: github
 "#;
-        let file = write_temp_config(config);
-        let err = load_config(file.path()).expect_err("expected empty on_doctor_setup error");
-        assert!(err.contains("hooks.on_doctor_setup"));
+        let err = load_config_from_str("test", base).expect_err("repo is required");
+        assert!(err.contains("github.repo is required"), "got: {err}");
 
-        let config = r#"
+        let content = format!("{}github:\n  repo: acme/widgets\n", base);
+        let loaded = load_config_from_str("test", &content).expect("config should load");
+        assert_eq!(loaded.config.backend, Backend::Github);
+        assert_eq!(
+            loaded.config.github,
+            GithubConfig {
+                repo: "acme/widgets".to_string(),
+                label: "trudgeable".to_string(),
+                api_url: "https://api.github.com".to_string(),
+                token_env: "GITHUB_TOKEN".to_string(),
+            }
+        );
+
+        let content = format!(
+            "{}github:\n  repo: acme/widgets\n  label: agent\n  api_url: http://127.0.0.1:8080\n  token_env: GH_TOKEN\n",
+            base
+        );
+        let loaded = load_config_from_str("test", &content).expect("config should load");
+        assert_eq!(loaded.config.github.label, "agent");
+        assert_eq!(loaded.config.github.api_url, "http://127.0.0.1:8080");
+        assert_eq!(loaded.config.github.token_env, "GH_TOKEN");
+
+        let content = format!("{}github:\n  repo: widgets\n", base);
+        let err = load_config_from_str("test", &content).expect_err("owner/name");
+        assert!(err.contains("github.repo must be owner/name"), "got: {err}");
+
+        let content = format!(
+            "{}github:\n  repo: acme/widgets\ncommands:\n  task_show: \"show\"\n",
+            base
+        );
+        let err = load_config_from_str("test", &content).expect_err("commands are rejected");
+        assert!(
+            err.contains("commands is not used with backend: github"),
+            "got: {err}"
+        );
+    }
+
+    #[test]
+    fn backend_markdown_defaults_to_todo_md() {
+        let base = r#"
 default_profile: codex
 profiles:
   codex:
@@ -1349,38 +3857,52 @@ invocations:
     command: "agent"
   codex-review:
     command: "review"
-commands:
-  next_task: "next"
-  task_show: "show"
-  task_status: "status"
-  task_update_status: "update"
 review_loop_limit: 3
 hooks:
   on_completed: "done"
   on_requires_human: "human"
-  on_doctor_setup: 123
+backend: markdown
 "#;
-        let file = write_temp_config(config);
-        let err = load_config(file.path()).expect_err("expected non-string on_doctor_setup error");
-        assert!(err.contains("hooks.on_doctor_setup"));
-        assert!(err.contains("string"));
+        let loaded = load_config_from_str("test", base).expect("config should load");
+        assert_eq!(loaded.config.backend, Backend::Markdown);
+        assert_eq!(loaded.config.markdown.path, PathBuf::from("TODO.md"));
+
+        let content = format!("{}markdown:\n  path: docs/tasks.md\n", base);
+        let loaded = load_config_from_str("test", &content).expect("config should load");
+        assert_eq!(loaded.config.markdown.path, PathBuf::from("docs/tasks.md"));
+
+        let content = format!("{}markdown:\n  path: \"\"\n", base);
+        let err = load_config_from_str("test", &content).expect_err("empty path");
+        assert!(err.contains("markdown.path"), "got: {err}");
+
+        let content = format!("{}concurrency: 2\n", base);
+        let err = load_config_from_str("test", &content).expect_err("markdown concurrency");
+        assert!(
+            err.contains("concurrency above 1 is not supported with backend: markdown"),
+            "got: {err}"
+        );
     }
 
     #[test]
-    fn optional_notification_value_errors() {
-        let config = r#"
+    fn prompts_resolve_with_the_active_profile_first() {
+        let content = r#"
 default_profile: codex
 profiles:
   codex:
     trudge: codex
-    trudge_review: codex-review
+    trudge_review: codex
+  claude:
+    trudge: claude
+    trudge_review: claude
+    prompts:
+      trudge: ~/.claude/commands/trudge.md
+      plan: plan.md
 invocations:
   codex:
-    command: "agent"
-  codex-review:
-    command: "review"
+    command: "codex"
+  claude:
+    command: "claude"
 commands:
-  next_task: "next"
   task_show: "show"
   task_status: "status"
   task_update_status: "update"
@@ -1388,54 +3910,204 @@ review_loop_limit: 3
 hooks:
   on_completed: "done"
   on_requires_human: "human"
-  on_notification: null
+prompts:
+  trudge: prompts/trudge.md
+  trudge_review: prompts/review.md
 "#;
-        let file = write_temp_config(config);
-        let err = load_config(file.path()).expect_err("expected null on_notification error");
-        assert!(err.contains("hooks.on_notification"));
+        let loaded = load_config_from_str("test", content).expect("config should load");
+        assert_eq!(
+            loaded.warnings,
+            vec!["profiles.claude.prompts.plan".to_string()]
+        );
+        assert_eq!(
+            loaded.config.prompts,
+            PromptPaths {
+                trudge: Some(PathBuf::from("prompts/trudge.md")),
+                trudge_review: Some(PathBuf::from("prompts/review.md")),
+            }
+        );
 
-        let config = r#"
+        let loaded = load_config_from_str_with_profile("test", content, Some("claude"))
+            .expect("config should load");
+        assert_eq!(
+            loaded.config.prompts,
+            PromptPaths {
+                trudge: Some(PathBuf::from("~/.claude/commands/trudge.md")),
+                trudge_review: Some(PathBuf::from("prompts/review.md")),
+            }
+        );
+
+        let cases = [
+            ("prompts: trudge.md\n", "prompts must be a mapping"),
+            (
+                "prompts:\n  trudge: \"\"\n",
+                "prompts.trudge must not be empty",
+            ),
+        ];
+        let base = content
+            .split("prompts:\n  trudge: prompts")
+            .next()
+            .expect("base");
+        for (extra, expected) in cases {
+            let err = load_config_from_str("test", &format!("{}{}", base, extra))
+                .expect_err("invalid prompts");
+            assert!(err.contains(expected), "{extra}: got {err}");
+        }
+    }
+
+    #[test]
+    fn profile_phases_build_the_pipeline_before_the_review() {
+        let content = r#"
 default_profile: codex
 profiles:
   codex:
     trudge: codex
-    trudge_review: codex-review
+    trudge_review: codex
+    phases:
+      - name: plan
+        invocation: planner
+        prompt: prompts/plan.md
+      - trudge
+      - name: test
+        invocation: tests
+        on_failure: next_loop
+        retries: 2
+      - trudge_review
+  claude:
+    trudge: codex
+    trudge_review: codex
 invocations:
   codex:
-    command: "agent"
-  codex-review:
-    command: "review"
+    command: "codex"
+  planner:
+    command: "plan"
+    timeout: 60
+  tests:
+    command: "cargo test"
 commands:
-  next_task: "next"
   task_show: "show"
   task_status: "status"
   task_update_status: "update"
 review_loop_limit: 3
+on_task_error: skip
 hooks:
   on_completed: "done"
   on_requires_human: "human"
-  on_notification: ""
 "#;
-        let file = write_temp_config(config);
-        let err = load_config(file.path()).expect_err("expected empty on_notification error");
-        assert!(err.contains("hooks.on_notification"));
+        let loaded = load_config_from_str("test", content).expect("config should load");
+        assert_eq!(
+            loaded.warnings,
+            vec!["profiles.codex.phases[2].retries".to_string()]
+        );
+        let config = loaded.config;
+        assert_eq!(
+            config.agent_phase_names(),
+            vec!["plan", "trudge", "test", "trudge_review"]
+        );
+        assert_eq!(
+            config.custom_phase("plan"),
+            Some(&AgentPhase {
+                name: "plan".to_string(),
+                invocation_id: "planner".to_string(),
+                command: "plan".to_string(),
+                timeout: Some(AgentTimeout {
+                    seconds: TimeoutSeconds::new(60).expect("timeout"),
+                    on_timeout: TimeoutAction::Quit,
+                }),
+                retry: None,
+                prompt: Some(PathBuf::from("prompts/plan.md")),
+                on_failure: PhaseFailureAction::Skip,
+            }),
+            "on_failure defaults to on_task_error"
+        );
+        assert_eq!(
+            config.custom_phase("test").map(|phase| phase.on_failure),
+            Some(PhaseFailureAction::NextLoop)
+        );
+
+        let loaded = load_config_from_str_with_profile("test", content, Some("claude"))
+            .expect("config should load");
+        assert_eq!(loaded.config.phases, vec![PipelinePhase::Solve]);
+
+        let phases_of = |phases: &str| {
+            content.replacen(
+            "    phases:\n      - name: plan\n        invocation: planner\n        prompt: prompts/plan.md\n      - trudge\n      - name: test\n        invocation: tests\n        on_failure: next_loop\n        retries: 2\n      - trudge_review\n",
+            phases,
+            1,
+        )
+        };
+        let cases = [
+            ("    phases: trudge\n", "profiles.codex.phases must be a list"),
+            (
+                "    phases: [trudge_review, trudge]\n",
+                "profiles.codex.phases must end with trudge_review",
+            ),
+            (
+                "    phases: [trudge_review]\n",
+                "profiles.codex.phases must include trudge",
+            ),
+            (
+                "    phases: [trudge, trudge, trudge_review]\n",
+                "profiles.codex.phases lists trudge more than once",
+            ),
+            (
+                "    phases: [plan, trudge, trudge_review]\n",
+                "profiles.codex.phases[0] must be trudge, trudge_review, or a mapping",
+            ),
+            (
+                "    phases: [{name: solve, invocation: codex}, trudge, trudge_review]\n",
+                "profiles.codex.phases[0].name solve is reserved for the built-in phases",
+            ),
+            (
+                "    phases: [{name: Lint, invocation: codex}, trudge, trudge_review]\n",
+                "profiles.codex.phases[0].name must use only lowercase letters, digits, and _",
+            ),
+            (
+                "    phases: [trudge, {name: lint}, trudge_review]\n",
+                "Missing required config value: profiles.codex.phases[1].invocation",
+            ),
+            (
+                "    phases: [trudge, {name: lint, invocation: nope}, trudge_review]\n",
+                "profiles.codex.phases[1].invocation references missing invocation: nope",
+            ),
+            (
+                "    phases: [trudge, {name: lint, invocation: codex, on_failure: ignore}, trudge_review]\n",
+                "profiles.codex.phases[1].on_failure must be one of escalate|skip|quit|continue|next_loop",
+            ),
+        ];
+        for (phases, expected) in cases {
+            let err = load_config_from_str("test", &phases_of(phases)).expect_err(phases);
+            assert!(err.contains(expected), "{phases}: got {err}");
+        }
     }
 
     #[test]
-    fn invalid_notification_scope_errors() {
-        let config = r#"
+    fn profile_fallback_lists_the_profiles_to_switch_to() {
+        let content = r#"
 default_profile: codex
 profiles:
   codex:
     trudge: codex
-    trudge_review: codex-review
+    trudge_review: codex
+    fallback: [claude, glm]
+    fallback_exit_codes: [75]
+  claude:
+    trudge: claude
+    trudge_review: claude
+    fallback_after_failures: 2
+  glm:
+    trudge: glm
+    trudge_review: claude
 invocations:
   codex:
-    command: "agent"
-  codex-review:
-    command: "review"
+    command: "codex"
+  claude:
+    command: "claude"
+    retry:
+      max_attempts: 3
+  glm:
+    command: "glm"
 commands:
-  next_task: "next"
   task_show: "show"
   task_status: "status"
   task_update_status: "update"
@@ -1443,48 +4115,218 @@ review_loop_limit: 3
 hooks:
   on_completed: "done"
   on_requires_human: "human"
-  on_notification: "notify"
-  on_notification_scope: "bad_scope"
 "#;
-        let file = write_temp_config(config);
-        let err =
-            load_config(file.path()).expect_err("expected invalid on_notification_scope error");
-        assert!(err.contains("hooks.on_notification_scope"));
-        assert!(err.contains("all_logs|task_boundaries|run_boundaries"));
+        let loaded = load_config_from_str("test", content).expect("config should load");
+        let fallback = &loaded.config.profile_fallback;
+        assert_eq!(
+            fallback
+                .profiles
+                .iter()
+                .map(|profile| profile.profile.as_str())
+                .collect::<Vec<_>>(),
+            vec!["claude", "glm"]
+        );
+        assert_eq!(fallback.exit_codes, vec![75]);
+        assert_eq!(fallback.after_failures, None);
+        let claude = &fallback.profiles[0];
+        assert_eq!(claude.solve_command, "claude");
+        assert_eq!(claude.review_invocation_id, "claude");
+        assert_eq!(
+            claude
+                .solve_retry
+                .as_ref()
+                .map(|retry| retry.max_attempts.get()),
+            Some(3)
+        );
+        assert_eq!(fallback.profiles[1].review_command, "claude");
+
+        let mut config = loaded.config.clone();
+        claude.apply(&mut config);
+        assert_eq!(config.agent_command, "claude");
+        assert_eq!(config.agent_review_command, "claude");
+        assert!(config.agent_retry.is_some());
+
+        let loaded = load_config_from_str_with_profile("test", content, Some("claude"))
+            .expect("config should load");
+        assert!(
+            !loaded.config.profile_fallback.should_fall_back(5, Some(75)),
+            "fallback_after_failures does nothing without fallback"
+        );
+
+        let profile_of = |settings: &str| {
+            content.replacen(
+                "    fallback: [claude, glm]\n    fallback_exit_codes: [75]\n",
+                settings,
+                1,
+            )
+        };
+        let cases = [
+            (
+                "    fallback: claude\n",
+                "profiles.codex.fallback must be a list of profile names",
+            ),
+            (
+                "    fallback: [codex]\n",
+                "profiles.codex.fallback must not list the profile itself",
+            ),
+            (
+                "    fallback: [gemini]\n",
+                "profiles.codex.fallback references missing profile: gemini",
+            ),
+            (
+                "    fallback: [claude, claude]\n",
+                "profiles.codex.fallback lists claude more than once",
+            ),
+            (
+                "    fallback: [claude]\n    fallback_after_failures: 0\n",
+                "profiles.codex.fallback_after_failures must be a positive integer",
+            ),
+            (
+                "    fallback: [claude]\n    fallback_exit_codes: [0]\n",
+                "profiles.codex.fallback_exit_codes must only contain non-zero integer exit codes",
+            ),
+            (
+                "    fallback: [claude]\n    fallback_exit_codes: 75\n",
+                "profiles.codex.fallback_exit_codes must be a list",
+            ),
+        ];
+        for (settings, expected) in cases {
+            let err = load_config_from_str("test", &profile_of(settings)).expect_err(settings);
+            assert!(err.contains(expected), "{settings}: got {err}");
+        }
     }
 
     #[test]
-    fn notification_scope_defaults_and_scope_without_hook_warns() {
-        let config = r#"
+    fn profile_rules_route_labels_to_profiles() {
+        let content = r#"
 default_profile: codex
 profiles:
   codex:
     trudge: codex
-    trudge_review: codex-review
+    trudge_review: codex
+  opus:
+    trudge: opus
+    trudge_review: opus
 invocations:
   codex:
-    command: "agent"
-  codex-review:
-    command: "review"
+    command: "codex"
+  opus:
+    command: "claude --model opus"
 commands:
-  next_task: "next"
   task_show: "show"
   task_status: "status"
   task_update_status: "update"
+  task_profile: "route"
+profile_rules:
+  - label: hard
+    profile: opus
+    priority: 1
+  - label: docs
+    profile: codex
 review_loop_limit: 3
 hooks:
   on_completed: "done"
   on_requires_human: "human"
-  on_notification: "notify"
 "#;
-        let file = write_temp_config(config);
-        let loaded = load_config(file.path()).expect("config should load");
+        let loaded = load_config_from_str("test", content).expect("config should load");
         assert_eq!(
-            loaded.config.hooks.effective_notification_scope(),
-            Some(NotificationScope::TaskBoundaries)
+            loaded.warnings,
+            vec!["profile_rules[0].priority".to_string()]
+        );
+        let config = loaded.config;
+        assert_eq!(config.commands.task_profile.as_deref(), Some("route"));
+        let routing = &config.task_routing;
+        assert_eq!(
+            routing
+                .rule_for(&["docs".to_string(), "hard".to_string()])
+                .map(|rule| rule.profile.as_str()),
+            Some("opus"),
+            "the first matching rule wins"
+        );
+        assert_eq!(routing.rule_for(&["bug".to_string()]), None);
+        assert_eq!(
+            routing
+                .profiles
+                .get("opus")
+                .map(|profile| profile.solve_command.as_str()),
+            Some("claude --model opus")
         );
+        assert_eq!(routing.profiles.len(), 2);
 
-        let config = r#"
+        let cases = [
+            ("profile_rules: hard\n", "profile_rules must be a list"),
+            (
+                "profile_rules: [hard]\n",
+                "profile_rules[0] must be a mapping with label and profile",
+            ),
+            (
+                "profile_rules: [{profile: opus}]\n",
+                "Missing required config value: profile_rules[0].label",
+            ),
+            (
+                "profile_rules: [{label: hard, profile: haiku}]\n",
+                "profile_rules[0].profile references missing profile: haiku",
+            ),
+            (
+                "profile_rules: [{label: hard, profile: opus}]\nconcurrency: 2\n",
+                "concurrency above 1 is not supported with profile_rules or commands.task_profile",
+            ),
+        ];
+        let without_rules = content.replacen(
+            "profile_rules:\n  - label: hard\n    profile: opus\n    priority: 1\n  - label: docs\n    profile: codex\n",
+            "",
+            1,
+        );
+        for (rules, expected) in cases {
+            let err = load_config_from_str("test", &format!("{}{}", without_rules, rules))
+                .expect_err(rules);
+            assert!(err.contains(expected), "{rules}: got {err}");
+        }
+    }
+
+    #[test]
+    fn should_fall_back_follows_exit_codes_and_failure_counts() {
+        let profile = AgentProfile {
+            profile: "claude".to_string(),
+            solve_invocation_id: "claude".to_string(),
+            review_invocation_id: "claude".to_string(),
+            solve_command: "claude".to_string(),
+            review_command: "claude".to_string(),
+            solve_timeout: None,
+            review_timeout: None,
+            solve_retry: None,
+            review_retry: None,
+            prompts: PromptPaths::default(),
+            phases: vec![PipelinePhase::Solve],
+        };
+        let any_failure = ProfileFallback {
+            profiles: vec![profile.clone()],
+            after_failures: None,
+            exit_codes: Vec::new(),
+        };
+        assert!(any_failure.should_fall_back(1, Some(1)));
+        assert!(any_failure.should_fall_back(1, None));
+        assert!(!ProfileFallback::default().should_fall_back(5, Some(75)));
+
+        let exit_codes_only = ProfileFallback {
+            exit_codes: vec![75],
+            ..any_failure.clone()
+        };
+        assert!(exit_codes_only.should_fall_back(1, Some(75)));
+        assert!(!exit_codes_only.should_fall_back(9, Some(1)));
+
+        let both = ProfileFallback {
+            after_failures: NonZeroU64::new(3),
+            ..exit_codes_only
+        };
+        assert!(both.should_fall_back(1, Some(75)));
+        assert!(!both.should_fall_back(2, Some(1)));
+        assert!(both.should_fall_back(3, None));
+    }
+
+    #[test]
+    fn backend_openspec_defaults_to_the_openspec_dir() {
+        let base = r#"
 default_profile: codex
 profiles:
   codex:
@@ -1495,31 +4337,39 @@ invocations:
     command: "agent"
   codex-review:
     command: "review"
-commands:
-  next_task: "next"
-  task_show: "show"
-  task_status: "status"
-  task_update_status: "update"
 review_loop_limit: 3
 hooks:
   on_completed: "done"
   on_requires_human: "human"
-  on_notification_scope: "run_boundaries"
+backend: openspec
 "#;
-        let file = write_temp_config(config);
-        let loaded = load_config(file.path()).expect("config should load");
-        let warning = loaded
-            .warnings
-            .iter()
-            .find(|warning| warning.contains("hooks.on_notification_scope"))
-            .expect("scope-without-hook warning");
-        assert!(warning.contains("ignored"));
-        assert_eq!(loaded.config.hooks.effective_notification_scope(), None);
+        let loaded = load_config_from_str("test", base).expect("config should load");
+        assert_eq!(loaded.config.backend, Backend::Openspec);
+        assert_eq!(loaded.config.openspec.dir, PathBuf::from("openspec"));
+
+        let content = format!("{}openspec:\n  dir: docs/openspec\n", base);
+        let loaded = load_config_from_str("test", &content).expect("config should load");
+        assert_eq!(loaded.config.openspec.dir, PathBuf::from("docs/openspec"));
+
+        let content = format!("{}openspec:\n  path: docs/openspec\n", base);
+        let loaded = load_config_from_str("test", &content).expect("config should load");
+        assert_eq!(loaded.warnings, vec!["openspec.path".to_string()]);
+
+        let content = format!("{}openspec:\n  dir: \"\"\n", base);
+        let err = load_config_from_str("test", &content).expect_err("empty dir");
+        assert!(err.contains("openspec.dir"), "got: {err}");
+
+        let content = format!("{}concurrency: 2\n", base);
+        let err = load_config_from_str("test", &content).expect_err("openspec concurrency");
+        assert!(
+            err.contains("concurrency above 1 is not supported with backend: openspec"),
+            "got: {err}"
+        );
     }
 
     #[test]
-    fn optional_log_path_value_errors() {
-        let config = r#"
+    fn statuses_map_tokens_to_trudger_statuses() {
+        let base = r#"
 default_profile: codex
 profiles:
   codex:
@@ -1536,20 +4386,69 @@ commands:
   task_status: "status"
   task_update_status: "update"
 review_loop_limit: 3
-log_path: 123
 hooks:
   on_completed: "done"
   on_requires_human: "human"
 "#;
-        let file = write_temp_config(config);
-        let err = load_config(file.path()).expect_err("expected non-string log_path error");
-        assert!(err.contains("log_path"));
-        assert!(err.contains("string"));
+        let loaded = load_config_from_str("test", base).expect("config should load");
+        assert_eq!(loaded.config.statuses, StatusMap::default());
+        assert_eq!(loaded.config.statuses.parse("open"), Some(TaskStatus::Open));
+        assert_eq!(loaded.config.statuses.token(&TaskStatus::Open), "open");
+
+        let content = format!(
+            "{}statuses:\n  todo: ready\n  backlog: ready\n  doing: in_progress\n  done: done\n  wontfix: skip\n  needs-review: needs_human\n",
+            base
+        );
+        let statuses = load_config_from_str("test", &content)
+            .expect("config should load")
+            .config
+            .statuses;
+        assert_eq!(statuses.parse(" backlog\n"), Some(TaskStatus::Ready));
+        assert_eq!(statuses.parse("doing"), Some(TaskStatus::InProgress));
+        assert_eq!(statuses.parse("done"), Some(TaskStatus::Closed));
+        assert_eq!(statuses.parse("wontfix"), Some(TaskStatus::Skipped));
+        assert_eq!(statuses.parse("needs-review"), Some(TaskStatus::Blocked));
+        assert_eq!(statuses.parse("closed"), Some(TaskStatus::Closed));
+        assert_eq!(
+            statuses.parse("stalled"),
+            Some(TaskStatus::Unknown("stalled".to_string()))
+        );
+        assert_eq!(statuses.parse(""), None);
+        assert_eq!(statuses.token(&TaskStatus::Open), "todo");
+        assert_eq!(statuses.token(&TaskStatus::InProgress), "doing");
+        assert_eq!(statuses.token(&TaskStatus::Blocked), "needs-review");
+
+        for (statuses, expected) in [
+            ("statuses: todo\n", "statuses must be a mapping"),
+            ("statuses:\n", "statuses must not be null"),
+            (
+                "statuses:\n  todo: started\n",
+                "statuses.todo must be one of ready|in_progress|done|needs_human|skip",
+            ),
+            (
+                "statuses:\n  \"in review\": ready\n",
+                "statuses.in review must be a single word",
+            ),
+        ] {
+            let content = format!("{}{}", base, statuses);
+            let err = load_config_from_str("test", &content).expect_err("invalid statuses");
+            assert!(err.contains(expected), "expected {expected:?}, got: {err}");
+        }
+
+        let content = format!(
+            "{}statuses:\n  todo: ready\n",
+            base.replace("commands:\n  next_task: \"next\"\n  task_show: \"show\"\n  task_status: \"status\"\n  task_update_status: \"update\"\n", "backend: br\n")
+        );
+        let err = load_config_from_str("test", &content).expect_err("statuses with br");
+        assert!(
+            err.contains("statuses is not used with backend: br"),
+            "got: {err}"
+        );
     }
 
     #[test]
-    fn missing_log_path_is_allowed() {
-        let config = r#"
+    fn agent_output_dir_is_optional() {
+        let base = r#"
 default_profile: codex
 profiles:
   codex:
@@ -1570,14 +4469,38 @@ hooks:
   on_completed: "done"
   on_requires_human: "human"
 "#;
-        let file = write_temp_config(config);
-        let loaded = load_config(file.path()).expect("config should load");
-        assert_eq!(loaded.config.log_path, None);
+        let loaded = load_config_from_str("test", base).expect("config should load");
+        assert_eq!(loaded.config.agent_output_dir, None);
+
+        let content = format!("{}agent_output_dir: .trudger/runs\n", base);
+        let loaded = load_config_from_str("test", &content).expect("config should load");
+        assert_eq!(
+            loaded.config.agent_output_dir,
+            Some(PathBuf::from(".trudger/runs"))
+        );
+
+        let content = format!("{}agent_output_dir: \"\"\n", base);
+        let loaded = load_config_from_str("test", &content).expect("config should load");
+        assert_eq!(loaded.config.agent_output_dir, None);
+
+        let content = format!("{}agent_output_dir: [runs]\n", base);
+        let err = load_config_from_str("test", &content).expect_err("list should be rejected");
+        assert!(err.contains("agent_output_dir"), "got: {err}");
     }
 
     #[test]
-    fn empty_log_path_is_allowed() {
-        let config = r#"
+    fn task_error_action_as_str_matches_config_names() {
+        let name = |action: TaskErrorAction| PhaseFailureAction::from(action).as_str();
+        assert_eq!(name(TaskErrorAction::Escalate), "escalate");
+        assert_eq!(name(TaskErrorAction::Skip), "skip");
+        assert_eq!(name(TaskErrorAction::Quit), "quit");
+        assert_eq!(PhaseFailureAction::Continue.as_str(), "continue");
+        assert_eq!(PhaseFailureAction::NextLoop.as_str(), "next_loop");
+    }
+
+    #[test]
+    fn invocation_timeout_resolves_per_phase_with_quit_default() {
+        let base = r#"
 default_profile: codex
 profiles:
   codex:
@@ -1586,27 +4509,61 @@ profiles:
 invocations:
   codex:
     command: "agent"
+    timeout: 600
+    on_timeout: escalate
   codex-review:
     command: "review"
+    timeout: 120
 commands:
   next_task: "next"
   task_show: "show"
   task_status: "status"
   task_update_status: "update"
 review_loop_limit: 3
-log_path: ""
 hooks:
   on_completed: "done"
   on_requires_human: "human"
 "#;
-        let file = write_temp_config(config);
-        let loaded = load_config(file.path()).expect("config should load");
-        assert_eq!(loaded.config.log_path, None);
+        let loaded = load_config_from_str("test", base).expect("config should load");
+        let solve = loaded.config.agent_timeout.expect("solve timeout");
+        assert_eq!(solve.seconds.get(), 600);
+        assert_eq!(solve.on_timeout, TimeoutAction::Escalate);
+        let review = loaded.config.agent_review_timeout.expect("review timeout");
+        assert_eq!(review.seconds.get(), 120);
+        assert_eq!(review.on_timeout, TimeoutAction::Quit);
+        assert!(loaded.warnings.is_empty());
+
+        let without_timeout = base.replace("    timeout: 600\n", "");
+        let loaded = load_config_from_str("test", &without_timeout).expect("config should load");
+        assert_eq!(loaded.config.agent_timeout, None);
+        assert_eq!(
+            loaded.warnings,
+            vec![
+                "invocations.codex.on_timeout is ignored because invocations.codex.timeout is not configured."
+                    .to_string()
+            ]
+        );
+
+        let zero = base.replace("timeout: 600", "timeout: 0");
+        let err = load_config_from_str("test", &zero).expect_err("expected zero timeout");
+        assert!(err.contains("invocations.codex.timeout must be a positive integer"));
+
+        let null = base.replace("timeout: 600", "timeout: null");
+        let err = load_config_from_str("test", &null).expect_err("expected null timeout");
+        assert!(err.contains("invocations.codex.timeout must not be null"));
+
+        let bad_action = base.replace("on_timeout: escalate", "on_timeout: ignore");
+        let err = load_config_from_str("test", &bad_action).expect_err("expected bad action");
+        assert!(err.contains("invocations.codex.on_timeout must be one of retry|escalate|quit"));
+
+        let null_action = base.replace("on_timeout: escalate", "on_timeout: null");
+        let err = load_config_from_str("test", &null_action).expect_err("expected null action");
+        assert!(err.contains("invocations.codex.on_timeout must not be null"));
     }
 
     #[test]
-    fn null_log_path_errors() {
-        let config = r#"
+    fn invocation_retry_policy_parses_with_defaults_and_validates() {
+        let base = r#"
 default_profile: codex
 profiles:
   codex:
@@ -1615,6 +4572,9 @@ profiles:
 invocations:
   codex:
     command: "agent"
+    retry:
+      max_attempts: 3
+      exit_codes: [1, 75]
   codex-review:
     command: "review"
 commands:
@@ -1623,14 +4583,102 @@ commands:
   task_status: "status"
   task_update_status: "update"
 review_loop_limit: 3
-log_path: null
 hooks:
   on_completed: "done"
   on_requires_human: "human"
 "#;
-        let file = write_temp_config(config);
-        let err = load_config(file.path()).expect_err("expected null log_path");
-        assert!(err.contains("log_path"));
+        let loaded = load_config_from_str("test", base).expect("config should load");
+        let retry = loaded.config.agent_retry.expect("solve retry");
+        assert_eq!(retry.max_attempts.get(), 3);
+        assert_eq!(retry.backoff_seconds, DEFAULT_RETRY_BACKOFF_SECONDS);
+        assert_eq!(retry.max_backoff_seconds, DEFAULT_RETRY_MAX_BACKOFF_SECONDS);
+        assert_eq!(retry.exit_codes, vec![1, 75]);
+        assert_eq!(loaded.config.agent_review_retry, None);
+        assert!(loaded.warnings.is_empty());
+
+        let unknown = base.replace("max_attempts: 3", "max_attempts: 3\n      jitter: true");
+        let loaded = load_config_from_str("test", &unknown).expect("config should load");
+        assert_eq!(
+            loaded.warnings,
+            vec!["invocations.codex.retry.jitter".to_string()]
+        );
+
+        for (from, to, expected) in [
+            (
+                "max_attempts: 3",
+                "max_attempts: 0",
+                "invocations.codex.retry.max_attempts must be a positive integer",
+            ),
+            (
+                "max_attempts: 3",
+                "backoff_seconds: 1",
+                "Missing required config value: invocations.codex.retry.max_attempts",
+            ),
+            (
+                "exit_codes: [1, 75]",
+                "exit_codes: [0]",
+                "invocations.codex.retry.exit_codes must only contain non-zero integer exit codes",
+            ),
+            (
+                "exit_codes: [1, 75]",
+                "exit_codes: 1",
+                "invocations.codex.retry.exit_codes must be a list",
+            ),
+            (
+                "exit_codes: [1, 75]",
+                "max_backoff_seconds: -1",
+                "invocations.codex.retry.max_backoff_seconds must be a non-negative integer",
+            ),
+        ] {
+            let invalid = base.replace(from, to);
+            let err = load_config_from_str("test", &invalid).expect_err("expected retry error");
+            assert!(err.contains(expected), "expected {expected:?}, got {err:?}");
+        }
+
+        let not_mapping = base.replace(
+            "retry:\n      max_attempts: 3\n      exit_codes: [1, 75]",
+            "retry: 3",
+        );
+        let err = load_config_from_str("test", &not_mapping).expect_err("expected mapping error");
+        assert!(err.contains("invocations.codex.retry must be a mapping"));
+
+        let null = base.replace(
+            "retry:\n      max_attempts: 3\n      exit_codes: [1, 75]",
+            "retry: null",
+        );
+        let err = load_config_from_str("test", &null).expect_err("expected null error");
+        assert!(err.contains("invocations.codex.retry must not be null"));
+    }
+
+    #[test]
+    fn retry_policy_backoff_doubles_up_to_cap_and_filters_exit_codes() {
+        let policy = RetryPolicy {
+            max_attempts: NonZeroU64::new(10).expect("non-zero"),
+            backoff_seconds: 5,
+            max_backoff_seconds: 30,
+            exit_codes: Vec::new(),
+        };
+        assert_eq!(policy.backoff_after(1), Duration::from_secs(5));
+        assert_eq!(policy.backoff_after(2), Duration::from_secs(10));
+        assert_eq!(policy.backoff_after(3), Duration::from_secs(20));
+        assert_eq!(policy.backoff_after(4), Duration::from_secs(30));
+        assert_eq!(policy.backoff_after(u64::MAX), Duration::from_secs(30));
+        assert!(policy.retries_exit_code(1));
+        assert!(!policy.retries_exit_code(0));
+
+        let filtered = RetryPolicy {
+            exit_codes: vec![75],
+            ..policy
+        };
+        assert!(filtered.retries_exit_code(75));
+        assert!(!filtered.retries_exit_code(1));
+    }
+
+    #[test]
+    fn timeout_action_as_str_matches_config_names() {
+        assert_eq!(TimeoutAction::Retry.as_str(), "retry");
+        assert_eq!(TimeoutAction::Escalate.as_str(), "escalate");
+        assert_eq!(TimeoutAction::Quit.as_str(), "quit");
     }
 
     #[test]
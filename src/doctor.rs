@@ -2,15 +2,17 @@ use std::collections::BTreeMap;
 use std::env;
 use std::fs;
 use std::io::{BufRead, BufReader};
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
-use crate::config::Config;
-use crate::logger::sanitize_log_value;
-use crate::logger::Logger;
+use crate::beads::Br;
+use crate::config::{Backend, Config};
+use crate::logger::{Logger, Transition};
+use crate::prompt_search::resolve_prompt;
 use crate::run_loop::{quit, validate_config, Quit};
 use crate::shell::{
     run_shell_command_capture, run_shell_command_status, CommandEnv, CommandResult,
 };
+use crate::task_backend::parse_task_dependencies;
 use crate::task_types::{TaskId, TaskStatus};
 
 #[derive(Debug, serde::Deserialize)]
@@ -108,13 +110,17 @@ impl DoctorCtx<'_> {
             task_id: task.task_id.map(|value| value.to_string()),
             task_show: task.task_show.map(|value| value.to_string()),
             task_status: task.task_status.map(|value| value.as_str().to_string()),
-            target_status: task.target_status.map(|value| value.as_str().to_string()),
+            target_status: task
+                .target_status
+                .map(|value| self.config.statuses.token(value).to_string()),
             agent_prompt: None,
             agent_phase: None,
             agent_profile: None,
             agent_invocation_id: None,
             completed: None,
             needs_human: None,
+            skipped: None,
+            declined: None,
             notify_event: None,
             notify_duration_ms: None,
             notify_folder: None,
@@ -123,6 +129,11 @@ impl DoctorCtx<'_> {
             notify_task_description: None,
             notify_message: None,
             notify_payload_path: None,
+            agent_output: None,
+            review_result_path: None,
+            review_feedback: None,
+            review_follow_ups: None,
+            review_loop: None,
         }
     }
 
@@ -149,9 +160,25 @@ impl DoctorCtx<'_> {
         let env = self.env(task);
         run_shell_command_status(command, log_label, task_token, args, &env, self.logger)
     }
+
+    fn br(&self) -> Option<Br<'_>> {
+        (self.config.backend == Backend::Br).then_some(Br {
+            cwd: Some(self.scratch_dir),
+            logger: self.logger,
+        })
+    }
 }
 
 fn doctor_run_next_task(ctx: &DoctorCtx<'_>) -> Result<(), String> {
+    if let Some(br) = ctx.br() {
+        let token = br.next_task(&ctx.config.br.label, "doctor-next-task")?;
+        if let Some(token) = token {
+            TaskId::try_from(token.as_str()).map_err(|err| {
+                format!("br ready returned an invalid task id: {} ({})", token, err)
+            })?;
+        }
+        return Ok(());
+    }
     let next_task = ctx
         .config
         .commands
@@ -193,6 +220,9 @@ fn doctor_run_next_task(ctx: &DoctorCtx<'_>) -> Result<(), String> {
 }
 
 fn doctor_run_task_show(ctx: &DoctorCtx<'_>, task_id: &TaskId) -> Result<String, String> {
+    if let Some(br) = ctx.br() {
+        return br.show(task_id, "doctor-task-show");
+    }
     let output = ctx.run_capture(
         &ctx.config.commands.task_show,
         "doctor-task-show",
@@ -209,30 +239,71 @@ fn doctor_run_task_show(ctx: &DoctorCtx<'_>, task_id: &TaskId) -> Result<String,
     Ok(output.stdout)
 }
 
-fn doctor_run_task_status(ctx: &DoctorCtx<'_>, task_id: &TaskId) -> Result<TaskStatus, String> {
+/// Runs `commands.task_dependencies`, when configured, and checks that it prints task ids.
+fn doctor_run_task_dependencies(ctx: &DoctorCtx<'_>, task_id: &TaskId) -> Result<(), String> {
+    let Some(command) = ctx
+        .config
+        .commands
+        .task_dependencies
+        .as_deref()
+        .filter(|command| !command.trim().is_empty())
+    else {
+        return Ok(());
+    };
     let output = ctx.run_capture(
-        &ctx.config.commands.task_status,
-        "doctor-task-status",
+        command,
+        "doctor-task-dependencies",
         task_id.as_str(),
         &[],
         DoctorTaskEnv::for_task(task_id),
     )?;
     if output.exit_code != 0 {
         return Err(format!(
-            "commands.task_status failed with exit code {}",
+            "commands.task_dependencies failed with exit code {}",
             output.exit_code
         ));
     }
-    let token = output.stdout.split_whitespace().next().unwrap_or("");
-    let Some(status) = TaskStatus::parse(token) else {
-        return Err("commands.task_status returned an empty status.".to_string());
+    parse_task_dependencies(&output.stdout)
+        .map(|_| ())
+        .map_err(|err| format!("commands.{}", err))
+}
+
+fn doctor_run_task_status(ctx: &DoctorCtx<'_>, task_id: &TaskId) -> Result<TaskStatus, String> {
+    let parsed = if let Some(br) = ctx.br() {
+        br.status(task_id, "doctor-task-status")?
+    } else {
+        let output = ctx.run_capture(
+            &ctx.config.commands.task_status,
+            "doctor-task-status",
+            task_id.as_str(),
+            &[],
+            DoctorTaskEnv::for_task(task_id),
+        )?;
+        if output.exit_code != 0 {
+            return Err(format!(
+                "commands.task_status failed with exit code {}",
+                output.exit_code
+            ));
+        }
+        let token = output.stdout.split_whitespace().next().unwrap_or("");
+        ctx.config.statuses.parse(token)
+    };
+    let Some(status) = parsed else {
+        return Err(match ctx.config.backend {
+            Backend::Shell => "commands.task_status returned an empty status.".to_string(),
+            backend => format!(
+                "backend {} returned no status for task {}.",
+                backend.as_str(),
+                task_id
+            ),
+        });
     };
     if status.is_unknown() {
-        ctx.logger.log_transition(&format!(
-            "unknown_task_status task={} status={}",
-            task_id,
-            sanitize_log_value(status.as_str())
-        ));
+        ctx.logger.log_transition(
+            Transition::new("unknown_task_status")
+                .field("task", task_id)
+                .field("status", &status),
+        );
     }
     Ok(status)
 }
@@ -246,6 +317,9 @@ fn doctor_run_task_update_status(
         !status.is_unknown(),
         "doctor_run_task_update_status must not be called with an unknown status"
     );
+    if let Some(br) = ctx.br() {
+        return br.set_status(task_id, &status, "doctor-task-update");
+    }
     let exit = ctx.run_status(
         &ctx.config.commands.task_update_status,
         "doctor-task-update",
@@ -344,6 +418,7 @@ fn run_doctor_checks(ctx: &DoctorCtx<'_>) -> Result<(), String> {
 
     // Verify show runs successfully (content is prompt-only in run mode).
     let show = doctor_run_task_show(ctx, &task_id)?;
+    doctor_run_task_dependencies(ctx, &task_id)?;
 
     // Verify update -> in_progress parsing.
     doctor_run_task_update_status(ctx, &task_id, TaskStatus::InProgress)?;
@@ -413,6 +488,41 @@ fn run_doctor_checks(ctx: &DoctorCtx<'_>) -> Result<(), String> {
     Ok(())
 }
 
+/// Prints which prompt file each agent phase would use; a missing prompt is only a warning here
+/// because a run reports it before starting.
+fn report_prompts(config: &Config, cwd: &Path, logger: &Logger) {
+    let Some(home) = env::var_os("HOME").map(PathBuf::from) else {
+        eprintln!("Warning: HOME is not set; cannot resolve prompt files.");
+        return;
+    };
+    for phase in config.agent_phase_names() {
+        match resolve_prompt(config, phase, &home, cwd) {
+            Ok(found) => {
+                println!(
+                    "Prompt for {}: {} ({})",
+                    phase,
+                    found.path.display(),
+                    found.source
+                );
+                logger.log_transition(
+                    Transition::new("doctor_prompt")
+                        .field("phase", phase)
+                        .field("source", found.source)
+                        .field("path", found.path.display().to_string()),
+                );
+            }
+            Err(err) => {
+                eprintln!("Warning: {}.", err);
+                logger.log_transition(
+                    Transition::new("doctor_prompt")
+                        .field("phase", phase)
+                        .field("source", "missing"),
+                );
+            }
+        }
+    }
+}
+
 pub(crate) fn run_doctor_mode(
     config: &Config,
     config_path: &Path,
@@ -425,6 +535,16 @@ pub(crate) fn run_doctor_mode(
         eprintln!("{}", message);
         return Err(quit(logger, &message, 1));
     }
+    report_prompts(config, &invocation_cwd, logger);
+    if !matches!(config.backend, Backend::Shell | Backend::Br) {
+        // The doctor checks a scratch beads DB prepared by hooks.on_doctor_setup.
+        let message = format!(
+            "trudger doctor does not support backend: {}.",
+            config.backend.as_str()
+        );
+        eprintln!("{}", message);
+        return Err(quit(logger, &message, 1));
+    }
 
     let hook = config
         .hooks
@@ -460,6 +580,8 @@ pub(crate) fn run_doctor_mode(
         agent_invocation_id: None,
         completed: None,
         needs_human: None,
+        skipped: None,
+        declined: None,
         notify_event: None,
         notify_duration_ms: None,
         notify_folder: None,
@@ -468,6 +590,11 @@ pub(crate) fn run_doctor_mode(
         notify_task_description: None,
         notify_message: None,
         notify_payload_path: None,
+        agent_output: None,
+        review_result_path: None,
+        review_feedback: None,
+        review_follow_ups: None,
+        review_loop: None,
     };
 
     let hook_exit = run_shell_command_status(&hook, "doctor-setup", "none", &[], &env, logger);
@@ -538,6 +665,8 @@ mod tests {
                 task_show: "printf 'SHOW'".to_string(),
                 task_status: "printf 'open\\n'".to_string(),
                 task_update_status: "exit 0".to_string(),
+                task_dependencies: None,
+                task_profile: None,
             },
             hooks: crate::config::Hooks {
                 on_completed: "exit 0".to_string(),
@@ -545,10 +674,36 @@ mod tests {
                 on_doctor_setup: Some("exit 0".to_string()),
                 on_notification: None,
                 on_notification_scope: None,
+                on_task_error: None,
+                on_skipped: None,
             },
             review_loop_limit: crate::task_types::ReviewLoopLimit::new(1)
                 .expect("review_loop_limit"),
             log_path: None,
+            journal_path: None,
+            agent_timeout: None,
+            agent_review_timeout: None,
+            agent_retry: None,
+            agent_review_retry: None,
+            on_task_error: crate::config::TaskErrorAction::Quit,
+            watch: crate::config::WatchConfig::default(),
+            workspaces: Vec::new(),
+            workspace_order: crate::config::WorkspaceOrder::RoundRobin,
+            concurrency: std::num::NonZeroUsize::MIN,
+            worktree_root: None,
+            log_format: crate::config::LogFormat::Text,
+            agent_output_dir: None,
+            backend: crate::config::Backend::Shell,
+            br: crate::config::BrConfig::default(),
+            github: crate::config::GithubConfig::default(),
+            markdown: crate::config::MarkdownConfig::default(),
+            openspec: crate::config::OpenspecConfig::default(),
+            statuses: crate::config::StatusMap::default(),
+            prompt_format: crate::config::PromptFormat::Plain,
+            prompts: crate::config::PromptPaths::default(),
+            phases: vec![crate::config::PipelinePhase::Solve],
+            profile_fallback: crate::config::ProfileFallback::default(),
+            task_routing: crate::config::TaskRouting::default(),
         }
     }
 
@@ -692,6 +847,81 @@ mod tests {
         assert!(err.contains("commands.next_task must not be empty"));
     }
 
+    #[test]
+    fn doctor_run_task_dependencies_checks_the_printed_ids() {
+        let _guard = crate::unit_tests::ENV_MUTEX.lock().unwrap();
+        crate::unit_tests::reset_test_env();
+
+        let temp = TempDir::new().expect("temp dir");
+        let scratch = TempDir::new().expect("scratch");
+        let mut config = base_config();
+        let logger = Logger::new(None);
+        let config_path = temp.path().join("trudger.yml");
+        let scratch_path = scratch.path().display().to_string();
+        let task_id = TaskId::try_from("tr-1").expect("task id");
+        let run = |config: &Config| {
+            doctor_run_task_dependencies(
+                &DoctorCtx {
+                    config,
+                    config_path: &config_path,
+                    scratch_dir: scratch.path(),
+                    scratch_path: &scratch_path,
+                    logger: &logger,
+                },
+                &task_id,
+            )
+        };
+
+        run(&config).expect("unset command is skipped");
+        config.commands.task_dependencies = Some("printf 'tr-2\\ntr-3\\n'".to_string());
+        run(&config).expect("valid ids");
+        config.commands.task_dependencies = Some("printf 'bad id!'".to_string());
+        let err = run(&config).expect_err("invalid id");
+        assert!(
+            err.starts_with("commands.task_dependencies returned an invalid task id: bad id!"),
+            "got: {err}"
+        );
+        config.commands.task_dependencies = Some("exit 4".to_string());
+        let err = run(&config).expect_err("failing command");
+        assert_eq!(err, "commands.task_dependencies failed with exit code 4");
+    }
+
+    #[test]
+    fn report_prompts_logs_the_file_each_phase_would_use() {
+        let _guard = crate::unit_tests::ENV_MUTEX.lock().unwrap();
+        crate::unit_tests::reset_test_env();
+
+        let temp = TempDir::new().expect("temp dir");
+        let home = temp.path().join("home");
+        let repo = temp.path().join("repo");
+        let repo_prompt = repo.join(".trudger").join("prompts").join("trudge.md");
+        fs::create_dir_all(repo_prompt.parent().expect("parent")).expect("create prompts dir");
+        fs::write(&repo_prompt, "solve").expect("write prompt");
+        let log_path = temp.path().join("trudger.log");
+        let logger = Logger::new(Some(log_path.clone()));
+        let old_home = env::var_os("HOME");
+        env::set_var("HOME", &home);
+
+        report_prompts(&base_config(), &repo, &logger);
+
+        match old_home {
+            Some(value) => env::set_var("HOME", value),
+            None => env::remove_var("HOME"),
+        }
+        let log = fs::read_to_string(&log_path).expect("read log");
+        assert!(
+            log.contains(&format!(
+                "doctor_prompt phase=trudge source=repo path={}",
+                repo_prompt.display()
+            )),
+            "got:\n{log}"
+        );
+        assert!(
+            log.contains("doctor_prompt phase=trudge_review source=missing"),
+            "got:\n{log}"
+        );
+    }
+
     #[test]
     fn doctor_run_next_task_accepts_exit_code_1() {
         let _guard = crate::unit_tests::ENV_MUTEX.lock().unwrap();
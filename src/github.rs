@@ -0,0 +1,799 @@
+use serde_json::{json, Value};
+use std::env;
+use std::time::{Duration, Instant};
+
+use crate::config::GithubConfig;
+use crate::logger::{Logger, Transition};
+use crate::task_types::{TaskId, TaskStatus};
+
+/// Labels that carry the statuses GitHub issues lack; `open`/`closed` is the issue state.
+const IN_PROGRESS_LABEL: &str = "in_progress";
+const BLOCKED_LABEL: &str = "blocked";
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Task operations for `backend: github`. Task ids are issue numbers of `github.repo`.
+pub(crate) struct GitHub<'a> {
+    pub(crate) config: &'a GithubConfig,
+    pub(crate) logger: &'a Logger,
+}
+
+enum Method {
+    Get,
+    Patch(Value),
+}
+
+impl Method {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::Get => "GET",
+            Self::Patch(_) => "PATCH",
+        }
+    }
+}
+
+impl GitHub<'_> {
+    /// The oldest open issue labeled `github.label` that is neither `in_progress` nor `blocked`
+    /// and not in `exclude`. Pages are fetched 100 issues at a time, following `Link: rel="next"`
+    /// until one is found.
+    pub(crate) fn next_task(
+        &self,
+        exclude: &[TaskId],
+        log_label: &str,
+    ) -> Result<Option<String>, String> {
+        let first_page = format!("{}/issues", self.repo_path());
+        let mut page = Some(first_page.clone());
+        while let Some(path) = page {
+            let query: &[(&str, &str)] = if path == first_page {
+                &[
+                    ("state", "open"),
+                    ("labels", &self.config.label),
+                    ("sort", "created"),
+                    ("direction", "asc"),
+                    ("per_page", "100"),
+                ]
+            } else {
+                // Next-page links already carry the query.
+                &[]
+            };
+            let (issues, next) = self.request_page(Method::Get, &path, query, log_label, "none")?;
+            let Value::Array(issues) = issues else {
+                return Err("GitHub returned something other than a list of issues".to_string());
+            };
+            let found = issues
+                .iter()
+                // The issues endpoint also lists pull requests.
+                .filter(|issue| issue.get("pull_request").is_none())
+                .filter(|issue| issue_status(issue) == Some(TaskStatus::Open))
+                .filter_map(|issue| issue.get("number").and_then(Value::as_u64))
+                .map(|number| number.to_string())
+                .find(|id| !exclude.iter().any(|excluded| excluded.as_str() == id));
+            if found.is_some() {
+                return Ok(found);
+            }
+            page = next.map(|url| self.api_path(&url)).transpose()?;
+        }
+        Ok(None)
+    }
+
+    /// Title, URL, labels, body, and comments as plain text for the prompt (`TRUDGER_TASK_SHOW`).
+    pub(crate) fn show(&self, task_id: &TaskId, log_label: &str) -> Result<String, String> {
+        let issue = self.issue(task_id, log_label)?;
+        let comment_count = issue.get("comments").and_then(Value::as_u64).unwrap_or(0);
+        let comments = if comment_count > 0 {
+            let path = format!("{}/comments", self.issue_path(task_id)?);
+            self.request(
+                Method::Get,
+                &path,
+                &[("per_page", "100")],
+                log_label,
+                task_id.as_str(),
+            )?
+        } else {
+            Value::Array(Vec::new())
+        };
+        Ok(render_issue(&issue, &comments))
+    }
+
+    pub(crate) fn status(
+        &self,
+        task_id: &TaskId,
+        log_label: &str,
+    ) -> Result<Option<TaskStatus>, String> {
+        let issue = self.issue(task_id, log_label)?;
+        Ok(issue_status(&issue))
+    }
+
+    /// Sets the issue state and swaps the `in_progress`/`blocked` labels, keeping all others.
+    pub(crate) fn set_status(
+        &self,
+        task_id: &TaskId,
+        status: &TaskStatus,
+        log_label: &str,
+    ) -> Result<(), String> {
+        let (state, status_label) = match status {
+            TaskStatus::Ready | TaskStatus::Open => ("open", None),
+            TaskStatus::InProgress => ("open", Some(IN_PROGRESS_LABEL)),
+            TaskStatus::Blocked => ("open", Some(BLOCKED_LABEL)),
+            TaskStatus::Closed => ("closed", None),
+            TaskStatus::Skipped | TaskStatus::Unknown(_) => {
+                return Err(format!("GitHub issues cannot be set to status {}", status));
+            }
+        };
+        let issue = self.issue(task_id, log_label)?;
+        let mut labels: Vec<String> = label_names(&issue)
+            .filter(|name| *name != IN_PROGRESS_LABEL && *name != BLOCKED_LABEL)
+            .map(str::to_string)
+            .collect();
+        labels.extend(status_label.map(str::to_string));
+        self.request(
+            Method::Patch(json!({ "state": state, "labels": labels })),
+            &self.issue_path(task_id)?,
+            &[],
+            log_label,
+            task_id.as_str(),
+        )
+        .map(|_| ())
+    }
+
+    fn issue(&self, task_id: &TaskId, log_label: &str) -> Result<Value, String> {
+        self.request(
+            Method::Get,
+            &self.issue_path(task_id)?,
+            &[],
+            log_label,
+            task_id.as_str(),
+        )
+    }
+
+    fn repo_path(&self) -> String {
+        format!("/repos/{}", self.config.repo)
+    }
+
+    fn issue_path(&self, task_id: &TaskId) -> Result<String, String> {
+        let number: u64 = task_id.as_str().parse().map_err(|_| {
+            format!(
+                "backend: github task ids are issue numbers, got '{}'",
+                task_id
+            )
+        })?;
+        Ok(format!("{}/issues/{}", self.repo_path(), number))
+    }
+
+    /// The path of a `Link` URL under `github.api_url`, so every request goes to the same API.
+    fn api_path(&self, url: &str) -> Result<String, String> {
+        url.strip_prefix(self.config.api_url.trim_end_matches('/'))
+            .filter(|path| path.starts_with('/'))
+            .map(str::to_string)
+            .ok_or_else(|| format!("GitHub linked a next page outside github.api_url: {}", url))
+    }
+
+    fn request(
+        &self,
+        method: Method,
+        path: &str,
+        query: &[(&str, &str)],
+        log_label: &str,
+        task_token: &str,
+    ) -> Result<Value, String> {
+        self.request_page(method, path, query, log_label, task_token)
+            .map(|(value, _)| value)
+    }
+
+    /// Sends one request and returns its JSON body with the `rel="next"` URL of its `Link`
+    /// header, if any.
+    fn request_page(
+        &self,
+        method: Method,
+        path: &str,
+        query: &[(&str, &str)],
+        log_label: &str,
+        task_token: &str,
+    ) -> Result<(Value, Option<String>), String> {
+        let method_name = method.as_str();
+        self.logger.log_transition(
+            Transition::new("api start")
+                .field("label", log_label)
+                .field("task", task_token)
+                .field("method", method_name)
+                .field("path", path),
+        );
+
+        let agent: ureq::Agent = ureq::Agent::config_builder()
+            .http_status_as_error(false)
+            .timeout_global(Some(REQUEST_TIMEOUT))
+            .build()
+            .into();
+        let url = format!("{}{}", self.config.api_url.trim_end_matches('/'), path);
+        let token = env::var(&self.config.token_env)
+            .ok()
+            .filter(|token| !token.trim().is_empty());
+
+        let started_at = Instant::now();
+        let result = match method {
+            Method::Get => {
+                let mut request = agent.get(&url);
+                for (key, value) in query {
+                    request = request.query(*key, *value);
+                }
+                request = request
+                    .header("Accept", "application/vnd.github+json")
+                    .header("User-Agent", "trudger");
+                if let Some(token) = token.as_deref() {
+                    request = request.header("Authorization", format!("Bearer {}", token));
+                }
+                request.call()
+            }
+            Method::Patch(body) => {
+                let mut request = agent
+                    .patch(&url)
+                    .header("Accept", "application/vnd.github+json")
+                    .header("User-Agent", "trudger");
+                if let Some(token) = token.as_deref() {
+                    request = request.header("Authorization", format!("Bearer {}", token));
+                }
+                request.send_json(body)
+            }
+        };
+        let mut response =
+            result.map_err(|err| format!("GitHub {} {} failed: {}", method_name, path, err))?;
+        let http_status = response.status().as_u16();
+        self.logger.log_transition(
+            Transition::new("api exit")
+                .field("label", log_label)
+                .field("task", task_token)
+                .field("http_status", http_status)
+                .field("duration_ms", started_at.elapsed().as_millis()),
+        );
+
+        let next = response
+            .headers()
+            .get("link")
+            .and_then(|value| value.to_str().ok())
+            .and_then(next_link);
+        let body = response
+            .body_mut()
+            .read_to_string()
+            .map_err(|err| format!("GitHub {} {} failed: {}", method_name, path, err))?;
+        if !(200..300).contains(&http_status) {
+            let message = serde_json::from_str::<Value>(&body)
+                .ok()
+                .and_then(|value| value.get("message")?.as_str().map(str::to_string))
+                .unwrap_or_default();
+            return Err(format!(
+                "GitHub {} {} returned {}: {}",
+                method_name, path, http_status, message
+            ));
+        }
+        let value = serde_json::from_str(&body).map_err(|err| {
+            format!(
+                "GitHub {} {} returned invalid JSON: {}",
+                method_name, path, err
+            )
+        })?;
+        Ok((value, next))
+    }
+}
+
+/// The `rel="next"` URL of a `Link` header such as `<https://…?page=2>; rel="next", <…>; rel="last"`.
+fn next_link(header: &str) -> Option<String> {
+    header.split(',').find_map(|link| {
+        let (url, params) = link.split_once(';')?;
+        params
+            .split(';')
+            .any(|param| param.trim() == r#"rel="next""#)
+            .then(|| {
+                url.trim()
+                    .trim_start_matches('<')
+                    .trim_end_matches('>')
+                    .to_string()
+            })
+    })
+}
+
+fn label_names(issue: &Value) -> impl Iterator<Item = &str> {
+    issue
+        .get("labels")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(|label| label.get("name").and_then(Value::as_str))
+}
+
+/// `closed` issues are closed; open ones are `blocked` or `in_progress` when labeled so, else
+/// `open`.
+fn issue_status(issue: &Value) -> Option<TaskStatus> {
+    match issue.get("state").and_then(Value::as_str)? {
+        "closed" => Some(TaskStatus::Closed),
+        "open" => {
+            let labels: Vec<&str> = label_names(issue).collect();
+            Some(if labels.contains(&BLOCKED_LABEL) {
+                TaskStatus::Blocked
+            } else if labels.contains(&IN_PROGRESS_LABEL) {
+                TaskStatus::InProgress
+            } else {
+                TaskStatus::Open
+            })
+        }
+        other => TaskStatus::parse(other),
+    }
+}
+
+fn render_issue(issue: &Value, comments: &Value) -> String {
+    let text = |value: &Value, key: &str| {
+        value
+            .get(key)
+            .and_then(Value::as_str)
+            .unwrap_or("")
+            .trim()
+            .to_string()
+    };
+
+    let mut lines = vec![text(issue, "title")];
+    let url = text(issue, "html_url");
+    if !url.is_empty() {
+        lines.push(url);
+    }
+    let labels: Vec<&str> = label_names(issue).collect();
+    if !labels.is_empty() {
+        lines.push(format!("Labels: {}", labels.join(", ")));
+    }
+    let body = text(issue, "body");
+    if !body.is_empty() {
+        lines.push(String::new());
+        lines.push(body);
+    }
+    for comment in comments.as_array().into_iter().flatten() {
+        let author = comment
+            .get("user")
+            .map(|user| text(user, "login"))
+            .unwrap_or_default();
+        lines.push(String::new());
+        lines.push(format!(
+            "Comment by {} ({}):",
+            author,
+            text(comment, "created_at")
+        ));
+        lines.push(text(comment, "body"));
+    }
+    let mut rendered = lines.join("\n");
+    rendered.push('\n');
+    rendered
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::{TcpListener, TcpStream};
+    use std::sync::{Arc, Mutex};
+    use std::thread;
+
+    /// A local stand-in for the REST API: answers `"<METHOD> <path>"` with a canned status and
+    /// JSON body and records every request as `"<METHOD> <path and query> <body>"`. A route that
+    /// names the full target (path and query) wins over one that names only the path.
+    struct StubApi {
+        url: String,
+        requests: Arc<Mutex<Vec<String>>>,
+    }
+
+    impl StubApi {
+        fn start(routes: &[(&str, u16, Value)]) -> Self {
+            Self::start_with_links(routes, &[])
+        }
+
+        /// Like `start`, also sending `Link` headers for `"<METHOD> <target>"`; `{url}` in a
+        /// header becomes the stub's base URL.
+        fn start_with_links(routes: &[(&str, u16, Value)], links: &[(&str, &str)]) -> Self {
+            let listener = TcpListener::bind("127.0.0.1:0").expect("bind stub");
+            let url = format!("http://{}", listener.local_addr().expect("stub addr"));
+            let routes: Arc<HashMap<String, (u16, String)>> = Arc::new(
+                routes
+                    .iter()
+                    .map(|(route, code, body)| (route.to_string(), (*code, body.to_string())))
+                    .collect(),
+            );
+            let links: Arc<HashMap<String, String>> = Arc::new(
+                links
+                    .iter()
+                    .map(|(target, link)| (target.to_string(), link.replace("{url}", &url)))
+                    .collect(),
+            );
+            let requests = Arc::new(Mutex::new(Vec::new()));
+            let recorded = requests.clone();
+            thread::spawn(move || {
+                for stream in listener.incoming().flatten() {
+                    let routes = routes.clone();
+                    let links = links.clone();
+                    let recorded = recorded.clone();
+                    thread::spawn(move || serve(stream, &routes, &links, &recorded));
+                }
+            });
+            Self { url, requests }
+        }
+
+        fn requests(&self) -> Vec<String> {
+            self.requests.lock().expect("stub requests").clone()
+        }
+    }
+
+    fn serve(
+        stream: TcpStream,
+        routes: &HashMap<String, (u16, String)>,
+        links: &HashMap<String, String>,
+        recorded: &Mutex<Vec<String>>,
+    ) {
+        let mut reader = BufReader::new(stream.try_clone().expect("clone stream"));
+        let mut writer = stream;
+        loop {
+            let mut request_line = String::new();
+            if reader.read_line(&mut request_line).unwrap_or(0) == 0 {
+                return;
+            }
+            let mut content_length = 0;
+            let mut authorization = String::new();
+            loop {
+                let mut header = String::new();
+                reader.read_line(&mut header).expect("read header");
+                let header = header.trim_end();
+                if header.is_empty() {
+                    break;
+                }
+                let (name, value) = header.split_once(':').unwrap_or((header, ""));
+                match name.to_ascii_lowercase().as_str() {
+                    "content-length" => content_length = value.trim().parse().unwrap_or(0),
+                    "authorization" => authorization = value.trim().to_string(),
+                    _ => {}
+                }
+            }
+            let mut body = vec![0; content_length];
+            reader.read_exact(&mut body).expect("read body");
+
+            let mut parts = request_line.split_whitespace();
+            let method = parts.next().unwrap_or("").to_string();
+            let target = parts.next().unwrap_or("").to_string();
+            let path = target.split('?').next().unwrap_or("");
+            let mut entry = format!("{} {}", method, target);
+            if !body.is_empty() {
+                entry.push(' ');
+                // Compact JSON bodies so tests can compare them as one line.
+                let body = serde_json::from_slice::<Value>(&body)
+                    .map(|value| value.to_string())
+                    .unwrap_or_else(|_| String::from_utf8_lossy(&body).to_string());
+                entry.push_str(&body);
+            }
+            if !authorization.is_empty() {
+                entry.push_str(&format!(" [{}]", authorization));
+            }
+            recorded.lock().expect("stub requests").push(entry);
+
+            let (code, response) = routes
+                .get(&format!("{} {}", method, target))
+                .or_else(|| routes.get(&format!("{} {}", method, path)))
+                .cloned()
+                .unwrap_or((404, r#"{"message":"Not Found"}"#.to_string()));
+            let link = links
+                .get(&format!("{} {}", method, target))
+                .map(|link| format!("Link: {}\r\n", link))
+                .unwrap_or_default();
+            let reply = format!(
+                "HTTP/1.1 {} Stub\r\nContent-Type: application/json\r\n{}Content-Length: {}\r\n\r\n{}",
+                code,
+                link,
+                response.len(),
+                response
+            );
+            if writer.write_all(reply.as_bytes()).is_err() {
+                return;
+            }
+        }
+    }
+
+    fn config(stub: &StubApi) -> GithubConfig {
+        GithubConfig {
+            repo: "acme/widgets".to_string(),
+            label: "trudgeable".to_string(),
+            api_url: stub.url.clone(),
+            token_env: "TRUDGER_TEST_GITHUB_TOKEN_UNSET".to_string(),
+        }
+    }
+
+    fn task(id: &str) -> TaskId {
+        TaskId::try_from(id).expect("task id")
+    }
+
+    fn issue(number: u64, state: &str, labels: &[&str]) -> Value {
+        json!({
+            "number": number,
+            "state": state,
+            "title": format!("Issue {}", number),
+            "labels": labels.iter().map(|name| json!({ "name": name })).collect::<Vec<_>>(),
+        })
+    }
+
+    #[test]
+    fn next_task_skips_pull_requests_and_busy_issues() {
+        let mut pull_request = issue(1, "open", &["trudgeable"]);
+        pull_request["pull_request"] = json!({});
+        let stub = StubApi::start(&[(
+            "GET /repos/acme/widgets/issues",
+            200,
+            json!([
+                pull_request,
+                issue(2, "open", &["trudgeable", "in_progress"]),
+                issue(3, "open", &["trudgeable", "blocked"]),
+                issue(4, "open", &["trudgeable"]),
+            ]),
+        )]);
+        let config = config(&stub);
+        let logger = Logger::new(None);
+        let github = GitHub {
+            config: &config,
+            logger: &logger,
+        };
+
+        assert_eq!(
+            github.next_task(&[], "next-task").expect("next task"),
+            Some("4".to_string())
+        );
+        assert_eq!(
+            stub.requests(),
+            vec!["GET /repos/acme/widgets/issues?state=open&labels=trudgeable&sort=created&direction=asc&per_page=100"]
+        );
+    }
+
+    #[test]
+    fn next_task_passes_over_excluded_issues() {
+        let stub = StubApi::start(&[(
+            "GET /repos/acme/widgets/issues",
+            200,
+            json!([
+                issue(1, "open", &["trudgeable"]),
+                issue(2, "open", &["trudgeable"]),
+            ]),
+        )]);
+        let config = config(&stub);
+        let logger = Logger::new(None);
+        let github = GitHub {
+            config: &config,
+            logger: &logger,
+        };
+
+        assert_eq!(
+            github
+                .next_task(&[task("1")], "next-task")
+                .expect("next task"),
+            Some("2".to_string())
+        );
+        assert_eq!(
+            github
+                .next_task(&[task("1"), task("2")], "next-task")
+                .expect("next task"),
+            None
+        );
+    }
+
+    #[test]
+    fn next_task_follows_next_links_until_an_issue_is_open() {
+        let first = "/repos/acme/widgets/issues?state=open&labels=trudgeable&sort=created&direction=asc&per_page=100";
+        let second = "/repos/acme/widgets/issues?state=open&labels=trudgeable&per_page=100&page=2";
+        let third = "/repos/acme/widgets/issues?state=open&labels=trudgeable&per_page=100&page=3";
+        let first_link = format!(
+            r#"<{{url}}{}>; rel="next", <{{url}}{}>; rel="last""#,
+            second, third
+        );
+        let second_link = format!(r#"<{{url}}{}>; rel="next""#, third);
+        let stub = StubApi::start_with_links(
+            &[
+                (
+                    &format!("GET {}", first),
+                    200,
+                    json!([issue(1, "open", &["trudgeable", "blocked"])]),
+                ),
+                (
+                    &format!("GET {}", second),
+                    200,
+                    json!([issue(2, "open", &["trudgeable", "in_progress"])]),
+                ),
+                (
+                    &format!("GET {}", third),
+                    200,
+                    json!([issue(3, "open", &["trudgeable"])]),
+                ),
+            ],
+            &[
+                (&format!("GET {}", first), &first_link),
+                (&format!("GET {}", second), &second_link),
+            ],
+        );
+        let config = config(&stub);
+        let logger = Logger::new(None);
+        let github = GitHub {
+            config: &config,
+            logger: &logger,
+        };
+
+        assert_eq!(
+            github.next_task(&[], "next-task").expect("next task"),
+            Some("3".to_string())
+        );
+        assert_eq!(
+            stub.requests(),
+            vec![
+                format!("GET {}", first),
+                format!("GET {}", second),
+                format!("GET {}", third)
+            ]
+        );
+    }
+
+    #[test]
+    fn next_task_stops_at_the_last_page_and_rejects_foreign_links() {
+        let stub = StubApi::start_with_links(
+            &[(
+                "GET /repos/acme/widgets/issues",
+                200,
+                json!([issue(1, "open", &["trudgeable", "blocked"])]),
+            )],
+            &[(
+                "GET /repos/acme/widgets/issues?state=open&labels=trudgeable&sort=created&direction=asc&per_page=100",
+                r#"<{url}/repos/acme/widgets/issues?page=1>; rel="first""#,
+            )],
+        );
+        let mut config = config(&stub);
+        let logger = Logger::new(None);
+        let github = GitHub {
+            config: &config,
+            logger: &logger,
+        };
+        assert_eq!(github.next_task(&[], "next-task").expect("next task"), None);
+
+        let foreign = StubApi::start_with_links(
+            &[(
+                "GET /repos/acme/widgets/issues",
+                200,
+                json!([]),
+            )],
+            &[(
+                "GET /repos/acme/widgets/issues?state=open&labels=trudgeable&sort=created&direction=asc&per_page=100",
+                r#"<https://elsewhere.example/repos/acme/widgets/issues?page=2>; rel="next""#,
+            )],
+        );
+        config.api_url = foreign.url.clone();
+        let github = GitHub {
+            config: &config,
+            logger: &logger,
+        };
+        let err = github
+            .next_task(&[], "next-task")
+            .expect_err("foreign link");
+        assert!(
+            err.contains("next page outside github.api_url"),
+            "got: {err}"
+        );
+    }
+
+    #[test]
+    fn status_maps_state_and_labels() {
+        assert_eq!(issue_status(&issue(1, "open", &[])), Some(TaskStatus::Open));
+        assert_eq!(
+            issue_status(&issue(1, "open", &["in_progress"])),
+            Some(TaskStatus::InProgress)
+        );
+        assert_eq!(
+            issue_status(&issue(1, "open", &["in_progress", "blocked"])),
+            Some(TaskStatus::Blocked)
+        );
+        assert_eq!(
+            issue_status(&issue(1, "closed", &["blocked"])),
+            Some(TaskStatus::Closed)
+        );
+        assert_eq!(issue_status(&json!({})), None);
+    }
+
+    #[test]
+    fn set_status_patches_state_and_swaps_status_labels() {
+        let stub = StubApi::start(&[
+            (
+                "GET /repos/acme/widgets/issues/7",
+                200,
+                issue(7, "open", &["trudgeable", "in_progress"]),
+            ),
+            ("PATCH /repos/acme/widgets/issues/7", 200, json!({})),
+        ]);
+        let config = config(&stub);
+        let logger = Logger::new(None);
+        let github = GitHub {
+            config: &config,
+            logger: &logger,
+        };
+
+        github
+            .set_status(&task("7"), &TaskStatus::Blocked, "task")
+            .expect("blocked");
+        github
+            .set_status(&task("7"), &TaskStatus::Closed, "task")
+            .expect("closed");
+        let requests = stub.requests();
+        assert_eq!(
+            requests[1],
+            r#"PATCH /repos/acme/widgets/issues/7 {"labels":["trudgeable","blocked"],"state":"open"}"#
+        );
+        assert_eq!(
+            requests[3],
+            r#"PATCH /repos/acme/widgets/issues/7 {"labels":["trudgeable"],"state":"closed"}"#
+        );
+
+        let err = github
+            .set_status(&task("7"), &TaskStatus::Unknown("done".to_string()), "task")
+            .expect_err("unknown status");
+        assert!(err.contains("cannot be set to status done"), "got: {err}");
+    }
+
+    #[test]
+    fn show_renders_title_body_and_comments() {
+        let mut with_comments = issue(5, "open", &["trudgeable"]);
+        with_comments["html_url"] = json!("https://github.com/acme/widgets/issues/5");
+        with_comments["body"] = json!("The widget spins backwards.\n");
+        with_comments["comments"] = json!(1);
+        let stub = StubApi::start(&[
+            ("GET /repos/acme/widgets/issues/5", 200, with_comments),
+            (
+                "GET /repos/acme/widgets/issues/5/comments",
+                200,
+                json!([{
+                    "user": { "login": "octocat" },
+                    "created_at": "2026-10-01T12:00:00Z",
+                    "body": "Only on Tuesdays.",
+                }]),
+            ),
+        ]);
+        let config = config(&stub);
+        let logger = Logger::new(None);
+        let github = GitHub {
+            config: &config,
+            logger: &logger,
+        };
+
+        assert_eq!(
+            github.show(&task("5"), "task").expect("show"),
+            "Issue 5\nhttps://github.com/acme/widgets/issues/5\nLabels: trudgeable\n\nThe widget spins backwards.\n\nComment by octocat (2026-10-01T12:00:00Z):\nOnly on Tuesdays.\n"
+        );
+    }
+
+    #[test]
+    fn requests_send_the_token_and_report_api_errors() {
+        let stub = StubApi::start(&[(
+            "GET /repos/acme/widgets/issues/9",
+            403,
+            json!({ "message": "Resource not accessible by integration" }),
+        )]);
+        let mut config = config(&stub);
+        config.token_env = "TRUDGER_TEST_GITHUB_TOKEN".to_string();
+        env::set_var("TRUDGER_TEST_GITHUB_TOKEN", "secret");
+        let logger = Logger::new(None);
+        let github = GitHub {
+            config: &config,
+            logger: &logger,
+        };
+
+        let err = github
+            .status(&task("9"), "task")
+            .expect_err("forbidden should fail");
+        env::remove_var("TRUDGER_TEST_GITHUB_TOKEN");
+        assert_eq!(
+            err,
+            "GitHub GET /repos/acme/widgets/issues/9 returned 403: Resource not accessible by integration"
+        );
+        assert_eq!(
+            stub.requests(),
+            vec!["GET /repos/acme/widgets/issues/9 [Bearer secret]"]
+        );
+
+        let err = github
+            .status(&task("tr-1"), "task")
+            .expect_err("non-numeric id");
+        assert!(err.contains("task ids are issue numbers"), "got: {err}");
+    }
+}
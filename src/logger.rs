@@ -1,14 +1,139 @@
 use chrono::Utc;
+use std::fmt;
 use std::fs;
 use std::io::Write;
 use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::time::Instant;
 
+use crate::config::LogFormat;
 use crate::notification_payload::NotificationPayload;
 use crate::shell::{
     run_shell_command_status, truncate_utf8_to_bytes, CommandEnv, TRUDGER_ENV_VALUE_MAX_BYTES,
 };
+use crate::task_types::{TaskId, TaskStatus};
+
+/// A field value as the call site typed it; `log_format: jsonl` keeps numbers and flags typed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) enum FieldValue {
+    Text(String),
+    Number(i64),
+    Flag(bool),
+}
+
+impl fmt::Display for FieldValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Text(value) => f.write_str(value),
+            Self::Number(value) => write!(f, "{}", value),
+            Self::Flag(value) => write!(f, "{}", value),
+        }
+    }
+}
+
+impl From<&str> for FieldValue {
+    fn from(value: &str) -> Self {
+        Self::Text(value.to_string())
+    }
+}
+
+impl From<String> for FieldValue {
+    fn from(value: String) -> Self {
+        Self::Text(value)
+    }
+}
+
+impl From<&String> for FieldValue {
+    fn from(value: &String) -> Self {
+        Self::Text(value.clone())
+    }
+}
+
+impl From<&TaskId> for FieldValue {
+    fn from(value: &TaskId) -> Self {
+        Self::Text(value.to_string())
+    }
+}
+
+impl From<TaskId> for FieldValue {
+    fn from(value: TaskId) -> Self {
+        Self::from(&value)
+    }
+}
+
+impl From<&TaskStatus> for FieldValue {
+    fn from(value: &TaskStatus) -> Self {
+        Self::Text(value.to_string())
+    }
+}
+
+impl From<TaskStatus> for FieldValue {
+    fn from(value: TaskStatus) -> Self {
+        Self::from(&value)
+    }
+}
+
+impl From<bool> for FieldValue {
+    fn from(value: bool) -> Self {
+        Self::Flag(value)
+    }
+}
+
+macro_rules! number_field_value {
+    ($($ty:ty),*) => {$(
+        impl From<$ty> for FieldValue {
+            fn from(value: $ty) -> Self {
+                Self::Number(i64::try_from(value).unwrap_or(i64::MAX))
+            }
+        }
+    )*};
+}
+
+number_field_value!(i32, i64, u16, u32, u64, u128, usize);
+
+/// One transition: an event name and its `key=value` fields, in the order they are written.
+///
+/// The text log renders it as `event key=value ...`; an event named after its first field (such
+/// as `state=SOLVING`) is written as that field alone. `log_format: jsonl` writes the same fields
+/// as JSON members.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct Transition {
+    event: String,
+    fields: Vec<(&'static str, FieldValue)>,
+}
+
+impl Transition {
+    pub(crate) fn new(event: &str) -> Self {
+        Self {
+            event: event.to_string(),
+            fields: Vec::new(),
+        }
+    }
+
+    pub(crate) fn field(mut self, key: &'static str, value: impl Into<FieldValue>) -> Self {
+        self.fields.push((key, value.into()));
+        self
+    }
+}
+
+impl fmt::Display for Transition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let keyed = self
+            .fields
+            .first()
+            .is_some_and(|(key, _)| *key == self.event);
+        if !keyed {
+            f.write_str(&self.event)?;
+        }
+        for (index, (key, value)) in self.fields.iter().enumerate() {
+            if index > 0 || !keyed {
+                f.write_str(" ")?;
+            }
+            write!(f, "{}={}", key, value)?;
+        }
+        Ok(())
+    }
+}
 
 struct NotificationInFlightGuard<'a> {
     flag: &'a AtomicBool,
@@ -36,6 +161,7 @@ impl Drop for NotificationInFlightGuard<'_> {
 #[derive(Debug)]
 pub(crate) struct Logger {
     path: Option<PathBuf>,
+    format: LogFormat,
     disabled: AtomicBool,
     all_logs_notification_command: Option<String>,
     notification_config_path: String,
@@ -48,12 +174,16 @@ pub(crate) struct Logger {
     notification_task_show: Option<String>,
     notification_task_status: Option<String>,
     notification_task_description: String,
+
+    // Set for the loggers of parallel workers (`concurrency`); tags every line `worker=N`.
+    worker: Option<usize>,
 }
 
 impl Logger {
     pub(crate) fn new(path: Option<PathBuf>) -> Self {
         Self {
             path,
+            format: LogFormat::Text,
             disabled: AtomicBool::new(false),
             all_logs_notification_command: None,
             notification_config_path: String::new(),
@@ -64,9 +194,33 @@ impl Logger {
             notification_task_show: None,
             notification_task_status: None,
             notification_task_description: String::new(),
+            worker: None,
         }
     }
 
+    /// Logger for parallel worker `worker`: same log file and all_logs hook, its own task context.
+    pub(crate) fn for_worker(&self, worker: usize, invocation_folder: String) -> Self {
+        Self {
+            path: self.path.clone(),
+            format: self.format,
+            disabled: AtomicBool::new(self.disabled.load(Ordering::Relaxed)),
+            all_logs_notification_command: self.all_logs_notification_command.clone(),
+            notification_config_path: self.notification_config_path.clone(),
+            notification_invocation_folder: invocation_folder,
+            notification_in_flight: AtomicBool::new(false),
+            notification_run_started_at: self.notification_run_started_at,
+            notification_task_id: None,
+            notification_task_show: None,
+            notification_task_status: None,
+            notification_task_description: String::new(),
+            worker: Some(worker),
+        }
+    }
+
+    pub(crate) fn set_format(&mut self, format: LogFormat) {
+        self.format = format;
+    }
+
     pub(crate) fn configure_all_logs_notification(
         &mut self,
         hook_command: Option<&str>,
@@ -127,13 +281,13 @@ impl Logger {
         self.notification_task_description = task_description;
     }
 
-    pub(crate) fn log_transition(&self, message: &str) {
-        self.dispatch_all_logs_notification_if_needed(message);
+    pub(crate) fn log_transition(&self, transition: Transition) {
+        self.dispatch_all_logs_notification_if_needed(&transition.to_string());
 
-        self.write_transition(message);
+        self.write_transition(&transition);
     }
 
-    fn write_transition(&self, message: &str) {
+    fn write_transition(&self, transition: &Transition) {
         let Some(path) = &self.path else {
             return;
         };
@@ -141,8 +295,21 @@ impl Logger {
             return;
         }
         let ts = Utc::now().format("%Y-%m-%dT%H:%M:%SZ").to_string();
-        let sanitized = sanitize_log_value(message);
-        let line = format!("{} {}\n", ts, sanitized);
+        let message = transition.to_string();
+        let line = match (self.format, self.worker) {
+            (LogFormat::Jsonl, worker) => {
+                format!("{}\n", transition_json(&ts, worker, transition, &message))
+            }
+            (LogFormat::Text, Some(worker)) => {
+                format!(
+                    "{} worker={} {}\n",
+                    ts,
+                    worker,
+                    sanitize_log_value(&message)
+                )
+            }
+            (LogFormat::Text, None) => format!("{} {}\n", ts, sanitize_log_value(&message)),
+        };
         let mut file = match fs::OpenOptions::new().create(true).append(true).open(path) {
             Ok(file) => file,
             Err(err) => {
@@ -195,6 +362,8 @@ impl Logger {
             agent_invocation_id: None,
             completed: None,
             needs_human: None,
+            skipped: None,
+            declined: None,
             notify_event: Some("log".to_string()),
             notify_duration_ms: Some(duration_ms.to_string()),
             notify_folder: Some(folder),
@@ -203,6 +372,11 @@ impl Logger {
             notify_task_description: Some(notify_task_description.clone()),
             notify_message: Some(redacted_message.clone()),
             notify_payload_path: None,
+            agent_output: None,
+            review_result_path: None,
+            review_feedback: None,
+            review_follow_ups: None,
+            review_loop: None,
         };
 
         let payload = NotificationPayload {
@@ -224,15 +398,19 @@ impl Logger {
             message: Some(
                 truncate_utf8_to_bytes(&redacted_message, TRUDGER_ENV_VALUE_MAX_BYTES).to_string(),
             ),
+            agent_output: None,
+            profile: None,
         };
         let payload_file = match payload.write_to_temp_file() {
             Ok(file) => file,
             Err(err) => {
                 let escaped = sanitize_log_value(&err);
-                self.write_transition(&format!(
-                    "notification_hook_failed event=log task=none err={}",
-                    escaped
-                ));
+                self.write_transition(
+                    &Transition::new("notification_hook_failed")
+                        .field("event", "log")
+                        .field("task", "none")
+                        .field("err", escaped),
+                );
                 let mut stderr = std::io::stderr().lock();
                 let _ = writeln!(
                     stderr,
@@ -249,10 +427,12 @@ impl Logger {
             Ok(0) => {}
             Ok(exit_code) => {
                 // Avoid recursive notification dispatch for notification-generated transitions.
-                self.write_transition(&format!(
-                    "notification_hook_failed event=log task=none exit_code={}",
-                    exit_code
-                ));
+                self.write_transition(
+                    &Transition::new("notification_hook_failed")
+                        .field("event", "log")
+                        .field("task", "none")
+                        .field("exit_code", exit_code),
+                );
                 let mut stderr = std::io::stderr().lock();
                 let _ = writeln!(
                     stderr,
@@ -262,10 +442,12 @@ impl Logger {
             }
             Err(err) => {
                 let escaped = sanitize_log_value(&err);
-                self.write_transition(&format!(
-                    "notification_hook_failed event=log task=none err={}",
-                    escaped
-                ));
+                self.write_transition(
+                    &Transition::new("notification_hook_failed")
+                        .field("event", "log")
+                        .field("task", "none")
+                        .field("err", escaped),
+                );
                 let mut stderr = std::io::stderr().lock();
                 let _ = writeln!(stderr, "Warning: failed to run notification hook: {}.", err);
             }
@@ -291,6 +473,44 @@ impl Logger {
     }
 }
 
+/// Renders a transition as one JSON object: `ts`, `event`, its fields, and the text `message`.
+/// `task`, `exit` and `state` are renamed to `task_id`, `exit_code` and `phase`, and `task=none`
+/// is left out.
+fn transition_json(
+    ts: &str,
+    worker: Option<usize>,
+    transition: &Transition,
+    message: &str,
+) -> String {
+    let mut record = serde_json::Map::new();
+    record.insert("ts".to_string(), ts.into());
+    if let Some(worker) = worker {
+        record.insert("worker".to_string(), worker.into());
+    }
+    record.insert(
+        "event".to_string(),
+        transition.event.replace(' ', "_").into(),
+    );
+    for (key, value) in &transition.fields {
+        let key = match (*key, value) {
+            ("task", FieldValue::Text(task)) if task == "none" => continue,
+            ("task", _) => "task_id",
+            ("exit", _) => "exit_code",
+            ("state", _) => "phase",
+            (other, _) => other,
+        };
+        let value = match value {
+            FieldValue::Text(text) if key == "phase" => text.to_lowercase().into(),
+            FieldValue::Text(text) => text.as_str().into(),
+            FieldValue::Number(number) => (*number).into(),
+            FieldValue::Flag(flag) => (*flag).into(),
+        };
+        record.insert(key.to_string(), value);
+    }
+    record.insert("message".to_string(), message.into());
+    serde_json::Value::Object(record).to_string()
+}
+
 fn redact_transition_message_for_notification(message: &str) -> String {
     let mut redacted = sanitize_log_value(message);
     redacted = redact_field_between_markers(redacted, "command=", Some(" args="));
@@ -1,19 +1,30 @@
 use std::process::ExitCode;
 
 mod app;
+mod beads;
 mod cli;
 mod config;
 mod doctor;
+mod github;
 mod logger;
 mod notification_payload;
+mod openspec;
 mod prompt_defaults;
 mod prompt_install;
+mod prompt_search;
+mod prompt_template;
+mod review_result;
+mod run_journal;
 mod run_loop;
 mod shell;
+mod status;
+mod task_backend;
 mod task_types;
 mod tmux;
+mod todo_file;
 mod wizard;
 mod wizard_templates;
+mod worktree;
 
 #[cfg(test)]
 mod unit_tests;
@@ -0,0 +1,387 @@
+use regex::Regex;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+
+use crate::task_types::{TaskId, TaskStatus};
+use crate::todo_file;
+
+/// Changes moved here by `openspec archive` are done and never offered as tasks.
+const ARCHIVE_DIR: &str = "archive";
+const TASKS_FILE: &str = "tasks.md";
+/// Files fed to the agent ahead of the specs, in this order; `tasks.md` comes last.
+const CHANGE_FILES: &[&str] = &["proposal.md", "design.md"];
+
+/// `tasks.md` line recording an `in_progress` or `blocked` change. Checklists alone only say
+/// open or closed, and an HTML comment does not show up when the file is rendered.
+const STATUS_MARKER_PREFIX: &str = "<!-- trudger:";
+const STATUS_MARKER_SUFFIX: &str = "-->";
+
+/// Serializes read-modify-write cycles on `tasks.md` across parallel workers.
+static TASKS_FILE_LOCK: Mutex<()> = Mutex::new(());
+
+fn checkbox_pattern() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    PATTERN.get_or_init(|| {
+        Regex::new(r"^(\s*[-*+]\s+\[)([ xX])(\].*)$").expect("openspec checkbox pattern")
+    })
+}
+
+fn marker(line: &str) -> Option<&str> {
+    line.trim()
+        .strip_prefix(STATUS_MARKER_PREFIX)?
+        .strip_suffix(STATUS_MARKER_SUFFIX)
+        .map(str::trim)
+}
+
+/// A change's status from its `tasks.md`: `closed` once every item is checked, otherwise the
+/// marker's status or `open`. `None` when the file has no checklist items.
+fn tasks_status(content: &str) -> Option<TaskStatus> {
+    let mut checked = 0;
+    let mut unchecked = 0;
+    let mut marked = None;
+    for line in content.lines() {
+        if let Some(captures) = checkbox_pattern().captures(line) {
+            if &captures[2] == " " {
+                unchecked += 1;
+            } else {
+                checked += 1;
+            }
+        } else if let Some(value) = marker(line) {
+            marked = TaskStatus::parse(value);
+        }
+    }
+    if checked + unchecked == 0 {
+        return None;
+    }
+    if unchecked == 0 {
+        return Some(TaskStatus::Closed);
+    }
+    Some(match marked {
+        Some(status @ (TaskStatus::InProgress | TaskStatus::Blocked)) => status,
+        _ => TaskStatus::Open,
+    })
+}
+
+/// `content` rewritten for `status`: the marker is replaced (or dropped for `open`), and
+/// `closed` checks every remaining item. Checked items are never unchecked.
+fn with_status(content: &str, status: &TaskStatus) -> Result<String, String> {
+    let (marker_status, check_all) = match status {
+        TaskStatus::Ready | TaskStatus::Open => (None, false),
+        TaskStatus::InProgress | TaskStatus::Blocked => (Some(status.as_str()), false),
+        TaskStatus::Closed => (None, true),
+        TaskStatus::Skipped | TaskStatus::Unknown(_) => {
+            return Err(format!(
+                "openspec changes cannot be set to status {}",
+                status
+            ));
+        }
+    };
+    let mut lines: Vec<String> = Vec::new();
+    if let Some(value) = marker_status {
+        lines.push(format!(
+            "{} {} {}",
+            STATUS_MARKER_PREFIX, value, STATUS_MARKER_SUFFIX
+        ));
+    }
+    for line in content.lines() {
+        if marker(line).is_some() {
+            continue;
+        }
+        match checkbox_pattern().captures(line) {
+            Some(captures) if check_all && &captures[2] == " " => {
+                lines.push(format!("{}x{}", &captures[1], &captures[3]));
+            }
+            _ => lines.push(line.to_string()),
+        }
+    }
+    let mut updated = lines.join("\n");
+    if content.ends_with('\n') || content.is_empty() {
+        updated.push('\n');
+    }
+    Ok(updated)
+}
+
+/// Task operations for `backend: openspec`. Each directory under `<dir>/changes` is a task named
+/// by its change id, and its `tasks.md` checklist tracks progress.
+pub(crate) struct Openspec<'a> {
+    /// The openspec directory, holding `changes/`.
+    pub(crate) dir: &'a Path,
+}
+
+impl Openspec<'_> {
+    fn changes_dir(&self) -> PathBuf {
+        self.dir.join("changes")
+    }
+
+    fn change_dir(&self, task_id: &TaskId) -> Result<PathBuf, String> {
+        let path = self.changes_dir().join(task_id.as_str());
+        if task_id.as_str() == ARCHIVE_DIR || !path.is_dir() {
+            return Err(format!(
+                "task {} is not an openspec change in {}",
+                task_id,
+                self.changes_dir().display()
+            ));
+        }
+        Ok(path)
+    }
+
+    /// The first open change by id that is not in `exclude`. Changes without checklist items are
+    /// skipped.
+    pub(crate) fn next_task(&self, exclude: &[TaskId]) -> Result<Option<String>, String> {
+        let changes_dir = self.changes_dir();
+        let entries = fs::read_dir(&changes_dir)
+            .map_err(|err| format!("failed to read {}: {}", changes_dir.display(), err))?;
+        let mut ids: Vec<String> = entries
+            .filter_map(Result::ok)
+            .filter(|entry| entry.path().is_dir())
+            .filter_map(|entry| entry.file_name().into_string().ok())
+            .filter(|id| id != ARCHIVE_DIR && TaskId::try_from(id.as_str()).is_ok())
+            .filter(|id| !exclude.iter().any(|excluded| excluded.as_str() == id))
+            .collect();
+        ids.sort();
+        for id in ids {
+            let Ok(content) = fs::read_to_string(changes_dir.join(&id).join(TASKS_FILE)) else {
+                continue;
+            };
+            if tasks_status(&content) == Some(TaskStatus::Open) {
+                return Ok(Some(id));
+            }
+        }
+        Ok(None)
+    }
+
+    pub(crate) fn status(&self, task_id: &TaskId) -> Result<TaskStatus, String> {
+        let path = self.change_dir(task_id)?.join(TASKS_FILE);
+        tasks_status(&todo_file::read(&path)?)
+            .ok_or_else(|| format!("{} has no checklist items", path.display()))
+    }
+
+    /// The proposal title, then the proposal, design, spec deltas, and checklist of the change,
+    /// each under a `==> <path> <==` header relative to the change directory.
+    pub(crate) fn show(&self, task_id: &TaskId) -> Result<String, String> {
+        let change_dir = self.change_dir(task_id)?;
+        let mut files: Vec<PathBuf> = CHANGE_FILES.iter().map(PathBuf::from).collect();
+        files.extend(spec_files(&change_dir)?);
+        files.push(PathBuf::from(TASKS_FILE));
+
+        let mut title = None;
+        let mut sections = Vec::new();
+        for file in files {
+            let path = change_dir.join(&file);
+            if !path.is_file() {
+                continue;
+            }
+            let mut content = todo_file::read(&path)?;
+            if file.as_os_str() == TASKS_FILE {
+                content = content
+                    .lines()
+                    .filter(|line| marker(line).is_none())
+                    .collect::<Vec<_>>()
+                    .join("\n");
+            } else if title.is_none() && file.as_os_str() == CHANGE_FILES[0] {
+                title = proposal_title(&content);
+            }
+            sections.push(format!(
+                "==> {} <==\n{}",
+                file.display(),
+                content.trim_end()
+            ));
+        }
+
+        let mut rendered = match title {
+            Some(title) => format!("{}: {}\n", task_id, title),
+            None => format!("{}\n", task_id),
+        };
+        for section in sections {
+            rendered.push('\n');
+            rendered.push_str(&section);
+            rendered.push('\n');
+        }
+        Ok(rendered)
+    }
+
+    /// Records `status` in the change's `tasks.md`, replacing the file atomically.
+    pub(crate) fn update_status(
+        &self,
+        task_id: &TaskId,
+        status: &TaskStatus,
+    ) -> Result<(), String> {
+        let path = self.change_dir(task_id)?.join(TASKS_FILE);
+        let _guard = TASKS_FILE_LOCK
+            .lock()
+            .unwrap_or_else(|err| err.into_inner());
+        let content = todo_file::read(&path)?;
+        let updated = with_status(&content, status)?;
+        todo_file::replace_file(&path, &updated)
+    }
+}
+
+/// `specs/<capability>/spec.md` files of a change, sorted by capability.
+fn spec_files(change_dir: &Path) -> Result<Vec<PathBuf>, String> {
+    let specs_dir = change_dir.join("specs");
+    if !specs_dir.is_dir() {
+        return Ok(Vec::new());
+    }
+    let entries = fs::read_dir(&specs_dir)
+        .map_err(|err| format!("failed to read {}: {}", specs_dir.display(), err))?;
+    let mut capabilities: Vec<_> = entries
+        .filter_map(Result::ok)
+        .map(|entry| entry.file_name())
+        .filter(|name| specs_dir.join(name).join("spec.md").is_file())
+        .collect();
+    capabilities.sort();
+    Ok(capabilities
+        .into_iter()
+        .map(|name| Path::new("specs").join(name).join("spec.md"))
+        .collect())
+}
+
+/// The proposal's first non-empty line without its heading marks and `Change:` prefix.
+fn proposal_title(content: &str) -> Option<String> {
+    let line = content
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty())?;
+    let line = line.trim_start_matches('#').trim_start();
+    let line = line.strip_prefix("Change:").unwrap_or(line).trim();
+    (!line.is_empty()).then(|| line.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    const TASKS: &str = concat!(
+        "## 1. Config\n",
+        "- [x] 1.1 Add the key.\n",
+        "- [ ] 1.2 Validate it.\n",
+        "\n",
+        "## 2. Docs\n",
+        "  - [ ] 2.1 Update the README.\n",
+    );
+
+    fn task(id: &str) -> TaskId {
+        TaskId::try_from(id).expect("task id")
+    }
+
+    fn write(path: &Path, content: &str) {
+        fs::create_dir_all(path.parent().expect("parent")).expect("create dir");
+        fs::write(path, content).expect("write file");
+    }
+
+    #[test]
+    fn tasks_status_follows_the_checklist_and_marker() {
+        assert_eq!(tasks_status(TASKS), Some(TaskStatus::Open));
+        assert_eq!(
+            tasks_status("## 1. Done\n- [x] 1.1 Yes\n- [X] 1.2 Also\n"),
+            Some(TaskStatus::Closed)
+        );
+        assert_eq!(tasks_status("# Tasks\nNothing here.\n"), None);
+
+        let in_progress = with_status(TASKS, &TaskStatus::InProgress).expect("update");
+        assert!(in_progress.starts_with("<!-- trudger: in_progress -->\n## 1. Config\n"));
+        assert_eq!(tasks_status(&in_progress), Some(TaskStatus::InProgress));
+        let blocked = with_status(&in_progress, &TaskStatus::Blocked).expect("update");
+        assert_eq!(tasks_status(&blocked), Some(TaskStatus::Blocked));
+        assert_eq!(blocked.matches(STATUS_MARKER_PREFIX).count(), 1);
+        assert_eq!(
+            with_status(&blocked, &TaskStatus::Open).expect("update"),
+            TASKS
+        );
+
+        let closed = with_status(&blocked, &TaskStatus::Closed).expect("update");
+        assert_eq!(
+            closed,
+            "## 1. Config\n- [x] 1.1 Add the key.\n- [x] 1.2 Validate it.\n\n## 2. Docs\n  - [x] 2.1 Update the README.\n"
+        );
+        assert_eq!(tasks_status(&closed), Some(TaskStatus::Closed));
+
+        let err = with_status(TASKS, &TaskStatus::Unknown("done".to_string()))
+            .expect_err("unknown status");
+        assert!(err.contains("cannot be set to status done"), "got: {err}");
+    }
+
+    #[test]
+    fn changes_are_tasks_until_their_checklist_is_done() {
+        let temp = TempDir::new().expect("temp dir");
+        let changes = temp.path().join("changes");
+        write(&changes.join("add-a/tasks.md"), "- [x] 1.1 Done\n");
+        write(&changes.join("add-b/tasks.md"), "# Tasks\n");
+        write(&changes.join("add-c/tasks.md"), TASKS);
+        write(&changes.join("add-d/tasks.md"), TASKS);
+        write(&changes.join("archive/2026-01-01-old/tasks.md"), TASKS);
+        let openspec = Openspec { dir: temp.path() };
+
+        assert_eq!(openspec.next_task(&[]), Ok(Some("add-c".to_string())));
+        assert_eq!(
+            openspec.next_task(&[task("add-c")]),
+            Ok(Some("add-d".to_string()))
+        );
+        openspec
+            .update_status(&task("add-c"), &TaskStatus::InProgress)
+            .expect("update");
+        assert_eq!(openspec.status(&task("add-c")), Ok(TaskStatus::InProgress));
+        assert_eq!(openspec.next_task(&[]), Ok(Some("add-d".to_string())));
+        openspec
+            .update_status(&task("add-d"), &TaskStatus::Closed)
+            .expect("update");
+        assert_eq!(openspec.status(&task("add-d")), Ok(TaskStatus::Closed));
+        assert_eq!(openspec.next_task(&[]), Ok(None));
+
+        assert_eq!(openspec.status(&task("add-a")), Ok(TaskStatus::Closed));
+        let err = openspec.status(&task("add-b")).expect_err("no checklist");
+        assert!(err.ends_with("has no checklist items"), "got: {err}");
+        let err = openspec.status(&task("archive")).expect_err("archive");
+        assert!(err.contains("is not an openspec change"), "got: {err}");
+        let err = Openspec {
+            dir: &temp.path().join("missing"),
+        }
+        .next_task(&[])
+        .expect_err("missing dir");
+        assert!(err.starts_with("failed to read"), "got: {err}");
+    }
+
+    #[test]
+    fn show_includes_the_proposal_specs_and_checklist() {
+        let temp = TempDir::new().expect("temp dir");
+        let change = temp.path().join("changes/add-dark-mode");
+        write(
+            &change.join("proposal.md"),
+            "# Change: Add dark mode\n\n## Why\nEyes.\n",
+        );
+        write(&change.join("specs/ui/spec.md"), "## ADDED Requirements\n");
+        write(
+            &change.join("specs/config/spec.md"),
+            "## MODIFIED Requirements\n",
+        );
+        write(
+            &change.join("tasks.md"),
+            "<!-- trudger: in_progress -->\n- [ ] 1.1 Add the theme.\n",
+        );
+        let openspec = Openspec { dir: temp.path() };
+
+        assert_eq!(
+            openspec.show(&task("add-dark-mode")).expect("show"),
+            concat!(
+                "add-dark-mode: Add dark mode\n",
+                "\n",
+                "==> proposal.md <==\n",
+                "# Change: Add dark mode\n",
+                "\n",
+                "## Why\n",
+                "Eyes.\n",
+                "\n",
+                "==> specs/config/spec.md <==\n",
+                "## MODIFIED Requirements\n",
+                "\n",
+                "==> specs/ui/spec.md <==\n",
+                "## ADDED Requirements\n",
+                "\n",
+                "==> tasks.md <==\n",
+                "- [ ] 1.1 Add the theme.\n",
+            )
+        );
+    }
+}
@@ -0,0 +1,233 @@
+use std::path::{Path, PathBuf};
+
+use crate::config::Config;
+
+/// Repo-local prompt overrides, relative to the directory trudger starts in.
+const REPO_PROMPT_DIR: &str = ".trudger/prompts";
+/// Where prompts are found when nothing else provides one, relative to `$HOME`.
+const HOME_PROMPT_DIR: &str = ".codex/prompts";
+
+/// A place a phase's prompt file may come from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct PromptCandidate {
+    pub(crate) path: PathBuf,
+    /// `repo` (`.trudger/prompts`), `config` (`prompts` or a phase's `prompt`), or `default`
+    /// (`~/.codex/prompts`).
+    pub(crate) source: &'static str,
+}
+
+/// Where `phase`'s prompt is looked for, in order: the repo-local override, the configured
+/// `prompts` entry (the active profile's first) or custom phase `prompt`, then `~/.codex/prompts`.
+pub(crate) fn prompt_search_path(
+    config: &Config,
+    phase: &str,
+    home: &Path,
+    cwd: &Path,
+) -> Vec<PromptCandidate> {
+    let file_name = format!("{}.md", phase);
+    let mut candidates = vec![PromptCandidate {
+        path: cwd.join(REPO_PROMPT_DIR).join(&file_name),
+        source: "repo",
+    }];
+    let configured = config.prompts.get(phase).or_else(|| {
+        config
+            .custom_phase(phase)
+            .and_then(|phase| phase.prompt.as_deref())
+    });
+    if let Some(path) = configured {
+        candidates.push(PromptCandidate {
+            path: expand_prompt_path(path, home, cwd),
+            source: "config",
+        });
+    }
+    candidates.push(PromptCandidate {
+        path: home.join(HOME_PROMPT_DIR).join(&file_name),
+        source: "default",
+    });
+    candidates
+}
+
+/// The first prompt file on `phase`'s search path that exists. The repo and home locations are
+/// optional, but a configured path is not: if no repo override comes first, it must exist.
+pub(crate) fn resolve_prompt(
+    config: &Config,
+    phase: &str,
+    home: &Path,
+    cwd: &Path,
+) -> Result<PromptCandidate, String> {
+    let candidates = prompt_search_path(config, phase, home, cwd);
+    for candidate in &candidates {
+        if candidate.path.is_file() {
+            return Ok(candidate.clone());
+        }
+        if candidate.source == "config" {
+            return Err(format!(
+                "Configured prompt file for {} does not exist: {}",
+                phase,
+                candidate.path.display()
+            ));
+        }
+    }
+    let tried = candidates
+        .iter()
+        .map(|candidate| candidate.path.display().to_string())
+        .collect::<Vec<_>>()
+        .join(", ");
+    Err(format!(
+        "Missing prompt file for {}; looked in: {}",
+        phase, tried
+    ))
+}
+
+/// Configured prompt paths may start with `~/`; relative ones are taken from the start directory.
+fn expand_prompt_path(path: &Path, home: &Path, cwd: &Path) -> PathBuf {
+    match path.strip_prefix("~") {
+        Ok(rest) => home.join(rest),
+        Err(_) => cwd.join(path),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{AgentPhase, PhaseFailureAction, PipelinePhase, PromptPaths};
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn config_with_prompts(prompts: PromptPaths) -> Config {
+        let mut config = crate::config::load_config_from_str(
+            "test",
+            r#"
+default_profile: codex
+profiles:
+  codex:
+    trudge: codex
+    trudge_review: codex
+invocations:
+  codex:
+    command: "agent"
+commands:
+  task_show: "show"
+  task_status: "status"
+  task_update_status: "update"
+review_loop_limit: 3
+hooks:
+  on_completed: "done"
+  on_requires_human: "human"
+"#,
+        )
+        .expect("config should load")
+        .config;
+        config.prompts = prompts;
+        config
+    }
+
+    #[test]
+    fn resolve_prompt_prefers_repo_then_config_then_home() {
+        let temp = TempDir::new().expect("temp dir");
+        let home = temp.path().join("home");
+        let repo = temp.path().join("repo");
+        let config = config_with_prompts(PromptPaths {
+            trudge: Some(PathBuf::from("~/prompts/solve.md")),
+            trudge_review: Some(PathBuf::from("prompts/review.md")),
+        });
+        for path in [
+            home.join(".codex/prompts/trudge.md"),
+            home.join(".codex/prompts/trudge_review.md"),
+            home.join("prompts/solve.md"),
+            repo.join(".trudger/prompts/trudge.md"),
+        ] {
+            fs::create_dir_all(path.parent().expect("parent")).expect("create dir");
+            fs::write(&path, "prompt").expect("write prompt");
+        }
+
+        let found = resolve_prompt(&config, "trudge", &home, &repo).expect("trudge prompt");
+        assert_eq!(found.path, repo.join(".trudger/prompts/trudge.md"));
+        assert_eq!(found.source, "repo");
+
+        let err = resolve_prompt(&config, "trudge_review", &home, &repo)
+            .expect_err("a configured prompt that does not exist is an error");
+        assert_eq!(
+            err,
+            format!(
+                "Configured prompt file for trudge_review does not exist: {}",
+                repo.join("prompts/review.md").display()
+            )
+        );
+        fs::create_dir_all(repo.join(".trudger/prompts")).expect("create dir");
+        fs::write(repo.join(".trudger/prompts/trudge_review.md"), "prompt").expect("write");
+        let found = resolve_prompt(&config, "trudge_review", &home, &repo).expect("review prompt");
+        assert_eq!(found.source, "repo", "a repo override still wins");
+        fs::remove_file(repo.join(".trudger/prompts/trudge_review.md")).expect("remove");
+
+        fs::remove_file(repo.join(".trudger/prompts/trudge.md")).expect("remove repo prompt");
+        let found = resolve_prompt(&config, "trudge", &home, &repo).expect("trudge prompt");
+        assert_eq!(found.path, home.join("prompts/solve.md"));
+        assert_eq!(found.source, "config");
+
+        assert_eq!(
+            prompt_search_path(&config, "trudge_review", &home, &repo)
+                .into_iter()
+                .map(|candidate| candidate.path)
+                .collect::<Vec<_>>(),
+            vec![
+                repo.join(".trudger/prompts/trudge_review.md"),
+                repo.join("prompts/review.md"),
+                home.join(".codex/prompts/trudge_review.md"),
+            ]
+        );
+    }
+
+    #[test]
+    fn custom_phases_use_their_own_prompt_setting() {
+        let temp = TempDir::new().expect("temp dir");
+        let home = temp.path().join("home");
+        let mut config = config_with_prompts(PromptPaths::default());
+        config.phases = vec![
+            PipelinePhase::Custom(AgentPhase {
+                name: "plan".to_string(),
+                invocation_id: "codex".to_string(),
+                command: "agent".to_string(),
+                timeout: None,
+                retry: None,
+                prompt: Some(PathBuf::from("~/prompts/plan.md")),
+                on_failure: PhaseFailureAction::Quit,
+            }),
+            PipelinePhase::Solve,
+        ];
+
+        assert_eq!(
+            prompt_search_path(&config, "plan", &home, temp.path()),
+            vec![
+                PromptCandidate {
+                    path: temp.path().join(".trudger/prompts/plan.md"),
+                    source: "repo",
+                },
+                PromptCandidate {
+                    path: home.join("prompts/plan.md"),
+                    source: "config",
+                },
+                PromptCandidate {
+                    path: home.join(".codex/prompts/plan.md"),
+                    source: "default",
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn resolve_prompt_lists_every_candidate_when_none_exists() {
+        let temp = TempDir::new().expect("temp dir");
+        let config = config_with_prompts(PromptPaths::default());
+        let err = resolve_prompt(&config, "trudge", &temp.path().join("home"), temp.path())
+            .expect_err("expected missing prompt");
+        assert_eq!(
+            err,
+            format!(
+                "Missing prompt file for trudge; looked in: {}, {}",
+                temp.path().join(".trudger/prompts/trudge.md").display(),
+                temp.path().join("home/.codex/prompts/trudge.md").display()
+            )
+        );
+    }
+}
@@ -0,0 +1,299 @@
+use std::collections::HashMap;
+
+/// Variables a prompt template may reference with `prompt_format: template`.
+pub(crate) const TEMPLATE_VARIABLES: &[&str] = &[
+    "task.id",
+    "task.show",
+    "task.status",
+    "task.description",
+    "review.feedback",
+    "loop",
+    "phase",
+    "profile",
+];
+
+#[derive(Debug, PartialEq, Eq)]
+enum Node<'a> {
+    Text(&'a str),
+    Variable(&'a str),
+    If {
+        variable: &'a str,
+        then: Vec<Node<'a>>,
+        otherwise: Vec<Node<'a>>,
+    },
+}
+
+/// An `{{#if}}` block still waiting for its `{{/if}}`.
+struct OpenIf<'a> {
+    variable: &'a str,
+    line: usize,
+    then: Vec<Node<'a>>,
+    otherwise: Vec<Node<'a>>,
+    in_else: bool,
+}
+
+/// Where parsed nodes go: the innermost open block's current branch, or the top level.
+fn target<'t, 'a>(
+    open: &'t mut [OpenIf<'a>],
+    nodes: &'t mut Vec<Node<'a>>,
+) -> &'t mut Vec<Node<'a>> {
+    match open.last_mut() {
+        Some(frame) if frame.in_else => &mut frame.otherwise,
+        Some(frame) => &mut frame.then,
+        None => nodes,
+    }
+}
+
+/// Checks that a prompt only uses known variables and balanced `{{#if}}` blocks.
+pub(crate) fn validate_template(template: &str) -> Result<(), String> {
+    parse(template).map(|_| ())
+}
+
+/// Renders `{{name}}` variables and `{{#if name}}…{{else}}…{{/if}}` blocks; unset values are
+/// empty, and a block's condition holds when its value is not blank (`loop` only from loop 1, so
+/// `{{#if loop}}` means "this is a retry").
+pub(crate) fn render_template(
+    template: &str,
+    values: &HashMap<&str, String>,
+) -> Result<String, String> {
+    let nodes = parse(template)?;
+    let mut out = String::with_capacity(template.len());
+    render_nodes(&nodes, values, &mut out);
+    Ok(out)
+}
+
+fn render_nodes(nodes: &[Node<'_>], values: &HashMap<&str, String>, out: &mut String) {
+    for node in nodes {
+        match node {
+            Node::Text(text) => out.push_str(text),
+            Node::Variable(name) => {
+                if let Some(value) = values.get(name) {
+                    out.push_str(value);
+                }
+            }
+            Node::If {
+                variable,
+                then,
+                otherwise,
+            } => {
+                let set = values
+                    .get(variable)
+                    .is_some_and(|value| match value.trim() {
+                        "" => false,
+                        "0" => *variable != "loop",
+                        _ => true,
+                    });
+                render_nodes(if set { then } else { otherwise }, values, out);
+            }
+        }
+    }
+}
+
+fn parse(template: &str) -> Result<Vec<Node<'_>>, String> {
+    let mut nodes = Vec::new();
+    let mut open: Vec<OpenIf<'_>> = Vec::new();
+    let mut cursor = 0;
+
+    while let Some(offset) = template[cursor..].find("{{") {
+        let tag_start = cursor + offset;
+        let line = line_number(template, tag_start);
+        let tag_end = template[tag_start..]
+            .find("}}")
+            .map(|end| tag_start + end + 2)
+            .ok_or_else(|| format!("prompt template line {}: unclosed `{{{{`", line))?;
+        let tag = template[tag_start + 2..tag_end - 2].trim();
+
+        let block = tag.starts_with('#') || tag.starts_with('/') || tag == "else";
+        // A block tag alone on its line takes the whole line with it.
+        let (text_end, next) = match standalone_line(template, tag_start, tag_end) {
+            Some(bounds) if block => bounds,
+            _ => (tag_start, tag_end),
+        };
+        if text_end > cursor {
+            target(&mut open, &mut nodes).push(Node::Text(&template[cursor..text_end]));
+        }
+        cursor = next;
+
+        if let Some(variable) = tag.strip_prefix("#if") {
+            let variable = known_variable(variable.trim(), line)?;
+            open.push(OpenIf {
+                variable,
+                line,
+                then: Vec::new(),
+                otherwise: Vec::new(),
+                in_else: false,
+            });
+        } else if tag == "else" {
+            let frame = open
+                .last_mut()
+                .filter(|frame| !frame.in_else)
+                .ok_or_else(|| {
+                    format!(
+                        "prompt template line {}: `{{{{else}}}}` outside an `{{{{#if}}}}` block",
+                        line
+                    )
+                })?;
+            frame.in_else = true;
+        } else if tag == "/if" {
+            let frame = open.pop().ok_or_else(|| {
+                format!(
+                    "prompt template line {}: `{{{{/if}}}}` without an `{{{{#if}}}}`",
+                    line
+                )
+            })?;
+            target(&mut open, &mut nodes).push(Node::If {
+                variable: frame.variable,
+                then: frame.then,
+                otherwise: frame.otherwise,
+            });
+        } else if tag.starts_with('#') || tag.starts_with('/') {
+            return Err(format!(
+                "prompt template line {}: unsupported block `{{{{{}}}}}`",
+                line, tag
+            ));
+        } else {
+            let variable = known_variable(tag, line)?;
+            target(&mut open, &mut nodes).push(Node::Variable(variable));
+        }
+    }
+
+    if let Some(frame) = open.last() {
+        return Err(format!(
+            "prompt template line {}: `{{{{#if {}}}}}` is missing its `{{{{/if}}}}`",
+            frame.line, frame.variable
+        ));
+    }
+    if cursor < template.len() {
+        nodes.push(Node::Text(&template[cursor..]));
+    }
+    Ok(nodes)
+}
+
+fn known_variable(name: &str, line: usize) -> Result<&str, String> {
+    if TEMPLATE_VARIABLES.contains(&name) {
+        return Ok(name);
+    }
+    Err(format!(
+        "prompt template line {}: unknown variable `{}` (expected one of {})",
+        line,
+        name,
+        TEMPLATE_VARIABLES.join(", ")
+    ))
+}
+
+/// When only whitespace shares the tag's line, returns where the line starts and where the next
+/// line begins.
+fn standalone_line(template: &str, tag_start: usize, tag_end: usize) -> Option<(usize, usize)> {
+    let line_start = template[..tag_start]
+        .rfind('\n')
+        .map_or(0, |index| index + 1);
+    if !template[line_start..tag_start].trim().is_empty() {
+        return None;
+    }
+    let rest = &template[tag_end..];
+    let line_end = rest
+        .find('\n')
+        .map_or(template.len(), |index| tag_end + index + 1);
+    if !template[tag_end..line_end].trim().is_empty() {
+        return None;
+    }
+    Some((line_start, line_end))
+}
+
+fn line_number(template: &str, offset: usize) -> usize {
+    template[..offset].matches('\n').count() + 1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn values(pairs: &[(&'static str, &str)]) -> HashMap<&'static str, String> {
+        pairs
+            .iter()
+            .map(|(name, value)| (*name, value.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn render_template_substitutes_variables() {
+        let rendered = render_template(
+            "Task {{task.id}} ({{ task.status }}), loop {{loop}}:\n{{task.show}}",
+            &values(&[
+                ("task.id", "tr-1"),
+                ("task.status", "in_progress"),
+                ("loop", "0"),
+                ("task.show", "Fix the login form"),
+            ]),
+        )
+        .expect("render");
+        assert_eq!(
+            rendered,
+            "Task tr-1 (in_progress), loop 0:\nFix the login form"
+        );
+
+        let rendered = render_template("[{{review.feedback}}]", &values(&[])).expect("render");
+        assert_eq!(rendered, "[]", "unset variables render empty");
+    }
+
+    #[test]
+    fn render_template_handles_conditionals() {
+        let template = "Solve {{task.id}}.\n{{#if review.feedback}}\nThe last review said:\n{{review.feedback}}\n{{else}}\nFirst attempt.\n{{/if}}\nDone.";
+        let rendered = render_template(
+            template,
+            &values(&[("task.id", "tr-1"), ("review.feedback", "add a test")]),
+        )
+        .expect("render");
+        assert_eq!(
+            rendered,
+            "Solve tr-1.\nThe last review said:\nadd a test\nDone."
+        );
+
+        let rendered = render_template(
+            template,
+            &values(&[("task.id", "tr-1"), ("review.feedback", "  ")]),
+        )
+        .expect("render");
+        assert_eq!(rendered, "Solve tr-1.\nFirst attempt.\nDone.");
+
+        let rendered = render_template(
+            "a{{#if loop}}b{{#if phase}}c{{/if}}d{{/if}}e",
+            &values(&[("loop", "1")]),
+        )
+        .expect("render");
+        assert_eq!(rendered, "abde", "inline and nested blocks");
+
+        let template = "{{#if loop}}Retry {{loop}}.{{else}}First attempt.{{/if}}";
+        let rendered = render_template(template, &values(&[("loop", "0")])).expect("render");
+        assert_eq!(rendered, "First attempt.", "loop 0 is not a retry");
+        let rendered = render_template(template, &values(&[("loop", "2")])).expect("render");
+        assert_eq!(rendered, "Retry 2.");
+    }
+
+    #[test]
+    fn validate_template_reports_the_offending_line() {
+        let cases = [
+            ("Hi {{task.name}}", "line 1: unknown variable `task.name`"),
+            (
+                "x\n{{#if loop}}\ny",
+                "line 2: `{{#if loop}}` is missing its `{{/if}}`",
+            ),
+            ("{{/if}}", "line 1: `{{/if}}` without an `{{#if}}`"),
+            ("{{else}}", "line 1: `{{else}}` outside an `{{#if}}` block"),
+            (
+                "{{#if loop}}a{{else}}b{{else}}c{{/if}}",
+                "`{{else}}` outside an `{{#if}}` block",
+            ),
+            (
+                "{{#each loop}}{{/each}}",
+                "unsupported block `{{#each loop}}`",
+            ),
+            ("ok\n\n{{task.id", "line 3: unclosed `{{`"),
+        ];
+        for (template, expected) in cases {
+            let err = validate_template(template).expect_err(template);
+            assert!(err.contains(expected), "{template:?}: got {err}");
+        }
+        validate_template("Plain prompt without tags").expect("plain prompt is valid");
+    }
+}
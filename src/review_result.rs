@@ -0,0 +1,133 @@
+use serde::Deserialize;
+use std::fmt;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::Path;
+
+use crate::task_types::TaskStatus;
+
+/// What the review decided for the task (`verdict` in the review result file).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum ReviewVerdict {
+    Complete,
+    Retry,
+    Escalate,
+    Skip,
+}
+
+impl ReviewVerdict {
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            Self::Complete => "complete",
+            Self::Retry => "retry",
+            Self::Escalate => "escalate",
+            Self::Skip => "skip",
+        }
+    }
+
+    /// The post-review status the run loop acts on for this verdict.
+    pub(crate) fn outcome_status(self) -> TaskStatus {
+        match self {
+            Self::Complete => TaskStatus::Closed,
+            Self::Retry => TaskStatus::InProgress,
+            Self::Escalate => TaskStatus::Blocked,
+            Self::Skip => TaskStatus::Skipped,
+        }
+    }
+}
+
+impl fmt::Display for ReviewVerdict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// JSON a review invocation may write to `TRUDGER_REVIEW_RESULT_PATH`. Unknown fields are ignored.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+pub(crate) struct ReviewResult {
+    pub(crate) verdict: ReviewVerdict,
+    #[serde(default)]
+    pub(crate) reason: Option<String>,
+    #[serde(default)]
+    pub(crate) follow_up_tasks: Vec<String>,
+    #[serde(default)]
+    pub(crate) feedback_for_next_solve: Option<String>,
+}
+
+/// Reads the review result file; a missing or blank file means the review wrote no verdict.
+pub(crate) fn read_review_result(path: &Path) -> Result<Option<ReviewResult>, String> {
+    let content = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(err) if err.kind() == ErrorKind::NotFound => return Ok(None),
+        Err(err) => {
+            return Err(format!(
+                "Failed to read review result {}: {}",
+                path.display(),
+                err
+            ))
+        }
+    };
+    if content.trim().is_empty() {
+        return Ok(None);
+    }
+    serde_json::from_str(&content)
+        .map(Some)
+        .map_err(|err| format!("Failed to parse review result {}: {}", path.display(), err))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn read_review_result_parses_the_verdict_file() {
+        let temp = TempDir::new().expect("temp dir");
+        let path = temp.path().join("review-result.json");
+
+        fs::write(
+            &path,
+            r#"{"verdict":"retry","reason":"tests fail","follow_up_tasks":["Add docs"],"feedback_for_next_solve":"Run cargo test","score":3}"#,
+        )
+        .expect("write result");
+        let result = read_review_result(&path).expect("read").expect("result");
+        assert_eq!(
+            result,
+            ReviewResult {
+                verdict: ReviewVerdict::Retry,
+                reason: Some("tests fail".to_string()),
+                follow_up_tasks: vec!["Add docs".to_string()],
+                feedback_for_next_solve: Some("Run cargo test".to_string()),
+            }
+        );
+
+        fs::write(&path, r#"{"verdict":"complete"}"#).expect("write result");
+        let result = read_review_result(&path).expect("read").expect("result");
+        assert_eq!(result.verdict.outcome_status(), TaskStatus::Closed);
+        assert!(result.follow_up_tasks.is_empty());
+    }
+
+    #[test]
+    fn read_review_result_treats_missing_or_blank_files_as_no_verdict() {
+        let temp = TempDir::new().expect("temp dir");
+        let path = temp.path().join("review-result.json");
+        assert_eq!(read_review_result(&path).expect("read"), None);
+
+        fs::write(&path, "\n").expect("write result");
+        assert_eq!(read_review_result(&path).expect("read"), None);
+    }
+
+    #[test]
+    fn read_review_result_rejects_unknown_verdicts() {
+        let temp = TempDir::new().expect("temp dir");
+        let path = temp.path().join("review-result.json");
+
+        fs::write(&path, r#"{"verdict":"maybe"}"#).expect("write result");
+        let err = read_review_result(&path).expect_err("expected parse error");
+        assert!(err.contains("Failed to parse review result"), "{err}");
+
+        fs::write(&path, r#"{"reason":"no verdict"}"#).expect("write result");
+        assert!(read_review_result(&path).is_err());
+    }
+}
@@ -0,0 +1,247 @@
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::fs;
+use std::io::{ErrorKind, Write};
+use std::path::Path;
+use tempfile::NamedTempFile;
+
+use crate::task_types::{TaskId, WorkspaceTask};
+
+const RUN_JOURNAL_VERSION: u32 = 1;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum JournalPhase {
+    Solving,
+    Reviewing,
+}
+
+impl JournalPhase {
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            Self::Solving => "solving",
+            Self::Reviewing => "reviewing",
+        }
+    }
+}
+
+impl fmt::Display for JournalPhase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// The task a run was working on when the journal was last checkpointed.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct JournalTask {
+    pub(crate) task_id: TaskId,
+    pub(crate) phase: JournalPhase,
+    pub(crate) review_loop: u64,
+    /// Pipeline phase (`trudge` or a custom phase) a `solving` checkpoint was taken in.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) pipeline_phase: Option<String>,
+    /// Workspace the task belongs to when the config defines `workspaces`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) workspace: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct RunJournal {
+    pub(crate) version: u32,
+    pub(crate) updated_at: String,
+    pub(crate) config_path: String,
+    #[serde(default)]
+    pub(crate) profile: Option<String>,
+    #[serde(default)]
+    pub(crate) manual_tasks: Vec<TaskId>,
+    #[serde(default)]
+    pub(crate) completed_tasks: Vec<TaskId>,
+    #[serde(default)]
+    pub(crate) needs_human_tasks: Vec<TaskId>,
+    #[serde(default)]
+    pub(crate) skipped_tasks: Vec<WorkspaceTask>,
+    #[serde(default)]
+    pub(crate) declined_tasks: Vec<WorkspaceTask>,
+    #[serde(default)]
+    pub(crate) current_task: Option<JournalTask>,
+}
+
+impl RunJournal {
+    pub(crate) fn new(config_path: String, profile: Option<String>) -> Self {
+        Self {
+            version: RUN_JOURNAL_VERSION,
+            updated_at: Utc::now().format("%Y-%m-%dT%H:%M:%SZ").to_string(),
+            config_path,
+            profile,
+            manual_tasks: Vec::new(),
+            completed_tasks: Vec::new(),
+            needs_human_tasks: Vec::new(),
+            skipped_tasks: Vec::new(),
+            declined_tasks: Vec::new(),
+            current_task: None,
+        }
+    }
+}
+
+pub(crate) fn read_run_journal(path: &Path) -> Result<Option<RunJournal>, String> {
+    let content = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(err) if err.kind() == ErrorKind::NotFound => return Ok(None),
+        Err(err) => {
+            return Err(format!(
+                "Failed to read run journal {}: {}",
+                path.display(),
+                err
+            ))
+        }
+    };
+    let journal: RunJournal = serde_json::from_str(&content)
+        .map_err(|err| format!("Failed to parse run journal {}: {}", path.display(), err))?;
+    if journal.version != RUN_JOURNAL_VERSION {
+        return Err(format!(
+            "Unsupported run journal version {} in {} (expected {})",
+            journal.version,
+            path.display(),
+            RUN_JOURNAL_VERSION
+        ));
+    }
+    Ok(Some(journal))
+}
+
+pub(crate) fn write_run_journal(path: &Path, journal: &RunJournal) -> Result<(), String> {
+    let parent = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    fs::create_dir_all(parent)
+        .map_err(|err| format!("failed to create {}: {}", parent.display(), err))?;
+
+    // Write to a sibling temp file and rename so a crash never leaves a torn journal behind.
+    let mut file = NamedTempFile::new_in(parent).map_err(|err| {
+        format!(
+            "failed to create temp file in {}: {}",
+            parent.display(),
+            err
+        )
+    })?;
+    serde_json::to_writer_pretty(&mut file, journal)
+        .map_err(|err| format!("failed to serialize run journal: {}", err))?;
+    file.write_all(b"\n")
+        .map_err(|err| format!("failed to write run journal: {}", err))?;
+    file.persist(path)
+        .map_err(|err| format!("failed to replace {}: {}", path.display(), err.error))?;
+    Ok(())
+}
+
+pub(crate) fn remove_run_journal(path: &Path) -> Result<(), String> {
+    match fs::remove_file(path) {
+        Ok(()) => Ok(()),
+        Err(err) if err.kind() == ErrorKind::NotFound => Ok(()),
+        Err(err) => Err(format!("failed to remove {}: {}", path.display(), err)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn task(id: &str) -> TaskId {
+        TaskId::try_from(id).expect("task id")
+    }
+
+    #[test]
+    fn run_journal_round_trips_through_disk() {
+        let temp = TempDir::new().expect("temp dir");
+        let path = temp.path().join(".trudger").join("journal.json");
+
+        let mut journal = RunJournal::new("/tmp/trudger.yml".to_string(), Some("codex".into()));
+        journal.manual_tasks = vec![task("tr-3")];
+        journal.completed_tasks = vec![task("tr-1")];
+        journal.needs_human_tasks = vec![task("tr-2")];
+        journal.skipped_tasks = vec![WorkspaceTask {
+            task_id: task("tr-5"),
+            workspace: Some("api".to_string()),
+        }];
+        journal.declined_tasks = vec![WorkspaceTask {
+            task_id: task("tr-6"),
+            workspace: None,
+        }];
+        journal.current_task = Some(JournalTask {
+            task_id: task("tr-4"),
+            phase: JournalPhase::Reviewing,
+            review_loop: 2,
+            pipeline_phase: None,
+            workspace: None,
+        });
+
+        write_run_journal(&path, &journal).expect("write journal");
+        let contents = fs::read_to_string(&path).expect("read journal");
+        assert!(contents.contains("\"phase\": \"reviewing\""));
+        assert!(contents.contains("\"task_id\": \"tr-4\""));
+
+        let loaded = read_run_journal(&path).expect("read").expect("journal");
+        assert_eq!(loaded, journal);
+
+        remove_run_journal(&path).expect("remove journal");
+        assert!(!path.exists());
+        remove_run_journal(&path).expect("removing a missing journal is a no-op");
+    }
+
+    #[test]
+    fn read_run_journal_returns_none_when_missing() {
+        let temp = TempDir::new().expect("temp dir");
+        let loaded = read_run_journal(&temp.path().join("journal.json")).expect("read");
+        assert_eq!(loaded, None);
+    }
+
+    #[test]
+    fn read_run_journal_rejects_invalid_contents() {
+        let temp = TempDir::new().expect("temp dir");
+        let path = temp.path().join("journal.json");
+
+        fs::write(&path, "not json").expect("write journal");
+        let err = read_run_journal(&path).expect_err("expected parse error");
+        assert!(err.contains("Failed to parse run journal"));
+
+        let mut journal = RunJournal::new("config".to_string(), None);
+        journal.version = RUN_JOURNAL_VERSION + 1;
+        fs::write(&path, serde_json::to_string(&journal).expect("json")).expect("write");
+        let err = read_run_journal(&path).expect_err("expected version error");
+        assert!(err.contains("Unsupported run journal version"));
+
+        fs::write(
+            &path,
+            r#"{"version":1,"updated_at":"","config_path":"","current_task":{"task_id":"-bad","phase":"solving","review_loop":0}}"#,
+        )
+        .expect("write");
+        let err = read_run_journal(&path).expect_err("expected task id error");
+        assert!(err.contains("task_id must start"));
+    }
+
+    #[test]
+    fn read_run_journal_reports_io_errors() {
+        let temp = TempDir::new().expect("temp dir");
+        let err = read_run_journal(temp.path()).expect_err("expected read error");
+        assert!(err.contains("Failed to read run journal"));
+    }
+
+    #[test]
+    fn write_run_journal_reports_unwritable_parent() {
+        let temp = TempDir::new().expect("temp dir");
+        let blocker = temp.path().join("blocker");
+        fs::write(&blocker, "file").expect("write blocker");
+
+        let journal = RunJournal::new("config".to_string(), None);
+        let err = write_run_journal(&blocker.join("journal.json"), &journal)
+            .expect_err("expected mkdir error");
+        assert!(err.contains("failed to create"));
+    }
+
+    #[test]
+    fn journal_phase_display_matches_serialized_name() {
+        assert_eq!(JournalPhase::Solving.to_string(), "solving");
+        assert_eq!(JournalPhase::Reviewing.to_string(), "reviewing");
+    }
+}
@@ -1,78 +1,128 @@
+use std::collections::HashMap;
 use std::env;
-use std::path::PathBuf;
+use std::fmt;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
 use std::process::ExitCode;
 use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::Arc;
-use std::sync::{Mutex, OnceLock};
-use std::time::Instant;
+use std::sync::{mpsc, Arc};
+use std::thread;
+use std::time::{Duration, Instant};
 
 use serde_json::Value;
+use tempfile::NamedTempFile;
 
-use crate::config::{Config, NotificationScope};
-use crate::logger::{sanitize_log_value, Logger};
+use crate::app::load_agent_prompts;
+use crate::config::{
+    AgentPhase, AgentProfile, AgentTimeout, Backend, Config, NotificationScope, PhaseFailureAction,
+    PipelinePhase, PromptFormat, RetryPolicy, TimeoutAction, Workspace, WorkspaceOrder,
+};
+use crate::logger::{sanitize_log_value, Logger, Transition};
 use crate::notification_payload::NotificationPayload;
+use crate::prompt_template::render_template;
+use crate::review_result::{read_review_result, ReviewResult};
+use crate::run_journal::{
+    remove_run_journal, write_run_journal, JournalPhase, JournalTask, RunJournal,
+};
 use crate::shell::{
-    run_shell_command_capture, run_shell_command_status, truncate_utf8_to_bytes, CommandEnv,
-    CommandResult, TRUDGER_ENV_VALUE_MAX_BYTES,
+    run_shell_command_capture, run_shell_command_status, run_shell_command_status_with_deadline,
+    run_shell_command_tee, truncate_utf8_to_bytes, CommandDeadline, CommandEnv, CommandResult,
+    CommandStatus, TRUDGER_ENV_VALUE_MAX_BYTES,
 };
-use crate::task_types::{Phase, TaskId, TaskStatus};
+use crate::task_backend::{NextTaskOutput, TaskBackend};
+use crate::task_types::{Phase, TaskId, TaskStatus, WorkspaceTask};
 use crate::tmux::TmuxState;
+use crate::worktree::{
+    create_worktree, path_safe_task_id, remove_worktree, repository_root, worktree_root,
+};
+
+// How often a retry backoff checks for Ctrl-C.
+const RETRY_SLEEP_POLL_INTERVAL: Duration = Duration::from_millis(100);
+// How much of the end of a transcript is passed on as feedback when nothing better is available.
+const FEEDBACK_TRANSCRIPT_TAIL_BYTES: usize = 16 * 1024;
 
 #[derive(Debug)]
 pub(crate) struct RuntimeState {
     pub(crate) config: Config,
     pub(crate) config_path: PathBuf,
     pub(crate) invocation_folder: String,
+    /// Name of the workspace whose task is being processed (`workspaces` in config).
+    pub(crate) workspace: Option<String>,
+    /// Working directory for commands and hooks; `None` inherits trudger's own.
+    pub(crate) cwd: Option<PathBuf>,
     pub(crate) prompt_trudge: String,
     pub(crate) prompt_review: String,
+    /// Prompt text for each custom phase in the pipeline, by phase name.
+    pub(crate) phase_prompts: HashMap<String, String>,
     pub(crate) logger: Logger,
     pub(crate) tmux: TmuxState,
     pub(crate) interrupt_flag: Arc<AtomicBool>,
     pub(crate) manual_tasks: Vec<TaskId>,
     pub(crate) completed_tasks: Vec<TaskId>,
     pub(crate) needs_human_tasks: Vec<TaskId>,
+    pub(crate) skipped_tasks: Vec<WorkspaceTask>,
+    /// Tasks the review declined as out of scope; unlike skipped tasks they stay out of the rest
+    /// of the run, watch idles included.
+    pub(crate) declined_tasks: Vec<WorkspaceTask>,
     pub(crate) current_task_id: Option<TaskId>,
     pub(crate) current_task_show: Option<String>,
     pub(crate) current_task_status: Option<TaskStatus>,
     pub(crate) run_started_at: Instant,
     pub(crate) current_task_started_at: Option<Instant>,
     pub(crate) run_exit_code: i32,
+    /// This run's directory under `agent_output_dir`; `None` leaves agent output untouched.
+    pub(crate) agent_output_dir: Option<PathBuf>,
+    /// Transcript of the current task's latest agent invocation (`TRUDGER_AGENT_OUTPUT`).
+    pub(crate) current_agent_output: Option<PathBuf>,
+    /// Verdict file handed to the current task's latest review (`TRUDGER_REVIEW_RESULT_PATH`).
+    pub(crate) current_review_result: Option<NamedTempFile>,
+    /// Why the latest review sent the task back (`TRUDGER_REVIEW_FEEDBACK`).
+    pub(crate) current_review_feedback: Option<String>,
+    /// `follow_up_tasks` from the latest review verdict (`TRUDGER_REVIEW_FOLLOW_UPS`).
+    pub(crate) current_review_follow_ups: Vec<String>,
+    /// Solve/review loop the current task is in (`TRUDGER_REVIEW_LOOP`).
+    pub(crate) current_review_loop: Option<u64>,
+    /// Agent invocations that failed in a row, counted toward `fallback_after_failures`.
+    pub(crate) agent_failures: u64,
+    /// The run's own profile while the current task runs with a routed one (`profile_rules`).
+    pub(crate) routed_from: Option<ProfileSnapshot>,
+    /// Profile whose agent commands run now (`TRUDGER_PROFILE`, `TRUDGER_INVOCATION_ID`).
+    pub(crate) agent_context: AgentInvocationContext,
+    pub(crate) resume: Option<JournalTask>,
+    /// Where tasks come from and how their status moves; see `task_backend`.
+    pub(crate) task_backend: Arc<dyn TaskBackend>,
 }
 
+/// A profile's agent settings, prompts, and invocation context, to switch back to.
+#[derive(Debug)]
+pub(crate) struct ProfileSnapshot {
+    config: Config,
+    prompt_trudge: String,
+    prompt_review: String,
+    phase_prompts: HashMap<String, String>,
+    context: AgentInvocationContext,
+}
+
+/// The active profile and the invocation ids its agent commands run under.
 #[derive(Debug, Default, Clone)]
-struct AgentInvocationContext {
+pub(crate) struct AgentInvocationContext {
     profile: Option<String>,
     solve_invocation_id: Option<String>,
     review_invocation_id: Option<String>,
 }
 
-static AGENT_INVOCATION_CONTEXT: OnceLock<Mutex<AgentInvocationContext>> = OnceLock::new();
-
-fn agent_invocation_context() -> &'static Mutex<AgentInvocationContext> {
-    AGENT_INVOCATION_CONTEXT.get_or_init(|| Mutex::new(AgentInvocationContext::default()))
-}
-
-pub(crate) fn set_agent_invocation_context(
-    profile: String,
-    solve_invocation_id: String,
-    review_invocation_id: String,
-) {
-    let mut context = agent_invocation_context()
-        .lock()
-        .expect("invocation context mutex");
-    *context = AgentInvocationContext {
-        profile: Some(profile),
-        solve_invocation_id: Some(solve_invocation_id),
-        review_invocation_id: Some(review_invocation_id),
-    };
-}
-
-#[cfg(test)]
-pub(crate) fn reset_agent_invocation_context() {
-    let mut context = agent_invocation_context()
-        .lock()
-        .expect("invocation context mutex");
-    *context = AgentInvocationContext::default();
+impl AgentInvocationContext {
+    pub(crate) fn new(
+        profile: String,
+        solve_invocation_id: String,
+        review_invocation_id: String,
+    ) -> Self {
+        Self {
+            profile: Some(profile),
+            solve_invocation_id: Some(solve_invocation_id),
+            review_invocation_id: Some(review_invocation_id),
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -96,7 +146,7 @@ pub(crate) fn quit(logger: &Logger, reason: &str, code: i32) -> Quit {
     } else {
         sanitize_log_value(reason)
     };
-    logger.log_transition(&format!("quit reason={}", sanitized));
+    logger.log_transition(Transition::new("quit").field("reason", sanitized));
     Quit {
         code,
         reason: reason.to_string(),
@@ -104,6 +154,27 @@ pub(crate) fn quit(logger: &Logger, reason: &str, code: i32) -> Quit {
 }
 
 pub(crate) fn validate_config(config: &Config, manual_tasks: &[TaskId]) -> Result<(), String> {
+    if !config.workspaces.is_empty() {
+        if !manual_tasks.is_empty() {
+            return Err(
+                "-t/--task is not supported with workspaces; each workspace selects its own tasks via commands.next_task."
+                    .to_string(),
+            );
+        }
+        for workspace in &config.workspaces {
+            validate_config(&workspace.config, &[])
+                .map_err(|err| format!("workspace {}: {}", workspace.name, err))?;
+            if !workspace.path.is_dir() {
+                return Err(format!(
+                    "workspace {}: path is not a directory: {}",
+                    workspace.name,
+                    workspace.path.display()
+                ));
+            }
+        }
+        return Ok(());
+    }
+
     if config.agent_command.trim().is_empty() {
         return Err("agent_command must not be empty.".to_string());
     }
@@ -112,7 +183,9 @@ pub(crate) fn validate_config(config: &Config, manual_tasks: &[TaskId]) -> Resul
     }
 
     let next_task = config.commands.next_task.as_deref().unwrap_or("").trim();
-    if next_task.is_empty() {
+    if config.backend != Backend::Shell {
+        // The backend provides every task operation; `commands` is rejected at load time.
+    } else if next_task.is_empty() {
         if manual_tasks.is_empty() {
             return Err(
                 "commands.next_task must not be empty.\nMigration: add commands.next_task to your config (required when no manual task IDs). See README.md or sample_configuration/*.yml.".to_string(),
@@ -123,14 +196,16 @@ pub(crate) fn validate_config(config: &Config, manual_tasks: &[TaskId]) -> Resul
         );
     }
 
-    if config.commands.task_show.trim().is_empty() {
-        return Err("commands.task_show must not be empty.".to_string());
-    }
-    if config.commands.task_status.trim().is_empty() {
-        return Err("commands.task_status must not be empty.".to_string());
-    }
-    if config.commands.task_update_status.trim().is_empty() {
-        return Err("commands.task_update_status must not be empty.".to_string());
+    if config.backend == Backend::Shell {
+        if config.commands.task_show.trim().is_empty() {
+            return Err("commands.task_show must not be empty.".to_string());
+        }
+        if config.commands.task_status.trim().is_empty() {
+            return Err("commands.task_status must not be empty.".to_string());
+        }
+        if config.commands.task_update_status.trim().is_empty() {
+            return Err("commands.task_update_status must not be empty.".to_string());
+        }
     }
     if config.hooks.on_completed.trim().is_empty() {
         return Err("hooks.on_completed must not be empty.".to_string());
@@ -148,6 +223,8 @@ pub(crate) enum NotificationEvent {
     RunEnd,
     TaskStart,
     TaskEnd,
+    AgentRetry,
+    ProfileFallback,
 }
 
 impl NotificationEvent {
@@ -157,6 +234,8 @@ impl NotificationEvent {
             Self::RunEnd => "run_end",
             Self::TaskStart => "task_start",
             Self::TaskEnd => "task_end",
+            Self::AgentRetry => "agent_retry",
+            Self::ProfileFallback => "profile_fallback",
         }
     }
 }
@@ -229,6 +308,30 @@ fn extract_task_description_from_task_show(task_show: &str) -> Option<String> {
     first_non_empty_trimmed_line(task_show)
 }
 
+/// Labels from JSON `task_show` output: a `labels` list of names or of objects with a `name`.
+fn task_labels_from_task_show(task_show: &str) -> Vec<String> {
+    let Ok(value) = serde_json::from_str::<Value>(task_show.trim()) else {
+        return Vec::new();
+    };
+    let task = match &value {
+        Value::Array(entries) => entries.first(),
+        value => Some(value),
+    };
+    let Some(Value::Array(labels)) = task.and_then(|task| task.get("labels")) else {
+        return Vec::new();
+    };
+    labels
+        .iter()
+        .filter_map(|label| match label {
+            Value::String(name) => Some(name.clone()),
+            label => label
+                .get("name")
+                .and_then(Value::as_str)
+                .map(str::to_string),
+        })
+        .collect()
+}
+
 #[allow(clippy::too_many_arguments)]
 fn build_command_env(
     state: &RuntimeState,
@@ -261,9 +364,29 @@ fn build_command_env(
     } else {
         Some(join_task_ids(&state.needs_human_tasks))
     };
+    let skipped = if state.skipped_tasks.is_empty() {
+        None
+    } else {
+        let skipped = state
+            .skipped_tasks
+            .iter()
+            .map(|task| task.task_id.clone())
+            .collect::<Vec<_>>();
+        Some(join_task_ids(&skipped))
+    };
+    let declined = if state.declined_tasks.is_empty() {
+        None
+    } else {
+        let declined = state
+            .declined_tasks
+            .iter()
+            .map(|task| task.task_id.clone())
+            .collect::<Vec<_>>();
+        Some(join_task_ids(&declined))
+    };
 
     CommandEnv {
-        cwd: None,
+        cwd: state.cwd.clone(),
         config_path: state.config_path.display().to_string(),
         scratch_dir: None,
         task_id: task_id.map(|value| value.to_string()).or_else(|| {
@@ -282,6 +405,8 @@ fn build_command_env(
         agent_phase,
         completed,
         needs_human,
+        skipped,
+        declined,
         notify_event: notify_event.map(|value| value.as_str().to_string()),
         notify_duration_ms: None,
         notify_folder: None,
@@ -290,12 +415,24 @@ fn build_command_env(
         notify_task_description: None,
         notify_message: None,
         notify_payload_path: None,
+        agent_output: state
+            .current_agent_output
+            .as_ref()
+            .map(|path| path.display().to_string()),
+        review_result_path: state
+            .current_review_result
+            .as_ref()
+            .map(|file| file.path().display().to_string()),
+        review_feedback: state.current_review_feedback.clone(),
+        review_follow_ups: (!state.current_review_follow_ups.is_empty())
+            .then(|| state.current_review_follow_ups.join("\n")),
+        review_loop: state.current_review_loop.map(|value| value.to_string()),
         agent_profile,
         agent_invocation_id,
     }
 }
 
-fn run_config_command(
+pub(crate) fn run_config_command(
     state: &RuntimeState,
     command: &str,
     task_id: Option<&TaskId>,
@@ -313,7 +450,7 @@ fn run_config_command(
     )
 }
 
-fn run_config_command_status(
+pub(crate) fn run_config_command_status(
     state: &RuntimeState,
     command: &str,
     task_id: Option<&TaskId>,
@@ -347,19 +484,32 @@ fn run_agent_command(
     log_label: &str,
     agent_prompt: Option<String>,
     agent_phase: Option<String>,
-) -> Result<i32, String> {
-    let context = {
-        let guard = agent_invocation_context()
-            .lock()
-            .expect("invocation context mutex");
-        guard.clone()
-    };
+    timeout: Option<AgentTimeout>,
+) -> Result<CommandStatus, String> {
+    let context = state.agent_context.clone();
 
-    let invocation_id = if agent_phase.as_deref() == Some("trudge_review") {
-        context.review_invocation_id
-    } else {
-        context.solve_invocation_id
+    let invocation_id = match agent_phase.as_deref() {
+        Some("trudge_review") => context.review_invocation_id,
+        Some(phase) if state.config.custom_phase(phase).is_some() => state
+            .config
+            .custom_phase(phase)
+            .map(|phase| phase.invocation_id.clone()),
+        _ => context.solve_invocation_id,
     };
+    state.logger.log_transition(
+        Transition::new("agent_start")
+            .field(
+                "task",
+                state
+                    .current_task_id
+                    .as_ref()
+                    .map(|task_id| task_id.as_str())
+                    .unwrap_or("none"),
+            )
+            .field("label", log_label)
+            .field("profile", context.profile.as_deref().unwrap_or("none"))
+            .field("invocation_id", invocation_id.as_deref().unwrap_or("none")),
+    );
 
     let env = build_command_env(
         state,
@@ -371,29 +521,41 @@ fn run_agent_command(
         context.profile,
         invocation_id,
     );
-    run_shell_command_status(command, log_label, "none", &[], &env, &state.logger)
+    let deadline = timeout.map(|timeout| CommandDeadline {
+        timeout: timeout.seconds.as_duration(),
+        interrupt_flag: &state.interrupt_flag,
+    });
+    match (state.current_agent_output.as_deref(), deadline) {
+        (Some(transcript), deadline) => run_shell_command_tee(
+            command,
+            log_label,
+            "none",
+            &[],
+            &env,
+            &state.logger,
+            deadline,
+            transcript,
+        ),
+        (None, Some(deadline)) => run_shell_command_status_with_deadline(
+            command,
+            log_label,
+            "none",
+            &[],
+            &env,
+            &state.logger,
+            deadline,
+        ),
+        (None, None) => {
+            run_shell_command_status(command, log_label, "none", &[], &env, &state.logger)
+                .map(CommandStatus::Exited)
+        }
+    }
 }
 
-fn run_task_show(
-    state: &mut RuntimeState,
-    task_id: &TaskId,
-    args: &[String],
-) -> Result<(), String> {
+fn run_task_show(state: &mut RuntimeState, task_id: &TaskId) -> Result<(), String> {
     state.current_task_show = None;
-    let output = run_config_command(
-        state,
-        &state.config.commands.task_show,
-        Some(task_id),
-        "task",
-        args,
-    )?;
-    if output.exit_code != 0 {
-        return Err(format!(
-            "task_show failed with exit code {}",
-            output.exit_code
-        ));
-    }
-    state.current_task_show = Some(output.stdout);
+    let show = state.task_backend.show(state, task_id)?;
+    state.current_task_show = Some(show);
     if state.current_task_id.as_ref() == Some(task_id) {
         let show = state.current_task_show.clone();
         let description = show
@@ -408,28 +570,14 @@ fn run_task_show(
 
 fn run_task_status(state: &mut RuntimeState, task_id: &TaskId) -> Result<(), String> {
     state.current_task_status = None;
-    let output = run_config_command(
-        state,
-        &state.config.commands.task_status,
-        Some(task_id),
-        "task",
-        &[],
-    )?;
-    if output.exit_code != 0 {
-        return Err(format!(
-            "task_status failed with exit code {}",
-            output.exit_code
-        ));
-    }
-    let token = output.stdout.split_whitespace().next().unwrap_or("");
-    let parsed = TaskStatus::parse(token);
+    let parsed = state.task_backend.status(state, task_id)?;
     if let Some(status) = parsed.as_ref() {
         if status.is_unknown() {
-            state.logger.log_transition(&format!(
-                "unknown_task_status task={} status={}",
-                task_id,
-                sanitize_log_value(status.as_str())
-            ));
+            state.logger.log_transition(
+                Transition::new("unknown_task_status")
+                    .field("task", task_id)
+                    .field("status", status),
+            );
             return Err(format!(
                 "unknown_task_status:{}:{}",
                 task_id,
@@ -449,35 +597,41 @@ fn run_task_status(state: &mut RuntimeState, task_id: &TaskId) -> Result<(), Str
     Ok(())
 }
 
-fn get_next_task_id(state: &RuntimeState) -> Result<Option<TaskId>, Quit> {
-    let output = run_config_command(
-        state,
-        state.config.commands.next_task.as_deref().unwrap_or(""),
-        None,
-        "next-task",
-        &[],
-    )
-    .map_err(|err| quit(&state.logger, &format!("next_task_failed:{err}"), 1))?;
+/// Result of polling `commands.next_task`.
+enum NextTask {
+    Task(TaskId),
+    /// Nothing to select: `no_next_task` (exit 1) or `no_task` (empty output).
+    Idle(&'static str),
+}
 
-    if output.exit_code == 1 {
-        state.logger.log_transition("idle next_task_exit=1");
-        return Err(quit(&state.logger, "no_next_task", 0));
-    }
-    if output.exit_code != 0 {
-        eprintln!(
-            "next_task command failed with exit code {}.",
-            output.exit_code
-        );
-        return Err(quit(
-            &state.logger,
-            &format!("next_task_failed:{}", output.exit_code),
-            output.exit_code,
-        ));
-    }
+fn get_next_task_id(state: &RuntimeState, exclude: &[TaskId]) -> Result<NextTask, Quit> {
+    let output = state
+        .task_backend
+        .next_task(state, exclude)
+        .map_err(|err| quit(&state.logger, &format!("next_task_failed:{err}"), 1))?;
 
-    let token = output.stdout.split_whitespace().next().unwrap_or("");
+    let token = match output {
+        NextTaskOutput::Task(token) => token,
+        NextTaskOutput::Empty => String::new(),
+        NextTaskOutput::NoneSelectable => {
+            state
+                .logger
+                .log_transition(Transition::new("idle").field("next_task_exit", 1));
+            return Ok(NextTask::Idle("no_next_task"));
+        }
+        NextTaskOutput::Failed(code) => {
+            eprintln!("next_task command failed with exit code {}.", code);
+            return Err(quit(
+                &state.logger,
+                &format!("next_task_failed:{}", code),
+                code,
+            ));
+        }
+    };
+    let token = token.as_str();
     if token.trim().is_empty() {
-        return Ok(None);
+        state.logger.log_transition(Transition::new("idle no_task"));
+        return Ok(NextTask::Idle("no_task"));
     }
     let task_id = TaskId::try_from(token).map_err(|err| {
         eprintln!("next_task returned an invalid task id: {} ({})", token, err);
@@ -487,7 +641,7 @@ fn get_next_task_id(state: &RuntimeState) -> Result<Option<TaskId>, Quit> {
             1,
         )
     })?;
-    Ok(Some(task_id))
+    Ok(NextTask::Task(task_id))
 }
 
 fn ensure_task_ready(state: &mut RuntimeState, task_id: &TaskId) -> Result<(), Quit> {
@@ -497,15 +651,50 @@ fn ensure_task_ready(state: &mut RuntimeState, task_id: &TaskId) -> Result<(), Q
         .current_task_status
         .clone()
         .unwrap_or(TaskStatus::Unknown(String::new()));
-    if status.is_ready() {
-        return Ok(());
+    if !status.is_ready() {
+        eprintln!("Task {} is not ready (status: {}).", task_id, status);
+        return Err(quit(
+            &state.logger,
+            &format!("task_not_ready:{}", task_id),
+            1,
+        ));
     }
-    eprintln!("Task {} is not ready (status: {}).", task_id, status);
-    Err(quit(
-        &state.logger,
-        &format!("task_not_ready:{}", task_id),
-        1,
-    ))
+    let blocker = open_blocker(state, task_id)
+        .map_err(|err| quit(&state.logger, &format!("task_dependencies_failed:{err}"), 1))?;
+    if let Some((blocker, blocker_status)) = blocker {
+        state.logger.log_transition(
+            Transition::new("skip_blocked_by")
+                .field("task", task_id)
+                .field("blocker", &blocker)
+                .field("status", &blocker_status),
+        );
+        eprintln!(
+            "Task {} is blocked by {} (status: {}).",
+            task_id, blocker, blocker_status
+        );
+        return Err(quit(
+            &state.logger,
+            &format!("task_blocked_by:{}:{}", task_id, blocker),
+            1,
+        ));
+    }
+    Ok(())
+}
+
+/// The first of the task's dependencies that is not closed, with its status (`none` when the
+/// backend reports none).
+fn open_blocker(
+    state: &RuntimeState,
+    task_id: &TaskId,
+) -> Result<Option<(TaskId, String)>, String> {
+    for blocker in state.task_backend.dependencies(state, task_id)? {
+        let status = state.task_backend.status(state, &blocker)?;
+        if status != Some(TaskStatus::Closed) {
+            let status = status.map_or_else(|| "none".to_string(), |status| status.to_string());
+            return Ok(Some((blocker, status)));
+        }
+    }
+    Ok(None)
 }
 
 fn update_task_status(
@@ -513,22 +702,7 @@ fn update_task_status(
     task_id: &TaskId,
     status: TaskStatus,
 ) -> Result<(), String> {
-    let exit = run_config_command_status(
-        state,
-        &state.config.commands.task_update_status,
-        Some(task_id),
-        "task",
-        Some(status.as_str()),
-        &[],
-    )?;
-    if exit != 0 {
-        return Err(format!(
-            "task_update_status failed to set status {} (exit code {})",
-            status.as_str(),
-            exit
-        ));
-    }
-    Ok(())
+    state.task_backend.set_status(state, task_id, &status)
 }
 
 fn update_in_progress(state: &RuntimeState, task_id: &TaskId) -> Result<(), String> {
@@ -539,24 +713,6 @@ fn reset_task(state: &RuntimeState, task_id: &TaskId) -> Result<(), String> {
     update_task_status(state, task_id, TaskStatus::Open)
 }
 
-fn task_status_token(state: &RuntimeState, task_id: &TaskId) -> Result<Option<TaskStatus>, String> {
-    let output = run_config_command(
-        state,
-        &state.config.commands.task_status,
-        Some(task_id),
-        "task",
-        &[],
-    )?;
-    if output.exit_code != 0 {
-        return Err(format!(
-            "task_status failed with exit code {}",
-            output.exit_code
-        ));
-    }
-    let token = output.stdout.split_whitespace().next().unwrap_or("");
-    Ok(TaskStatus::parse(token))
-}
-
 pub(crate) fn reset_task_on_exit(state: &RuntimeState, result: &Result<(), Quit>) {
     if result.is_ok() {
         return;
@@ -565,18 +721,19 @@ pub(crate) fn reset_task_on_exit(state: &RuntimeState, result: &Result<(), Quit>
         return;
     };
 
-    let status = match task_status_token(state, task_id) {
+    let status = match state.task_backend.status(state, task_id) {
         Ok(status) => status,
         Err(err) => {
             eprintln!(
                 "Warning: failed to check task status for task {}, skipping reset: {}",
                 task_id, err
             );
-            state.logger.log_transition(&format!(
-                "reset_task_skip task={} reason=task_status_failed err={}",
-                task_id,
-                sanitize_log_value(&err)
-            ));
+            state.logger.log_transition(
+                Transition::new("reset_task_skip")
+                    .field("task", task_id)
+                    .field("reason", "task_status_failed")
+                    .field("err", err),
+            );
             return;
         }
     };
@@ -586,36 +743,149 @@ pub(crate) fn reset_task_on_exit(state: &RuntimeState, result: &Result<(), Quit>
             "Warning: commands.task_status returned an empty status for task {}, skipping reset.",
             task_id
         );
-        state.logger.log_transition(&format!(
-            "reset_task_skip task={} reason=task_status_empty",
-            task_id
-        ));
+        state.logger.log_transition(
+            Transition::new("reset_task_skip")
+                .field("task", task_id)
+                .field("reason", "task_status_empty"),
+        );
         return;
     };
 
     if status != TaskStatus::InProgress {
-        state.logger.log_transition(&format!(
-            "reset_task_skip task={} status={}",
-            task_id, status
-        ));
+        state.logger.log_transition(
+            Transition::new("reset_task_skip")
+                .field("task", task_id)
+                .field("status", status),
+        );
         return;
     }
 
     match reset_task(state, task_id) {
         Ok(()) => state
             .logger
-            .log_transition(&format!("reset_task task={}", task_id)),
+            .log_transition(Transition::new("reset_task").field("task", task_id)),
         Err(err) => {
             eprintln!("Failed to reset task {}: {}", task_id, err);
-            state.logger.log_transition(&format!(
-                "reset_task_failed task={} err={}",
-                task_id,
-                sanitize_log_value(&err)
-            ));
+            state.logger.log_transition(
+                Transition::new("reset_task_failed")
+                    .field("task", task_id)
+                    .field("err", err),
+            );
         }
     }
 }
 
+fn checkpoint_run_journal(state: &RuntimeState, current_task: Option<JournalTask>) {
+    let Some(path) = state.config.journal_path.as_deref() else {
+        return;
+    };
+
+    // A task routed to another profile still resumes under the run's own.
+    let profile = match state.routed_from.as_ref() {
+        Some(snapshot) => snapshot.context.profile.clone(),
+        None => state.agent_context.profile.clone(),
+    };
+    let mut journal = RunJournal::new(state.config_path.display().to_string(), profile);
+    journal.manual_tasks = state.manual_tasks.clone();
+    journal.completed_tasks = state.completed_tasks.clone();
+    journal.needs_human_tasks = state.needs_human_tasks.clone();
+    journal.skipped_tasks = state.skipped_tasks.clone();
+    journal.declined_tasks = state.declined_tasks.clone();
+    journal.current_task = current_task;
+
+    if let Err(err) = write_run_journal(path, &journal) {
+        eprintln!("Warning: failed to write run journal: {}.", err);
+        state
+            .logger
+            .log_transition(Transition::new("journal_write_failed").field("err", err));
+    }
+}
+
+fn checkpoint_task_phase(
+    state: &RuntimeState,
+    task_id: &TaskId,
+    phase: JournalPhase,
+    review_loop: u64,
+    pipeline_phase: Option<&str>,
+) {
+    checkpoint_run_journal(
+        state,
+        Some(JournalTask {
+            task_id: task_id.clone(),
+            phase,
+            review_loop,
+            pipeline_phase: pipeline_phase.map(str::to_string),
+            workspace: state.workspace.clone(),
+        }),
+    );
+}
+
+/// Drops the journal once a run ends cleanly; failed or interrupted runs keep it for `trudger resume`.
+pub(crate) fn finish_run_journal(state: &RuntimeState, result: &Result<(), Quit>) {
+    let Some(path) = state.config.journal_path.as_deref() else {
+        return;
+    };
+
+    let finished = match result {
+        Ok(()) => true,
+        Err(quit) => quit.code == 0,
+    };
+    if !finished {
+        eprintln!(
+            "Run journal saved to {}; run `trudger resume` to continue.",
+            path.display()
+        );
+        return;
+    }
+
+    if let Err(err) = remove_run_journal(path) {
+        eprintln!("Warning: failed to remove run journal: {}.", err);
+        state
+            .logger
+            .log_transition(Transition::new("journal_remove_failed").field("err", err));
+    }
+}
+
+fn resume_task_point(
+    state: &mut RuntimeState,
+    point: JournalTask,
+) -> Result<Option<JournalTask>, Quit> {
+    run_task_status(state, &point.task_id)
+        .map_err(|err| quit(&state.logger, &format!("task_status_failed:{err}"), 1))?;
+    let status = state
+        .current_task_status
+        .clone()
+        .unwrap_or(TaskStatus::Unknown(String::new()));
+
+    // A crash leaves the task `in_progress`; a handled exit resets it to `open`.
+    if !status.is_ready() && status != TaskStatus::InProgress {
+        state.logger.log_transition(
+            Transition::new("resume_skip")
+                .field("task", &point.task_id)
+                .field("status", &status),
+        );
+        eprintln!(
+            "Task {} can no longer be resumed (status: {}); continuing with the next task.",
+            point.task_id, status
+        );
+        return Ok(None);
+    }
+
+    state.logger.log_transition(
+        Transition::new("resume")
+            .field("task", &point.task_id)
+            .field("phase", point.phase.as_str())
+            .field("loop", point.review_loop),
+    );
+
+    // The solving phase claims the task itself; a resumed review has to re-claim it here.
+    if point.phase == JournalPhase::Reviewing && status != TaskStatus::InProgress {
+        update_in_progress(state, &point.task_id)
+            .map_err(|err| quit(&state.logger, &format!("error:{err}"), 1))?;
+    }
+    Ok(Some(point))
+}
+
 fn check_interrupted(state: &RuntimeState) -> Result<(), Quit> {
     if state.interrupt_flag.load(Ordering::SeqCst) {
         return Err(quit(&state.logger, "interrupted", 130));
@@ -633,11 +903,9 @@ fn run_hook(
         return Ok(());
     }
 
-    let exit = run_config_command_status(state, hook_command, Some(task_id), hook_name, None, &[])?;
-    if exit != 0 {
-        return Err(format!("hook {} failed with exit code {}", hook_name, exit));
-    }
-    Ok(())
+    state
+        .task_backend
+        .run_hook(state, hook_command, task_id, hook_name)
 }
 
 fn should_dispatch_notification(state: &RuntimeState, event: NotificationEvent) -> bool {
@@ -645,7 +913,10 @@ fn should_dispatch_notification(state: &RuntimeState, event: NotificationEvent)
         Some(NotificationScope::TaskBoundaries) => {
             matches!(
                 event,
-                NotificationEvent::TaskStart | NotificationEvent::TaskEnd
+                NotificationEvent::TaskStart
+                    | NotificationEvent::TaskEnd
+                    | NotificationEvent::AgentRetry
+                    | NotificationEvent::ProfileFallback
             )
         }
         Some(NotificationScope::RunBoundaries) => {
@@ -662,6 +933,15 @@ pub(crate) fn dispatch_notification_hook(
     state: &RuntimeState,
     task_id: Option<&TaskId>,
     event: NotificationEvent,
+) {
+    dispatch_notification_hook_with_message(state, task_id, event, None);
+}
+
+fn dispatch_notification_hook_with_message(
+    state: &RuntimeState,
+    task_id: Option<&TaskId>,
+    event: NotificationEvent,
+    message: Option<String>,
 ) {
     let Some(hook_command) = state
         .config
@@ -678,11 +958,23 @@ pub(crate) fn dispatch_notification_hook(
         return;
     }
 
-    let mut env = build_command_env(state, task_id, None, None, None, Some(event), None, None);
+    let profile = state.agent_context.profile.clone();
+    let mut env = build_command_env(
+        state,
+        task_id,
+        None,
+        None,
+        None,
+        Some(event),
+        profile.clone(),
+        None,
+    );
     let notify_duration_ms = match event {
         NotificationEvent::RunStart | NotificationEvent::TaskStart => 0,
         NotificationEvent::RunEnd => state.run_started_at.elapsed().as_millis(),
-        NotificationEvent::TaskEnd => state
+        NotificationEvent::TaskEnd
+        | NotificationEvent::AgentRetry
+        | NotificationEvent::ProfileFallback => state
             .current_task_started_at
             .map(|started_at| started_at.elapsed().as_millis())
             .unwrap_or(0),
@@ -708,6 +1000,7 @@ pub(crate) fn dispatch_notification_hook(
     env.notify_exit_code = notify_exit_code.map(|value| value.to_string());
     env.notify_task_id = Some(notify_task_id);
     env.notify_task_description = Some(notify_task_description);
+    env.notify_message = message.clone();
 
     let task_token = task_id.map(|value| value.as_str()).unwrap_or("none");
     let payload_folder = env.notify_folder.clone().unwrap_or_default();
@@ -724,18 +1017,20 @@ pub(crate) fn dispatch_notification_hook(
             TRUDGER_ENV_VALUE_MAX_BYTES,
         )
         .to_string(),
-        message: None,
+        message,
+        agent_output: env.agent_output.clone(),
+        profile,
     };
     let payload_file = match payload.write_to_temp_file() {
         Ok(file) => file,
         Err(err) => {
             eprintln!("Warning: failed to prepare notification payload: {}.", err);
-            state.logger.log_transition(&format!(
-                "notification_hook_failed event={} task={} err={}",
-                event.as_str(),
-                task_token,
-                sanitize_log_value(&err)
-            ));
+            state.logger.log_transition(
+                Transition::new("notification_hook_failed")
+                    .field("event", event.as_str())
+                    .field("task", task_token)
+                    .field("err", err),
+            );
             return;
         }
     };
@@ -755,21 +1050,27 @@ pub(crate) fn dispatch_notification_hook(
                 "Warning: notification hook failed with exit code {}.",
                 exit_code
             );
-            state.logger.log_transition(&format!(
-                "notification_hook_failed event={} task={} exit_code={}",
-                event.as_str(),
-                task_id.map(|value| value.as_str()).unwrap_or("none"),
-                exit_code
-            ));
+            state.logger.log_transition(
+                Transition::new("notification_hook_failed")
+                    .field("event", event.as_str())
+                    .field(
+                        "task",
+                        task_id.map(|value| value.as_str()).unwrap_or("none"),
+                    )
+                    .field("exit_code", exit_code),
+            );
         }
         Err(err) => {
             eprintln!("Warning: failed to run notification hook: {}.", err);
-            state.logger.log_transition(&format!(
-                "notification_hook_failed event={} task={} err={}",
-                event.as_str(),
-                task_id.map(|value| value.as_str()).unwrap_or("none"),
-                sanitize_log_value(&err)
-            ));
+            state.logger.log_transition(
+                Transition::new("notification_hook_failed")
+                    .field("event", event.as_str())
+                    .field(
+                        "task",
+                        task_id.map(|value| value.as_str()).unwrap_or("none"),
+                    )
+                    .field("err", err),
+            );
         }
     }
 }
@@ -786,314 +1087,1612 @@ fn clear_current_task_context(state: &mut RuntimeState) {
     state.current_task_show = None;
     state.current_task_status = None;
     state.current_task_started_at = None;
+    state.current_agent_output = None;
+    state.current_review_result = None;
+    state.current_review_feedback = None;
+    state.current_review_follow_ups.clear();
+    state.current_review_loop = None;
     state.logger.set_all_logs_task_id(None);
 }
 
-fn run_agent_solve(state: &RuntimeState) -> Result<(), String> {
-    let exit = run_agent_command(
-        state,
-        &state.config.agent_command,
-        "agent_solve",
-        Some(state.prompt_trudge.clone()),
-        Some("trudge".to_string()),
-    )?;
-    if exit != 0 {
-        return Err(format!("agent_solve failed with exit code {}", exit));
-    }
-    Ok(())
+#[derive(Debug)]
+enum AgentError {
+    Failed(String),
+    /// The agent command exited non-zero, after any retries.
+    Exited {
+        label: String,
+        exit_code: i32,
+    },
+    /// The invocation hit its timeout; carries the action left to take after any retry.
+    TimedOut(TimeoutAction),
+    /// Ctrl-C was pressed while waiting to retry; the run quits rather than failing the task.
+    Interrupted(Quit),
 }
 
-fn run_agent_review(state: &RuntimeState) -> Result<(), String> {
-    let exit = run_agent_command(
-        state,
-        &state.config.agent_review_command,
-        "agent_review",
-        Some(state.prompt_review.clone()),
-        Some("trudge_review".to_string()),
-    )?;
-    if exit != 0 {
-        return Err(format!("agent_review failed with exit code {}", exit));
+impl fmt::Display for AgentError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Failed(err) => f.write_str(err),
+            Self::Exited { label, exit_code } => {
+                write!(f, "{} failed with exit code {}", label, exit_code)
+            }
+            Self::TimedOut(action) => write!(f, "timed out (on_timeout={})", action.as_str()),
+            Self::Interrupted(_) => f.write_str("interrupted"),
+        }
     }
-    Ok(())
 }
 
-pub(crate) fn run_loop(state: &mut RuntimeState) -> Result<(), Quit> {
-    check_interrupted(state)?;
-    if !state.manual_tasks.is_empty() {
-        for task_id in &state.manual_tasks.clone() {
-            check_interrupted(state)?;
-            ensure_task_ready(state, task_id)?;
-        }
-    }
+/// Whether a task stays in the solve/review loop after an agent phase.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum PhaseOutcome {
+    Proceed,
+    TaskEnded,
+    /// A custom phase failed with `on_failure: next_loop`; the review is not run.
+    NextLoop,
+}
 
+fn run_agent_phase(
+    state: &RuntimeState,
+    command: &str,
+    log_label: &str,
+    agent_prompt: &str,
+    agent_phase: &str,
+    timeout: Option<AgentTimeout>,
+    retry: Option<&RetryPolicy>,
+) -> Result<(), AgentError> {
+    let task_token = state
+        .current_task_id
+        .as_ref()
+        .map(|task_id| task_id.as_str())
+        .unwrap_or("none");
+    let mut attempt: u64 = 1;
+    let mut retried_timeout = false;
     loop {
-        check_interrupted(state)?;
-        let task_id = if !state.manual_tasks.is_empty() {
-            state.manual_tasks.remove(0)
-        } else {
-            let next_task_cmd = state.config.commands.next_task.as_deref().unwrap_or("");
-            if next_task_cmd.trim().is_empty() {
-                state
-                    .logger
-                    .log_transition("idle missing_next_task_command");
-                return Err(quit(&state.logger, "missing_next_task_command", 0));
-            }
-
-            let skip_limit = env::var("TRUDGER_SKIP_NOT_READY_LIMIT")
-                .ok()
-                .and_then(|value| value.parse::<usize>().ok())
-                .filter(|value| *value >= 1)
-                .unwrap_or(5);
-
-            let mut skip_count = 0usize;
-            let selected = loop {
-                check_interrupted(state)?;
-                let task_id = match get_next_task_id(state)? {
-                    Some(task_id) => task_id,
-                    None => {
-                        state.logger.log_transition("idle no_task");
-                        return Err(quit(&state.logger, "no_task", 0));
-                    }
+        let status = run_agent_command(
+            state,
+            command,
+            log_label,
+            Some(agent_prompt.to_string()),
+            Some(agent_phase.to_string()),
+            timeout,
+        )
+        .map_err(AgentError::Failed)?;
+        let timeout = match (status, timeout) {
+            (CommandStatus::Exited(0), _) => return Ok(()),
+            (CommandStatus::Exited(exit), _) => {
+                let failed = || AgentError::Exited {
+                    label: log_label.to_string(),
+                    exit_code: exit,
                 };
-                run_task_status(state, &task_id)
-                    .map_err(|err| quit(&state.logger, &format!("task_status_failed:{err}"), 1))?;
-                let Some(status) = state.current_task_status.clone() else {
-                    eprintln!("Task {} missing status.", task_id);
-                    return Err(quit(
-                        &state.logger,
-                        &format!("task_missing_status:{}", task_id),
-                        1,
-                    ));
+                let Some(policy) = retry.filter(|policy| {
+                    policy.retries_exit_code(exit) && attempt < policy.max_attempts.get()
+                }) else {
+                    return Err(failed());
                 };
-                if status.is_ready() {
-                    break task_id;
-                }
-                state.logger.log_transition(&format!(
-                    "skip_not_ready task={} status={}",
-                    task_id, status
-                ));
-                skip_count += 1;
-                if skip_count >= skip_limit {
-                    state
-                        .logger
-                        .log_transition(&format!("idle no_ready_task attempts={}", skip_count));
-                    eprintln!("Task {} is not ready (status: {}).", task_id, status);
-                    return Err(quit(&state.logger, "no_ready_task", 0));
-                }
-            };
-            selected
-        };
 
-        state.current_task_id = Some(task_id.clone());
-        state.logger.set_all_logs_task_id(Some(task_id.as_str()));
-        state.current_task_started_at = Some(Instant::now());
-        state.current_task_show = None;
-        state.current_task_status = None;
-        if state
+                let delay = policy.backoff_after(attempt);
+                attempt += 1;
+                state.logger.log_transition(
+                    Transition::new("agent_retry")
+                        .field("task", task_token)
+                        .field("label", log_label)
+                        .field("exit", exit)
+                        .field("attempt", attempt)
+                        .field("max_attempts", policy.max_attempts.get())
+                        .field("delay_s", delay.as_secs()),
+                );
+                let message = format!(
+                    "{} exited with code {}; retrying (attempt {}/{}) in {}s",
+                    log_label,
+                    exit,
+                    attempt,
+                    policy.max_attempts,
+                    delay.as_secs()
+                );
+                eprintln!("Warning: {}.", message);
+                dispatch_notification_hook_with_message(
+                    state,
+                    state.current_task_id.as_ref(),
+                    NotificationEvent::AgentRetry,
+                    Some(message),
+                );
+                sleep_unless_interrupted(state, delay).map_err(AgentError::Interrupted)?;
+                continue;
+            }
+            (CommandStatus::TimedOut, Some(timeout)) => timeout,
+            (CommandStatus::TimedOut, None) => {
+                return Err(AgentError::TimedOut(TimeoutAction::Quit))
+            }
+        };
+
+        state.logger.log_transition(
+            Transition::new("agent_timeout")
+                .field("task", task_token)
+                .field("label", log_label)
+                .field("timeout_s", timeout.seconds.get())
+                .field("on_timeout", timeout.on_timeout.as_str())
+                .field("retried", retried_timeout),
+        );
+        eprintln!(
+            "Warning: {} timed out after {}s for task {}.",
+            log_label, timeout.seconds, task_token
+        );
+        match timeout.on_timeout {
+            TimeoutAction::Retry if !retried_timeout => retried_timeout = true,
+            TimeoutAction::Retry => return Err(AgentError::TimedOut(TimeoutAction::Quit)),
+            action => return Err(AgentError::TimedOut(action)),
+        }
+    }
+}
+
+/// Sleeps for `delay`, waking early to quit if Ctrl-C is pressed.
+fn sleep_unless_interrupted(state: &RuntimeState, delay: Duration) -> Result<(), Quit> {
+    let started_at = Instant::now();
+    loop {
+        check_interrupted(state)?;
+        let elapsed = started_at.elapsed();
+        if elapsed >= delay {
+            return Ok(());
+        }
+        thread::sleep((delay - elapsed).min(RETRY_SLEEP_POLL_INTERVAL));
+    }
+}
+
+/// The prompt for an agent phase; with `prompt_format: template` it is rendered for the current
+/// task and phase.
+fn agent_prompt(state: &RuntimeState, prompt: &str, agent_phase: &str) -> Result<String, String> {
+    if state.config.prompt_format == PromptFormat::Plain {
+        return Ok(prompt.to_string());
+    }
+    let profile = state.agent_context.profile.clone();
+    let task_show = state.current_task_show.clone();
+    let values = HashMap::from([
+        (
+            "task.id",
+            state
+                .current_task_id
+                .as_ref()
+                .map(|task_id| task_id.to_string()),
+        ),
+        (
+            "task.description",
+            task_show
+                .as_deref()
+                .and_then(extract_task_description_from_task_show),
+        ),
+        ("task.show", task_show),
+        (
+            "task.status",
+            state
+                .current_task_status
+                .as_ref()
+                .map(|status| status.as_str().to_string()),
+        ),
+        ("review.feedback", state.current_review_feedback.clone()),
+        (
+            "loop",
+            state.current_review_loop.map(|value| value.to_string()),
+        ),
+        ("phase", Some(agent_phase.to_string())),
+        ("profile", profile),
+    ]);
+    let values = values
+        .into_iter()
+        .filter_map(|(name, value)| value.map(|value| (name, value)))
+        .collect();
+    render_template(prompt, &values)
+}
+
+fn run_agent_solve(state: &mut RuntimeState, review_loop: u64) -> Result<(), AgentError> {
+    state.current_agent_output = agent_output_path(state, review_loop, "solve");
+    let prompt = agent_prompt(state, &state.prompt_trudge, "trudge").map_err(AgentError::Failed)?;
+    run_agent_phase(
+        state,
+        &state.config.agent_command,
+        "agent_solve",
+        &prompt,
+        "trudge",
+        state.config.agent_timeout,
+        state.config.agent_retry.as_ref(),
+    )
+}
+
+fn run_agent_review(state: &mut RuntimeState, review_loop: u64) -> Result<(), AgentError> {
+    state.current_agent_output = agent_output_path(state, review_loop, "review");
+    // A fresh file per review keeps an earlier verdict from deciding this one.
+    let result_file = tempfile::Builder::new()
+        .prefix("trudger-review-")
+        .suffix(".json")
+        .tempfile()
+        .map_err(|err| {
+            AgentError::Failed(format!("failed to create review result file: {}", err))
+        })?;
+    state.current_review_result = Some(result_file);
+    let prompt =
+        agent_prompt(state, &state.prompt_review, "trudge_review").map_err(AgentError::Failed)?;
+    run_agent_phase(
+        state,
+        &state.config.agent_review_command,
+        "agent_review",
+        &prompt,
+        "trudge_review",
+        state.config.agent_review_timeout,
+        state.config.agent_review_retry.as_ref(),
+    )
+}
+
+fn run_agent_custom_phase(
+    state: &mut RuntimeState,
+    phase: &AgentPhase,
+    review_loop: u64,
+) -> Result<(), AgentError> {
+    state.current_agent_output = agent_output_path(state, review_loop, &phase.name);
+    let template = state
+        .phase_prompts
+        .get(&phase.name)
+        .cloned()
+        .unwrap_or_default();
+    let prompt = agent_prompt(state, &template, &phase.name).map_err(AgentError::Failed)?;
+    run_agent_phase(
+        state,
+        &phase.command,
+        &format!("agent_{}", phase.name),
+        &prompt,
+        &phase.name,
+        phase.timeout,
+        phase.retry.as_ref(),
+    )
+}
+
+/// Runs an agent step; when its failures call for a fallback, switches to the next profile and
+/// runs the step again.
+fn run_agent_with_fallback(
+    state: &mut RuntimeState,
+    task_id: &TaskId,
+    mut run: impl FnMut(&mut RuntimeState) -> Result<(), AgentError>,
+) -> Result<Result<(), AgentError>, Quit> {
+    loop {
+        let err = match run(state) {
+            Ok(()) => {
+                state.agent_failures = 0;
+                return Ok(Ok(()));
+            }
+            Err(err) => err,
+        };
+        let exit_code = match &err {
+            AgentError::Exited { exit_code, .. } => Some(*exit_code),
+            AgentError::Failed(_) => None,
+            // Timeouts follow `on_timeout` and interrupts quit; neither counts toward a fallback.
+            AgentError::TimedOut(_) | AgentError::Interrupted(_) => return Ok(Err(err)),
+        };
+        state.agent_failures += 1;
+        let fallback = &state.config.profile_fallback;
+        if !fallback.should_fall_back(state.agent_failures, exit_code) {
+            return Ok(Err(err));
+        }
+
+        let failures = state.agent_failures;
+        let from = state
+            .agent_context
+            .profile
+            .clone()
+            .unwrap_or_else(|| "none".to_string());
+        let next = state.config.profile_fallback.profiles.remove(0);
+        switch_agent_profile(state, &next)?;
+        state.logger.log_transition(
+            Transition::new("profile_fallback")
+                .field("task", task_id)
+                .field("from", &from)
+                .field("to", &next.profile)
+                .field("failures", failures)
+                .field(
+                    "exit",
+                    exit_code.map_or_else(|| "none".to_string(), |code| code.to_string()),
+                ),
+        );
+        let message = format!(
+            "{}; switching from profile {} to {}",
+            err, from, next.profile
+        );
+        eprintln!("Warning: {}.", message);
+        dispatch_notification_hook_with_message(
+            state,
+            Some(task_id),
+            NotificationEvent::ProfileFallback,
+            Some(message),
+        );
+        check_interrupted(state)?;
+    }
+}
+
+/// Switches the coordinator to the fallback profile a parallel worker ended on, so workers
+/// started after it begin there too.
+fn follow_profile_fallback(state: &mut RuntimeState, active: Option<&str>) -> Result<(), Quit> {
+    let Some(position) = active.and_then(|active| {
+        state
             .config
-            .hooks
-            .on_notification
-            .as_deref()
-            .map(str::trim)
-            .filter(|value| !value.is_empty())
-            .is_some()
-            && should_dispatch_notification(state, NotificationEvent::TaskStart)
-        {
-            // Best-effort: attempt to populate task_show so task_start notifications can include a
-            // useful `task_description` (for example a JSON `title` field) without making this an
-            // additional failure point.
-            let _ = run_task_show(state, &task_id, &[]);
+            .profile_fallback
+            .profiles
+            .iter()
+            .position(|profile| profile.profile == active)
+    }) else {
+        return Ok(());
+    };
+    let next = state
+        .config
+        .profile_fallback
+        .profiles
+        .drain(..=position)
+        .next_back()
+        .expect("fallback position is in range");
+    switch_agent_profile(state, &next)
+}
+
+/// Makes `profile` the active one: its invocations, settings, and prompts.
+fn switch_agent_profile(state: &mut RuntimeState, profile: &AgentProfile) -> Result<(), Quit> {
+    profile.apply(&mut state.config);
+    state.agent_context = AgentInvocationContext::new(
+        profile.profile.clone(),
+        profile.solve_invocation_id.clone(),
+        profile.review_invocation_id.clone(),
+    );
+    state.agent_failures = 0;
+    let cwd = PathBuf::from(&state.invocation_folder);
+    let prompts = crate::app::home_dir()
+        .and_then(|home| load_agent_prompts(&state.config, &home, &cwd, &state.logger));
+    match prompts {
+        Ok(prompts) => {
+            state.prompt_trudge = prompts.trudge;
+            state.prompt_review = prompts.review;
+            state.phase_prompts = prompts.phases;
+            Ok(())
         }
-        dispatch_notification_hook(state, Some(&task_id), NotificationEvent::TaskStart);
-        let mut review_loops: u64 = 0;
+        Err(err) => {
+            eprintln!("{}", err);
+            Err(quit(
+                &state.logger,
+                &format!("profile_fallback_failed:{}", profile.profile),
+                1,
+            ))
+        }
+    }
+}
 
-        loop {
-            check_interrupted(state)?;
-            state.tmux.update_name(
-                Phase::Solving,
-                &task_id,
-                &state.completed_tasks,
-                &state.needs_human_tasks,
-            );
-            state.logger.log_transition(&format!(
-                "state=SOLVING task={} loop={}",
-                task_id, review_loops
-            ));
+/// `<run dir>/<task>/<loop>-<phase>.log` when `agent_output_dir` is configured. Retries of the
+/// same phase append to one file.
+fn agent_output_path(state: &RuntimeState, review_loop: u64, phase: &str) -> Option<PathBuf> {
+    let run_dir = state.agent_output_dir.as_ref()?;
+    let task_id = state.current_task_id.as_ref()?;
+    Some(
+        run_dir
+            .join(path_safe_task_id(task_id))
+            .join(format!("{}-{}.log", review_loop, phase)),
+    )
+}
 
-            if let Err(err) = update_in_progress(state, &task_id) {
-                state.tmux.update_name(
-                    Phase::Error,
-                    &task_id,
-                    &state.completed_tasks,
-                    &state.needs_human_tasks,
+/// Maps a failed agent invocation to `action` (`on_task_error` for solve and review, a custom
+/// phase's `on_failure`); timeouts follow the invocation's `on_timeout` instead.
+fn handle_agent_error(
+    state: &mut RuntimeState,
+    task_id: &TaskId,
+    phase: &str,
+    err: AgentError,
+    action: PhaseFailureAction,
+) -> Result<PhaseOutcome, Quit> {
+    match err {
+        AgentError::Interrupted(quit) => Err(quit),
+        AgentError::TimedOut(TimeoutAction::Escalate) => {
+            escalate_to_human(state, task_id)?;
+            Ok(PhaseOutcome::TaskEnded)
+        }
+        AgentError::TimedOut(_) => {
+            update_tmux_name(state, Phase::Error, task_id);
+            eprintln!("Agent {} timed out for task {}.", phase, task_id);
+            Err(quit(
+                &state.logger,
+                &format!("agent_timeout:{}", task_id),
+                1,
+            ))
+        }
+        AgentError::Failed(_) | AgentError::Exited { .. } => {
+            state.logger.log_transition(
+                Transition::new(&format!("{}_failed", phase)).field("task", task_id),
+            );
+            if action != PhaseFailureAction::Quit {
+                state.logger.log_transition(
+                    Transition::new("task_error")
+                        .field("task", task_id)
+                        .field("phase", phase)
+                        .field("action", action.as_str()),
                 );
-                return Err(quit(&state.logger, &format!("error:{err}"), 1));
             }
+            match action {
+                PhaseFailureAction::Continue => {
+                    eprintln!(
+                        "Agent {} failed for task {}; continuing with the next phase.",
+                        phase, task_id
+                    );
+                    Ok(PhaseOutcome::Proceed)
+                }
+                PhaseFailureAction::NextLoop => {
+                    eprintln!(
+                        "Agent {} failed for task {}; starting the next loop.",
+                        phase, task_id
+                    );
+                    Ok(PhaseOutcome::NextLoop)
+                }
+                PhaseFailureAction::Escalate => {
+                    eprintln!(
+                        "Agent {} failed for task {}; escalating and continuing.",
+                        phase, task_id
+                    );
+                    escalate_task_error(state, task_id)?;
+                    Ok(PhaseOutcome::TaskEnded)
+                }
+                PhaseFailureAction::Skip => {
+                    eprintln!(
+                        "Agent {} failed for task {}; skipping it for this run.",
+                        phase, task_id
+                    );
+                    skip_task(state, task_id)?;
+                    Ok(PhaseOutcome::TaskEnded)
+                }
+                PhaseFailureAction::Quit => {
+                    update_tmux_name(state, Phase::Error, task_id);
+                    eprintln!("Agent {} failed for task {}.", phase, task_id);
+                    Err(quit(
+                        &state.logger,
+                        &format!("{}_failed:{}", phase, task_id),
+                        1,
+                    ))
+                }
+            }
+        }
+    }
+}
 
-            check_interrupted(state)?;
-            if let Err(err) = run_task_show(state, &task_id, &[]) {
-                state.tmux.update_name(
-                    Phase::Error,
-                    &task_id,
-                    &state.completed_tasks,
-                    &state.needs_human_tasks,
-                );
-                state
-                    .logger
-                    .log_transition(&format!("error task={}", task_id));
-                return Err(quit(&state.logger, &format!("error:{err}"), 1));
+/// Blocks the task, records it as needing a human, and runs `hooks.on_requires_human`.
+fn escalate_to_human(state: &mut RuntimeState, task_id: &TaskId) -> Result<(), Quit> {
+    mark_needs_human(state, task_id)?;
+    if let Err(err) = run_hook(
+        state,
+        &state.config.hooks.on_requires_human,
+        task_id,
+        "on_requires_human",
+    ) {
+        return Err(quit(&state.logger, &format!("error:{err}"), 1));
+    }
+    Ok(())
+}
+
+/// Like `escalate_to_human`, but prefers `hooks.on_task_error` when it is configured.
+fn escalate_task_error(state: &mut RuntimeState, task_id: &TaskId) -> Result<(), Quit> {
+    mark_needs_human(state, task_id)?;
+    let result = match state.config.hooks.on_task_error.as_deref() {
+        Some(hook) => run_hook(state, hook, task_id, "on_task_error"),
+        None => run_hook(
+            state,
+            &state.config.hooks.on_requires_human,
+            task_id,
+            "on_requires_human",
+        ),
+    };
+    if let Err(err) = result {
+        return Err(quit(&state.logger, &format!("error:{err}"), 1));
+    }
+    Ok(())
+}
+
+fn mark_needs_human(state: &mut RuntimeState, task_id: &TaskId) -> Result<(), Quit> {
+    if let Err(err) = update_task_status(state, task_id, TaskStatus::Blocked) {
+        update_tmux_name(state, Phase::Error, task_id);
+        return Err(quit(&state.logger, &format!("error:{err}"), 1));
+    }
+    state.current_task_status = Some(TaskStatus::Blocked);
+
+    state.needs_human_tasks.push(task_id.clone());
+    state
+        .logger
+        .log_transition(Transition::new("needs_human").field("task", task_id));
+    dispatch_notification_hook(state, Some(task_id), NotificationEvent::TaskEnd);
+    state.current_task_id = None;
+    checkpoint_run_journal(state, None);
+    Ok(())
+}
+
+/// Returns the task to `open` and keeps `next_task` from handing it back for the rest of the run.
+fn skip_task(state: &mut RuntimeState, task_id: &TaskId) -> Result<(), Quit> {
+    if let Err(err) = reset_task(state, task_id) {
+        update_tmux_name(state, Phase::Error, task_id);
+        return Err(quit(&state.logger, &format!("error:{err}"), 1));
+    }
+    state.current_task_status = Some(TaskStatus::Open);
+
+    state.skipped_tasks.push(workspace_task(state, task_id));
+    state
+        .logger
+        .log_transition(Transition::new("skipped").field("task", task_id));
+    dispatch_notification_hook(state, Some(task_id), NotificationEvent::TaskEnd);
+    state.current_task_id = None;
+    checkpoint_run_journal(state, None);
+    Ok(())
+}
+
+/// Re-reads the task status after a review that wrote no verdict file.
+fn review_task_status(state: &mut RuntimeState, task_id: &TaskId) -> Result<TaskStatus, Quit> {
+    run_task_status(state, task_id)
+        .map_err(|err| quit(&state.logger, &format!("task_status_failed:{err}"), 1))?;
+    let Some(status) = state.current_task_status.clone() else {
+        update_tmux_name(state, Phase::Error, task_id);
+        state
+            .logger
+            .log_transition(Transition::new("review_state_missing").field("task", task_id));
+        eprintln!("Task {} missing status after review.", task_id);
+        return Err(quit(
+            &state.logger,
+            &format!("task_missing_status_after_review:{}", task_id),
+            1,
+        ));
+    };
+    state.logger.log_transition(
+        Transition::new("review_state")
+            .field("task", task_id)
+            .field("status", &status),
+    );
+    Ok(status)
+}
+
+/// Reads the verdict the review wrote to `TRUDGER_REVIEW_RESULT_PATH`, if any, and keeps its
+/// feedback (or else the end of the review transcript) for the next solve.
+fn take_review_result(
+    state: &mut RuntimeState,
+    task_id: &TaskId,
+) -> Result<Option<ReviewResult>, Quit> {
+    state.current_review_feedback = None;
+    state.current_review_follow_ups.clear();
+    let result = match state.current_review_result.as_ref() {
+        Some(file) => read_review_result(file.path())
+            .map_err(|err| quit(&state.logger, &format!("review_result_invalid:{err}"), 1))?,
+        None => None,
+    };
+    if let Some(result) = result.as_ref() {
+        log_review_verdict(state, task_id, result);
+        state.current_review_follow_ups = result
+            .follow_up_tasks
+            .iter()
+            // One title per line, so a title cannot span lines.
+            .map(|title| title.split_whitespace().collect::<Vec<_>>().join(" "))
+            .filter(|title| !title.is_empty())
+            .collect();
+    }
+
+    let feedback = match result
+        .as_ref()
+        .and_then(|result| result.feedback_for_next_solve.as_deref())
+        .filter(|feedback| !feedback.trim().is_empty())
+    {
+        Some(feedback) => Some((feedback.to_string(), "verdict")),
+        None => agent_transcript_tail(state).map(|tail| (tail, "transcript")),
+    };
+    if let Some((feedback, source)) = feedback {
+        state.logger.log_transition(
+            Transition::new("review_feedback")
+                .field("task", task_id)
+                .field("source", source)
+                .field("bytes", feedback.len()),
+        );
+        state.current_review_feedback = Some(feedback);
+    }
+    Ok(result)
+}
+
+fn log_review_verdict(state: &RuntimeState, task_id: &TaskId, result: &ReviewResult) {
+    state.logger.log_transition(
+        Transition::new("review_verdict")
+            .field("task", task_id)
+            .field("verdict", result.verdict.as_str())
+            .field(
+                "reason",
+                sanitize_log_value(result.reason.as_deref().unwrap_or_default()),
+            ),
+    );
+    for follow_up in &result.follow_up_tasks {
+        state.logger.log_transition(
+            Transition::new("review_follow_up")
+                .field("task", task_id)
+                .field("title", sanitize_log_value(follow_up)),
+        );
+    }
+}
+
+/// The last part of the latest agent transcript (`agent_output_dir`), if it printed anything.
+fn agent_transcript_tail(state: &RuntimeState) -> Option<String> {
+    let transcript = std::fs::read(state.current_agent_output.as_deref()?).ok()?;
+    let transcript = String::from_utf8_lossy(&transcript);
+    let mut start = transcript
+        .len()
+        .saturating_sub(FEEDBACK_TRANSCRIPT_TAIL_BYTES);
+    while !transcript.is_char_boundary(start) {
+        start += 1;
+    }
+    let tail = transcript[start..].trim();
+    (!tail.is_empty()).then(|| tail.to_string())
+}
+
+/// Moves the task in the tracker to match the review verdict and returns the outcome to act on.
+fn apply_review_verdict(
+    state: &mut RuntimeState,
+    task_id: &TaskId,
+    result: &ReviewResult,
+) -> Result<TaskStatus, Quit> {
+    let outcome = result.verdict.outcome_status();
+    let tracker_status = match outcome {
+        TaskStatus::Closed | TaskStatus::Blocked => outcome.clone(),
+        TaskStatus::Skipped if state.task_backend.holds_skipped() => outcome.clone(),
+        // A tracker without `skipped` gets the task back as open, like an error skip.
+        TaskStatus::Skipped => TaskStatus::Open,
+        _ => return Ok(outcome),
+    };
+    if let Err(err) = update_task_status(state, task_id, tracker_status.clone()) {
+        update_tmux_name(state, Phase::Error, task_id);
+        return Err(quit(&state.logger, &format!("error:{err}"), 1));
+    }
+    state.current_task_status = Some(tracker_status);
+    Ok(outcome)
+}
+
+/// Runs the active profile's pipeline from phase `start` up to the review; the first phase run
+/// claims the task.
+fn run_pipeline(
+    state: &mut RuntimeState,
+    task_id: &TaskId,
+    review_loops: u64,
+    start: usize,
+) -> Result<PhaseOutcome, Quit> {
+    let phases = state.config.phases.clone();
+    for (index, phase) in phases.iter().enumerate().skip(start) {
+        let claim = index == start;
+        let outcome = match phase {
+            PipelinePhase::Solve => run_solving_phase(state, task_id, review_loops, claim)?,
+            PipelinePhase::Custom(phase) => {
+                run_custom_phase(state, task_id, review_loops, claim, phase)?
             }
+        };
+        if outcome != PhaseOutcome::Proceed {
+            return Ok(outcome);
+        }
+    }
+    Ok(PhaseOutcome::Proceed)
+}
 
-            check_interrupted(state)?;
-            if let Err(_err) = run_agent_solve(state) {
-                state.tmux.update_name(
-                    Phase::Error,
-                    &task_id,
-                    &state.completed_tasks,
-                    &state.needs_human_tasks,
-                );
-                state
+/// Index of the pipeline phase a task resumed while solving picks up at. Journals that name no
+/// phase start the pipeline over.
+fn resume_pipeline_index(state: &RuntimeState, point: &JournalTask) -> Result<usize, Quit> {
+    let Some(name) = point
+        .pipeline_phase
+        .as_deref()
+        .filter(|_| point.phase == JournalPhase::Solving)
+    else {
+        return Ok(0);
+    };
+    let names = state.config.agent_phase_names();
+    let pipeline = &names[..state.config.phases.len()];
+    match pipeline.iter().position(|phase| *phase == name) {
+        Some(index) => Ok(index),
+        None => {
+            eprintln!(
+                "Run journal resumes task {} in phase {}, which is not in the active pipeline ({}).",
+                point.task_id,
+                name,
+                pipeline.join(", ")
+            );
+            Err(quit(
+                &state.logger,
+                &format!("resume_phase_unknown:{}", name),
+                1,
+            ))
+        }
+    }
+}
+
+/// Shared start of every phase before the review: title, `state=` log, journal checkpoint,
+/// claiming the task when asked, and a fresh `task_show`.
+fn enter_pipeline_phase(
+    state: &mut RuntimeState,
+    task_id: &TaskId,
+    review_loops: u64,
+    phase: Phase,
+    claim: bool,
+) -> Result<(), Quit> {
+    check_interrupted(state)?;
+    let (label, pipeline_phase) = match &phase {
+        Phase::Custom(name) => (name.to_uppercase(), name.clone()),
+        _ => ("SOLVING".to_string(), "trudge".to_string()),
+    };
+    update_tmux_name(state, phase, task_id);
+    state.logger.log_transition(
+        Transition::new("state")
+            .field("state", label)
+            .field("task", task_id)
+            .field("loop", review_loops),
+    );
+    checkpoint_task_phase(
+        state,
+        task_id,
+        JournalPhase::Solving,
+        review_loops,
+        Some(&pipeline_phase),
+    );
+
+    if claim {
+        if let Err(err) = update_in_progress(state, task_id) {
+            update_tmux_name(state, Phase::Error, task_id);
+            return Err(quit(&state.logger, &format!("error:{err}"), 1));
+        }
+    }
+
+    check_interrupted(state)?;
+    if let Err(err) = run_task_show(state, task_id) {
+        update_tmux_name(state, Phase::Error, task_id);
+        state
+            .logger
+            .log_transition(Transition::new("error").field("task", task_id));
+        return Err(quit(&state.logger, &format!("error:{err}"), 1));
+    }
+    check_interrupted(state)
+}
+
+fn run_solving_phase(
+    state: &mut RuntimeState,
+    task_id: &TaskId,
+    review_loops: u64,
+    claim: bool,
+) -> Result<PhaseOutcome, Quit> {
+    enter_pipeline_phase(state, task_id, review_loops, Phase::Solving, claim)?;
+    if let Err(err) =
+        run_agent_with_fallback(state, task_id, |state| run_agent_solve(state, review_loops))?
+    {
+        let action = state.config.on_task_error.into();
+        return handle_agent_error(state, task_id, "solve", err, action);
+    }
+
+    Ok(PhaseOutcome::Proceed)
+}
+
+fn run_custom_phase(
+    state: &mut RuntimeState,
+    task_id: &TaskId,
+    review_loops: u64,
+    claim: bool,
+    phase: &AgentPhase,
+) -> Result<PhaseOutcome, Quit> {
+    enter_pipeline_phase(
+        state,
+        task_id,
+        review_loops,
+        Phase::Custom(phase.name.clone()),
+        claim,
+    )?;
+    let Err(err) = run_agent_with_fallback(state, task_id, |state| {
+        // A fallback profile runs its own version of the phase, or skips it if it has none.
+        match state.config.custom_phase(&phase.name).cloned() {
+            Some(phase) => run_agent_custom_phase(state, &phase, review_loops),
+            None => Ok(()),
+        }
+    })?
+    else {
+        return Ok(PhaseOutcome::Proceed);
+    };
+    let on_failure = state
+        .config
+        .custom_phase(&phase.name)
+        .map_or(phase.on_failure, |phase| phase.on_failure);
+    let outcome = handle_agent_error(state, task_id, &phase.name, err, on_failure)?;
+    if outcome == PhaseOutcome::NextLoop {
+        // The failed phase's output replaces any earlier review feedback for the next solve.
+        state.current_review_feedback = agent_transcript_tail(state);
+        if let Some(feedback) = state.current_review_feedback.as_deref() {
+            state.logger.log_transition(
+                Transition::new("review_feedback")
+                    .field("task", task_id)
+                    .field("source", &phase.name)
+                    .field("bytes", feedback.len()),
+            );
+        }
+    }
+    Ok(outcome)
+}
+
+/// Why `process_tasks` stopped without an error: nothing in the queue was runnable.
+struct Idle {
+    reason: &'static str,
+    tasks_started: u64,
+}
+
+pub(crate) fn run_loop(state: &mut RuntimeState) -> Result<(), Quit> {
+    let idle = process_queue(state)?;
+    Err(quit(&state.logger, idle.reason, 0))
+}
+
+/// Keeps one process polling `commands.next_task` after the queue drains (`--watch`).
+///
+/// Empty polls back off from `watch.poll_interval_seconds` up to
+/// `watch.max_poll_interval_seconds`; starting a task resets the delay. When `reload_requested`
+/// is set (SIGHUP), `reload` runs before the next poll and a failed reload keeps the old config.
+pub(crate) fn run_watch_loop<F>(
+    state: &mut RuntimeState,
+    reload_requested: &AtomicBool,
+    mut reload: F,
+) -> Result<(), Quit>
+where
+    F: FnMut(&mut RuntimeState) -> Result<(), String>,
+{
+    let mut idle_polls = 0u64;
+    loop {
+        check_interrupted(state)?;
+        if reload_requested.swap(false, Ordering::SeqCst) {
+            match reload(state) {
+                Ok(()) => state
                     .logger
-                    .log_transition(&format!("solve_failed task={}", task_id));
-                eprintln!("Agent solve failed for task {}.", task_id);
-                return Err(quit(&state.logger, &format!("solve_failed:{}", task_id), 1));
+                    .log_transition(Transition::new("watch_reload").field("status", "ok")),
+                Err(err) => {
+                    state
+                        .logger
+                        .log_transition(Transition::new("watch_reload").field("status", "failed"));
+                    eprintln!(
+                        "Failed to reload config: {}; keeping the previous config.",
+                        err
+                    );
+                }
             }
+        }
 
-            state.tmux.update_name(
-                Phase::Reviewing,
-                &task_id,
-                &state.completed_tasks,
-                &state.needs_human_tasks,
-            );
-            state.logger.log_transition(&format!(
-                "state=REVIEWING task={} loop={}",
-                task_id, review_loops
-            ));
+        let idle = process_queue(state)?;
+        if idle.tasks_started > 0 {
+            idle_polls = 0;
+        }
+        // A drained queue ends the watch cycle: forget its finished and skipped tasks so the
+        // lists stay bounded and skipped tasks get another try on a later poll. Declined tasks
+        // stay out; the review already decided against them.
+        state.completed_tasks.clear();
+        state.skipped_tasks.clear();
+        checkpoint_run_journal(state, None);
+        let delay = state.config.watch.poll_delay(idle_polls);
+        idle_polls = idle_polls.saturating_add(1);
+        state.logger.log_transition(
+            Transition::new("watch_idle")
+                .field("reason", idle.reason)
+                .field("sleep_s", delay.as_secs()),
+        );
+        sleep_until_woken(state, delay, reload_requested);
+    }
+}
 
-            check_interrupted(state)?;
-            if let Err(err) = run_task_show(state, &task_id, &[]) {
-                state.tmux.update_name(
-                    Phase::Error,
-                    &task_id,
-                    &state.completed_tasks,
-                    &state.needs_human_tasks,
+/// Sleeps for `delay`, returning early on Ctrl-C or when `wake` is set.
+fn sleep_until_woken(state: &RuntimeState, delay: Duration, wake: &AtomicBool) {
+    let started_at = Instant::now();
+    loop {
+        if state.interrupt_flag.load(Ordering::SeqCst) || wake.load(Ordering::SeqCst) {
+            return;
+        }
+        let elapsed = started_at.elapsed();
+        if elapsed >= delay {
+            return;
+        }
+        thread::sleep((delay - elapsed).min(RETRY_SLEEP_POLL_INTERVAL));
+    }
+}
+
+fn process_queue(state: &mut RuntimeState) -> Result<Idle, Quit> {
+    if state.config.workspaces.is_empty() {
+        process_tasks(state, None)
+    } else {
+        process_workspaces(state)
+    }
+}
+
+/// Takes one task at a time from the configured workspaces in `workspace_order` until none of
+/// them has a runnable task. Each task runs with its workspace's config and directory.
+fn process_workspaces(state: &mut RuntimeState) -> Result<Idle, Quit> {
+    let mut workspaces = std::mem::take(&mut state.config.workspaces);
+    let base_config = state.config.clone();
+    let base_folder = state.invocation_folder.clone();
+    let base_context = state.agent_context.clone();
+
+    let resume_workspace = state
+        .resume
+        .as_ref()
+        .and_then(|point| point.workspace.clone());
+    let mut index = 0;
+    if let Some(name) = resume_workspace {
+        match workspaces
+            .iter()
+            .position(|workspace| workspace.name == name)
+        {
+            Some(position) => index = position,
+            None => {
+                eprintln!(
+                    "Warning: run journal names workspace {} which is no longer configured; not resuming its task.",
+                    name
                 );
-                return Err(quit(&state.logger, &format!("error:{err}"), 1));
+                state.logger.log_transition(
+                    Transition::new("resume_skip_workspace").field("workspace", name),
+                );
+                state.resume = None;
+            }
+        }
+    }
+
+    let mut tasks_started = 0u64;
+    let mut idle_turns = 0usize;
+    let turns = loop {
+        if idle_turns >= workspaces.len() {
+            break Ok(());
+        }
+        enter_workspace(state, &workspaces[index], &base_folder);
+        let turn = process_tasks(state, Some(1));
+        keep_workspace_profile(state, &mut workspaces[index]);
+        let turn = match turn {
+            Ok(turn) => turn,
+            Err(quit) => {
+                // Wind the task down with its workspace's commands and hooks while they are
+                // still active.
+                let result = Err(quit);
+                reset_task_on_exit(state, &result);
+                finish_current_task_context(state);
+                break result;
+            }
+        };
+        if turn.tasks_started > 0 {
+            tasks_started += turn.tasks_started;
+            idle_turns = 0;
+            if base_config.workspace_order == WorkspaceOrder::Priority {
+                index = 0;
+                continue;
             }
+        } else {
+            idle_turns += 1;
+        }
+        index = (index + 1) % workspaces.len();
+    };
+
+    // Leave the base config in place even when a turn quits, so the caller (and a `--watch`
+    // reload) still sees every workspace.
+    state.config = Config {
+        workspaces,
+        ..base_config
+    };
+    state.workspace = None;
+    state.cwd = None;
+    state.invocation_folder = base_folder;
+    state.agent_context = base_context;
+    turns?;
+    state
+        .logger
+        .log_transition(Transition::new("idle workspaces"));
+    Ok(Idle {
+        reason: "no_task",
+        tasks_started,
+    })
+}
 
+fn enter_workspace(state: &mut RuntimeState, workspace: &Workspace, base_folder: &str) {
+    let folder = Path::new(base_folder).join(&workspace.path);
+    state.config = workspace.config.clone();
+    state.workspace = Some(workspace.name.clone());
+    state.cwd = Some(folder.clone());
+    state.invocation_folder = folder.display().to_string();
+    state.agent_context = AgentInvocationContext::new(
+        workspace.profile.clone(),
+        workspace.solve_invocation_id.clone(),
+        workspace.review_invocation_id.clone(),
+    );
+    state.logger.log_transition(
+        Transition::new("workspace")
+            .field("name", &workspace.name)
+            .field("path", &state.invocation_folder),
+    );
+}
+
+/// Carries a profile fallback taken during a workspace's turn over to its later turns.
+fn keep_workspace_profile(state: &RuntimeState, workspace: &mut Workspace) {
+    let context = state.agent_context.clone();
+    if let (Some(profile), Some(solve_invocation_id), Some(review_invocation_id)) = (
+        context.profile,
+        context.solve_invocation_id,
+        context.review_invocation_id,
+    ) {
+        workspace.profile = profile;
+        workspace.solve_invocation_id = solve_invocation_id;
+        workspace.review_invocation_id = review_invocation_id;
+    }
+    workspace.config = state.config.clone();
+}
+
+/// Runs tasks from the active `commands.next_task` (or the manual queue) until it goes idle or
+/// `task_limit` tasks have been started.
+fn process_tasks(state: &mut RuntimeState, task_limit: Option<u64>) -> Result<Idle, Quit> {
+    check_interrupted(state)?;
+    if !state.manual_tasks.is_empty() {
+        for task_id in &state.manual_tasks.clone() {
             check_interrupted(state)?;
-            if let Err(_err) = run_agent_review(state) {
-                state.tmux.update_name(
-                    Phase::Error,
-                    &task_id,
-                    &state.completed_tasks,
-                    &state.needs_human_tasks,
-                );
-                state
-                    .logger
-                    .log_transition(&format!("review_failed task={}", task_id));
-                eprintln!("Agent review failed for task {}.", task_id);
-                return Err(quit(
-                    &state.logger,
-                    &format!("review_failed:{}", task_id),
-                    1,
-                ));
+            ensure_task_ready(state, task_id)?;
+        }
+    }
+
+    let mut resume_point = state.resume.take();
+    checkpoint_run_journal(state, resume_point.clone());
+    if state.config.concurrency.get() > 1 {
+        return process_tasks_in_parallel(state, resume_point);
+    }
+    let mut tasks_started = 0u64;
+
+    loop {
+        check_interrupted(state)?;
+        if task_limit.is_some_and(|limit| tasks_started >= limit) {
+            return Ok(Idle {
+                reason: "task_limit",
+                tasks_started,
+            });
+        }
+        let resumed = match resume_point.take() {
+            Some(point) => resume_task_point(state, point)?,
+            None => None,
+        };
+        let task_id = match &resumed {
+            Some(point) => point.task_id.clone(),
+            None => match select_next_task(state, &[])? {
+                NextTask::Task(task_id) => task_id,
+                NextTask::Idle(reason) => {
+                    return Ok(Idle {
+                        reason,
+                        tasks_started,
+                    })
+                }
+            },
+        };
+        tasks_started += 1;
+        run_task(state, &task_id, resumed.as_ref())?;
+    }
+}
+
+/// What a worker thread hands back once its task ends.
+struct WorkerReport {
+    worker: usize,
+    task_id: TaskId,
+    worktree: PathBuf,
+    result: Result<(), Quit>,
+    completed_tasks: Vec<TaskId>,
+    needs_human_tasks: Vec<TaskId>,
+    skipped_tasks: Vec<WorkspaceTask>,
+    declined_tasks: Vec<WorkspaceTask>,
+    /// Profile the worker ended on, which differs from the coordinator's after a fallback.
+    profile: Option<String>,
+}
+
+/// Runs up to `concurrency` tasks at once, each in its own worker thread and git worktree.
+///
+/// This thread selects and claims tasks and merges each worker's outcome into the run's task
+/// lists. A worker that quits stops new tasks from starting; the others finish first.
+fn process_tasks_in_parallel(
+    state: &mut RuntimeState,
+    mut resume_point: Option<JournalTask>,
+) -> Result<Idle, Quit> {
+    let repo = match repository_root(Path::new(&state.invocation_folder)) {
+        Ok(repo) => repo,
+        Err(err) => {
+            eprintln!("concurrency needs a git repository: {}", err);
+            return Err(quit(&state.logger, "not_a_git_repository", 1));
+        }
+    };
+    let root = worktree_root(&repo, state.config.worktree_root.as_deref());
+    let (sender, receiver) = mpsc::channel();
+    let mut active: Vec<(usize, TaskId)> = Vec::new();
+    let mut tasks_started = 0u64;
+    let mut idle_reason = None;
+    let mut stop = None;
+
+    loop {
+        while stop.is_none()
+            && idle_reason.is_none()
+            && active.len() < state.config.concurrency.get()
+        {
+            let worker = (1..)
+                .find(|slot| active.iter().all(|(worker, _)| worker != slot))
+                .unwrap_or(1);
+            let started = check_interrupted(state).and_then(|()| {
+                let resumed = match resume_point.take() {
+                    Some(point) => resume_task_point(state, point)?,
+                    None => None,
+                };
+                start_worker(state, &repo, &root, &active, resumed, worker, &sender)
+            });
+            match started {
+                Ok(NextTask::Task(task_id)) => {
+                    active.push((worker, task_id));
+                    tasks_started += 1;
+                    update_workers_title(state, &active);
+                }
+                Ok(NextTask::Idle(reason)) => idle_reason = Some(reason),
+                Err(quit) => stop = Some(quit),
             }
+        }
+        if active.is_empty() {
+            return match stop {
+                Some(quit) => Err(quit),
+                None => Ok(Idle {
+                    reason: idle_reason.unwrap_or("no_task"),
+                    tasks_started,
+                }),
+            };
+        }
+
+        let report: WorkerReport = receiver.recv().expect("worker report channel");
+        active.retain(|(worker, _)| *worker != report.worker);
+        if let Err(quit) = finish_worker(state, &repo, report) {
+            stop.get_or_insert(quit);
+        }
+        update_workers_title(state, &active);
+        // A finished task can unblock others, so poll again instead of waiting out the rest.
+        idle_reason = None;
+    }
+}
+
+/// `task_id` in the active workspace, as the run's skip and busy checks compare tasks.
+fn workspace_task(state: &RuntimeState, task_id: &TaskId) -> WorkspaceTask {
+    WorkspaceTask {
+        task_id: task_id.clone(),
+        workspace: state.workspace.clone(),
+    }
+}
+
+fn update_tmux_name(state: &RuntimeState, phase: Phase, task_id: &TaskId) {
+    state.tmux.update_name(
+        phase,
+        task_id,
+        &state.completed_tasks,
+        &state.needs_human_tasks,
+        &state.skipped_tasks,
+        &state.declined_tasks,
+    );
+}
+
+fn update_workers_title(state: &RuntimeState, active: &[(usize, TaskId)]) {
+    let tasks = active
+        .iter()
+        .map(|(_, task_id)| task_id.clone())
+        .collect::<Vec<_>>();
+    state.tmux.update_workers(
+        &tasks,
+        &state.completed_tasks,
+        &state.needs_human_tasks,
+        &state.skipped_tasks,
+        &state.declined_tasks,
+    );
+}
+
+/// Selects and claims the next task, checks it out in a worktree, and hands it to worker `worker`.
+fn start_worker(
+    state: &mut RuntimeState,
+    repo: &Path,
+    root: &Path,
+    active: &[(usize, TaskId)],
+    resumed: Option<JournalTask>,
+    worker: usize,
+    sender: &mpsc::Sender<WorkerReport>,
+) -> Result<NextTask, Quit> {
+    let task_id = match &resumed {
+        Some(point) => point.task_id.clone(),
+        None => {
+            let busy = active
+                .iter()
+                .map(|(_, task_id)| workspace_task(state, task_id))
+                .collect::<Vec<_>>();
+            match select_next_task(state, &busy)? {
+                NextTask::Task(task_id) => task_id,
+                idle => return Ok(idle),
+            }
+        }
+    };
+
+    // Claim the task before its worker starts so `commands.next_task` stops offering it.
+    update_in_progress(state, &task_id)
+        .map_err(|err| quit(&state.logger, &format!("error:{err}"), 1))?;
+    let worktree = match create_worktree(repo, root, &task_id) {
+        Ok(path) => path,
+        Err(err) => {
+            state.logger.log_transition(
+                Transition::new("worktree_failed")
+                    .field("task", &task_id)
+                    .field("err", &err),
+            );
+            eprintln!("Failed to create a worktree for task {}: {}", task_id, err);
+            if let Err(err) = reset_task(state, &task_id) {
+                eprintln!("Failed to reset task {}: {}", task_id, err);
+            }
+            return Err(quit(
+                &state.logger,
+                &format!("worktree_failed:{}", task_id),
+                1,
+            ));
+        }
+    };
+    state.logger.log_transition(
+        Transition::new("worker_start")
+            .field("worker", worker)
+            .field("task", &task_id)
+            .field("worktree", worktree.display().to_string()),
+    );
+
+    let mut worker_state = worker_state(state, worker, &worktree);
+    let sender = sender.clone();
+    let started = task_id.clone();
+    thread::spawn(move || {
+        let completed_from = worker_state.completed_tasks.len();
+        let needs_human_from = worker_state.needs_human_tasks.len();
+        let skipped_from = worker_state.skipped_tasks.len();
+        let declined_from = worker_state.declined_tasks.len();
+        // A panicking worker still reports back, or the coordinator would wait for it forever.
+        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+            let result = run_task(&mut worker_state, &task_id, resumed.as_ref());
+            reset_task_on_exit(&worker_state, &result);
+            result
+        }))
+        .unwrap_or_else(|_| {
+            eprintln!("Worker {} panicked while running task {}.", worker, task_id);
+            Err(quit(
+                &worker_state.logger,
+                &format!("worker_panicked:{}", task_id),
+                1,
+            ))
+        });
+        let _ = sender.send(WorkerReport {
+            worker,
+            task_id,
+            worktree,
+            result,
+            completed_tasks: worker_state.completed_tasks.split_off(completed_from),
+            needs_human_tasks: worker_state.needs_human_tasks.split_off(needs_human_from),
+            skipped_tasks: worker_state.skipped_tasks.split_off(skipped_from),
+            declined_tasks: worker_state.declined_tasks.split_off(declined_from),
+            profile: worker_state.agent_context.profile.clone(),
+        });
+    });
+    clear_current_task_context(state);
+    Ok(NextTask::Task(started))
+}
+
+/// A worker's view of the run: the shared config and task lists, with every command and hook
+/// running in its worktree.
+fn worker_state(state: &RuntimeState, worker: usize, worktree: &Path) -> RuntimeState {
+    let folder = worktree.display().to_string();
+    let mut config = state.config.clone();
+    // Only the coordinating thread writes the run journal.
+    config.journal_path = None;
+    RuntimeState {
+        config,
+        config_path: state.config_path.clone(),
+        invocation_folder: folder.clone(),
+        workspace: None,
+        cwd: Some(worktree.to_path_buf()),
+        prompt_trudge: state.prompt_trudge.clone(),
+        prompt_review: state.prompt_review.clone(),
+        phase_prompts: state.phase_prompts.clone(),
+        logger: state.logger.for_worker(worker, folder),
+        tmux: TmuxState::disabled(),
+        interrupt_flag: state.interrupt_flag.clone(),
+        manual_tasks: Vec::new(),
+        completed_tasks: state.completed_tasks.clone(),
+        needs_human_tasks: state.needs_human_tasks.clone(),
+        skipped_tasks: state.skipped_tasks.clone(),
+        declined_tasks: state.declined_tasks.clone(),
+        current_task_id: None,
+        current_task_show: None,
+        current_task_status: None,
+        run_started_at: state.run_started_at,
+        current_task_started_at: None,
+        run_exit_code: 0,
+        agent_output_dir: state.agent_output_dir.clone(),
+        current_agent_output: None,
+        current_review_result: None,
+        current_review_feedback: None,
+        current_review_follow_ups: Vec::new(),
+        current_review_loop: None,
+        agent_failures: 0,
+        routed_from: None,
+        agent_context: state.agent_context.clone(),
+        resume: None,
+        task_backend: state.task_backend.clone(),
+    }
+}
+
+/// Folds a finished worker's task lists into the run. The worktree of a completed task is
+/// removed; any other outcome keeps it for a human (or the next attempt) to pick up.
+fn finish_worker(state: &mut RuntimeState, repo: &Path, report: WorkerReport) -> Result<(), Quit> {
+    let completed = report.completed_tasks.contains(&report.task_id);
+    state.completed_tasks.extend(report.completed_tasks);
+    state.needs_human_tasks.extend(report.needs_human_tasks);
+    state.skipped_tasks.extend(report.skipped_tasks);
+    state.declined_tasks.extend(report.declined_tasks);
+    state.logger.log_transition(
+        Transition::new("worker_end")
+            .field("worker", report.worker)
+            .field("task", &report.task_id),
+    );
+    let followed = follow_profile_fallback(state, report.profile.as_deref());
+    checkpoint_run_journal(state, None);
+
+    let worktree = report.worktree.display().to_string();
+    if !completed {
+        state.logger.log_transition(
+            Transition::new("worktree_kept")
+                .field("task", &report.task_id)
+                .field("worktree", &worktree),
+        );
+    } else if let Err(err) = remove_worktree(repo, &report.worktree) {
+        eprintln!(
+            "Warning: failed to remove worktree {} for task {}: {}",
+            report.worktree.display(),
+            report.task_id,
+            err
+        );
+        state.logger.log_transition(
+            Transition::new("worktree_remove_failed")
+                .field("task", &report.task_id)
+                .field("worktree", &worktree)
+                .field("err", err),
+        );
+    } else {
+        state.logger.log_transition(
+            Transition::new("worktree_removed")
+                .field("task", &report.task_id)
+                .field("worktree", &worktree),
+        );
+    }
+    report.result.and(followed)
+}
 
-            check_interrupted(state)?;
+/// Takes the next task from the manual queue, or the next ready one from `commands.next_task`.
+/// Tasks in `busy` (running in another worker) are passed over like tasks skipped after an error.
+fn select_next_task(state: &mut RuntimeState, busy: &[WorkspaceTask]) -> Result<NextTask, Quit> {
+    if !state.manual_tasks.is_empty() {
+        return Ok(NextTask::Task(state.manual_tasks.remove(0)));
+    }
+    let next_task_cmd = state.config.commands.next_task.as_deref().unwrap_or("");
+    if state.config.backend == Backend::Shell && next_task_cmd.trim().is_empty() {
+        state
+            .logger
+            .log_transition(Transition::new("idle missing_next_task_command"));
+        return Err(quit(&state.logger, "missing_next_task_command", 0));
+    }
+
+    let skip_limit = env::var("TRUDGER_SKIP_NOT_READY_LIMIT")
+        .ok()
+        .and_then(|value| value.parse::<usize>().ok())
+        .filter(|value| *value >= 1)
+        .unwrap_or(5);
+
+    // Busy and skipped tasks of this workspace, plus every task passed over below, so backends
+    // that always start from the top of a file move on to the next item.
+    let mut exclude: Vec<TaskId> = busy
+        .iter()
+        .chain(&state.skipped_tasks)
+        .chain(&state.declined_tasks)
+        .filter(|task| task.workspace == state.workspace)
+        .map(|task| task.task_id.clone())
+        .collect();
+    let mut skip_count = 0usize;
+    loop {
+        check_interrupted(state)?;
+        let task_id = match get_next_task_id(state, &exclude)? {
+            NextTask::Task(task_id) => task_id,
+            NextTask::Idle(reason) => return Ok(NextTask::Idle(reason)),
+        };
+        let key = workspace_task(state, &task_id);
+        let reason = if busy.contains(&key) {
+            state
+                .logger
+                .log_transition(Transition::new("skip_busy").field("task", &task_id));
+            "being worked on by another worker".to_string()
+        } else if state.skipped_tasks.contains(&key) {
+            state
+                .logger
+                .log_transition(Transition::new("skip_errored").field("task", &task_id));
+            "skipped after an agent error".to_string()
+        } else if state.declined_tasks.contains(&key) {
+            state
+                .logger
+                .log_transition(Transition::new("skip_declined").field("task", &task_id));
+            "declined by the review".to_string()
+        } else {
             run_task_status(state, &task_id)
                 .map_err(|err| quit(&state.logger, &format!("task_status_failed:{err}"), 1))?;
             let Some(status) = state.current_task_status.clone() else {
-                state.tmux.update_name(
-                    Phase::Error,
-                    &task_id,
-                    &state.completed_tasks,
-                    &state.needs_human_tasks,
-                );
-                state
-                    .logger
-                    .log_transition(&format!("review_state_missing task={}", task_id));
-                eprintln!("Task {} missing status after review.", task_id);
+                eprintln!("Task {} missing status.", task_id);
                 return Err(quit(
                     &state.logger,
-                    &format!("task_missing_status_after_review:{}", task_id),
+                    &format!("task_missing_status:{}", task_id),
                     1,
                 ));
             };
-            state
-                .logger
-                .log_transition(&format!("review_state task={} status={}", task_id, status));
-
-            if status == TaskStatus::Closed {
-                state.completed_tasks.push(task_id.clone());
-                state
-                    .logger
-                    .log_transition(&format!("completed task={}", task_id));
-                dispatch_notification_hook(state, Some(&task_id), NotificationEvent::TaskEnd);
-                state.current_task_id = None;
-                if let Err(err) = run_hook(
-                    state,
-                    &state.config.hooks.on_completed,
-                    &task_id,
-                    "on_completed",
-                ) {
-                    return Err(quit(&state.logger, &format!("error:{err}"), 1));
-                }
-                break;
+            if !status.is_ready() {
+                state.logger.log_transition(
+                    Transition::new("skip_not_ready")
+                        .field("task", &task_id)
+                        .field("status", &status),
+                );
+                format!("status: {}", status)
+            } else {
+                let blocker = open_blocker(state, &task_id).map_err(|err| {
+                    quit(&state.logger, &format!("task_dependencies_failed:{err}"), 1)
+                })?;
+                let Some((blocker, blocker_status)) = blocker else {
+                    return Ok(NextTask::Task(task_id));
+                };
+                state.logger.log_transition(
+                    Transition::new("skip_blocked_by")
+                        .field("task", &task_id)
+                        .field("blocker", &blocker)
+                        .field("status", &blocker_status),
+                );
+                format!("blocked by {} (status: {})", blocker, blocker_status)
             }
+        };
+        if !exclude.contains(&task_id) {
+            exclude.push(task_id.clone());
+        }
+        skip_count += 1;
+        if skip_count >= skip_limit {
+            state.logger.log_transition(
+                Transition::new("idle no_ready_task").field("attempts", skip_count),
+            );
+            eprintln!("Task {} is not ready ({}).", task_id, reason);
+            return Ok(NextTask::Idle("no_ready_task"));
+        }
+    }
+}
 
-            if status == TaskStatus::Blocked {
-                state.needs_human_tasks.push(task_id.clone());
-                state
-                    .logger
-                    .log_transition(&format!("needs_human task={}", task_id));
-                dispatch_notification_hook(state, Some(&task_id), NotificationEvent::TaskEnd);
-                state.current_task_id = None;
-                if let Err(err) = run_hook(
-                    state,
-                    &state.config.hooks.on_requires_human,
-                    &task_id,
-                    "on_requires_human",
-                ) {
-                    return Err(quit(&state.logger, &format!("error:{err}"), 1));
+/// Counts a loop that sent the task back; once `review_loop_limit` is used up the task is escalated
+/// and this returns `false`.
+fn start_next_loop(
+    state: &mut RuntimeState,
+    task_id: &TaskId,
+    review_loops: &mut u64,
+) -> Result<bool, Quit> {
+    *review_loops += 1;
+    if *review_loops < state.config.review_loop_limit.get() {
+        state.logger.log_transition(
+            Transition::new("review_loop_retry")
+                .field("task", task_id)
+                .field("loop", *review_loops)
+                .field("limit", state.config.review_loop_limit.get()),
+        );
+        return Ok(true);
+    }
+
+    state.logger.log_transition(
+        Transition::new("review_loop_exhausted")
+            .field("task", task_id)
+            .field("loops", *review_loops)
+            .field("limit", state.config.review_loop_limit.get()),
+    );
+    escalate_to_human(state, task_id)?;
+    Ok(false)
+}
+
+/// Solves and reviews one task until it is completed, escalated, or skipped.
+fn run_task(
+    state: &mut RuntimeState,
+    task_id: &TaskId,
+    resumed: Option<&JournalTask>,
+) -> Result<(), Quit> {
+    state.current_task_id = Some(task_id.clone());
+    state.logger.set_all_logs_task_id(Some(task_id.as_str()));
+    state.current_task_started_at = Some(Instant::now());
+    state.current_task_show = None;
+    state.current_task_status = None;
+    if state
+        .config
+        .hooks
+        .on_notification
+        .as_deref()
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .is_some()
+        && should_dispatch_notification(state, NotificationEvent::TaskStart)
+    {
+        // Best-effort: attempt to populate task_show so task_start notifications can include a
+        // useful `task_description` (for example a JSON `title` field) without making this an
+        // additional failure point.
+        let _ = run_task_show(state, task_id);
+    }
+    route_task_profile(state, task_id)?;
+    dispatch_notification_hook(state, Some(task_id), NotificationEvent::TaskStart);
+    let (mut review_loops, mut resume_in_review, mut resume_at) = match resumed {
+        Some(point) => (
+            point.review_loop,
+            point.phase == JournalPhase::Reviewing,
+            resume_pipeline_index(state, point)?,
+        ),
+        None => (0, false, 0),
+    };
+
+    loop {
+        state.current_review_loop = Some(review_loops);
+        // A run resumed mid-review picks up at the review step instead of re-solving.
+        if !std::mem::take(&mut resume_in_review) {
+            match run_pipeline(state, task_id, review_loops, std::mem::take(&mut resume_at))? {
+                PhaseOutcome::Proceed => {}
+                PhaseOutcome::TaskEnded => break,
+                PhaseOutcome::NextLoop if start_next_loop(state, task_id, &mut review_loops)? => {
+                    continue
                 }
-                break;
+                PhaseOutcome::NextLoop => break,
             }
+        }
 
-            review_loops += 1;
-            if review_loops < state.config.review_loop_limit.get() {
-                state.logger.log_transition(&format!(
-                    "review_loop_retry task={} loop={} limit={}",
-                    task_id, review_loops, state.config.review_loop_limit
-                ));
-                continue;
-            }
+        update_tmux_name(state, Phase::Reviewing, task_id);
+        state.logger.log_transition(
+            Transition::new("state")
+                .field("state", "REVIEWING")
+                .field("task", task_id)
+                .field("loop", review_loops),
+        );
+        checkpoint_task_phase(state, task_id, JournalPhase::Reviewing, review_loops, None);
 
-            state.logger.log_transition(&format!(
-                "review_loop_exhausted task={} loops={} limit={}",
-                task_id, review_loops, state.config.review_loop_limit
-            ));
-            if let Err(err) = update_task_status(state, &task_id, TaskStatus::Blocked) {
-                state.tmux.update_name(
-                    Phase::Error,
-                    &task_id,
-                    &state.completed_tasks,
-                    &state.needs_human_tasks,
-                );
+        check_interrupted(state)?;
+        if let Err(err) = run_task_show(state, task_id) {
+            update_tmux_name(state, Phase::Error, task_id);
+            return Err(quit(&state.logger, &format!("error:{err}"), 1));
+        }
+
+        check_interrupted(state)?;
+        if let Err(err) = run_agent_with_fallback(state, task_id, |state| {
+            run_agent_review(state, review_loops)
+        })? {
+            let action = state.config.on_task_error.into();
+            handle_agent_error(state, task_id, "review", err, action)?;
+            break;
+        }
+
+        check_interrupted(state)?;
+        let verdict = take_review_result(state, task_id)?;
+        let status = match verdict {
+            Some(result) => apply_review_verdict(state, task_id, &result)?,
+            None => review_task_status(state, task_id)?,
+        };
+
+        if status == TaskStatus::Closed {
+            state.completed_tasks.push(task_id.clone());
+            state
+                .logger
+                .log_transition(Transition::new("completed").field("task", task_id));
+            dispatch_notification_hook(state, Some(task_id), NotificationEvent::TaskEnd);
+            state.current_task_id = None;
+            checkpoint_run_journal(state, None);
+            if let Err(err) = run_hook(
+                state,
+                &state.config.hooks.on_completed,
+                task_id,
+                "on_completed",
+            ) {
                 return Err(quit(&state.logger, &format!("error:{err}"), 1));
             }
-            state.current_task_status = Some(TaskStatus::Blocked);
+            break;
+        }
 
+        if status == TaskStatus::Blocked {
             state.needs_human_tasks.push(task_id.clone());
             state
                 .logger
-                .log_transition(&format!("needs_human task={}", task_id));
-            dispatch_notification_hook(state, Some(&task_id), NotificationEvent::TaskEnd);
+                .log_transition(Transition::new("needs_human").field("task", task_id));
+            dispatch_notification_hook(state, Some(task_id), NotificationEvent::TaskEnd);
             state.current_task_id = None;
+            checkpoint_run_journal(state, None);
             if let Err(err) = run_hook(
                 state,
                 &state.config.hooks.on_requires_human,
-                &task_id,
+                task_id,
                 "on_requires_human",
             ) {
                 return Err(quit(&state.logger, &format!("error:{err}"), 1));
@@ -1101,30 +2700,165 @@ pub(crate) fn run_loop(state: &mut RuntimeState) -> Result<(), Quit> {
             break;
         }
 
-        let completed_env = state
-            .completed_tasks
-            .iter()
-            .map(|task| task.as_str())
-            .collect::<Vec<_>>()
-            .join(",");
-        let needs_human_env = state
-            .needs_human_tasks
-            .iter()
-            .map(|task| task.as_str())
-            .collect::<Vec<_>>()
-            .join(",");
-        state.logger.log_transition(&format!(
-            "task_lists completed={} needs_human={}",
-            completed_env, needs_human_env
-        ));
+        if status == TaskStatus::Skipped {
+            state.declined_tasks.push(workspace_task(state, task_id));
+            state
+                .logger
+                .log_transition(Transition::new("declined").field("task", task_id));
+            dispatch_notification_hook(state, Some(task_id), NotificationEvent::TaskEnd);
+            state.current_task_id = None;
+            checkpoint_run_journal(state, None);
+            let hook = state.config.hooks.on_skipped.clone().unwrap_or_default();
+            if let Err(err) = run_hook(state, &hook, task_id, "on_skipped") {
+                return Err(quit(&state.logger, &format!("error:{err}"), 1));
+            }
+            break;
+        }
+
+        if !start_next_loop(state, task_id, &mut review_loops)? {
+            break;
+        }
+    }
+
+    let completed_env = state
+        .completed_tasks
+        .iter()
+        .map(|task| task.as_str())
+        .collect::<Vec<_>>()
+        .join(",");
+    let needs_human_env = state
+        .needs_human_tasks
+        .iter()
+        .map(|task| task.as_str())
+        .collect::<Vec<_>>()
+        .join(",");
+    let skipped_env = state
+        .skipped_tasks
+        .iter()
+        .map(|task| task.task_id.as_str())
+        .collect::<Vec<_>>()
+        .join(",");
+    let declined_env = state
+        .declined_tasks
+        .iter()
+        .map(|task| task.task_id.as_str())
+        .collect::<Vec<_>>()
+        .join(",");
+    state.logger.log_transition(
+        Transition::new("task_lists")
+            .field("completed", completed_env)
+            .field("needs_human", needs_human_env)
+            .field("skipped", skipped_env)
+            .field("declined", declined_env),
+    );
+
+    clear_current_task_context(state);
+    if let Some(snapshot) = state.routed_from.take() {
+        restore_profile(state, snapshot);
+    }
+    Ok(())
+}
+
+/// Picks the task's profile from `commands.task_profile`, then `profile_rules`, and switches to it
+/// for this task when it is not the active one.
+fn route_task_profile(state: &mut RuntimeState, task_id: &TaskId) -> Result<(), Quit> {
+    let command = state
+        .config
+        .commands
+        .task_profile
+        .clone()
+        .filter(|command| !command.trim().is_empty());
+    if command.is_none() && state.config.task_routing.rules.is_empty() {
+        return Ok(());
+    }
+    if let Err(err) = run_task_show(state, task_id) {
+        return Err(quit(&state.logger, &format!("error:{err}"), 1));
+    }
 
-        clear_current_task_context(state);
+    let mut routed = None;
+    if let Some(command) = command {
+        let output = run_config_command(state, &command, Some(task_id), "task-profile", &[])
+            .and_then(|output| match output.exit_code {
+                0 => Ok(output.stdout),
+                code => Err(format!("task_profile failed with exit code {}", code)),
+            })
+            .map_err(|err| quit(&state.logger, &format!("task_profile_failed:{err}"), 1))?;
+        routed = output
+            .lines()
+            .map(str::trim)
+            .find(|line| !line.is_empty())
+            .map(|profile| (profile.to_string(), "task_profile".to_string()));
+    }
+    if routed.is_none() {
+        let labels = state
+            .current_task_show
+            .as_deref()
+            .map(task_labels_from_task_show)
+            .unwrap_or_default();
+        routed = state
+            .config
+            .task_routing
+            .rule_for(&labels)
+            .map(|rule| (rule.profile.clone(), format!("label:{}", rule.label)));
     }
+
+    let context = state.agent_context.clone();
+    let active = context
+        .profile
+        .clone()
+        .unwrap_or_else(|| "none".to_string());
+    let Some((name, source)) = routed.filter(|(name, _)| *name != active) else {
+        state.logger.log_transition(
+            Transition::new("task_profile")
+                .field("task", task_id)
+                .field("profile", active)
+                .field("source", "default"),
+        );
+        return Ok(());
+    };
+    let Some(profile) = state.config.task_routing.profiles.get(&name).cloned() else {
+        eprintln!(
+            "commands.task_profile printed unknown profile {} for task {}.",
+            name, task_id
+        );
+        return Err(quit(
+            &state.logger,
+            &format!("task_profile_unknown:{}", name),
+            1,
+        ));
+    };
+
+    state.routed_from = Some(ProfileSnapshot {
+        config: state.config.clone(),
+        prompt_trudge: state.prompt_trudge.clone(),
+        prompt_review: state.prompt_review.clone(),
+        phase_prompts: state.phase_prompts.clone(),
+        context,
+    });
+    switch_agent_profile(state, &profile)?;
+    state.logger.log_transition(
+        Transition::new("task_profile")
+            .field("task", task_id)
+            .field("profile", name)
+            .field("source", source),
+    );
+    Ok(())
+}
+
+/// Switches back to the profile active before `route_task_profile`.
+fn restore_profile(state: &mut RuntimeState, snapshot: ProfileSnapshot) {
+    state.config = snapshot.config;
+    state.prompt_trudge = snapshot.prompt_trudge;
+    state.prompt_review = snapshot.prompt_review;
+    state.phase_prompts = snapshot.phase_prompts;
+    state.agent_failures = 0;
+    state.agent_context = snapshot.context;
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::task_backend::ShellBackend;
     use std::path::PathBuf;
     use std::time::{Duration, Instant};
     use tempfile::TempDir;
@@ -1157,6 +2891,8 @@ mod tests {
                     task_show: "true".to_string(),
                     task_status: "true".to_string(),
                     task_update_status: "true".to_string(),
+                    task_dependencies: None,
+                    task_profile: None,
                 },
                 hooks: crate::config::Hooks {
                     on_completed: "true".to_string(),
@@ -1164,10 +2900,36 @@ mod tests {
                     on_doctor_setup: None,
                     on_notification: None,
                     on_notification_scope: None,
+                    on_task_error: None,
+                    on_skipped: None,
                 },
                 review_loop_limit: crate::task_types::ReviewLoopLimit::new(1)
                     .expect("review_loop_limit"),
                 log_path: None,
+                journal_path: None,
+                agent_timeout: None,
+                agent_review_timeout: None,
+                agent_retry: None,
+                agent_review_retry: None,
+                on_task_error: crate::config::TaskErrorAction::Quit,
+                watch: crate::config::WatchConfig::default(),
+                workspaces: Vec::new(),
+                workspace_order: crate::config::WorkspaceOrder::RoundRobin,
+                concurrency: std::num::NonZeroUsize::MIN,
+                worktree_root: None,
+                log_format: crate::config::LogFormat::Text,
+                agent_output_dir: None,
+                backend: crate::config::Backend::Shell,
+                br: crate::config::BrConfig::default(),
+                github: crate::config::GithubConfig::default(),
+                markdown: crate::config::MarkdownConfig::default(),
+                openspec: crate::config::OpenspecConfig::default(),
+                statuses: crate::config::StatusMap::default(),
+                prompt_format: crate::config::PromptFormat::Plain,
+                prompts: crate::config::PromptPaths::default(),
+                phases: vec![PipelinePhase::Solve],
+                profile_fallback: crate::config::ProfileFallback::default(),
+                task_routing: crate::config::TaskRouting::default(),
             },
             config_path: temp.path().join("trudger.yml"),
             invocation_folder: std::env::current_dir()
@@ -1176,18 +2938,34 @@ mod tests {
                 .unwrap_or_default(),
             prompt_trudge: "prompt".to_string(),
             prompt_review: "review".to_string(),
+            phase_prompts: HashMap::new(),
             logger: Logger::new(None),
             tmux: TmuxState::disabled(),
             interrupt_flag: Arc::new(AtomicBool::new(false)),
             manual_tasks: Vec::new(),
             completed_tasks: Vec::new(),
             needs_human_tasks: Vec::new(),
+            skipped_tasks: Vec::new(),
+            declined_tasks: Vec::new(),
+            workspace: None,
+            cwd: None,
             current_task_id: None,
             current_task_show: None,
             current_task_status: None,
             run_started_at: Instant::now(),
             current_task_started_at: None,
             run_exit_code: 0,
+            agent_output_dir: None,
+            current_agent_output: None,
+            current_review_result: None,
+            current_review_feedback: None,
+            current_review_follow_ups: Vec::new(),
+            current_review_loop: None,
+            agent_failures: 0,
+            routed_from: None,
+            agent_context: Default::default(),
+            resume: None,
+            task_backend: Arc::new(ShellBackend),
         }
     }
 
@@ -1792,7 +3570,7 @@ mod tests {
         let mut state = base_state(&temp);
 
         std::env::set_var("PATH", temp.path());
-        let err = run_task_show(&mut state, &task("tr-1"), &[]).expect_err("expected spawn error");
+        let err = run_task_show(&mut state, &task("tr-1")).expect_err("expected spawn error");
         assert!(err.contains("Failed to run command"));
 
         crate::unit_tests::reset_test_env();
@@ -1847,6 +3625,50 @@ mod tests {
         crate::unit_tests::reset_test_env();
     }
 
+    #[test]
+    fn agent_output_is_teed_per_task_and_loop() {
+        let _guard = crate::unit_tests::ENV_MUTEX.lock().unwrap();
+        crate::unit_tests::reset_test_env();
+
+        let temp = TempDir::new().expect("temp dir");
+        let run_dir = temp.path().join("runs").join("run-1");
+        let seen = temp.path().join("seen.log");
+        let mut state = base_state(&temp);
+        state.agent_output_dir = Some(run_dir.clone());
+        state.current_task_id = Some(task("gh:7"));
+        state.config.agent_command = format!(
+            "echo solve-out; echo solve-err >&2; echo \"$TRUDGER_AGENT_OUTPUT\" >> {}",
+            seen.display()
+        );
+        state.config.agent_review_command = "echo review-out".to_string();
+
+        run_agent_solve(&mut state, 0).expect("agent solve should succeed");
+        run_agent_review(&mut state, 1).expect("agent review should succeed");
+
+        let solve_log = run_dir.join("gh-7").join("0-solve.log");
+        let review_log = run_dir.join("gh-7").join("1-review.log");
+        let solve = std::fs::read_to_string(&solve_log).expect("read solve transcript");
+        assert!(solve.contains("solve-out\n"), "got: {solve}");
+        assert!(solve.contains("solve-err\n"), "got: {solve}");
+        assert_eq!(
+            std::fs::read_to_string(&review_log).expect("read review transcript"),
+            "review-out\n"
+        );
+        assert_eq!(
+            std::fs::read_to_string(&seen).expect("read seen"),
+            format!("{}\n", solve_log.display())
+        );
+        assert_eq!(
+            state.current_agent_output.as_deref(),
+            Some(review_log.as_path())
+        );
+
+        clear_current_task_context(&mut state);
+        assert_eq!(state.current_agent_output, None);
+
+        crate::unit_tests::reset_test_env();
+    }
+
     #[cfg(unix)]
     #[test]
     fn agent_solve_propagates_spawn_errors() {
@@ -1854,11 +3676,11 @@ mod tests {
         crate::unit_tests::reset_test_env();
 
         let temp = TempDir::new().expect("temp dir");
-        let state = base_state(&temp);
+        let mut state = base_state(&temp);
 
         std::env::set_var("PATH", temp.path());
-        let err = run_agent_solve(&state).expect_err("spawn error");
-        assert!(err.contains("Failed to run command"));
+        let err = run_agent_solve(&mut state, 0).expect_err("spawn error");
+        assert!(err.to_string().contains("Failed to run command"));
 
         crate::unit_tests::reset_test_env();
     }
@@ -1870,11 +3692,11 @@ mod tests {
         crate::unit_tests::reset_test_env();
 
         let temp = TempDir::new().expect("temp dir");
-        let state = base_state(&temp);
+        let mut state = base_state(&temp);
 
         std::env::set_var("PATH", temp.path());
-        let err = run_agent_review(&state).expect_err("spawn error");
-        assert!(err.contains("Failed to run command"));
+        let err = run_agent_review(&mut state, 0).expect_err("spawn error");
+        assert!(err.to_string().contains("Failed to run command"));
 
         crate::unit_tests::reset_test_env();
     }
@@ -1899,14 +3721,14 @@ mod tests {
         state.config.agent_review_command = "codex --yolo exec --review \"$@\"".to_string();
         state.current_task_id = Some(task("tr-1"));
 
-        set_agent_invocation_context(
+        state.agent_context = AgentInvocationContext::new(
             "shared-profile".to_string(),
             "shared-id".to_string(),
             "shared-id".to_string(),
         );
 
-        run_agent_solve(&state).expect("agent solve should succeed");
-        run_agent_review(&state).expect("agent review should succeed");
+        run_agent_solve(&mut state, 0).expect("agent solve should succeed");
+        run_agent_review(&mut state, 0).expect("agent review should succeed");
 
         let contents = std::fs::read_to_string(&codex_log).expect("read codex log");
         let command_lines: Vec<&str> = contents
@@ -1941,7 +3763,6 @@ mod tests {
             "expected shared invocation id on both invocations, got:\n{contents}"
         );
 
-        reset_agent_invocation_context();
         crate::unit_tests::reset_test_env();
     }
 
@@ -1965,14 +3786,14 @@ mod tests {
         state.config.agent_review_command = "codex --yolo exec --review \"$@\"".to_string();
         state.current_task_id = Some(task("tr-1"));
 
-        set_agent_invocation_context(
+        state.agent_context = AgentInvocationContext::new(
             "split-profile".to_string(),
             "solve-id".to_string(),
             "review-id".to_string(),
         );
 
-        run_agent_solve(&state).expect("agent solve should succeed");
-        run_agent_review(&state).expect("agent review should succeed");
+        run_agent_solve(&mut state, 0).expect("agent solve should succeed");
+        run_agent_review(&mut state, 0).expect("agent review should succeed");
 
         let contents = std::fs::read_to_string(&codex_log).expect("read codex log");
         let command_lines: Vec<&str> = contents
@@ -2001,7 +3822,6 @@ mod tests {
 
         assert!(!command_lines[1].contains("tr-1"));
 
-        reset_agent_invocation_context();
         crate::unit_tests::reset_test_env();
     }
 
@@ -2027,7 +3847,7 @@ mod tests {
         state.config.agent_review_command = "codex".to_string();
         state.current_task_id = Some(task("tr-1"));
 
-        set_agent_invocation_context(
+        state.agent_context = AgentInvocationContext::new(
             "manual-profile".to_string(),
             "solve-id".to_string(),
             "review-id".to_string(),
@@ -2036,8 +3856,8 @@ mod tests {
         state.prompt_trudge = "solve prompt".to_string();
         state.prompt_review = "review prompt".to_string();
 
-        run_agent_solve(&state).expect("agent solve should succeed");
-        run_agent_review(&state).expect("agent review should succeed");
+        run_agent_solve(&mut state, 0).expect("agent solve should succeed");
+        run_agent_review(&mut state, 0).expect("agent review should succeed");
 
         let contents = std::fs::read_to_string(&codex_log).expect("read codex log");
         let invocation_lines: Vec<&str> = contents
@@ -2094,7 +3914,6 @@ mod tests {
             "legacy TRUDGER_REVIEW_PROMPT should be removed from agent env"
         );
 
-        reset_agent_invocation_context();
         crate::unit_tests::reset_test_env();
     }
 
@@ -2109,7 +3928,9 @@ mod tests {
         state.config.commands.next_task = Some("next-task".to_string());
 
         std::env::set_var("PATH", temp.path());
-        let quit = get_next_task_id(&state).expect_err("expected quit");
+        let Err(quit) = get_next_task_id(&state, &[]) else {
+            panic!("expected quit");
+        };
         assert_eq!(quit.code, 1);
         assert!(quit.reason.starts_with("next_task_failed:"));
 
@@ -2125,7 +3946,9 @@ mod tests {
         let mut state = base_state(&temp);
         state.config.commands.next_task = Some("printf '$'".to_string());
 
-        let quit = get_next_task_id(&state).expect_err("expected quit");
+        let Err(quit) = get_next_task_id(&state, &[]) else {
+            panic!("expected quit");
+        };
         assert_eq!(quit.code, 1);
         assert!(quit.reason.starts_with("next_task_invalid_task_id:"));
 
@@ -2499,4 +4322,246 @@ mod tests {
         assert_eq!(quit.code, 130);
         assert_eq!(quit.reason, "interrupted");
     }
+
+    fn init_git_repo(repo: &Path) {
+        std::fs::create_dir_all(repo).expect("create repo");
+        for args in [
+            vec!["init", "--quiet"],
+            vec![
+                "-c",
+                "user.name=trudger",
+                "-c",
+                "user.email=trudger@example.com",
+                "commit",
+                "--quiet",
+                "--allow-empty",
+                "-m",
+                "init",
+            ],
+        ] {
+            let status = std::process::Command::new("git")
+                .arg("-C")
+                .arg(repo)
+                .args(&args)
+                .status()
+                .expect("run git");
+            assert!(status.success(), "git {args:?} failed");
+        }
+    }
+
+    #[test]
+    fn run_loop_runs_tasks_in_parallel_worktrees() {
+        let _guard = crate::unit_tests::ENV_MUTEX.lock().unwrap();
+        crate::unit_tests::reset_test_env();
+
+        let temp = TempDir::new().expect("temp dir");
+        let repo = temp.path().join("repo");
+        let statuses = temp.path().join("statuses");
+        let out = temp.path().join("out");
+        init_git_repo(&repo);
+        std::fs::create_dir_all(&statuses).expect("create statuses");
+        std::fs::create_dir_all(&out).expect("create out");
+        std::fs::write(statuses.join("tr-1"), "open\n").expect("write status");
+        std::fs::write(statuses.join("tr-2"), "open\n").expect("write status");
+
+        let statuses = statuses.display();
+        let log_path = temp.path().join("trudger.log");
+        let mut state = base_state(&temp);
+        state.logger = Logger::new(Some(log_path.clone()));
+        state.invocation_folder = repo.display().to_string();
+        state.config.concurrency = std::num::NonZeroUsize::new(2).expect("concurrency");
+        state.config.commands.next_task = Some(format!(
+            "for f in '{statuses}'/*; do if [ \"$(cat \"$f\")\" = open ]; then basename \"$f\"; exit 0; fi; done; exit 1"
+        ));
+        state.config.commands.task_status = format!("cat '{statuses}'/\"$TRUDGER_TASK_ID\"");
+        state.config.commands.task_update_status = format!(
+            "printf '%s\\n' \"$TRUDGER_TARGET_STATUS\" > '{statuses}'/\"$TRUDGER_TASK_ID\""
+        );
+        // Each solve waits for the other one to start, so a serial loop would fail here.
+        state.config.agent_command = format!(
+            "pwd -P > '{out}'/\"$TRUDGER_TASK_ID.cwd\"; touch '{out}'/\"$TRUDGER_TASK_ID.started\"; for i in $(seq 200); do [ \"$(ls '{out}' | grep -c started)\" -ge 2 ] && exit 0; sleep 0.05; done; exit 1",
+            out = out.display()
+        );
+        state.config.agent_review_command = format!(
+            "if [ \"$TRUDGER_TASK_ID\" = tr-1 ]; then s=closed; else s=blocked; fi; printf '%s\\n' \"$s\" > '{statuses}'/\"$TRUDGER_TASK_ID\""
+        );
+
+        let result = run_loop(&mut state).expect_err("expected graceful idle exit");
+        assert_eq!(result.code, 0, "reason: {}", result.reason);
+        assert_eq!(result.reason, "no_next_task");
+        assert_eq!(state.completed_tasks, vec![task("tr-1")]);
+        assert_eq!(state.needs_human_tasks, vec![task("tr-2")]);
+
+        let worktrees = temp.path().join("repo.worktrees");
+        for task_id in ["tr-1", "tr-2"] {
+            let cwd =
+                std::fs::read_to_string(out.join(format!("{task_id}.cwd"))).expect("read cwd");
+            assert!(
+                cwd.trim().ends_with(&format!("repo.worktrees/{task_id}")),
+                "{task_id} should run in its worktree, got: {cwd}"
+            );
+        }
+        assert!(
+            !worktrees.join("tr-1").exists(),
+            "completed task's worktree should be removed"
+        );
+        assert!(
+            worktrees.join("tr-2").is_dir(),
+            "needs_human task's worktree should be kept"
+        );
+
+        let log_contents = std::fs::read_to_string(&log_path).expect("read log");
+        for expected in [
+            "worker=1 state=SOLVING",
+            "worker=2 state=SOLVING",
+            "worktree_removed task=tr-1",
+            "worktree_kept task=tr-2",
+        ] {
+            assert!(
+                log_contents.contains(expected),
+                "expected {expected:?} in log, got:\n{log_contents}"
+            );
+        }
+
+        crate::unit_tests::reset_test_env();
+    }
+
+    /// Shell commands, except that showing a task panics like a bug in a worker would.
+    #[derive(Debug)]
+    struct PanickingShowBackend;
+
+    impl TaskBackend for PanickingShowBackend {
+        fn next_task(
+            &self,
+            state: &RuntimeState,
+            exclude: &[TaskId],
+        ) -> Result<NextTaskOutput, String> {
+            ShellBackend.next_task(state, exclude)
+        }
+
+        fn show(&self, _state: &RuntimeState, task_id: &TaskId) -> Result<String, String> {
+            panic!("show {}", task_id);
+        }
+
+        fn status(
+            &self,
+            state: &RuntimeState,
+            task_id: &TaskId,
+        ) -> Result<Option<TaskStatus>, String> {
+            ShellBackend.status(state, task_id)
+        }
+
+        fn set_status(
+            &self,
+            state: &RuntimeState,
+            task_id: &TaskId,
+            status: &TaskStatus,
+        ) -> Result<(), String> {
+            ShellBackend.set_status(state, task_id, status)
+        }
+    }
+
+    #[test]
+    fn parallel_run_quits_when_a_worker_panics() {
+        let _guard = crate::unit_tests::ENV_MUTEX.lock().unwrap();
+        crate::unit_tests::reset_test_env();
+
+        let temp = TempDir::new().expect("temp dir");
+        let repo = temp.path().join("repo");
+        init_git_repo(&repo);
+        let nested = repo.join("src");
+        std::fs::create_dir_all(&nested).expect("create nested dir");
+        let status_path = temp.path().join("tr-1.status");
+        std::fs::write(&status_path, "open\n").expect("write status");
+
+        let log_path = temp.path().join("trudger.log");
+        let mut state = base_state(&temp);
+        state.logger = Logger::new(Some(log_path.clone()));
+        state.task_backend = Arc::new(PanickingShowBackend);
+        // Started below the top level; worktrees still go next to the repository.
+        state.invocation_folder = nested.display().to_string();
+        state.config.concurrency = std::num::NonZeroUsize::new(2).expect("concurrency");
+        state.config.commands.next_task = Some(format!(
+            "[ \"$(cat '{status}')\" = open ] && printf tr-1 || exit 1",
+            status = status_path.display()
+        ));
+        state.config.commands.task_status = format!("cat '{}'", status_path.display());
+        state.config.commands.task_update_status = format!(
+            "printf '%s\\n' \"$TRUDGER_TARGET_STATUS\" > '{}'",
+            status_path.display()
+        );
+
+        let result = run_loop(&mut state).expect_err("a panicking worker should end the run");
+        assert_eq!(result.code, 1);
+        assert_eq!(result.reason, "worker_panicked:tr-1");
+
+        let log_contents = std::fs::read_to_string(&log_path).expect("read log");
+        let worktree = temp.path().join("repo.worktrees").join("tr-1");
+        assert!(
+            log_contents.contains(&format!("worktree={}", worktree.display())),
+            "expected worktree under repo.worktrees, got:\n{log_contents}"
+        );
+
+        crate::unit_tests::reset_test_env();
+    }
+
+    #[test]
+    fn finish_worker_moves_the_run_to_the_fallback_profile_a_worker_ended_on() {
+        let _guard = crate::unit_tests::ENV_MUTEX.lock().unwrap();
+        crate::unit_tests::reset_test_env();
+
+        let temp = TempDir::new().expect("temp dir");
+        let prompt_dir = temp.path().join(".trudger/prompts");
+        std::fs::create_dir_all(&prompt_dir).expect("create prompt dir");
+        std::fs::write(prompt_dir.join("trudge.md"), "claude solve").expect("write prompt");
+        std::fs::write(prompt_dir.join("trudge_review.md"), "claude review").expect("write prompt");
+        let mut state = base_state(&temp);
+        state.invocation_folder = temp.path().display().to_string();
+        state.agent_context = AgentInvocationContext::new(
+            "codex".to_string(),
+            "codex".to_string(),
+            "codex".to_string(),
+        );
+        state.config.profile_fallback.profiles = vec![AgentProfile {
+            profile: "claude".to_string(),
+            solve_invocation_id: "claude-solve".to_string(),
+            review_invocation_id: "claude-review".to_string(),
+            solve_command: "claude solve".to_string(),
+            review_command: "claude review".to_string(),
+            solve_timeout: None,
+            review_timeout: None,
+            solve_retry: None,
+            review_retry: None,
+            prompts: crate::config::PromptPaths::default(),
+            phases: vec![PipelinePhase::Solve],
+        }];
+        let report = |task_id: &str, profile: &str| WorkerReport {
+            worker: 1,
+            task_id: task(task_id),
+            worktree: temp.path().join(task_id),
+            result: Ok(()),
+            completed_tasks: Vec::new(),
+            needs_human_tasks: Vec::new(),
+            skipped_tasks: Vec::new(),
+            declined_tasks: Vec::new(),
+            profile: Some(profile.to_string()),
+        };
+
+        finish_worker(&mut state, temp.path(), report("tr-1", "claude")).expect("worker result");
+        assert_eq!(state.agent_context.profile.as_deref(), Some("claude"));
+        assert_eq!(
+            state.agent_context.review_invocation_id.as_deref(),
+            Some("claude-review")
+        );
+        assert_eq!(state.config.agent_command, "claude solve");
+        assert_eq!(state.prompt_trudge, "claude solve");
+        assert!(state.config.profile_fallback.profiles.is_empty());
+
+        // A worker started before the switch ends on the old profile, which is not followed back.
+        finish_worker(&mut state, temp.path(), report("tr-2", "codex")).expect("worker result");
+        assert_eq!(state.agent_context.profile.as_deref(), Some("claude"));
+        assert_eq!(state.config.agent_command, "claude solve");
+
+        crate::unit_tests::reset_test_env();
+    }
 }
@@ -2,11 +2,15 @@ use shell_escape::unix::escape;
 use std::borrow::Cow;
 use std::env;
 use std::fs;
-use std::io::Write;
+use std::io::{Read, Write};
 use std::path::{Path, PathBuf};
-use std::process::Command;
+use std::process::{Child, Command};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
 
-use crate::logger::{sanitize_log_value, Logger};
+use crate::logger::{Logger, Transition};
 
 // Guardrail against `execve`/`spawn` failures (E2BIG) from oversized env values.
 // Keep this conservative; prompt/show payloads are expected to be small.
@@ -14,6 +18,15 @@ pub(crate) const TRUDGER_ENV_VALUE_MAX_BYTES: usize = 64 * 1024;
 // Total budget for all `TRUDGER_*` environment variables we set for a subprocess.
 // This intentionally ignores the inherited environment size; the goal is to cap our contribution.
 const TRUDGER_ENV_TOTAL_MAX_BYTES: usize = 128 * 1024;
+// How often a timed command is polled for exit, timeout, and interruption.
+const TIMED_COMMAND_POLL_INTERVAL: Duration = Duration::from_millis(20);
+// Time a timed-out process group gets to exit after SIGTERM before it is sent SIGKILL.
+const TIMED_COMMAND_KILL_GRACE: Duration = Duration::from_secs(5);
+
+const SIGINT: i32 = 2;
+const SIGKILL: i32 = 9;
+const SIGTERM: i32 = 15;
+const SIGTTOU: i32 = 22;
 
 pub(crate) fn truncate_utf8_to_bytes(value: &str, max_bytes: usize) -> &str {
     if value.len() <= max_bytes {
@@ -56,6 +69,8 @@ pub(crate) struct CommandEnv {
     pub(crate) agent_invocation_id: Option<String>,
     pub(crate) completed: Option<String>,
     pub(crate) needs_human: Option<String>,
+    pub(crate) skipped: Option<String>,
+    pub(crate) declined: Option<String>,
     pub(crate) notify_event: Option<String>,
     pub(crate) notify_duration_ms: Option<String>,
     pub(crate) notify_folder: Option<String>,
@@ -64,6 +79,11 @@ pub(crate) struct CommandEnv {
     pub(crate) notify_task_description: Option<String>,
     pub(crate) notify_message: Option<String>,
     pub(crate) notify_payload_path: Option<String>,
+    pub(crate) agent_output: Option<String>,
+    pub(crate) review_result_path: Option<String>,
+    pub(crate) review_feedback: Option<String>,
+    pub(crate) review_follow_ups: Option<String>,
+    pub(crate) review_loop: Option<String>,
 }
 
 impl CommandEnv {
@@ -100,6 +120,8 @@ impl CommandEnv {
             self.agent_invocation_id.as_deref(),
             self.completed.as_deref(),
             self.needs_human.as_deref(),
+            self.skipped.as_deref(),
+            self.declined.as_deref(),
             self.notify_event.as_deref(),
             self.notify_duration_ms.as_deref(),
             self.notify_folder.as_deref(),
@@ -108,6 +130,11 @@ impl CommandEnv {
             self.notify_task_description.as_deref(),
             self.notify_message.as_deref(),
             self.notify_payload_path.as_deref(),
+            self.agent_output.as_deref(),
+            self.review_result_path.as_deref(),
+            self.review_feedback.as_deref(),
+            self.review_follow_ups.as_deref(),
+            self.review_loop.as_deref(),
         );
 
         if total > TRUDGER_ENV_TOTAL_MAX_BYTES {
@@ -132,6 +159,8 @@ impl CommandEnv {
                 self.agent_invocation_id.as_deref(),
                 self.completed.as_deref(),
                 self.needs_human.as_deref(),
+                self.skipped.as_deref(),
+                self.declined.as_deref(),
                 self.notify_event.as_deref(),
                 self.notify_duration_ms.as_deref(),
                 self.notify_folder.as_deref(),
@@ -140,6 +169,11 @@ impl CommandEnv {
                 self.notify_task_description.as_deref(),
                 self.notify_message.as_deref(),
                 self.notify_payload_path.as_deref(),
+                self.agent_output.as_deref(),
+                self.review_result_path.as_deref(),
+                self.review_feedback.as_deref(),
+                self.review_follow_ups.as_deref(),
+                self.review_loop.as_deref(),
             );
 
             if new_total < total {
@@ -150,10 +184,13 @@ impl CommandEnv {
                     "Warning: TRUDGER_* env payload is {} bytes; truncating to {} bytes for command execution.",
                     total, new_total
                 );
-                logger.log_transition(&format!(
-                    "env_truncate_total label={} task={} original_bytes={} truncated_bytes={}",
-                    log_label, task_token, total, new_total
-                ));
+                logger.log_transition(
+                    Transition::new("env_truncate_total")
+                        .field("label", log_label)
+                        .field("task", task_token)
+                        .field("original_bytes", total)
+                        .field("truncated_bytes", new_total),
+                );
             }
         }
 
@@ -265,6 +302,24 @@ impl CommandEnv {
             self.needs_human.as_deref(),
             TRUDGER_ENV_VALUE_MAX_BYTES,
         );
+        Self::apply_optional_with_max(
+            cmd,
+            logger,
+            log_label,
+            task_token,
+            "TRUDGER_SKIPPED",
+            self.skipped.as_deref(),
+            TRUDGER_ENV_VALUE_MAX_BYTES,
+        );
+        Self::apply_optional_with_max(
+            cmd,
+            logger,
+            log_label,
+            task_token,
+            "TRUDGER_DECLINED",
+            self.declined.as_deref(),
+            TRUDGER_ENV_VALUE_MAX_BYTES,
+        );
         Self::apply_optional_with_max(
             cmd,
             logger,
@@ -337,6 +392,51 @@ impl CommandEnv {
             self.notify_payload_path.as_deref(),
             TRUDGER_ENV_VALUE_MAX_BYTES,
         );
+        Self::apply_optional_with_max(
+            cmd,
+            logger,
+            log_label,
+            task_token,
+            "TRUDGER_AGENT_OUTPUT",
+            self.agent_output.as_deref(),
+            TRUDGER_ENV_VALUE_MAX_BYTES,
+        );
+        Self::apply_optional_with_max(
+            cmd,
+            logger,
+            log_label,
+            task_token,
+            "TRUDGER_REVIEW_RESULT_PATH",
+            self.review_result_path.as_deref(),
+            TRUDGER_ENV_VALUE_MAX_BYTES,
+        );
+        Self::apply_optional_with_max(
+            cmd,
+            logger,
+            log_label,
+            task_token,
+            "TRUDGER_REVIEW_FEEDBACK",
+            self.review_feedback.as_deref(),
+            TRUDGER_ENV_VALUE_MAX_BYTES,
+        );
+        Self::apply_optional_with_max(
+            cmd,
+            logger,
+            log_label,
+            task_token,
+            "TRUDGER_REVIEW_FOLLOW_UPS",
+            self.review_follow_ups.as_deref(),
+            TRUDGER_ENV_VALUE_MAX_BYTES,
+        );
+        Self::apply_optional_with_max(
+            cmd,
+            logger,
+            log_label,
+            task_token,
+            "TRUDGER_REVIEW_LOOP",
+            self.review_loop.as_deref(),
+            TRUDGER_ENV_VALUE_MAX_BYTES,
+        );
     }
 
     fn maybe_truncate_utf8(value: &str, max_bytes: usize) -> (Cow<'_, str>, usize, usize) {
@@ -380,6 +480,8 @@ impl CommandEnv {
         agent_invocation_id: Option<&str>,
         completed: Option<&str>,
         needs_human: Option<&str>,
+        skipped: Option<&str>,
+        declined: Option<&str>,
         notify_event: Option<&str>,
         notify_duration_ms: Option<&str>,
         notify_folder: Option<&str>,
@@ -388,6 +490,11 @@ impl CommandEnv {
         notify_task_description: Option<&str>,
         notify_message: Option<&str>,
         notify_payload_path: Option<&str>,
+        agent_output: Option<&str>,
+        review_result_path: Option<&str>,
+        review_feedback: Option<&str>,
+        review_follow_ups: Option<&str>,
+        review_loop: Option<&str>,
     ) -> usize {
         let mut total = 0usize;
         total += Self::env_entry_payload_bytes(
@@ -440,6 +547,13 @@ impl CommandEnv {
             needs_human,
             TRUDGER_ENV_VALUE_MAX_BYTES,
         );
+        total +=
+            Self::env_entry_payload_bytes("TRUDGER_SKIPPED", skipped, TRUDGER_ENV_VALUE_MAX_BYTES);
+        total += Self::env_entry_payload_bytes(
+            "TRUDGER_DECLINED",
+            declined,
+            TRUDGER_ENV_VALUE_MAX_BYTES,
+        );
         total += Self::env_entry_payload_bytes(
             "TRUDGER_NOTIFY_EVENT",
             notify_event,
@@ -480,6 +594,31 @@ impl CommandEnv {
             notify_payload_path,
             TRUDGER_ENV_VALUE_MAX_BYTES,
         );
+        total += Self::env_entry_payload_bytes(
+            "TRUDGER_AGENT_OUTPUT",
+            agent_output,
+            TRUDGER_ENV_VALUE_MAX_BYTES,
+        );
+        total += Self::env_entry_payload_bytes(
+            "TRUDGER_REVIEW_RESULT_PATH",
+            review_result_path,
+            TRUDGER_ENV_VALUE_MAX_BYTES,
+        );
+        total += Self::env_entry_payload_bytes(
+            "TRUDGER_REVIEW_FEEDBACK",
+            review_feedback,
+            TRUDGER_ENV_VALUE_MAX_BYTES,
+        );
+        total += Self::env_entry_payload_bytes(
+            "TRUDGER_REVIEW_FOLLOW_UPS",
+            review_follow_ups,
+            TRUDGER_ENV_VALUE_MAX_BYTES,
+        );
+        total += Self::env_entry_payload_bytes(
+            "TRUDGER_REVIEW_LOOP",
+            review_loop,
+            TRUDGER_ENV_VALUE_MAX_BYTES,
+        );
         total
     }
 
@@ -522,10 +661,14 @@ impl CommandEnv {
                 "Warning: {} is {} bytes; truncating to {} bytes for command execution.",
                 key, original_bytes, truncated_bytes
             );
-            logger.log_transition(&format!(
-                "env_truncate label={} task={} key={} original_bytes={} truncated_bytes={}",
-                log_label, task_token, key, original_bytes, truncated_bytes
-            ));
+            logger.log_transition(
+                Transition::new("env_truncate")
+                    .field("label", log_label)
+                    .field("task", task_token)
+                    .field("key", key)
+                    .field("original_bytes", original_bytes)
+                    .field("truncated_bytes", truncated_bytes),
+            );
         }
         cmd.env(key, rendered.as_ref());
     }
@@ -552,14 +695,52 @@ impl CommandEnv {
 
 #[cfg(test)]
 mod tests {
-    use super::{run_shell_command_capture, CommandEnv, Logger};
+    use super::{
+        interrupted_by_ctrl_c, run_shell_command_capture, run_shell_command_status_with_deadline,
+        CommandDeadline, CommandEnv, CommandStatus, Logger,
+    };
     use std::fs;
     use std::io::Read;
     use std::os::fd::{AsRawFd, FromRawFd};
     use std::os::raw::c_int;
     use std::process::Command;
+    use std::sync::atomic::AtomicBool;
+    use std::time::{Duration, Instant};
     use tempfile::TempDir;
 
+    fn empty_env() -> CommandEnv {
+        CommandEnv {
+            cwd: None,
+            config_path: "config".to_string(),
+            scratch_dir: None,
+            task_id: None,
+            task_show: None,
+            task_status: None,
+            target_status: None,
+            agent_prompt: None,
+            agent_phase: None,
+            completed: None,
+            needs_human: None,
+            skipped: None,
+            declined: None,
+            notify_event: None,
+            notify_duration_ms: None,
+            notify_folder: None,
+            notify_exit_code: None,
+            notify_task_id: None,
+            notify_task_description: None,
+            notify_message: None,
+            notify_payload_path: None,
+            agent_output: None,
+            review_result_path: None,
+            review_feedback: None,
+            review_follow_ups: None,
+            review_loop: None,
+            agent_profile: None,
+            agent_invocation_id: None,
+        }
+    }
+
     #[cfg(unix)]
     fn capture_stderr<F: FnOnce()>(f: F) -> String {
         extern "C" {
@@ -651,6 +832,8 @@ mod tests {
             agent_invocation_id: None,
             completed: Some(huge),
             needs_human: None,
+            skipped: None,
+            declined: None,
             notify_event: None,
             notify_duration_ms: None,
             notify_folder: None,
@@ -659,6 +842,11 @@ mod tests {
             notify_task_description: None,
             notify_message: None,
             notify_payload_path: None,
+            agent_output: None,
+            review_result_path: None,
+            review_feedback: None,
+            review_follow_ups: None,
+            review_loop: None,
         };
 
         let mut cmd = Command::new("true");
@@ -696,6 +884,8 @@ mod tests {
             agent_phase: None,
             completed: None,
             needs_human: None,
+            skipped: None,
+            declined: None,
             notify_event: None,
             notify_duration_ms: None,
             notify_folder: None,
@@ -704,6 +894,11 @@ mod tests {
             notify_task_description: None,
             notify_message: None,
             notify_payload_path: None,
+            agent_output: None,
+            review_result_path: None,
+            review_feedback: None,
+            review_follow_ups: None,
+            review_loop: None,
             agent_profile: None,
             agent_invocation_id: None,
         };
@@ -735,6 +930,138 @@ mod tests {
 
         crate::unit_tests::reset_test_env();
     }
+
+    #[cfg(unix)]
+    #[test]
+    fn status_with_deadline_kills_the_whole_process_group_on_timeout() {
+        let temp = TempDir::new().expect("temp dir");
+        let log_path = temp.path().join("trudger.log");
+        let pid_path = temp.path().join("sleeper.pid");
+        let logger = Logger::new(Some(log_path.clone()));
+        let interrupt_flag = AtomicBool::new(false);
+
+        let started_at = Instant::now();
+        let status = run_shell_command_status_with_deadline(
+            &format!("sleep 30 & echo $! > '{}'; wait", pid_path.display()),
+            "agent_solve",
+            "tr-1",
+            &[],
+            &empty_env(),
+            &logger,
+            CommandDeadline {
+                timeout: Duration::from_secs(2),
+                interrupt_flag: &interrupt_flag,
+            },
+        )
+        .expect("command should spawn");
+        assert_eq!(status, CommandStatus::TimedOut);
+        assert!(started_at.elapsed() < Duration::from_secs(20));
+
+        let pid = fs::read_to_string(&pid_path).expect("read sleeper pid");
+        let alive = Command::new("kill")
+            .arg("-0")
+            .arg(pid.trim())
+            .stderr(std::process::Stdio::null())
+            .status()
+            .expect("run kill -0");
+        assert!(!alive.success(), "background sleeper should be killed");
+
+        let contents = fs::read_to_string(&log_path).expect("read log file");
+        assert!(
+            contents.contains("cmd timeout label=agent_solve task=tr-1 timeout_ms=2000"),
+            "expected timeout transition, got: {contents:?}"
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn status_with_deadline_kills_group_members_that_ignore_sigterm() {
+        let temp = TempDir::new().expect("temp dir");
+        let pgid_path = temp.path().join("group.pid");
+        let logger = Logger::new(None);
+        let interrupt_flag = AtomicBool::new(false);
+
+        let status = run_shell_command_status_with_deadline(
+            &format!(
+                "echo $$ > '{}'; (trap '' TERM; sleep 30) & wait",
+                pgid_path.display()
+            ),
+            "agent_solve",
+            "tr-1",
+            &[],
+            &empty_env(),
+            &logger,
+            CommandDeadline {
+                timeout: Duration::from_secs(1),
+                interrupt_flag: &interrupt_flag,
+            },
+        )
+        .expect("command should spawn");
+        assert_eq!(status, CommandStatus::TimedOut);
+
+        let pgid = fs::read_to_string(&pgid_path).expect("read group id");
+        let alive = Command::new("kill")
+            .arg("-0")
+            .arg("--")
+            .arg(format!("-{}", pgid.trim()))
+            .stderr(std::process::Stdio::null())
+            .status()
+            .expect("run kill -0");
+        assert!(
+            !alive.success(),
+            "the sleeper ignoring SIGTERM should be killed"
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn interrupted_by_ctrl_c_matches_sigint_deaths_and_exit_130() {
+        let status = |script: &str| {
+            Command::new("bash")
+                .arg("-c")
+                .arg(script)
+                .status()
+                .expect("run bash")
+        };
+        assert!(interrupted_by_ctrl_c(&status("kill -INT $$")));
+        assert!(interrupted_by_ctrl_c(&status("exit 130")));
+        assert!(!interrupted_by_ctrl_c(&status("exit 1")));
+        assert!(!interrupted_by_ctrl_c(&status("kill -TERM $$")));
+    }
+
+    #[test]
+    fn status_with_deadline_reports_exit_code_when_command_finishes() {
+        let logger = Logger::new(None);
+        let interrupt_flag = AtomicBool::new(false);
+        let deadline = CommandDeadline {
+            timeout: Duration::from_secs(30),
+            interrupt_flag: &interrupt_flag,
+        };
+
+        let status = run_shell_command_status_with_deadline(
+            "exit 3",
+            "agent_solve",
+            "none",
+            &[],
+            &empty_env(),
+            &logger,
+            deadline,
+        )
+        .expect("command should spawn");
+        assert_eq!(status, CommandStatus::Exited(3));
+
+        let status = run_shell_command_status_with_deadline(
+            "",
+            "agent_solve",
+            "none",
+            &[],
+            &empty_env(),
+            &logger,
+            deadline,
+        )
+        .expect("empty command is a no-op");
+        assert_eq!(status, CommandStatus::Exited(0));
+    }
 }
 
 #[derive(Debug)]
@@ -749,27 +1076,36 @@ enum ShellCommandStdioMode {
     Inherit,
 }
 
-fn run_shell_command_bash_lc(
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum CommandStatus {
+    Exited(i32),
+    TimedOut,
+}
+
+/// Bounds a status-mode command: it is killed once `timeout` elapses, and Ctrl-C (observed via
+/// `interrupt_flag`) is forwarded to it.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct CommandDeadline<'a> {
+    pub(crate) timeout: Duration,
+    pub(crate) interrupt_flag: &'a AtomicBool,
+}
+
+fn bash_lc_command(
     command: &str,
     log_label: &str,
     task_token: &str,
     args: &[String],
-    env: &CommandEnv,
     logger: &Logger,
-    stdio_mode: ShellCommandStdioMode,
-) -> Result<(i32, Option<String>), String> {
-    if command.is_empty() {
-        return Ok((0, None));
-    }
-
+) -> Command {
     let args_render = render_args(args);
-    logger.log_transition(&format!(
-        "cmd start label={} task={} mode=bash_lc command={} args={}",
-        log_label,
-        task_token,
-        sanitize_log_value(command),
-        sanitize_log_value(&args_render)
-    ));
+    logger.log_transition(
+        Transition::new("cmd start")
+            .field("label", log_label)
+            .field("task", task_token)
+            .field("mode", "bash_lc")
+            .field("command", command)
+            .field("args", args_render),
+    );
 
     let mut cmd = Command::new("bash");
     cmd.arg("-lc").arg(command);
@@ -777,6 +1113,23 @@ fn run_shell_command_bash_lc(
         cmd.arg("--");
         cmd.args(args);
     }
+    cmd
+}
+
+fn run_shell_command_bash_lc(
+    command: &str,
+    log_label: &str,
+    task_token: &str,
+    args: &[String],
+    env: &CommandEnv,
+    logger: &Logger,
+    stdio_mode: ShellCommandStdioMode,
+) -> Result<(i32, Option<String>), String> {
+    if command.is_empty() {
+        return Ok((0, None));
+    }
+
+    let mut cmd = bash_lc_command(command, log_label, task_token, args, logger);
 
     match stdio_mode {
         ShellCommandStdioMode::Capture => {}
@@ -789,6 +1142,7 @@ fn run_shell_command_bash_lc(
 
     env.apply(&mut cmd, logger, log_label, task_token);
 
+    let started_at = Instant::now();
     let (exit_code, stdout) = match stdio_mode {
         ShellCommandStdioMode::Capture => {
             let output = cmd
@@ -809,10 +1163,13 @@ fn run_shell_command_bash_lc(
         }
     };
 
-    logger.log_transition(&format!(
-        "cmd exit label={} task={} exit={}",
-        log_label, task_token, exit_code
-    ));
+    logger.log_transition(
+        Transition::new("cmd exit")
+            .field("label", log_label)
+            .field("task", task_token)
+            .field("exit", exit_code)
+            .field("duration_ms", started_at.elapsed().as_millis()),
+    );
 
     Ok((exit_code, stdout))
 }
@@ -841,6 +1198,51 @@ pub(crate) fn run_shell_command_capture(
     })
 }
 
+/// Runs `program` directly, without a shell, and captures its stdout; stderr passes through.
+/// Used by native task backends, which build their own argument lists.
+pub(crate) fn run_program_capture(
+    program: &str,
+    args: &[String],
+    cwd: Option<&Path>,
+    log_label: &str,
+    task_token: &str,
+    logger: &Logger,
+) -> Result<CommandResult, String> {
+    logger.log_transition(
+        Transition::new("cmd start")
+            .field("label", log_label)
+            .field("task", task_token)
+            .field("mode", "exec")
+            .field("command", program)
+            .field("args", render_args(args)),
+    );
+
+    let mut cmd = Command::new(program);
+    cmd.args(args);
+    cmd.stderr(std::process::Stdio::inherit());
+    if let Some(cwd) = cwd {
+        cmd.current_dir(cwd);
+    }
+
+    let started_at = Instant::now();
+    let output = cmd
+        .output()
+        .map_err(|err| format!("Failed to run command '{}': {}", program, err))?;
+    let exit_code = output.status.code().unwrap_or(1);
+    logger.log_transition(
+        Transition::new("cmd exit")
+            .field("label", log_label)
+            .field("task", task_token)
+            .field("exit", exit_code)
+            .field("duration_ms", started_at.elapsed().as_millis()),
+    );
+
+    Ok(CommandResult {
+        stdout: String::from_utf8_lossy(&output.stdout).to_string(),
+        exit_code,
+    })
+}
+
 pub(crate) fn run_shell_command_status(
     command: &str,
     log_label: &str,
@@ -862,6 +1264,433 @@ pub(crate) fn run_shell_command_status(
     Ok(exit_code)
 }
 
+pub(crate) fn run_shell_command_status_with_deadline(
+    command: &str,
+    log_label: &str,
+    task_token: &str,
+    args: &[String],
+    env: &CommandEnv,
+    logger: &Logger,
+    deadline: CommandDeadline<'_>,
+) -> Result<CommandStatus, String> {
+    run_shell_command_supervised(
+        command,
+        log_label,
+        task_token,
+        args,
+        env,
+        logger,
+        Some(deadline),
+        None,
+    )
+}
+
+/// Runs a status-mode command whose stdout and stderr still reach the terminal but are also
+/// appended to `transcript` (created along with its parent directories).
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn run_shell_command_tee(
+    command: &str,
+    log_label: &str,
+    task_token: &str,
+    args: &[String],
+    env: &CommandEnv,
+    logger: &Logger,
+    deadline: Option<CommandDeadline<'_>>,
+    transcript: &Path,
+) -> Result<CommandStatus, String> {
+    run_shell_command_supervised(
+        command,
+        log_label,
+        task_token,
+        args,
+        env,
+        logger,
+        deadline,
+        Some(transcript),
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
+fn run_shell_command_supervised(
+    command: &str,
+    log_label: &str,
+    task_token: &str,
+    args: &[String],
+    env: &CommandEnv,
+    logger: &Logger,
+    deadline: Option<CommandDeadline<'_>>,
+    transcript: Option<&Path>,
+) -> Result<CommandStatus, String> {
+    if command.is_empty() {
+        return Ok(CommandStatus::Exited(0));
+    }
+
+    let transcript = match transcript.map(open_transcript).transpose() {
+        Ok(file) => file,
+        Err(err) => {
+            // Losing the transcript must not cost the agent run; fall back to the terminal only.
+            let mut stderr = std::io::stderr().lock();
+            let _ = writeln!(stderr, "Warning: {}.", err);
+            logger.log_transition(
+                Transition::new("agent_output_failed")
+                    .field("label", log_label)
+                    .field("task", task_token)
+                    .field("err", err),
+            );
+            None
+        }
+    };
+
+    let mut cmd = bash_lc_command(command, log_label, task_token, args, logger);
+    cmd.stdin(std::process::Stdio::inherit());
+    if transcript.is_some() {
+        cmd.stdout(std::process::Stdio::piped());
+        cmd.stderr(std::process::Stdio::piped());
+    } else {
+        cmd.stdout(std::process::Stdio::inherit());
+        cmd.stderr(std::process::Stdio::inherit());
+    }
+    env.apply(&mut cmd, logger, log_label, task_token);
+
+    // Run in a dedicated process group so a timeout takes down everything the command spawned.
+    // When trudger owns the terminal, that group becomes its foreground group, so an interactive
+    // agent is not stopped by SIGTTIN/SIGTTOU when it touches the terminal.
+    let foreground = deadline.is_some() && owns_terminal();
+    #[cfg(unix)]
+    if deadline.is_some() {
+        use std::os::unix::process::CommandExt;
+        cmd.process_group(0);
+        if foreground {
+            // SAFETY: the hook only makes async-signal-safe calls (`getpid`, `signal`,
+            // `tcsetpgrp`). Both sides set the foreground group so neither can race the other.
+            unsafe {
+                cmd.pre_exec(|| {
+                    set_terminal_foreground(current_pid());
+                    Ok(())
+                });
+            }
+        }
+    }
+
+    let mut child = cmd
+        .spawn()
+        .map_err(|err| format!("Failed to run command '{}': {}", command, err))?;
+    let handoff = foreground.then(|| TerminalHandoff::start(&child)).flatten();
+    let tees = match transcript {
+        Some(file) => {
+            let file = Arc::new(Mutex::new(file));
+            let mut tees = Vec::new();
+            if let Some(stdout) = child.stdout.take() {
+                tees.push(tee_output(stdout, std::io::stdout(), file.clone()));
+            }
+            if let Some(stderr) = child.stderr.take() {
+                tees.push(tee_output(stderr, std::io::stderr(), file));
+            }
+            tees
+        }
+        None => Vec::new(),
+    };
+
+    let status = wait_for_command(
+        &mut child,
+        command,
+        log_label,
+        task_token,
+        logger,
+        deadline,
+        handoff.is_some(),
+    );
+    drop(handoff);
+    for tee in tees {
+        let _ = tee.join();
+    }
+    status
+}
+
+/// Whether stdin is a terminal whose foreground process group is trudger's own.
+#[cfg(unix)]
+fn owns_terminal() -> bool {
+    extern "C" {
+        fn isatty(fd: i32) -> i32;
+        fn tcgetpgrp(fd: i32) -> i32;
+        fn getpgrp() -> i32;
+    }
+    // SAFETY: these calls only query the state of fd 0 and of this process.
+    unsafe { isatty(0) == 1 && tcgetpgrp(0) == getpgrp() }
+}
+
+#[cfg(not(unix))]
+fn owns_terminal() -> bool {
+    false
+}
+
+#[cfg(unix)]
+fn current_pid() -> i32 {
+    extern "C" {
+        fn getpid() -> i32;
+    }
+    // SAFETY: `getpid` has no preconditions.
+    unsafe { getpid() }
+}
+
+/// Makes `pgid` the terminal's foreground process group. A background group changing it gets
+/// SIGTTOU, so the signal is blocked on this thread for the duration of the call.
+#[cfg(unix)]
+fn set_terminal_foreground(pgid: i32) {
+    extern "C" {
+        fn sigemptyset(set: *mut u64) -> i32;
+        fn sigaddset(set: *mut u64, signum: i32) -> i32;
+        fn pthread_sigmask(how: i32, set: *const u64, old: *mut u64) -> i32;
+        fn tcsetpgrp(fd: i32, pgrp: i32) -> i32;
+    }
+    #[cfg(target_os = "linux")]
+    const SIG_BLOCK: i32 = 0;
+    #[cfg(target_os = "linux")]
+    const SIG_SETMASK: i32 = 2;
+    #[cfg(not(target_os = "linux"))]
+    const SIG_BLOCK: i32 = 1;
+    #[cfg(not(target_os = "linux"))]
+    const SIG_SETMASK: i32 = 3;
+    // Large enough for any platform's `sigset_t`.
+    let mut block = [0u64; 16];
+    let mut previous = [0u64; 16];
+    // SAFETY: both buffers outlive the calls and are at least as large as `sigset_t`; the
+    // previous mask is restored right after `tcsetpgrp`.
+    unsafe {
+        sigemptyset(block.as_mut_ptr());
+        sigaddset(block.as_mut_ptr(), SIGTTOU);
+        pthread_sigmask(SIG_BLOCK, block.as_ptr(), previous.as_mut_ptr());
+        tcsetpgrp(0, pgid);
+        pthread_sigmask(SIG_SETMASK, previous.as_ptr(), std::ptr::null_mut());
+    }
+}
+
+/// A timed command's process group holding the terminal; dropping it hands the terminal back to
+/// trudger.
+struct TerminalHandoff {
+    pgid: i32,
+}
+
+impl TerminalHandoff {
+    #[cfg(unix)]
+    fn start(child: &Child) -> Option<Self> {
+        let pgid = i32::try_from(child.id()).ok()?;
+        set_terminal_foreground(pgid);
+        Some(Self { pgid })
+    }
+
+    #[cfg(not(unix))]
+    fn start(_child: &Child) -> Option<Self> {
+        None
+    }
+}
+
+impl Drop for TerminalHandoff {
+    #[cfg(unix)]
+    fn drop(&mut self) {
+        extern "C" {
+            fn tcgetpgrp(fd: i32) -> i32;
+            fn getpgrp() -> i32;
+        }
+        // SAFETY: see `owns_terminal`; the terminal is only taken back from this command's group.
+        unsafe {
+            if tcgetpgrp(0) == self.pgid {
+                set_terminal_foreground(getpgrp());
+            }
+        }
+    }
+
+    #[cfg(not(unix))]
+    fn drop(&mut self) {
+        let _ = self.pgid;
+    }
+}
+
+fn open_transcript(path: &Path) -> Result<fs::File, String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|err| {
+            format!(
+                "failed to create agent output directory {}: {}",
+                parent.display(),
+                err
+            )
+        })?;
+    }
+    fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .map_err(|err| format!("failed to create agent output {}: {}", path.display(), err))
+}
+
+/// Copies one of a child's output streams to trudger's own and into the shared transcript.
+fn tee_output<R, W>(mut source: R, mut sink: W, transcript: Arc<Mutex<fs::File>>) -> JoinHandle<()>
+where
+    R: Read + Send + 'static,
+    W: Write + Send + 'static,
+{
+    thread::spawn(move || {
+        let mut buffer = [0u8; 8192];
+        loop {
+            let read = match source.read(&mut buffer) {
+                Ok(0) | Err(_) => return,
+                Ok(read) => read,
+            };
+            let _ = sink.write_all(&buffer[..read]);
+            let _ = sink.flush();
+            if let Ok(mut file) = transcript.lock() {
+                let _ = file.write_all(&buffer[..read]);
+            }
+        }
+    })
+}
+
+fn wait_for_command(
+    child: &mut Child,
+    command: &str,
+    log_label: &str,
+    task_token: &str,
+    logger: &Logger,
+    deadline: Option<CommandDeadline<'_>>,
+    foreground: bool,
+) -> Result<CommandStatus, String> {
+    let started_at = Instant::now();
+    let log_exit = |exit_code: i32| {
+        logger.log_transition(
+            Transition::new("cmd exit")
+                .field("label", log_label)
+                .field("task", task_token)
+                .field("exit", exit_code)
+                .field("duration_ms", started_at.elapsed().as_millis()),
+        );
+    };
+    let Some(deadline) = deadline else {
+        let status = child
+            .wait()
+            .map_err(|err| format!("Failed to wait for command '{}': {}", command, err))?;
+        let exit_code = status.code().unwrap_or(1);
+        log_exit(exit_code);
+        return Ok(CommandStatus::Exited(exit_code));
+    };
+
+    let mut interrupt_forwarded = false;
+    loop {
+        let status = child
+            .try_wait()
+            .map_err(|err| format!("Failed to wait for command '{}': {}", command, err))?;
+        if let Some(status) = status {
+            // With the terminal handed over, Ctrl-C only reaches the command; pass it on.
+            if foreground && interrupted_by_ctrl_c(&status) {
+                deadline.interrupt_flag.store(true, Ordering::SeqCst);
+            }
+            let exit_code = status.code().unwrap_or(1);
+            log_exit(exit_code);
+            return Ok(CommandStatus::Exited(exit_code));
+        }
+
+        if started_at.elapsed() >= deadline.timeout {
+            break;
+        }
+
+        // The child's process group no longer receives the terminal's SIGINT, so forward it.
+        if !interrupt_forwarded && deadline.interrupt_flag.load(Ordering::SeqCst) {
+            signal_process_group(child, SIGINT);
+            interrupt_forwarded = true;
+        }
+        thread::sleep(TIMED_COMMAND_POLL_INTERVAL);
+    }
+
+    logger.log_transition(
+        Transition::new("cmd timeout")
+            .field("label", log_label)
+            .field("task", task_token)
+            .field("timeout_ms", deadline.timeout.as_millis()),
+    );
+    terminate_process_group(child);
+    Ok(CommandStatus::TimedOut)
+}
+
+#[cfg(unix)]
+fn interrupted_by_ctrl_c(status: &std::process::ExitStatus) -> bool {
+    use std::os::unix::process::ExitStatusExt;
+    status.signal() == Some(SIGINT) || status.code() == Some(128 + SIGINT)
+}
+
+#[cfg(not(unix))]
+fn interrupted_by_ctrl_c(_status: &std::process::ExitStatus) -> bool {
+    false
+}
+
+/// Sends SIGTERM to the child's process group and SIGKILL to whatever is left of it after
+/// `TIMED_COMMAND_KILL_GRACE`. Members that outlive the leader (an agent trapping SIGTERM, or its
+/// own children) would otherwise keep running and hold the transcript pipes open.
+fn terminate_process_group(child: &mut Child) {
+    signal_process_group(child, SIGTERM);
+    if wait_for_process_group(child, TIMED_COMMAND_KILL_GRACE) {
+        return;
+    }
+    signal_process_group(child, SIGKILL);
+    if !wait_for_process_group(child, TIMED_COMMAND_KILL_GRACE) {
+        let _ = child.wait();
+    }
+}
+
+/// Reaps the leader and waits up to `grace` for every other group member to exit; true once the
+/// group is gone.
+fn wait_for_process_group(child: &mut Child, grace: Duration) -> bool {
+    let started_at = Instant::now();
+    loop {
+        // The leader stays a zombie member of its group until reaped.
+        let _ = child.try_wait();
+        if !process_group_running(child) {
+            return true;
+        }
+        if started_at.elapsed() >= grace {
+            return false;
+        }
+        thread::sleep(TIMED_COMMAND_POLL_INTERVAL);
+    }
+}
+
+#[cfg(unix)]
+fn signal_process_group(child: &mut Child, signal: i32) {
+    extern "C" {
+        fn kill(pid: i32, sig: i32) -> i32;
+    }
+
+    let Ok(pgid) = i32::try_from(child.id()) else {
+        return;
+    };
+    // SAFETY: `kill` has no memory-safety preconditions; a negative pid addresses the process
+    // group that `process_group(0)` created for this child.
+    unsafe {
+        kill(-pgid, signal);
+    }
+}
+
+#[cfg(not(unix))]
+fn signal_process_group(child: &mut Child, _signal: i32) {
+    let _ = child.kill();
+}
+
+#[cfg(unix)]
+fn process_group_running(child: &mut Child) -> bool {
+    extern "C" {
+        fn kill(pid: i32, sig: i32) -> i32;
+    }
+
+    let Ok(pgid) = i32::try_from(child.id()) else {
+        return false;
+    };
+    // SAFETY: as in `signal_process_group`; signal 0 only checks that a group member exists.
+    unsafe { kill(-pgid, 0) == 0 }
+}
+
+#[cfg(not(unix))]
+fn process_group_running(child: &mut Child) -> bool {
+    matches!(child.try_wait(), Ok(None))
+}
+
 pub(crate) fn command_exists(name: &str) -> bool {
     let Some(paths) = env::var_os("PATH") else {
         return false;
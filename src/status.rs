@@ -0,0 +1,676 @@
+use chrono::{DateTime, NaiveDateTime, Utc};
+use serde::Serialize;
+use std::fs;
+use std::io::{ErrorKind, Write};
+use std::path::Path;
+
+use crate::config::Config;
+use crate::run_journal::{read_run_journal, RunJournal};
+use crate::run_loop::Quit;
+
+const LOG_TIMESTAMP_FORMAT: &str = "%Y-%m-%dT%H:%M:%SZ";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum RunState {
+    Running,
+    Finished,
+    /// Stopped without logging `run_end` (killed, or crashed) and is no longer running.
+    Interrupted,
+}
+
+impl RunState {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Running => "running",
+            Self::Finished => "finished",
+            Self::Interrupted => "interrupted",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum TaskOutcome {
+    InProgress,
+    Completed,
+    NeedsHuman,
+    Skipped,
+    Declined,
+    Failed,
+    /// Still in progress when its run ended.
+    Unfinished,
+}
+
+impl TaskOutcome {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::InProgress => "in_progress",
+            Self::Completed => "completed",
+            Self::NeedsHuman => "needs_human",
+            Self::Skipped => "skipped",
+            Self::Declined => "declined",
+            Self::Failed => "failed",
+            Self::Unfinished => "unfinished",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+struct TaskSummary {
+    task_id: String,
+    outcome: TaskOutcome,
+    /// Last phase the task entered (`solving` or `reviewing`).
+    phase: Option<String>,
+    review_loop: u64,
+    /// Review rounds started for the task.
+    review_loops: u64,
+    started_at: String,
+    ended_at: Option<String>,
+    duration_s: Option<i64>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+struct RunSummary {
+    state: RunState,
+    started_at: String,
+    ended_at: Option<String>,
+    last_activity_at: String,
+    duration_s: Option<i64>,
+    pid: Option<u32>,
+    profile: Option<String>,
+    exit_code: Option<i32>,
+    /// Last `quit reason=` of the run, for example `no_task` or `solve_failed`.
+    reason: Option<String>,
+    tasks: Vec<TaskSummary>,
+}
+
+impl RunSummary {
+    fn new(started_at: &str) -> Self {
+        Self {
+            state: RunState::Running,
+            started_at: started_at.to_string(),
+            ended_at: None,
+            last_activity_at: started_at.to_string(),
+            duration_s: None,
+            pid: None,
+            profile: None,
+            exit_code: None,
+            reason: None,
+            tasks: Vec::new(),
+        }
+    }
+
+    fn task_mut(&mut self, task_id: &str, ts: &str) -> &mut TaskSummary {
+        let index = match self.tasks.iter().position(|task| task.task_id == task_id) {
+            Some(index) => index,
+            None => {
+                self.tasks.push(TaskSummary {
+                    task_id: task_id.to_string(),
+                    outcome: TaskOutcome::InProgress,
+                    phase: None,
+                    review_loop: 0,
+                    review_loops: 0,
+                    started_at: ts.to_string(),
+                    ended_at: None,
+                    duration_s: None,
+                });
+                self.tasks.len() - 1
+            }
+        };
+        &mut self.tasks[index]
+    }
+
+    fn end_task(&mut self, task_id: &str, ts: &str, outcome: TaskOutcome) {
+        let task = self.task_mut(task_id, ts);
+        task.outcome = outcome;
+        task.ended_at = Some(ts.to_string());
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct StatusReport {
+    log_path: Option<String>,
+    runs: Vec<RunSummary>,
+    journal_path: Option<String>,
+    journal: Option<RunJournal>,
+}
+
+/// One transition read back from the log, in either `log_format`.
+struct LogRecord {
+    ts: String,
+    event: String,
+    fields: Vec<(String, String)>,
+}
+
+impl LogRecord {
+    fn field(&self, key: &str) -> Option<&str> {
+        self.fields
+            .iter()
+            .find(|(name, _)| name == key)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+fn parse_log_line(line: &str) -> Option<LogRecord> {
+    let line = line.trim_end();
+    if line.starts_with('{') {
+        return parse_json_log_line(line);
+    }
+    let (ts, message) = line.split_once(' ')?;
+    let message = match message.strip_prefix("worker=") {
+        Some(rest) => rest.split_once(' ').map(|(_, rest)| rest).unwrap_or(""),
+        None => message,
+    };
+    parse_timestamp(ts)?;
+    let (event, fields) = parse_transition(message);
+    Some(LogRecord {
+        ts: ts.to_string(),
+        event,
+        fields,
+    })
+}
+
+/// Reads a `jsonl` record's members back as fields, under the text log's `task` and `state` names.
+fn parse_json_log_line(line: &str) -> Option<LogRecord> {
+    let serde_json::Value::Object(record) = serde_json::from_str(line).ok()? else {
+        return None;
+    };
+    let ts = record.get("ts")?.as_str()?.to_string();
+    parse_timestamp(&ts)?;
+    let event = record.get("event")?.as_str()?.to_string();
+    let fields = record
+        .into_iter()
+        .filter(|(key, _)| !matches!(key.as_str(), "ts" | "worker" | "event" | "message"))
+        .map(|(key, value)| {
+            let key = match key.as_str() {
+                "task_id" => "task".to_string(),
+                "phase" => "state".to_string(),
+                _ => key,
+            };
+            let value = match value {
+                serde_json::Value::String(text) => text,
+                other => other.to_string(),
+            };
+            (key, value)
+        })
+        .collect();
+    Some(LogRecord { ts, event, fields })
+}
+
+/// Splits `event [detail...] key=value ...` into an event name (leading bare words joined with
+/// `_`, or the first key) and its fields. Words without a key continue the previous value; `err`
+/// and `args` run to the end of the message, and `command` up to its `args`.
+fn parse_transition(message: &str) -> (String, Vec<(String, String)>) {
+    let mut event = Vec::new();
+    let mut fields: Vec<(String, String)> = Vec::new();
+    let mut rest = message;
+    while !rest.is_empty() {
+        let (token, tail) = rest.split_once(' ').unwrap_or((rest, ""));
+        let field = token.split_once('=').filter(|(key, _)| is_field_key(key));
+        match field {
+            Some((key, value)) => {
+                let free_text_end = match key {
+                    "err" | "args" => Some(rest.len()),
+                    "command" => Some(rest.find(" args=").unwrap_or(rest.len())),
+                    _ => None,
+                };
+                match free_text_end {
+                    Some(end) => {
+                        fields.push((key.to_string(), rest[key.len() + 1..end].to_string()));
+                        rest = rest[end..].strip_prefix(' ').unwrap_or(&rest[end..]);
+                        continue;
+                    }
+                    None => fields.push((key.to_string(), value.to_string())),
+                }
+            }
+            None => match fields.last_mut() {
+                Some((_, value)) => {
+                    value.push(' ');
+                    value.push_str(token);
+                }
+                None => event.push(token),
+            },
+        }
+        rest = tail;
+    }
+
+    let event = if event.is_empty() {
+        fields
+            .first()
+            .map(|(key, _)| key.clone())
+            .unwrap_or_default()
+    } else {
+        event.join("_")
+    };
+    (event, fields)
+}
+
+fn is_field_key(key: &str) -> bool {
+    !key.is_empty()
+        && key
+            .chars()
+            .all(|ch| ch.is_ascii_lowercase() || ch.is_ascii_digit() || ch == '_')
+}
+
+fn parse_timestamp(ts: &str) -> Option<DateTime<Utc>> {
+    NaiveDateTime::parse_from_str(ts, LOG_TIMESTAMP_FORMAT)
+        .ok()
+        .map(|value| value.and_utc())
+}
+
+fn seconds_between(start: &str, end: &str) -> Option<i64> {
+    Some((parse_timestamp(end)? - parse_timestamp(start)?).num_seconds())
+}
+
+/// Folds a transition log into runs, oldest first. Lines written before the log recorded
+/// `run_start` are grouped into one run starting at the first of them.
+fn summarize_runs(
+    content: &str,
+    now: DateTime<Utc>,
+    is_running: impl Fn(u32) -> bool,
+) -> Vec<RunSummary> {
+    let mut runs: Vec<RunSummary> = Vec::new();
+    for record in content.lines().filter_map(parse_log_line) {
+        if record.event == "run_start" || runs.is_empty() {
+            runs.push(RunSummary::new(&record.ts));
+        }
+        let Some(run) = runs.last_mut() else {
+            continue;
+        };
+        run.last_activity_at = record.ts.clone();
+        let task_id = record.field("task").filter(|task| *task != "none");
+        match (record.event.as_str(), task_id) {
+            ("run_start", _) => {
+                run.pid = record.field("pid").and_then(|pid| pid.parse().ok());
+                run.profile = record
+                    .field("profile")
+                    .filter(|profile| *profile != "none")
+                    .map(str::to_string);
+            }
+            ("run_end", _) => {
+                run.state = RunState::Finished;
+                run.ended_at = Some(record.ts.clone());
+                run.exit_code = record.field("exit_code").and_then(|code| code.parse().ok());
+            }
+            ("quit", _) => run.reason = record.field("reason").map(str::to_string),
+            ("state" | "resume", Some(task_id)) => {
+                let phase = record
+                    .field("state")
+                    .or_else(|| record.field("phase"))
+                    .map(str::to_lowercase);
+                let review_loop = record
+                    .field("loop")
+                    .and_then(|value| value.parse().ok())
+                    .unwrap_or(0);
+                let task = run.task_mut(task_id, &record.ts);
+                task.outcome = TaskOutcome::InProgress;
+                task.ended_at = None;
+                task.review_loop = review_loop;
+                if phase.as_deref() == Some("reviewing") {
+                    task.review_loops = task.review_loops.max(review_loop + 1);
+                }
+                task.phase = phase;
+            }
+            ("completed", Some(task_id)) => {
+                run.end_task(task_id, &record.ts, TaskOutcome::Completed)
+            }
+            ("needs_human", Some(task_id)) => {
+                run.end_task(task_id, &record.ts, TaskOutcome::NeedsHuman)
+            }
+            ("skipped", Some(task_id)) => run.end_task(task_id, &record.ts, TaskOutcome::Skipped),
+            ("declined", Some(task_id)) => run.end_task(task_id, &record.ts, TaskOutcome::Declined),
+            ("solve_failed" | "review_failed" | "error", Some(task_id)) => {
+                run.end_task(task_id, &record.ts, TaskOutcome::Failed)
+            }
+            _ => {}
+        }
+    }
+
+    let run_count = runs.len();
+    let now = now.format(LOG_TIMESTAMP_FORMAT).to_string();
+    for (index, run) in runs.iter_mut().enumerate() {
+        let latest = index + 1 == run_count;
+        if run.state == RunState::Running && !(latest && run.pid.map(&is_running).unwrap_or(false))
+        {
+            run.state = RunState::Interrupted;
+        }
+        let end = match run.state {
+            RunState::Running => now.clone(),
+            RunState::Finished => run.ended_at.clone().unwrap_or_default(),
+            RunState::Interrupted => run.last_activity_at.clone(),
+        };
+        run.duration_s = seconds_between(&run.started_at, &end);
+        for task in &mut run.tasks {
+            if task.outcome == TaskOutcome::InProgress && run.state != RunState::Running {
+                task.outcome = TaskOutcome::Unfinished;
+            }
+            let task_end = match (&task.ended_at, task.outcome) {
+                (Some(ended_at), _) => ended_at.clone(),
+                (None, TaskOutcome::InProgress) => now.clone(),
+                (None, _) => end.clone(),
+            };
+            task.duration_s = seconds_between(&task.started_at, &task_end);
+        }
+    }
+    runs
+}
+
+#[cfg(unix)]
+fn process_is_running(pid: u32) -> bool {
+    extern "C" {
+        fn kill(pid: i32, sig: i32) -> i32;
+    }
+    const EPERM: i32 = 1;
+
+    let Ok(pid) = i32::try_from(pid) else {
+        return false;
+    };
+    // SAFETY: `kill` has no memory-safety preconditions; signal 0 only probes for the process.
+    if unsafe { kill(pid, 0) } == 0 {
+        return true;
+    }
+    std::io::Error::last_os_error().raw_os_error() == Some(EPERM)
+}
+
+#[cfg(not(unix))]
+fn process_is_running(_pid: u32) -> bool {
+    true
+}
+
+fn format_duration(seconds: Option<i64>) -> String {
+    let Some(seconds) = seconds.filter(|value| *value >= 0) else {
+        return "?".to_string();
+    };
+    let (hours, minutes, seconds) = (seconds / 3600, seconds / 60 % 60, seconds % 60);
+    if hours > 0 {
+        format!("{}h {}m {}s", hours, minutes, seconds)
+    } else if minutes > 0 {
+        format!("{}m {}s", minutes, seconds)
+    } else {
+        format!("{}s", seconds)
+    }
+}
+
+fn render_run(out: &mut String, run: &RunSummary) {
+    out.push_str(&format!(
+        "Run started {}: {} ({})",
+        run.started_at,
+        run.state.as_str(),
+        format_duration(run.duration_s)
+    ));
+    let mut details = Vec::new();
+    if let Some(pid) = run.pid {
+        details.push(format!("pid {}", pid));
+    }
+    if let Some(profile) = &run.profile {
+        details.push(format!("profile {}", profile));
+    }
+    if !details.is_empty() {
+        out.push_str(&format!(", {}", details.join(", ")));
+    }
+    out.push('\n');
+
+    for task in run
+        .tasks
+        .iter()
+        .filter(|task| task.outcome == TaskOutcome::InProgress)
+    {
+        out.push_str(&format!(
+            "  Current task: {} ({}, loop {})\n",
+            task.task_id,
+            task.phase.as_deref().unwrap_or("starting"),
+            task.review_loop
+        ));
+    }
+
+    if run.tasks.is_empty() {
+        out.push_str("  No tasks started.\n");
+    } else {
+        let width = run
+            .tasks
+            .iter()
+            .map(|task| task.task_id.len())
+            .max()
+            .unwrap_or(0);
+        for task in &run.tasks {
+            out.push_str(&format!(
+                "  {:<width$}  {:<11}  review loops: {}  {}\n",
+                task.task_id,
+                task.outcome.as_str(),
+                task.review_loops,
+                format_duration(task.duration_s),
+                width = width
+            ));
+        }
+    }
+
+    match (run.state, run.exit_code) {
+        (RunState::Finished, Some(exit_code)) => out.push_str(&format!(
+            "  Exit: {} ({})\n",
+            exit_code,
+            run.reason.as_deref().unwrap_or("done")
+        )),
+        (RunState::Interrupted, _) => out.push_str(&format!(
+            "  Stopped without a run_end record; last activity {}{}\n",
+            run.last_activity_at,
+            run.reason
+                .as_deref()
+                .map(|reason| format!(" (reason {})", reason))
+                .unwrap_or_default()
+        )),
+        _ => {}
+    }
+}
+
+fn render_report(report: &StatusReport) -> String {
+    let mut out = String::new();
+    match &report.log_path {
+        None => out.push_str("Transition log disabled (log_path is not set).\n"),
+        Some(path) if report.runs.is_empty() => {
+            out.push_str(&format!("No runs recorded in {}.\n", path))
+        }
+        Some(_) => {
+            for (index, run) in report.runs.iter().enumerate() {
+                if index > 0 {
+                    out.push('\n');
+                }
+                render_run(&mut out, run);
+            }
+        }
+    }
+
+    if let (Some(path), Some(journal)) = (&report.journal_path, &report.journal) {
+        out.push_str(&format!(
+            "\nRun journal {} (updated {}): {} completed, {} needing a human, {} skipped, {} declined",
+            path,
+            journal.updated_at,
+            journal.completed_tasks.len(),
+            journal.needs_human_tasks.len(),
+            journal.skipped_tasks.len(),
+            journal.declined_tasks.len()
+        ));
+        if let Some(task) = &journal.current_task {
+            out.push_str(&format!(
+                "; task {} was {} (loop {})",
+                task.task_id, task.phase, task.review_loop
+            ));
+        }
+        out.push_str(".\n");
+        let running = report
+            .runs
+            .last()
+            .is_some_and(|run| run.state == RunState::Running);
+        if !running {
+            out.push_str("Run `trudger resume` to continue it.\n");
+        }
+    }
+    out
+}
+
+fn build_report(config: &Config, all: bool) -> Result<StatusReport, String> {
+    if config.log_path.is_none() && config.journal_path.is_none() {
+        return Err(
+            "trudger status needs log_path or journal_path in config; both are disabled."
+                .to_string(),
+        );
+    }
+
+    let mut runs = match &config.log_path {
+        Some(path) => match fs::read_to_string(path) {
+            Ok(content) => summarize_runs(&content, Utc::now(), process_is_running),
+            Err(err) if err.kind() == ErrorKind::NotFound => Vec::new(),
+            Err(err) => return Err(format!("Failed to read log {}: {}", path.display(), err)),
+        },
+        None => Vec::new(),
+    };
+    if !all && runs.len() > 1 {
+        runs.drain(..runs.len() - 1);
+    }
+
+    let journal = match &config.journal_path {
+        Some(path) => read_run_journal(path)?,
+        None => None,
+    };
+    Ok(StatusReport {
+        log_path: config.log_path.as_deref().map(display_path),
+        runs,
+        journal_path: config.journal_path.as_deref().map(display_path),
+        journal,
+    })
+}
+
+fn display_path(path: &Path) -> String {
+    path.display().to_string()
+}
+
+/// Prints what the latest run (or, with `all`, every logged run) did, from the transition log
+/// and the run journal. Never writes to the log itself.
+pub(crate) fn run_status_mode(config: &Config, json: bool, all: bool) -> Result<(), Quit> {
+    let report = build_report(config, all).map_err(|message| {
+        eprintln!("{}", message);
+        Quit {
+            code: 1,
+            reason: message,
+        }
+    })?;
+    let rendered = if json {
+        let mut rendered = serde_json::to_string_pretty(&report).map_err(|err| Quit {
+            code: 1,
+            reason: format!("failed to serialize status: {}", err),
+        })?;
+        rendered.push('\n');
+        rendered
+    } else {
+        render_report(&report)
+    };
+    // Ignore write errors so piping into `head` does not panic on a closed pipe.
+    let _ = std::io::stdout().lock().write_all(rendered.as_bytes());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn now() -> DateTime<Utc> {
+        parse_timestamp("2026-02-10T10:10:00Z").expect("timestamp")
+    }
+
+    #[test]
+    fn summarize_runs_reads_outcomes_loops_and_exit() {
+        let log = "\
+2026-02-10T09:00:00Z run_start pid=10 profile=codex watch=false
+2026-02-10T09:00:01Z state=SOLVING task=tr-1 loop=0
+2026-02-10T09:02:00Z state=REVIEWING task=tr-1 loop=0
+2026-02-10T09:03:00Z state=SOLVING task=tr-1 loop=1
+2026-02-10T09:04:00Z state=REVIEWING task=tr-1 loop=1
+2026-02-10T09:05:00Z completed task=tr-1
+2026-02-10T09:05:01Z state=SOLVING task=tr-2 loop=0
+2026-02-10T09:06:00Z solve_failed task=tr-2
+2026-02-10T09:06:00Z task_error task=tr-2 phase=solve action=escalate
+2026-02-10T09:06:01Z needs_human task=tr-2
+2026-02-10T09:06:01Z state=SOLVING task=tr-3 loop=0
+2026-02-10T09:06:02Z declined task=tr-3
+2026-02-10T09:06:02Z quit reason=no_task
+2026-02-10T09:06:02Z run_end exit_code=0
+";
+        let runs = summarize_runs(log, now(), |_| true);
+        assert_eq!(runs.len(), 1);
+        let run = &runs[0];
+        assert_eq!(run.state, RunState::Finished);
+        assert_eq!(run.pid, Some(10));
+        assert_eq!(run.profile.as_deref(), Some("codex"));
+        assert_eq!(run.exit_code, Some(0));
+        assert_eq!(run.reason.as_deref(), Some("no_task"));
+        assert_eq!(run.duration_s, Some(362));
+
+        assert_eq!(run.tasks.len(), 3);
+        assert_eq!(run.tasks[0].task_id, "tr-1");
+        assert_eq!(run.tasks[0].outcome, TaskOutcome::Completed);
+        assert_eq!(run.tasks[0].review_loops, 2);
+        assert_eq!(run.tasks[0].duration_s, Some(299));
+        assert_eq!(run.tasks[1].outcome, TaskOutcome::NeedsHuman);
+        assert_eq!(run.tasks[1].review_loops, 0);
+        assert_eq!(run.tasks[2].outcome, TaskOutcome::Declined);
+
+        let rendered = render_report(&StatusReport {
+            log_path: Some(".trudger.log".to_string()),
+            runs,
+            journal_path: None,
+            journal: None,
+        });
+        assert!(rendered.contains("Run started 2026-02-10T09:00:00Z: finished (6m 2s), pid 10"));
+        assert!(rendered.contains("  tr-1  completed    review loops: 2  4m 59s\n"));
+        assert!(rendered.contains("  Exit: 0 (no_task)\n"));
+    }
+
+    #[test]
+    fn summarize_runs_tracks_current_task_and_interrupted_runs() {
+        let log = r#"2026-02-10T08:00:00Z run_start pid=7 profile=none watch=false
+2026-02-10T08:00:01Z state=SOLVING task=tr-9 loop=0
+2026-02-10T08:01:00Z cmd start label=agent_solve task=none mode=bash_lc command=codex args=
+{"ts":"2026-02-10T10:00:00Z","event":"run_start","pid":8,"message":"run_start pid=8 profile=codex watch=true"}
+{"ts":"2026-02-10T10:00:05Z","worker":1,"event":"state","phase":"reviewing","task_id":"tr-3","loop":2,"message":"state=REVIEWING task=tr-3 loop=2"}
+"#;
+        let runs = summarize_runs(log, now(), |pid| pid == 8);
+        assert_eq!(runs.len(), 2);
+
+        assert_eq!(runs[0].state, RunState::Interrupted);
+        assert_eq!(runs[0].profile, None);
+        assert_eq!(runs[0].last_activity_at, "2026-02-10T08:01:00Z");
+        assert_eq!(runs[0].tasks[0].outcome, TaskOutcome::Unfinished);
+
+        assert_eq!(runs[1].state, RunState::Running);
+        assert_eq!(runs[1].duration_s, Some(600));
+        let task = &runs[1].tasks[0];
+        assert_eq!(task.task_id, "tr-3");
+        assert_eq!(task.outcome, TaskOutcome::InProgress);
+        assert_eq!(task.phase.as_deref(), Some("reviewing"));
+        assert_eq!(task.review_loop, 2);
+        assert_eq!(task.duration_s, Some(595));
+
+        let not_running = summarize_runs(log, now(), |_| false);
+        assert_eq!(not_running[1].state, RunState::Interrupted);
+    }
+
+    #[test]
+    fn parse_log_line_strips_worker_prefix_and_skips_garbage() {
+        let record =
+            parse_log_line("2026-02-10T08:00:01Z worker=2 completed task=tr-1").expect("record");
+        assert_eq!(record.event, "completed");
+        assert_eq!(record.field("task"), Some("tr-1"));
+
+        assert!(parse_log_line("").is_none());
+        assert!(parse_log_line("not a log line").is_none());
+        assert!(parse_log_line("{\"ts\":\"x\"}").is_none());
+    }
+
+    #[test]
+    fn format_duration_uses_largest_units() {
+        assert_eq!(format_duration(Some(5)), "5s");
+        assert_eq!(format_duration(Some(65)), "1m 5s");
+        assert_eq!(format_duration(Some(3725)), "1h 2m 5s");
+        assert_eq!(format_duration(None), "?");
+    }
+}
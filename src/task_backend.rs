@@ -0,0 +1,541 @@
+use std::fmt;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use crate::beads::Br;
+use crate::config::{Backend, Config};
+use crate::github::GitHub;
+use crate::logger::Transition;
+use crate::openspec::Openspec;
+use crate::run_loop::{run_config_command, run_config_command_status, RuntimeState};
+use crate::task_types::{TaskId, TaskStatus};
+use crate::todo_file;
+
+/// What polling for the next task produced. The run loop validates the id and decides whether to
+/// idle or quit.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum NextTaskOutput {
+    /// Raw task id token, not yet validated.
+    Task(String),
+    /// Nothing to work on.
+    Empty,
+    /// The backend reported that nothing is selectable (`commands.next_task` exit 1).
+    NoneSelectable,
+    /// The backend failed with this exit code, which becomes trudger's exit code.
+    Failed(i32),
+}
+
+/// The task contract the run loop relies on: pick the next task, describe it, read and move its
+/// status, and run the completion/escalation hooks. `state` carries the config, working
+/// directory, logger, and task context of the caller.
+pub(crate) trait TaskBackend: fmt::Debug + Send + Sync {
+    /// `exclude` lists ids the run loop will not take this poll (skipped, already tried, or busy
+    /// in another worker). Backends that pick from a file pass over them so a skipped first item
+    /// does not hide the rest; command backends may still return one.
+    fn next_task(&self, state: &RuntimeState, exclude: &[TaskId])
+        -> Result<NextTaskOutput, String>;
+
+    /// Free-form task details for the agent (`TRUDGER_TASK_SHOW`).
+    fn show(&self, state: &RuntimeState, task_id: &TaskId) -> Result<String, String>;
+
+    /// The task's current status; `None` when the backend reports none.
+    fn status(&self, state: &RuntimeState, task_id: &TaskId) -> Result<Option<TaskStatus>, String>;
+
+    fn set_status(
+        &self,
+        state: &RuntimeState,
+        task_id: &TaskId,
+        status: &TaskStatus,
+    ) -> Result<(), String>;
+
+    /// Whether `set_status` can record `skipped`. A review `skip` verdict returns the task to
+    /// `open` on backends that cannot.
+    fn holds_skipped(&self) -> bool {
+        true
+    }
+
+    /// Ids of the tasks that must be closed before `task_id` is worked on. Backends without
+    /// dependency information report none.
+    fn dependencies(
+        &self,
+        _state: &RuntimeState,
+        _task_id: &TaskId,
+    ) -> Result<Vec<TaskId>, String> {
+        Ok(Vec::new())
+    }
+
+    /// Runs a `hooks.*` command. Hooks are shell commands for every real backend.
+    fn run_hook(
+        &self,
+        state: &RuntimeState,
+        hook_command: &str,
+        task_id: &TaskId,
+        hook_name: &str,
+    ) -> Result<(), String> {
+        let exit =
+            run_config_command_status(state, hook_command, Some(task_id), hook_name, None, &[])?;
+        if exit != 0 {
+            return Err(format!("hook {} failed with exit code {}", hook_name, exit));
+        }
+        Ok(())
+    }
+}
+
+/// The backend selected by `backend` in the config.
+pub(crate) fn task_backend(config: &Config) -> Arc<dyn TaskBackend> {
+    match config.backend {
+        Backend::Shell => Arc::new(ShellBackend),
+        Backend::Br => Arc::new(BrBackend),
+        Backend::Github => Arc::new(GithubBackend),
+        Backend::Markdown => Arc::new(MarkdownBackend),
+        Backend::Openspec => Arc::new(OpenspecBackend),
+    }
+}
+
+/// `backend: shell`: every operation is one of the `commands.*` strings, run with `bash -lc`.
+#[derive(Debug)]
+pub(crate) struct ShellBackend;
+
+impl TaskBackend for ShellBackend {
+    fn next_task(
+        &self,
+        state: &RuntimeState,
+        _exclude: &[TaskId],
+    ) -> Result<NextTaskOutput, String> {
+        let output = run_config_command(
+            state,
+            state.config.commands.next_task.as_deref().unwrap_or(""),
+            None,
+            "next-task",
+            &[],
+        )?;
+        Ok(match output.exit_code {
+            0 => match output.stdout.split_whitespace().next() {
+                Some(token) => NextTaskOutput::Task(token.to_string()),
+                None => NextTaskOutput::Empty,
+            },
+            1 => NextTaskOutput::NoneSelectable,
+            code => NextTaskOutput::Failed(code),
+        })
+    }
+
+    fn show(&self, state: &RuntimeState, task_id: &TaskId) -> Result<String, String> {
+        let output = run_config_command(
+            state,
+            &state.config.commands.task_show,
+            Some(task_id),
+            "task",
+            &[],
+        )?;
+        if output.exit_code != 0 {
+            return Err(format!(
+                "task_show failed with exit code {}",
+                output.exit_code
+            ));
+        }
+        Ok(output.stdout)
+    }
+
+    fn status(&self, state: &RuntimeState, task_id: &TaskId) -> Result<Option<TaskStatus>, String> {
+        let output = run_config_command(
+            state,
+            &state.config.commands.task_status,
+            Some(task_id),
+            "task",
+            &[],
+        )?;
+        if output.exit_code != 0 {
+            return Err(format!(
+                "task_status failed with exit code {}",
+                output.exit_code
+            ));
+        }
+        let token = output.stdout.split_whitespace().next().unwrap_or("");
+        Ok(state.config.statuses.parse(token))
+    }
+
+    fn set_status(
+        &self,
+        state: &RuntimeState,
+        task_id: &TaskId,
+        status: &TaskStatus,
+    ) -> Result<(), String> {
+        let exit = run_config_command_status(
+            state,
+            &state.config.commands.task_update_status,
+            Some(task_id),
+            "task",
+            Some(state.config.statuses.token(status)),
+            &[],
+        )?;
+        if exit != 0 {
+            return Err(format!(
+                "task_update_status failed to set status {} (exit code {})",
+                status.as_str(),
+                exit
+            ));
+        }
+        Ok(())
+    }
+
+    fn dependencies(&self, state: &RuntimeState, task_id: &TaskId) -> Result<Vec<TaskId>, String> {
+        let command = state
+            .config
+            .commands
+            .task_dependencies
+            .as_deref()
+            .unwrap_or("");
+        if command.trim().is_empty() {
+            return Ok(Vec::new());
+        }
+        let output = run_config_command(state, command, Some(task_id), "task-dependencies", &[])?;
+        if output.exit_code != 0 {
+            return Err(format!(
+                "task_dependencies failed with exit code {}",
+                output.exit_code
+            ));
+        }
+        parse_task_dependencies(&output.stdout)
+    }
+}
+
+/// Task ids printed one per line by `commands.task_dependencies`; blank lines are ignored.
+pub(crate) fn parse_task_dependencies(stdout: &str) -> Result<Vec<TaskId>, String> {
+    stdout
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(|line| {
+            TaskId::try_from(line).map_err(|err| {
+                format!(
+                    "task_dependencies returned an invalid task id: {} ({})",
+                    line, err
+                )
+            })
+        })
+        .collect()
+}
+
+/// `backend: br`: runs the `br` CLI directly (see `beads`).
+#[derive(Debug)]
+pub(crate) struct BrBackend;
+
+impl BrBackend {
+    fn br<'a>(&self, state: &'a RuntimeState) -> Br<'a> {
+        Br {
+            cwd: state.cwd.as_deref(),
+            logger: &state.logger,
+        }
+    }
+}
+
+impl TaskBackend for BrBackend {
+    fn next_task(
+        &self,
+        state: &RuntimeState,
+        _exclude: &[TaskId],
+    ) -> Result<NextTaskOutput, String> {
+        let next = self
+            .br(state)
+            .next_task(&state.config.br.label, "next-task")?;
+        Ok(next.map_or(NextTaskOutput::Empty, NextTaskOutput::Task))
+    }
+
+    fn show(&self, state: &RuntimeState, task_id: &TaskId) -> Result<String, String> {
+        self.br(state).show(task_id, "task")
+    }
+
+    fn status(&self, state: &RuntimeState, task_id: &TaskId) -> Result<Option<TaskStatus>, String> {
+        self.br(state).status(task_id, "task")
+    }
+
+    fn set_status(
+        &self,
+        state: &RuntimeState,
+        task_id: &TaskId,
+        status: &TaskStatus,
+    ) -> Result<(), String> {
+        self.br(state).set_status(task_id, status, "task")
+    }
+}
+
+/// `backend: github`: GitHub Issues through the REST API (see `github`).
+#[derive(Debug)]
+pub(crate) struct GithubBackend;
+
+impl GithubBackend {
+    fn github<'a>(&self, state: &'a RuntimeState) -> GitHub<'a> {
+        GitHub {
+            config: &state.config.github,
+            logger: &state.logger,
+        }
+    }
+}
+
+impl TaskBackend for GithubBackend {
+    fn next_task(
+        &self,
+        state: &RuntimeState,
+        exclude: &[TaskId],
+    ) -> Result<NextTaskOutput, String> {
+        let next = self.github(state).next_task(exclude, "next-task")?;
+        Ok(next.map_or(NextTaskOutput::Empty, NextTaskOutput::Task))
+    }
+
+    fn show(&self, state: &RuntimeState, task_id: &TaskId) -> Result<String, String> {
+        self.github(state).show(task_id, "task")
+    }
+
+    fn status(&self, state: &RuntimeState, task_id: &TaskId) -> Result<Option<TaskStatus>, String> {
+        self.github(state).status(task_id, "task")
+    }
+
+    fn set_status(
+        &self,
+        state: &RuntimeState,
+        task_id: &TaskId,
+        status: &TaskStatus,
+    ) -> Result<(), String> {
+        self.github(state).set_status(task_id, status, "task")
+    }
+
+    fn holds_skipped(&self) -> bool {
+        false
+    }
+}
+
+/// `backend: markdown`: a checklist file in the repository (see `todo_file`).
+#[derive(Debug)]
+pub(crate) struct MarkdownBackend;
+
+impl MarkdownBackend {
+    fn path(&self, state: &RuntimeState) -> PathBuf {
+        match state.cwd.as_deref() {
+            Some(cwd) => cwd.join(&state.config.markdown.path),
+            None => state.config.markdown.path.clone(),
+        }
+    }
+}
+
+impl TaskBackend for MarkdownBackend {
+    fn next_task(
+        &self,
+        state: &RuntimeState,
+        exclude: &[TaskId],
+    ) -> Result<NextTaskOutput, String> {
+        let content = todo_file::read(&self.path(state))?;
+        Ok(todo_file::next_task(&content, exclude)
+            .map_or(NextTaskOutput::Empty, NextTaskOutput::Task))
+    }
+
+    fn show(&self, state: &RuntimeState, task_id: &TaskId) -> Result<String, String> {
+        todo_file::show(&todo_file::read(&self.path(state))?, task_id)
+    }
+
+    fn status(&self, state: &RuntimeState, task_id: &TaskId) -> Result<Option<TaskStatus>, String> {
+        todo_file::status(&todo_file::read(&self.path(state))?, task_id).map(Some)
+    }
+
+    fn set_status(
+        &self,
+        state: &RuntimeState,
+        task_id: &TaskId,
+        status: &TaskStatus,
+    ) -> Result<(), String> {
+        let path = self.path(state);
+        todo_file::update_status(&path, task_id, status)?;
+        state.logger.log_transition(
+            Transition::new("todo_update")
+                .field("task", task_id)
+                .field("status", status)
+                .field("path", path.display().to_string()),
+        );
+        Ok(())
+    }
+
+    fn holds_skipped(&self) -> bool {
+        false
+    }
+}
+
+/// `backend: openspec`: openspec changes and their `tasks.md` checklists (see `openspec`).
+#[derive(Debug)]
+pub(crate) struct OpenspecBackend;
+
+impl OpenspecBackend {
+    fn dir(&self, state: &RuntimeState) -> PathBuf {
+        match state.cwd.as_deref() {
+            Some(cwd) => cwd.join(&state.config.openspec.dir),
+            None => state.config.openspec.dir.clone(),
+        }
+    }
+}
+
+impl TaskBackend for OpenspecBackend {
+    fn next_task(
+        &self,
+        state: &RuntimeState,
+        exclude: &[TaskId],
+    ) -> Result<NextTaskOutput, String> {
+        let next = Openspec {
+            dir: &self.dir(state),
+        }
+        .next_task(exclude)?;
+        Ok(next.map_or(NextTaskOutput::Empty, NextTaskOutput::Task))
+    }
+
+    fn show(&self, state: &RuntimeState, task_id: &TaskId) -> Result<String, String> {
+        Openspec {
+            dir: &self.dir(state),
+        }
+        .show(task_id)
+    }
+
+    fn status(&self, state: &RuntimeState, task_id: &TaskId) -> Result<Option<TaskStatus>, String> {
+        Openspec {
+            dir: &self.dir(state),
+        }
+        .status(task_id)
+        .map(Some)
+    }
+
+    fn set_status(
+        &self,
+        state: &RuntimeState,
+        task_id: &TaskId,
+        status: &TaskStatus,
+    ) -> Result<(), String> {
+        let dir = self.dir(state);
+        Openspec { dir: &dir }.update_status(task_id, status)?;
+        state.logger.log_transition(
+            Transition::new("openspec_update")
+                .field("task", task_id)
+                .field("status", status)
+                .field("dir", dir.display().to_string()),
+        );
+        Ok(())
+    }
+
+    fn holds_skipped(&self) -> bool {
+        false
+    }
+}
+
+/// In-memory tasks for run-loop tests: no commands run, and every call is recorded.
+#[cfg(test)]
+#[derive(Debug, Default)]
+pub(crate) struct MockBackend {
+    tasks: std::sync::Mutex<MockTasks>,
+}
+
+#[cfg(test)]
+#[derive(Debug, Default)]
+pub(crate) struct MockTasks {
+    /// Ids handed out by `next_task`, front first; empty means no task.
+    pub(crate) next: std::collections::VecDeque<String>,
+    pub(crate) show: std::collections::HashMap<String, String>,
+    /// Statuses reported per task, front first. The last one keeps being reported, so a task's
+    /// status changes only when the test says so (like an agent closing it).
+    pub(crate) statuses: std::collections::HashMap<String, std::collections::VecDeque<TaskStatus>>,
+    /// Blocker ids per task; tasks without an entry have none.
+    pub(crate) dependencies: std::collections::HashMap<String, Vec<String>>,
+    /// `next_task`, `show <id>`, `status <id>`, `set_status <id> <status>`, `dependencies <id>`,
+    /// `hook <name> <id>`.
+    pub(crate) calls: Vec<String>,
+}
+
+#[cfg(test)]
+impl MockBackend {
+    pub(crate) fn new(tasks: MockTasks) -> Self {
+        Self {
+            tasks: std::sync::Mutex::new(tasks),
+        }
+    }
+
+    pub(crate) fn calls(&self) -> Vec<String> {
+        self.lock().calls.clone()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, MockTasks> {
+        self.tasks.lock().expect("mock backend mutex")
+    }
+}
+
+#[cfg(test)]
+impl TaskBackend for MockBackend {
+    fn next_task(
+        &self,
+        _state: &RuntimeState,
+        _exclude: &[TaskId],
+    ) -> Result<NextTaskOutput, String> {
+        let mut tasks = self.lock();
+        tasks.calls.push("next_task".to_string());
+        Ok(tasks
+            .next
+            .pop_front()
+            .map_or(NextTaskOutput::Empty, NextTaskOutput::Task))
+    }
+
+    fn show(&self, _state: &RuntimeState, task_id: &TaskId) -> Result<String, String> {
+        let mut tasks = self.lock();
+        tasks.calls.push(format!("show {}", task_id));
+        Ok(tasks
+            .show
+            .get(task_id.as_str())
+            .cloned()
+            .unwrap_or_default())
+    }
+
+    fn status(
+        &self,
+        _state: &RuntimeState,
+        task_id: &TaskId,
+    ) -> Result<Option<TaskStatus>, String> {
+        let mut tasks = self.lock();
+        tasks.calls.push(format!("status {}", task_id));
+        let Some(queue) = tasks.statuses.get_mut(task_id.as_str()) else {
+            return Ok(None);
+        };
+        if queue.len() > 1 {
+            return Ok(queue.pop_front());
+        }
+        Ok(queue.front().cloned())
+    }
+
+    fn set_status(
+        &self,
+        _state: &RuntimeState,
+        task_id: &TaskId,
+        status: &TaskStatus,
+    ) -> Result<(), String> {
+        self.lock()
+            .calls
+            .push(format!("set_status {} {}", task_id, status));
+        Ok(())
+    }
+
+    fn dependencies(&self, _state: &RuntimeState, task_id: &TaskId) -> Result<Vec<TaskId>, String> {
+        let mut tasks = self.lock();
+        tasks.calls.push(format!("dependencies {}", task_id));
+        tasks
+            .dependencies
+            .get(task_id.as_str())
+            .into_iter()
+            .flatten()
+            .map(|id| TaskId::try_from(id.as_str()).map_err(|err| err.to_string()))
+            .collect()
+    }
+
+    fn run_hook(
+        &self,
+        _state: &RuntimeState,
+        _hook_command: &str,
+        task_id: &TaskId,
+        hook_name: &str,
+    ) -> Result<(), String> {
+        self.lock()
+            .calls
+            .push(format!("hook {} {}", hook_name, task_id));
+        Ok(())
+    }
+}
@@ -1,7 +1,8 @@
 use serde::de::Error as _;
-use serde::{Deserialize, Deserializer};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use std::fmt;
 use std::num::NonZeroU64;
+use std::time::Duration;
 
 const TASK_ID_MAX_LEN: usize = 200;
 
@@ -107,12 +108,30 @@ impl TryFrom<&str> for TaskId {
     }
 }
 
+impl Serialize for TaskId {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&self.0)
+    }
+}
+
 impl fmt::Display for TaskId {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.write_str(&self.0)
     }
 }
 
+/// A task together with the workspace it belongs to; the same id in two workspaces names two
+/// different tasks.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct WorkspaceTask {
+    pub(crate) task_id: TaskId,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) workspace: Option<String>,
+}
+
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub(crate) enum TaskStatus {
     Ready,
@@ -120,6 +139,9 @@ pub(crate) enum TaskStatus {
     InProgress,
     Closed,
     Blocked,
+    /// Declined as out of scope (`skipped`, `wontfix`, `deferred`): never selected, and a third
+    /// review outcome next to `closed` and `blocked`.
+    Skipped,
     Unknown(String),
 }
 
@@ -135,6 +157,7 @@ impl TaskStatus {
             "in_progress" => Self::InProgress,
             "closed" => Self::Closed,
             "blocked" => Self::Blocked,
+            "skipped" | "wontfix" | "deferred" => Self::Skipped,
             other => Self::Unknown(other.to_string()),
         })
     }
@@ -154,6 +177,7 @@ impl TaskStatus {
             Self::InProgress => "in_progress",
             Self::Closed => "closed",
             Self::Blocked => "blocked",
+            Self::Skipped => "skipped",
             Self::Unknown(value) => value,
         }
     }
@@ -165,10 +189,12 @@ impl fmt::Display for TaskStatus {
     }
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub(crate) enum Phase {
     Solving,
     Reviewing,
+    /// A custom pipeline phase (`profiles.<name>.phases`), by name.
+    Custom(String),
     Error,
 }
 
@@ -203,3 +229,39 @@ impl fmt::Display for ReviewLoopLimit {
         write!(f, "{}", self.get())
     }
 }
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub(crate) struct TimeoutSeconds(NonZeroU64);
+
+impl TimeoutSeconds {
+    pub(crate) fn new(value: u64) -> Result<Self, String> {
+        let Some(value) = NonZeroU64::new(value) else {
+            return Err("must be a positive integer number of seconds (got 0)".to_string());
+        };
+        Ok(Self(value))
+    }
+
+    pub(crate) fn get(self) -> u64 {
+        self.0.get()
+    }
+
+    pub(crate) fn as_duration(self) -> Duration {
+        Duration::from_secs(self.get())
+    }
+}
+
+impl<'de> Deserialize<'de> for TimeoutSeconds {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let value = u64::deserialize(deserializer)?;
+        TimeoutSeconds::new(value).map_err(D::Error::custom)
+    }
+}
+
+impl fmt::Display for TimeoutSeconds {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.get())
+    }
+}
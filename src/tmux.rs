@@ -3,7 +3,7 @@ use std::path::Path;
 use std::process::Command;
 
 use crate::shell::command_exists;
-use crate::task_types::{Phase, TaskId};
+use crate::task_types::{Phase, TaskId, WorkspaceTask};
 
 #[derive(Debug, Clone)]
 pub(crate) struct TmuxState {
@@ -76,6 +76,8 @@ impl TmuxState {
         task_id: &TaskId,
         completed: &[TaskId],
         needs_human: &[TaskId],
+        skipped: &[WorkspaceTask],
+        declined: &[WorkspaceTask],
     ) {
         if !self.enabled {
             return;
@@ -86,6 +88,31 @@ impl TmuxState {
             task_id,
             completed,
             needs_human,
+            skipped,
+            declined,
+        );
+        self.select_pane(&name);
+    }
+
+    /// Title for parallel workers (`concurrency`): lists every task in flight instead of a phase.
+    pub(crate) fn update_workers(
+        &self,
+        active: &[TaskId],
+        completed: &[TaskId],
+        needs_human: &[TaskId],
+        skipped: &[WorkspaceTask],
+        declined: &[WorkspaceTask],
+    ) {
+        if !self.enabled {
+            return;
+        }
+        let name = build_tmux_workers_name(
+            &self.base_name,
+            active,
+            completed,
+            needs_human,
+            skipped,
+            declined,
         );
         self.select_pane(&name);
     }
@@ -105,8 +132,12 @@ fn strip_trudger_title_suffixes(title: &str) -> String {
     const MARKERS: &[&str] = &[
         " COMPLETED [",
         " NEEDS_HUMAN [",
+        " SKIPPED [",
+        " DECLINED [",
+        " WORKING [",
         " SOLVING ",
         " REVIEWING ",
+        " RUNNING ",
         " HALTED ON ERROR ",
     ];
 
@@ -197,31 +228,85 @@ pub(crate) fn build_tmux_name(
     task_id: &TaskId,
     completed: &[TaskId],
     needs_human: &[TaskId],
+    skipped: &[WorkspaceTask],
+    declined: &[WorkspaceTask],
 ) -> String {
-    let mut base = base_name.to_string();
-    if let Some((prefix, command)) = base_name.rsplit_once(": ") {
-        if command == "fg" || command == "codex" {
-            base = prefix.to_string();
-        }
-    }
-
     let activity = match phase {
         Some(Phase::Solving) => format!("SOLVING {}", task_id),
         Some(Phase::Reviewing) => format!("REVIEWING {}", task_id),
+        Some(Phase::Custom(name)) => format!("RUNNING {} {}", name.to_uppercase(), task_id),
         Some(Phase::Error) => format!("HALTED ON ERROR {}", task_id),
         None => String::new(),
     };
+    join_tmux_name(
+        base_name,
+        activity,
+        completed,
+        needs_human,
+        skipped,
+        declined,
+    )
+}
+
+pub(crate) fn build_tmux_workers_name(
+    base_name: &str,
+    active: &[TaskId],
+    completed: &[TaskId],
+    needs_human: &[TaskId],
+    skipped: &[WorkspaceTask],
+    declined: &[WorkspaceTask],
+) -> String {
+    join_tmux_name(
+        base_name,
+        format_task_list("WORKING", active),
+        completed,
+        needs_human,
+        skipped,
+        declined,
+    )
+}
+
+fn join_tmux_name(
+    base_name: &str,
+    activity: String,
+    completed: &[TaskId],
+    needs_human: &[TaskId],
+    skipped: &[WorkspaceTask],
+    declined: &[WorkspaceTask],
+) -> String {
+    let mut base = base_name.to_string();
+    if let Some((prefix, command)) = base_name.rsplit_once(": ") {
+        if command == "fg" || command == "codex" {
+            base = prefix.to_string();
+        }
+    }
 
     let mut parts = Vec::new();
     parts.push(base);
     let completed_segment = format_task_list("COMPLETED", completed);
     let needs_human_segment = format_task_list("NEEDS_HUMAN", needs_human);
+    let skipped = skipped
+        .iter()
+        .map(|task| task.task_id.clone())
+        .collect::<Vec<_>>();
+    let skipped_segment = format_task_list("SKIPPED", &skipped);
+    let declined = declined
+        .iter()
+        .map(|task| task.task_id.clone())
+        .collect::<Vec<_>>();
+    let declined_segment = format_task_list("DECLINED", &declined);
     if !completed_segment.is_empty() {
         parts.push(completed_segment);
     }
     if !needs_human_segment.is_empty() {
         parts.push(needs_human_segment);
     }
+    if !skipped_segment.is_empty() {
+        parts.push(skipped_segment);
+    }
+    if !declined_segment.is_empty() {
+        parts.push(declined_segment);
+    }
     if !activity.is_empty() {
         parts.push(activity);
     }
@@ -0,0 +1,299 @@
+use regex::Regex;
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+use std::sync::{Mutex, OnceLock};
+use tempfile::NamedTempFile;
+
+use crate::task_types::{TaskId, TaskStatus};
+
+/// Markers after an unchecked item's text that set its status; checked items are `closed`.
+const BLOCKED_MARKER: &str = "(blocked)";
+const IN_PROGRESS_MARKER: &str = "(in_progress)";
+
+/// Serializes read-modify-write cycles so parallel workers never drop each other's updates.
+static TODO_FILE_LOCK: Mutex<()> = Mutex::new(());
+
+/// A checklist item: `- [ ] <id>: <text>`, optionally indented and with `*` or `+` bullets.
+struct Item<'a> {
+    line: usize,
+    indent: usize,
+    checked: bool,
+    id: &'a str,
+    text: &'a str,
+}
+
+impl Item<'_> {
+    fn status(&self) -> TaskStatus {
+        if self.checked {
+            TaskStatus::Closed
+        } else if self.text.ends_with(BLOCKED_MARKER) {
+            TaskStatus::Blocked
+        } else if self.text.ends_with(IN_PROGRESS_MARKER) {
+            TaskStatus::InProgress
+        } else {
+            TaskStatus::Open
+        }
+    }
+
+    /// The item text without a trailing status marker.
+    fn title(&self) -> &str {
+        self.text
+            .strip_suffix(BLOCKED_MARKER)
+            .or_else(|| self.text.strip_suffix(IN_PROGRESS_MARKER))
+            .unwrap_or(self.text)
+            .trim_end()
+    }
+}
+
+fn item_pattern() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    PATTERN.get_or_init(|| {
+        Regex::new(r"^(\s*)[-*+]\s+\[([ xX])\]\s+([A-Za-z0-9][A-Za-z0-9._:-]*):(?:\s+(.*?))?\s*$")
+            .expect("todo item pattern")
+    })
+}
+
+/// Checklist items with a valid task id, in file order. Other lines are left alone.
+fn items(content: &str) -> Vec<Item<'_>> {
+    content
+        .lines()
+        .enumerate()
+        .filter_map(|(line, text)| {
+            let captures = item_pattern().captures(text)?;
+            let id = captures.get(3)?.as_str();
+            TaskId::try_from(id).ok()?;
+            Some(Item {
+                line,
+                indent: captures.get(1).map_or(0, |value| value.as_str().len()),
+                checked: captures.get(2)?.as_str() != " ",
+                id,
+                text: captures.get(4).map_or("", |value| value.as_str()),
+            })
+        })
+        .collect()
+}
+
+fn find<'a>(content: &'a str, task_id: &TaskId) -> Result<Item<'a>, String> {
+    items(content)
+        .into_iter()
+        .find(|item| item.id == task_id.as_str())
+        .ok_or_else(|| format!("task {} is not in the todo file", task_id))
+}
+
+/// The first unchecked item without a status marker whose id is not in `exclude`.
+pub(crate) fn next_task(content: &str, exclude: &[TaskId]) -> Option<String> {
+    items(content)
+        .into_iter()
+        .filter(|item| !exclude.iter().any(|id| id.as_str() == item.id))
+        .find(|item| item.status() == TaskStatus::Open)
+        .map(|item| item.id.to_string())
+}
+
+pub(crate) fn status(content: &str, task_id: &TaskId) -> Result<TaskStatus, String> {
+    Ok(find(content, task_id)?.status())
+}
+
+/// The item line followed by the lines indented below it (notes, sub-items).
+pub(crate) fn show(content: &str, task_id: &TaskId) -> Result<String, String> {
+    let item = find(content, task_id)?;
+    let lines: Vec<&str> = content.lines().collect();
+    let mut shown = vec![format!("{}: {}", item.id, item.title())];
+    let mut details = Vec::new();
+    for line in &lines[item.line + 1..] {
+        let indent = line.len() - line.trim_start().len();
+        if !line.trim().is_empty() && indent <= item.indent {
+            break;
+        }
+        details.push(*line);
+    }
+    while details.last().is_some_and(|line| line.trim().is_empty()) {
+        details.pop();
+    }
+    shown.extend(details.into_iter().map(str::to_string));
+    let mut rendered = shown.join("\n");
+    rendered.push('\n');
+    Ok(rendered)
+}
+
+/// `content` with the item's checkbox and marker rewritten for `status`.
+pub(crate) fn with_status(
+    content: &str,
+    task_id: &TaskId,
+    status: &TaskStatus,
+) -> Result<String, String> {
+    let (checked, marker) = match status {
+        TaskStatus::Ready | TaskStatus::Open => (false, None),
+        TaskStatus::InProgress => (false, Some(IN_PROGRESS_MARKER)),
+        TaskStatus::Blocked => (false, Some(BLOCKED_MARKER)),
+        TaskStatus::Closed => (true, None),
+        TaskStatus::Skipped | TaskStatus::Unknown(_) => {
+            return Err(format!("todo items cannot be set to status {}", status));
+        }
+    };
+    let item = find(content, task_id)?;
+    let original = content.lines().nth(item.line).unwrap_or("");
+    let bullet_end = original.find('[').unwrap_or(0);
+    let mut line = format!(
+        "{}[{}] {}:",
+        &original[..bullet_end],
+        if checked { "x" } else { " " },
+        item.id
+    );
+    let title = item.title();
+    if !title.is_empty() {
+        line.push(' ');
+        line.push_str(title);
+    }
+    if let Some(marker) = marker {
+        line.push(' ');
+        line.push_str(marker);
+    }
+
+    let mut rewritten: Vec<&str> = content.lines().collect();
+    rewritten[item.line] = &line;
+    let mut updated = rewritten.join("\n");
+    if content.ends_with('\n') {
+        updated.push('\n');
+    }
+    Ok(updated)
+}
+
+pub(crate) fn read(path: &Path) -> Result<String, String> {
+    fs::read_to_string(path).map_err(|err| format!("failed to read {}: {}", path.display(), err))
+}
+
+/// Rewrites the item's status in the file at `path`, replacing the file atomically.
+pub(crate) fn update_status(
+    path: &Path,
+    task_id: &TaskId,
+    status: &TaskStatus,
+) -> Result<(), String> {
+    let _guard = TODO_FILE_LOCK.lock().unwrap_or_else(|err| err.into_inner());
+    let content = read(path)?;
+    let updated = with_status(&content, task_id, status)?;
+    replace_file(path, &updated)
+}
+
+/// Replaces `path` with `content` through a sibling temp file and rename, keeping its mode.
+pub(crate) fn replace_file(path: &Path, content: &str) -> Result<(), String> {
+    let parent = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    let mut file = NamedTempFile::new_in(parent).map_err(|err| {
+        format!(
+            "failed to create temp file in {}: {}",
+            parent.display(),
+            err
+        )
+    })?;
+    file.write_all(content.as_bytes())
+        .map_err(|err| format!("failed to write {}: {}", path.display(), err))?;
+    // Keep the checked-in file mode instead of the temp file's private one.
+    if let Ok(metadata) = fs::metadata(path) {
+        let _ = fs::set_permissions(file.path(), metadata.permissions());
+    }
+    file.persist(path)
+        .map_err(|err| format!("failed to replace {}: {}", path.display(), err.error))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    const TODO: &str = concat!(
+        "# Tasks\n",
+        "\n",
+        "- [x] tr-1: Set up CI\n",
+        "- [ ] tr-2: Fix the login form (blocked)\n",
+        "- [ ] tr-3: Add dark mode (in_progress)\n",
+        "* [ ] tr-4: Write the changelog\n",
+        "  The release is on Friday.\n",
+        "  - [ ] tr-5: Collect merged PRs\n",
+        "\n",
+        "Not a task: - [ ] bad id!: nope\n",
+        "- [ ] tr-6:\n",
+    );
+
+    fn task(id: &str) -> TaskId {
+        TaskId::try_from(id).expect("task id")
+    }
+
+    #[test]
+    fn items_map_checkboxes_and_markers_to_statuses() {
+        assert_eq!(next_task(TODO, &[]), Some("tr-4".to_string()));
+        assert_eq!(next_task(TODO, &[task("tr-4")]), Some("tr-5".to_string()));
+        assert_eq!(
+            next_task(TODO, &[task("tr-4"), task("tr-5")]),
+            Some("tr-6".to_string())
+        );
+        assert_eq!(status(TODO, &task("tr-1")), Ok(TaskStatus::Closed));
+        assert_eq!(status(TODO, &task("tr-2")), Ok(TaskStatus::Blocked));
+        assert_eq!(status(TODO, &task("tr-3")), Ok(TaskStatus::InProgress));
+        assert_eq!(status(TODO, &task("tr-5")), Ok(TaskStatus::Open));
+        assert_eq!(status(TODO, &task("tr-6")), Ok(TaskStatus::Open));
+        assert_eq!(
+            status(TODO, &task("tr-9")),
+            Err("task tr-9 is not in the todo file".to_string())
+        );
+        assert_eq!(next_task("# Nothing to do\n- [x] tr-1: done\n", &[]), None);
+    }
+
+    #[test]
+    fn show_includes_indented_details() {
+        assert_eq!(
+            show(TODO, &task("tr-4")).expect("show"),
+            "tr-4: Write the changelog\n  The release is on Friday.\n  - [ ] tr-5: Collect merged PRs\n"
+        );
+        assert_eq!(
+            show(TODO, &task("tr-2")).expect("show"),
+            "tr-2: Fix the login form\n"
+        );
+    }
+
+    #[test]
+    fn with_status_rewrites_only_the_item_line() {
+        let updated = with_status(TODO, &task("tr-4"), &TaskStatus::InProgress).expect("update");
+        assert!(updated.contains("\n* [ ] tr-4: Write the changelog (in_progress)\n"));
+        let updated = with_status(&updated, &task("tr-4"), &TaskStatus::Blocked).expect("update");
+        assert!(updated.contains("\n* [ ] tr-4: Write the changelog (blocked)\n"));
+        let updated = with_status(&updated, &task("tr-4"), &TaskStatus::Closed).expect("update");
+        assert!(updated.contains("\n* [x] tr-4: Write the changelog\n"));
+        let updated = with_status(&updated, &task("tr-5"), &TaskStatus::Closed).expect("update");
+        assert!(updated.contains("\n  - [x] tr-5: Collect merged PRs\n"));
+        let reopened = with_status(&updated, &task("tr-4"), &TaskStatus::Open).expect("update");
+        let reopened = with_status(&reopened, &task("tr-5"), &TaskStatus::Open).expect("update");
+        assert_eq!(reopened, TODO);
+
+        let err = with_status(
+            TODO,
+            &task("tr-4"),
+            &TaskStatus::Unknown("done".to_string()),
+        )
+        .expect_err("unknown status");
+        assert!(err.contains("cannot be set to status done"), "got: {err}");
+    }
+
+    #[test]
+    fn update_status_replaces_the_file() {
+        let temp = TempDir::new().expect("temp dir");
+        let path = temp.path().join("TODO.md");
+        fs::write(&path, TODO).expect("write todo");
+
+        update_status(&path, &task("tr-2"), &TaskStatus::Open).expect("update");
+        let content = read(&path).expect("read");
+        assert!(content.contains("\n- [ ] tr-2: Fix the login form\n"));
+        assert_eq!(next_task(&content, &[]), Some("tr-2".to_string()));
+
+        let err = update_status(
+            &temp.path().join("missing.md"),
+            &task("tr-2"),
+            &TaskStatus::Open,
+        )
+        .expect_err("missing file");
+        assert!(err.starts_with("failed to read"), "got: {err}");
+    }
+}
@@ -1,25 +1,40 @@
 use clap::Parser;
+use std::collections::{HashMap, VecDeque};
 use std::env;
 use std::fs;
 use std::io::Write;
+use std::num::NonZeroU64;
 use std::path::Path;
-use std::sync::atomic::AtomicBool;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex, OnceLock};
 use std::thread;
 use std::time::{Duration, Instant};
 use tempfile::{NamedTempFile, TempDir};
 
 use crate::app::{
-    main_with_args, render_prompt, require_file, run_with_args, run_with_cli, run_with_cli_for_test,
+    main_with_args, render_prompt, run_id, run_with_args, run_with_cli, run_with_cli_for_test,
 };
 use crate::cli::{parse_manual_tasks, Cli, CliCommand};
-use crate::config::{load_config, Commands, Config, Hooks};
+use crate::config::{
+    load_config, AgentPhase, AgentTimeout, Backend, BrConfig, Commands, Config, GithubConfig,
+    Hooks, LogFormat, MarkdownConfig, OpenspecConfig, PhaseFailureAction, PipelinePhase,
+    ProfileFallback, PromptFormat, PromptPaths, RetryPolicy, StatusMap, TaskErrorAction,
+    TaskRouting, TimeoutAction, WatchConfig, WorkspaceOrder,
+};
 use crate::doctor::run_doctor_mode;
-use crate::logger::{sanitize_log_value, Logger};
-use crate::run_loop::{reset_task_on_exit, run_loop, validate_config, Quit, RuntimeState};
+use crate::logger::{sanitize_log_value, Logger, Transition};
+use crate::run_journal::{read_run_journal, JournalPhase, JournalTask};
+use crate::run_loop::{
+    reset_task_on_exit, run_loop, run_watch_loop, validate_config, Quit, RuntimeState,
+};
 use crate::shell::render_args;
-use crate::task_types::{Phase, ReviewLoopLimit, TaskId, TaskIdError};
-use crate::tmux::{build_tmux_name, TmuxState};
+use crate::task_backend::{
+    BrBackend, MarkdownBackend, MockBackend, MockTasks, OpenspecBackend, ShellBackend,
+};
+use crate::task_types::{
+    Phase, ReviewLoopLimit, TaskId, TaskIdError, TaskStatus, TimeoutSeconds, WorkspaceTask,
+};
+use crate::tmux::{build_tmux_name, build_tmux_workers_name, TmuxState};
 
 pub(crate) static ENV_MUTEX: Mutex<()> = Mutex::new(());
 static ORIGINAL_PATH: OnceLock<Option<std::ffi::OsString>> = OnceLock::new();
@@ -61,6 +76,10 @@ pub(crate) fn reset_test_env() {
         "HOOK_MOCK_LOG",
         "NEXT_TASK_LOG",
         "TRUDGER_TEST_FORCE_ERR",
+        "BR_MOCK_LOG",
+        "BR_MOCK_READY_QUEUE",
+        "BR_MOCK_SHOW_QUEUE",
+        "BR_MOCK_SHOW_TEXT",
     ] {
         env::remove_var(key);
     }
@@ -70,6 +89,13 @@ fn task(id: &str) -> TaskId {
     TaskId::try_from(id).expect("task id")
 }
 
+fn workspace_task(workspace: Option<&str>, id: &str) -> WorkspaceTask {
+    WorkspaceTask {
+        task_id: task(id),
+        workspace: workspace.map(str::to_string),
+    }
+}
+
 fn limit(value: u64) -> ReviewLoopLimit {
     ReviewLoopLimit::new(value).expect("review_loop_limit")
 }
@@ -161,11 +187,14 @@ fn log_line_matches_shell_fixture() {
         "foo\\bar".to_string(),
     ];
     let args_render = render_args(&args);
-    logger.log_transition(&format!(
-        "cmd start label=next-task task=tr-1 mode=bash_lc command={} args={}",
-        sanitize_log_value(command),
-        sanitize_log_value(&args_render)
-    ));
+    logger.log_transition(
+        Transition::new("cmd start")
+            .field("label", "next-task")
+            .field("task", "tr-1")
+            .field("mode", "bash_lc")
+            .field("command", sanitize_log_value(command))
+            .field("args", sanitize_log_value(&args_render)),
+    );
 
     let log_contents = fs::read_to_string(&log_path).expect("read log file");
     let line = log_contents.lines().next().expect("log line");
@@ -180,6 +209,116 @@ fn log_line_matches_shell_fixture() {
     assert_eq!(message, expected);
 }
 
+#[test]
+fn jsonl_log_format_writes_typed_fields() {
+    let _guard = ENV_MUTEX.lock().unwrap();
+    reset_test_env();
+    let temp = TempDir::new().expect("temp dir");
+    let log_path = temp.path().join("trudger.jsonl");
+    let mut logger = Logger::new(Some(log_path.clone()));
+    logger.set_format(LogFormat::Jsonl);
+
+    logger.log_transition(
+        Transition::new("state")
+            .field("state", "SOLVING")
+            .field("task", "tr-1")
+            .field("loop", 2u64),
+    );
+    logger.log_transition(
+        Transition::new("cmd exit")
+            .field("label", "agent_solve")
+            .field("task", "tr-1")
+            .field("exit", 3)
+            .field("duration_ms", 1500u128),
+    );
+    logger.log_transition(
+        Transition::new("cmd start")
+            .field("label", "next-task")
+            .field("task", "none")
+            .field("mode", "bash_lc")
+            .field("command", "echo a=b\nnext")
+            .field("args", "'x y'"),
+    );
+    logger.log_transition(
+        Transition::new("reset_task_skip")
+            .field("task", "tr-1")
+            .field("reason", "task_status_failed")
+            .field("err", "bad exit=1\nmore"),
+    );
+    logger.log_transition(
+        Transition::new("agent_start")
+            .field("task", "tr-1")
+            .field("label", "agent_review")
+            .field("profile", "codex")
+            .field("invocation_id", "codex-review"),
+    );
+    logger.log_transition(
+        Transition::new("env_truncate")
+            .field("label", "agent_solve")
+            .field("task", "tr-1")
+            .field("key", "TRUDGER_TASK_SHOW")
+            .field("original_bytes", 70000usize)
+            .field("truncated_bytes", 65536usize),
+    );
+    logger.log_transition(
+        Transition::new("agent_timeout")
+            .field("task", "tr-1")
+            .field("timeout_s", 30u64)
+            .field("retried", false),
+    );
+    logger
+        .for_worker(2, String::new())
+        .log_transition(Transition::new("idle no_ready_task").field("attempts", 5usize));
+
+    let log_contents = fs::read_to_string(&log_path).expect("read log file");
+    let records = log_contents
+        .lines()
+        .map(|line| serde_json::from_str::<serde_json::Value>(line).expect("json line"))
+        .collect::<Vec<_>>();
+    assert_eq!(records.len(), 8);
+    assert!(records.iter().all(|record| record["ts"].is_string()));
+
+    assert_eq!(records[0]["event"], "state");
+    assert_eq!(records[0]["phase"], "solving");
+    assert_eq!(records[0]["task_id"], "tr-1");
+    assert_eq!(records[0]["loop"], 2);
+
+    assert_eq!(records[1]["event"], "cmd_exit");
+    assert_eq!(records[1]["label"], "agent_solve");
+    assert_eq!(records[1]["exit_code"], 3);
+    assert_eq!(records[1]["duration_ms"], 1500);
+
+    assert_eq!(records[2]["event"], "cmd_start");
+    assert!(records[2].get("task_id").is_none());
+    assert_eq!(records[2]["command"], "echo a=b\nnext");
+    assert_eq!(records[2]["args"], "'x y'");
+
+    assert_eq!(records[3]["reason"], "task_status_failed");
+    assert_eq!(records[3]["err"], "bad exit=1\nmore");
+    assert_eq!(
+        records[3]["message"],
+        "reset_task_skip task=tr-1 reason=task_status_failed err=bad exit=1\nmore"
+    );
+
+    assert_eq!(records[4]["profile"], "codex");
+    assert_eq!(records[4]["invocation_id"], "codex-review");
+
+    assert_eq!(records[5]["key"], "TRUDGER_TASK_SHOW");
+    assert_eq!(records[5]["original_bytes"], 70000);
+    assert_eq!(records[5]["truncated_bytes"], 65536);
+
+    assert_eq!(records[6]["timeout_s"], 30);
+    assert_eq!(records[6]["retried"], false);
+    assert_eq!(
+        records[6]["message"],
+        "agent_timeout task=tr-1 timeout_s=30 retried=false"
+    );
+
+    assert_eq!(records[7]["event"], "idle_no_ready_task");
+    assert_eq!(records[7]["worker"], 2);
+    assert_eq!(records[7]["attempts"], 5);
+}
+
 #[cfg(unix)]
 #[test]
 fn log_transition_warns_once_and_disables_after_error() {
@@ -191,9 +330,9 @@ fn log_transition_warns_once_and_disables_after_error() {
     let logger = Logger::new(Some(log_path.clone()));
 
     let stderr = capture_stderr(|| {
-        logger.log_transition("first");
+        logger.log_transition(Transition::new("first"));
         fs::create_dir(&log_dir).expect("create log dir");
-        logger.log_transition("second");
+        logger.log_transition(Transition::new("second"));
     });
 
     let lines: Vec<&str> = stderr
@@ -240,7 +379,7 @@ fn log_transition_warns_once_under_concurrency() {
             let barrier = Arc::clone(&barrier);
             handles.push(thread::spawn(move || {
                 barrier.wait();
-                logger.log_transition(&format!("msg-{index}"));
+                logger.log_transition(Transition::new(&format!("msg-{index}")));
             }));
         }
         for handle in handles {
@@ -292,32 +431,19 @@ fn render_prompt_can_return_empty_when_only_frontmatter() {
     assert_eq!(rendered, "");
 }
 
-#[test]
-fn require_file_reports_missing() {
-    let _guard = ENV_MUTEX.lock().unwrap();
-    reset_test_env();
-    let temp = TempDir::new().expect("temp dir");
-    let missing = temp.path().join("missing.txt");
-    let err = require_file(&missing, "prompt file").expect_err("should fail");
-    assert!(
-        err.contains("Missing prompt file"),
-        "error should mention missing prompt file, got: {err}"
-    );
-}
-
 #[test]
 fn build_tmux_name_trims_fg_and_codex_suffixes() {
     let task_id = task("tr-1");
     assert_eq!(
-        build_tmux_name("host: fg", None, &task_id, &[], &[]),
+        build_tmux_name("host: fg", None, &task_id, &[], &[], &[], &[]),
         "host"
     );
     assert_eq!(
-        build_tmux_name("host: codex", None, &task_id, &[], &[]),
+        build_tmux_name("host: codex", None, &task_id, &[], &[], &[], &[]),
         "host"
     );
     assert_eq!(
-        build_tmux_name("host: other", None, &task_id, &[], &[]),
+        build_tmux_name("host: other", None, &task_id, &[], &[], &[], &[]),
         "host: other"
     );
 }
@@ -326,6 +452,8 @@ fn build_tmux_name_trims_fg_and_codex_suffixes() {
 fn build_tmux_name_formats_task_lists_and_phase_suffixes() {
     let completed = vec![task("tr-1"), task("tr-2")];
     let needs_human = vec![task("tr-3")];
+    let skipped = vec![workspace_task(None, "tr-4")];
+    let declined = vec![workspace_task(None, "tr-5")];
     let task_id = task("tr-9");
 
     assert_eq!(
@@ -334,29 +462,69 @@ fn build_tmux_name_formats_task_lists_and_phase_suffixes() {
             Some(Phase::Solving),
             &task_id,
             &completed,
-            &needs_human
+            &needs_human,
+            &skipped,
+            &declined,
         ),
-        "base COMPLETED [tr-1, tr-2] NEEDS_HUMAN [tr-3] SOLVING tr-9"
+        "base COMPLETED [tr-1, tr-2] NEEDS_HUMAN [tr-3] SKIPPED [tr-4] DECLINED [tr-5] SOLVING tr-9"
     );
     assert_eq!(
-        build_tmux_name("base", Some(Phase::Reviewing), &task_id, &[], &needs_human),
+        build_tmux_name(
+            "base",
+            Some(Phase::Reviewing),
+            &task_id,
+            &[],
+            &needs_human,
+            &[],
+            &[]
+        ),
         "base NEEDS_HUMAN [tr-3] REVIEWING tr-9"
     );
     assert_eq!(
-        build_tmux_name("base", Some(Phase::Error), &task_id, &completed, &[]),
+        build_tmux_name(
+            "base",
+            Some(Phase::Custom("test".to_string())),
+            &task_id,
+            &[],
+            &[],
+            &[],
+            &[]
+        ),
+        "base RUNNING TEST tr-9"
+    );
+    assert_eq!(
+        build_tmux_name(
+            "base",
+            Some(Phase::Error),
+            &task_id,
+            &completed,
+            &[],
+            &[],
+            &[]
+        ),
         "base COMPLETED [tr-1, tr-2] HALTED ON ERROR tr-9"
     );
 }
 
+#[test]
+fn build_tmux_workers_name_lists_tasks_in_flight() {
+    let active = vec![task("tr-4"), task("tr-5")];
+    let completed = vec![task("tr-1")];
+
+    assert_eq!(
+        build_tmux_workers_name("host: codex", &active, &completed, &[], &[], &[]),
+        "host COMPLETED [tr-1] WORKING [tr-4, tr-5]"
+    );
+    assert_eq!(
+        build_tmux_workers_name("base", &[], &completed, &[], &[], &[]),
+        "base COMPLETED [tr-1]"
+    );
+}
+
 #[test]
 fn run_loop_executes_commands_and_hooks_with_env() {
     let _guard = ENV_MUTEX.lock().unwrap();
     reset_test_env();
-    crate::run_loop::set_agent_invocation_context(
-        "codex-profile".to_string(),
-        "codex".to_string(),
-        "codex-review".to_string(),
-    );
     let temp = TempDir::new().expect("temp dir");
     let log_path = temp.path().join("trudger.log");
     let next_task_log = temp.path().join("next-task.log");
@@ -395,6 +563,8 @@ fn run_loop_executes_commands_and_hooks_with_env() {
             task_show: "task-show \"$@\"".to_string(),
             task_status: "task-status".to_string(),
             task_update_status: "task-update \"$@\"".to_string(),
+            task_dependencies: None,
+            task_profile: None,
         },
         hooks: Hooks {
             on_completed: "hook --done".to_string(),
@@ -402,9 +572,35 @@ fn run_loop_executes_commands_and_hooks_with_env() {
             on_doctor_setup: None,
             on_notification: None,
             on_notification_scope: None,
+            on_task_error: None,
+            on_skipped: None,
         },
         review_loop_limit: limit(2),
         log_path: Some(log_path.clone()),
+        journal_path: None,
+        agent_timeout: None,
+        agent_review_timeout: None,
+        agent_retry: None,
+        agent_review_retry: None,
+        on_task_error: TaskErrorAction::Quit,
+        watch: WatchConfig::default(),
+        workspaces: Vec::new(),
+        workspace_order: WorkspaceOrder::RoundRobin,
+        concurrency: std::num::NonZeroUsize::MIN,
+        worktree_root: None,
+        log_format: LogFormat::Text,
+        agent_output_dir: None,
+        backend: Backend::Shell,
+        br: BrConfig::default(),
+        github: GithubConfig::default(),
+        markdown: MarkdownConfig::default(),
+        openspec: OpenspecConfig::default(),
+        statuses: StatusMap::default(),
+        prompt_format: PromptFormat::Plain,
+        prompts: PromptPaths::default(),
+        phases: vec![PipelinePhase::Solve],
+        profile_fallback: ProfileFallback::default(),
+        task_routing: TaskRouting::default(),
     };
 
     let mut state = RuntimeState {
@@ -416,18 +612,38 @@ fn run_loop_executes_commands_and_hooks_with_env() {
             .unwrap_or_default(),
         prompt_trudge: "Task context".to_string(),
         prompt_review: "Review context".to_string(),
+        phase_prompts: HashMap::new(),
         logger: Logger::new(Some(log_path.clone())),
         tmux: TmuxState::disabled(),
         interrupt_flag: Arc::new(AtomicBool::new(false)),
         manual_tasks: Vec::new(),
         completed_tasks: Vec::new(),
         needs_human_tasks: Vec::new(),
+        skipped_tasks: Vec::new(),
+        declined_tasks: Vec::new(),
+        workspace: None,
+        cwd: None,
         current_task_id: None,
         current_task_show: None,
         current_task_status: None,
         run_started_at: std::time::Instant::now(),
         current_task_started_at: None,
         run_exit_code: 0,
+        agent_output_dir: None,
+        current_agent_output: None,
+        current_review_result: None,
+        current_review_feedback: None,
+        current_review_follow_ups: Vec::new(),
+        current_review_loop: None,
+        agent_failures: 0,
+        routed_from: None,
+        agent_context: crate::run_loop::AgentInvocationContext::new(
+            "codex-profile".to_string(),
+            "codex".to_string(),
+            "codex-review".to_string(),
+        ),
+        resume: None,
+        task_backend: Arc::new(ShellBackend),
     };
 
     let result = run_loop(&mut state).expect_err("should exit after queue drained");
@@ -541,25 +757,25 @@ fn run_loop_executes_commands_and_hooks_with_env() {
         !raw_tab,
         "log should not include raw tab characters, got:\n{log_contents}"
     );
-
-    crate::run_loop::reset_agent_invocation_context();
 }
 
 #[test]
-fn manual_task_not_ready_fails_fast_without_invoking_next_task() {
+fn br_backend_runs_br_directly_for_task_operations() {
     let _guard = ENV_MUTEX.lock().unwrap();
     reset_test_env();
-
     let temp = TempDir::new().expect("temp dir");
-    let next_task_log = temp.path().join("next-task.log");
-    let task_status_log = temp.path().join("task-status.log");
-    let task_show_log = temp.path().join("task-show.log");
-    let task_update_log = temp.path().join("task-update.log");
+    let log_path = temp.path().join("trudger.log");
+    let br_log = temp.path().join("br.log");
     let hook_log = temp.path().join("hook.log");
-    let codex_log = temp.path().join("codex.log");
 
-    let status_queue = temp.path().join("status-queue.txt");
-    fs::write(&status_queue, "blocked\n").expect("write status queue");
+    let ready_queue = temp.path().join("ready-queue.txt");
+    fs::write(&ready_queue, "[{\"id\":\"tr-1\"}]\n[]\n").expect("write ready queue");
+    let show_queue = temp.path().join("show-queue.txt");
+    fs::write(
+        &show_queue,
+        "[{\"id\":\"tr-1\",\"status\":\"open\"}]\n[{\"id\":\"tr-1\",\"status\":\"closed\"}]\n",
+    )
+    .expect("write show queue");
 
     let fixtures_bin = Path::new(env!("CARGO_MANIFEST_DIR"))
         .join("tests")
@@ -567,273 +783,1939 @@ fn manual_task_not_ready_fails_fast_without_invoking_next_task() {
         .join("bin");
     let old_path = env::var("PATH").unwrap_or_default();
     env::set_var("PATH", format!("{}:{}", fixtures_bin.display(), old_path));
-
-    env::set_var("NEXT_TASK_LOG", &next_task_log);
-    env::set_var("TASK_STATUS_LOG", &task_status_log);
-    env::set_var("TASK_SHOW_LOG", &task_show_log);
-    env::set_var("TASK_UPDATE_LOG", &task_update_log);
+    env::set_var("BR_MOCK_LOG", &br_log);
+    env::set_var("BR_MOCK_READY_QUEUE", &ready_queue);
+    env::set_var("BR_MOCK_SHOW_QUEUE", &show_queue);
+    env::set_var("BR_MOCK_SHOW_TEXT", "BR_SHOW_PAYLOAD");
     env::set_var("HOOK_MOCK_LOG", &hook_log);
-    env::set_var("CODEX_MOCK_LOG", &codex_log);
-    env::set_var("TASK_STATUS_QUEUE", &status_queue);
 
     let config = Config {
         agent_command: "codex --yolo exec --default".to_string(),
         agent_review_command: "codex --yolo exec --review \"$@\"".to_string(),
-        commands: Commands {
-            next_task: Some("next-task".to_string()),
-            task_show: "task-show \"$@\"".to_string(),
-            task_status: "task-status".to_string(),
-            task_update_status: "task-update \"$@\"".to_string(),
-        },
+        commands: Commands::default(),
         hooks: Hooks {
             on_completed: "hook --done".to_string(),
             on_requires_human: "hook --human".to_string(),
             on_doctor_setup: None,
             on_notification: None,
             on_notification_scope: None,
+            on_task_error: None,
+            on_skipped: None,
         },
         review_loop_limit: limit(2),
-        log_path: Some(temp.path().join("trudger.log")),
+        log_path: Some(log_path.clone()),
+        journal_path: None,
+        agent_timeout: None,
+        agent_review_timeout: None,
+        agent_retry: None,
+        agent_review_retry: None,
+        on_task_error: TaskErrorAction::Quit,
+        watch: WatchConfig::default(),
+        workspaces: Vec::new(),
+        workspace_order: WorkspaceOrder::RoundRobin,
+        concurrency: std::num::NonZeroUsize::MIN,
+        worktree_root: None,
+        log_format: LogFormat::Text,
+        agent_output_dir: None,
+        backend: Backend::Br,
+        br: BrConfig {
+            label: "agent ready".to_string(),
+        },
+        github: GithubConfig::default(),
+        markdown: MarkdownConfig::default(),
+        openspec: OpenspecConfig::default(),
+        statuses: StatusMap::default(),
+        prompt_format: PromptFormat::Plain,
+        prompts: PromptPaths::default(),
+        phases: vec![PipelinePhase::Solve],
+        profile_fallback: ProfileFallback::default(),
+        task_routing: TaskRouting::default(),
     };
 
     let mut state = RuntimeState {
         config,
         config_path: temp.path().join("trudger.yml"),
-        invocation_folder: std::env::current_dir()
-            .ok()
-            .map(|path| path.display().to_string())
-            .unwrap_or_default(),
+        invocation_folder: temp.path().display().to_string(),
         prompt_trudge: "Task context".to_string(),
         prompt_review: "Review context".to_string(),
-        logger: Logger::new(None),
+        phase_prompts: HashMap::new(),
+        logger: Logger::new(Some(log_path.clone())),
         tmux: TmuxState::disabled(),
         interrupt_flag: Arc::new(AtomicBool::new(false)),
-        manual_tasks: vec![task("tr-1")],
+        manual_tasks: Vec::new(),
         completed_tasks: Vec::new(),
         needs_human_tasks: Vec::new(),
+        skipped_tasks: Vec::new(),
+        declined_tasks: Vec::new(),
+        workspace: None,
+        cwd: None,
         current_task_id: None,
         current_task_show: None,
         current_task_status: None,
         run_started_at: std::time::Instant::now(),
         current_task_started_at: None,
         run_exit_code: 0,
+        agent_output_dir: None,
+        current_agent_output: None,
+        current_review_result: None,
+        current_review_feedback: None,
+        current_review_follow_ups: Vec::new(),
+        current_review_loop: None,
+        agent_failures: 0,
+        routed_from: None,
+        agent_context: Default::default(),
+        resume: None,
+        task_backend: Arc::new(BrBackend),
     };
 
-    let result = run_loop(&mut state).expect_err("manual blocked task should fail fast");
-    assert_eq!(result.code, 1);
-    assert_eq!(result.reason, "task_not_ready:tr-1");
+    validate_config(&state.config, &[]).expect("br config needs no commands");
+    let result = run_loop(&mut state).expect_err("should exit after queue drained");
+    assert_eq!(result.code, 0, "expected graceful exit");
+    assert_eq!(state.completed_tasks, vec![task("tr-1")]);
 
-    assert!(!next_task_log.exists(), "next-task should not run");
-    assert!(!task_show_log.exists(), "task-show should not run");
-    assert!(!task_update_log.exists(), "task-update should not run");
-    assert!(!hook_log.exists(), "hooks should not run");
-    assert!(!codex_log.exists(), "agent should not run");
+    let br_contents = fs::read_to_string(&br_log).expect("read br log");
     assert!(
-        task_status_log.exists(),
-        "task-status should run for readiness check"
+        br_contents
+            .lines()
+            .any(|line| line == "br ready --json --label agent ready --sort priority --limit 1"),
+        "br ready should filter by the configured label, got:\n{br_contents}"
+    );
+    assert!(
+        br_contents.contains("br show tr-1\n"),
+        "got:\n{br_contents}"
+    );
+    assert!(
+        br_contents.contains("br show tr-1 --json"),
+        "got:\n{br_contents}"
+    );
+    assert!(
+        br_contents.contains("br update tr-1 --status in_progress"),
+        "got:\n{br_contents}"
+    );
+
+    let hook_contents = fs::read_to_string(&hook_log).expect("read hook log");
+    assert!(
+        hook_contents.contains("env TRUDGER_TASK_SHOW=BR_SHOW_PAYLOAD"),
+        "hooks should receive br show output, got:\n{hook_contents}"
+    );
+
+    let log_contents = fs::read_to_string(&log_path).expect("read log file");
+    assert!(
+        log_contents.contains("mode=exec command=br"),
+        "br should be logged as a direct exec, got:\n{log_contents}"
     );
 }
 
+fn mock_backend_state(temp: &TempDir, backend: Arc<MockBackend>) -> RuntimeState {
+    let mut state = agent_invocation_state(temp, TimeoutAction::Quit);
+    state.config.agent_command = "true".to_string();
+    state.config.agent_review_command = "true".to_string();
+    state.config.agent_timeout = None;
+    state.task_backend = backend;
+    state
+}
+
+fn mock_statuses(statuses: &[&str]) -> VecDeque<TaskStatus> {
+    statuses
+        .iter()
+        .map(|status| TaskStatus::parse(status).expect("status"))
+        .collect()
+}
+
 #[test]
-fn manual_task_runs_solve_review_and_hooks_without_invoking_next_task() {
+fn mock_backend_drives_the_run_loop_without_commands() {
     let _guard = ENV_MUTEX.lock().unwrap();
     reset_test_env();
-    crate::run_loop::set_agent_invocation_context(
-        "manual-profile".to_string(),
-        "manual-agent".to_string(),
-        "manual-review".to_string(),
+    let temp = TempDir::new().expect("temp dir");
+    let backend = Arc::new(MockBackend::new(MockTasks {
+        next: VecDeque::from(["tr-1".to_string(), "tr-2".to_string()]),
+        show: HashMap::from([("tr-1".to_string(), "Fix the login form".to_string())]),
+        statuses: HashMap::from([
+            ("tr-1".to_string(), mock_statuses(&["open", "closed"])),
+            ("tr-2".to_string(), mock_statuses(&["ready", "blocked"])),
+        ]),
+        ..MockTasks::default()
+    }));
+    let mut state = mock_backend_state(&temp, backend.clone());
+
+    let result = run_loop(&mut state).expect_err("should exit after queue drained");
+    assert_eq!(result.code, 0, "expected graceful exit");
+    assert_eq!(state.completed_tasks, vec![task("tr-1")]);
+    assert_eq!(state.needs_human_tasks, vec![task("tr-2")]);
+    assert_eq!(
+        backend.calls(),
+        vec![
+            "next_task",
+            "status tr-1",
+            "dependencies tr-1",
+            "set_status tr-1 in_progress",
+            "show tr-1",
+            "show tr-1",
+            "status tr-1",
+            "hook on_completed tr-1",
+            "next_task",
+            "status tr-2",
+            "dependencies tr-2",
+            "set_status tr-2 in_progress",
+            "show tr-2",
+            "show tr-2",
+            "status tr-2",
+            "hook on_requires_human tr-2",
+            "next_task",
+        ]
     );
+}
 
+#[test]
+fn tasks_with_open_blockers_are_skipped() {
+    let _guard = ENV_MUTEX.lock().unwrap();
+    reset_test_env();
     let temp = TempDir::new().expect("temp dir");
-    let log_path = temp.path().join("trudger.log");
-    let next_task_log = temp.path().join("next-task.log");
-    let task_show_log = temp.path().join("task-show.log");
-    let task_status_log = temp.path().join("task-status.log");
-    let task_update_log = temp.path().join("task-update.log");
-    let hook_log = temp.path().join("hook.log");
-    let codex_log = temp.path().join("codex.log");
+    let backend = Arc::new(MockBackend::new(MockTasks {
+        next: VecDeque::from(["tr-2".to_string(), "tr-1".to_string(), "tr-2".to_string()]),
+        statuses: HashMap::from([
+            (
+                "tr-1".to_string(),
+                mock_statuses(&["open", "open", "closed"]),
+            ),
+            (
+                "tr-2".to_string(),
+                mock_statuses(&["ready", "ready", "closed"]),
+            ),
+        ]),
+        dependencies: HashMap::from([("tr-2".to_string(), vec!["tr-1".to_string()])]),
+        ..MockTasks::default()
+    }));
+    let mut state = mock_backend_state(&temp, backend.clone());
 
-    let status_queue = temp.path().join("status-queue.txt");
-    fs::write(&status_queue, "ready\nclosed\n").expect("write status queue");
+    let result = run_loop(&mut state).expect_err("should exit after queue drained");
+    assert_eq!(result.code, 0, "expected graceful exit");
+    assert_eq!(state.completed_tasks, vec![task("tr-1"), task("tr-2")]);
+    assert_eq!(
+        backend.calls()[..6],
+        [
+            "next_task",
+            "status tr-2",
+            "dependencies tr-2",
+            "status tr-1",
+            "next_task",
+            "status tr-1",
+        ]
+    );
+    let log_contents = fs::read_to_string(temp.path().join("trudger.log")).expect("read log file");
+    assert!(
+        log_contents.contains("skip_blocked_by task=tr-2 blocker=tr-1 status=open"),
+        "got:\n{log_contents}"
+    );
+}
 
-    let fixtures_bin = Path::new(env!("CARGO_MANIFEST_DIR"))
-        .join("tests")
-        .join("fixtures")
-        .join("bin");
-    let old_path = env::var("PATH").unwrap_or_default();
-    env::set_var("PATH", format!("{}:{}", fixtures_bin.display(), old_path));
-    env::set_var("NEXT_TASK_EXIT_CODE", "1");
-    env::set_var("NEXT_TASK_LOG", &next_task_log);
-    env::set_var("TASK_SHOW_LOG", &task_show_log);
-    env::set_var("TASK_STATUS_LOG", &task_status_log);
-    env::set_var("TASK_UPDATE_LOG", &task_update_log);
-    env::set_var("HOOK_MOCK_LOG", &hook_log);
-    env::set_var("CODEX_MOCK_LOG", &codex_log);
-    env::set_var("TASK_STATUS_QUEUE", &status_queue);
-    env::set_var("TASK_SHOW_OUTPUT", "SHOW_PAYLOAD");
+#[test]
+fn review_can_skip_a_task_as_out_of_scope() {
+    let _guard = ENV_MUTEX.lock().unwrap();
+    reset_test_env();
+    let temp = TempDir::new().expect("temp dir");
+    let backend = Arc::new(MockBackend::new(MockTasks {
+        // tr-1 comes back once more after the review declines it.
+        next: VecDeque::from(["tr-1".to_string(), "tr-2".to_string(), "tr-1".to_string()]),
+        statuses: HashMap::from([
+            ("tr-1".to_string(), mock_statuses(&["open", "wontfix"])),
+            ("tr-2".to_string(), mock_statuses(&["open", "closed"])),
+        ]),
+        ..MockTasks::default()
+    }));
+    let mut state = mock_backend_state(&temp, backend.clone());
+    state.config.hooks.on_skipped = Some("skipped-hook".to_string());
 
-    let config = Config {
-        agent_command: "codex --yolo exec --default".to_string(),
-        agent_review_command: "codex --yolo exec --review \"$@\"".to_string(),
-        commands: Commands {
-            next_task: Some("next-task".to_string()),
-            task_show: "task-show \"$@\"".to_string(),
-            task_status: "task-status".to_string(),
-            task_update_status: "task-update \"$@\"".to_string(),
-        },
-        hooks: Hooks {
-            // Keep the hook running briefly so the interrupter can reliably observe its log.
-            on_completed: "hook --done; sleep 0.05".to_string(),
-            on_requires_human: "hook --human".to_string(),
-            on_doctor_setup: None,
-            on_notification: None,
-            on_notification_scope: None,
-        },
-        review_loop_limit: limit(2),
-        log_path: Some(log_path.clone()),
-    };
+    let result = run_loop(&mut state).expect_err("should exit after queue drained");
+    assert_eq!(result.code, 0, "expected graceful exit");
+    assert_eq!(state.declined_tasks, vec![workspace_task(None, "tr-1")]);
+    assert!(state.skipped_tasks.is_empty());
+    assert_eq!(state.completed_tasks, vec![task("tr-2")]);
+    assert!(state.needs_human_tasks.is_empty());
+    let calls = backend.calls();
+    assert!(
+        calls.contains(&"hook on_skipped tr-1".to_string()),
+        "got: {calls:?}"
+    );
+    assert_eq!(
+        calls
+            .iter()
+            .filter(|call| call.starts_with("show tr-1"))
+            .count(),
+        2,
+        "a skipped task is reviewed once, got: {calls:?}"
+    );
+    let log_contents = fs::read_to_string(temp.path().join("trudger.log")).expect("read log file");
+    assert!(
+        log_contents.contains("review_state task=tr-1 status=skipped"),
+        "got:\n{log_contents}"
+    );
+    assert!(
+        log_contents.contains("declined task=tr-1"),
+        "got:\n{log_contents}"
+    );
+    assert!(
+        log_contents.contains("skip_declined task=tr-1"),
+        "got:\n{log_contents}"
+    );
+    assert!(
+        !log_contents.contains("skip_errored"),
+        "got:\n{log_contents}"
+    );
+    assert!(
+        log_contents.contains("task_lists completed=tr-2 needs_human= skipped= declined=tr-1"),
+        "got:\n{log_contents}"
+    );
+}
 
-    let interrupt_flag = Arc::new(AtomicBool::new(false));
-    let interrupter_flag = Arc::clone(&interrupt_flag);
-    let interrupter_hook_log = hook_log.clone();
-    let interrupter = thread::spawn(move || {
-        let start = Instant::now();
-        while start.elapsed() < Duration::from_secs(5) {
-            if interrupter_hook_log.exists() {
-                interrupter_flag.store(true, std::sync::atomic::Ordering::SeqCst);
-                return;
-            }
-            thread::sleep(Duration::from_millis(5));
-        }
-    });
+#[test]
+fn review_verdict_file_decides_the_outcome() {
+    let _guard = ENV_MUTEX.lock().unwrap();
+    reset_test_env();
+    let temp = TempDir::new().expect("temp dir");
+    let backend = Arc::new(MockBackend::new(MockTasks {
+        next: VecDeque::from(["tr-1".to_string(), "tr-2".to_string(), "tr-3".to_string()]),
+        statuses: HashMap::from([
+            ("tr-1".to_string(), mock_statuses(&["open"])),
+            ("tr-2".to_string(), mock_statuses(&["open"])),
+            ("tr-3".to_string(), mock_statuses(&["open"])),
+            ("tr-4".to_string(), mock_statuses(&["open"])),
+        ]),
+        ..MockTasks::default()
+    }));
+    let verdicts = temp.path().join("verdicts");
+    fs::write(
+        &verdicts,
+        concat!(
+            r#"{"verdict":"retry","reason":"missing tests","feedback_for_next_solve":"add a test"}"#,
+            "\n",
+            r#"{"verdict":"complete","follow_up_tasks":["Document the flag"]}"#,
+            "\n",
+            r#"{"verdict":"escalate","reason":"needs a product decision"}"#,
+            "\n",
+            r#"{"verdict":"skip"}"#,
+            "\n",
+        ),
+    )
+    .expect("write verdicts");
+    let count = temp.path().join("count");
+    let solves = temp.path().join("solves.log");
+    let mut state = mock_backend_state(&temp, backend.clone());
+    state.config.review_loop_limit = limit(3);
+    state.config.agent_command = format!(
+        "printf '%s|%s\\n' \"$TRUDGER_TASK_ID\" \"${{TRUDGER_REVIEW_FEEDBACK:-}}\" >> '{}'",
+        solves.display()
+    );
+    state.config.agent_review_command = format!(
+        "n=$(($(cat '{count}' 2>/dev/null || echo 0) + 1)); echo $n > '{count}'; sed -n \"${{n}}p\" '{verdicts}' > \"$TRUDGER_REVIEW_RESULT_PATH\"",
+        count = count.display(),
+        verdicts = verdicts.display()
+    );
 
-    let mut state = RuntimeState {
-        config,
-        config_path: temp.path().join("trudger.yml"),
-        invocation_folder: std::env::current_dir()
-            .ok()
-            .map(|path| path.display().to_string())
-            .unwrap_or_default(),
-        prompt_trudge: "Task context".to_string(),
-        prompt_review: "Review context".to_string(),
-        logger: Logger::new(Some(log_path)),
-        tmux: TmuxState::disabled(),
+    let result = run_loop(&mut state).expect_err("should exit after queue drained");
+    assert_eq!(result.code, 0, "expected graceful exit");
+    assert_eq!(state.completed_tasks, vec![task("tr-1")]);
+    assert_eq!(state.needs_human_tasks, vec![task("tr-2")]);
+    assert_eq!(state.declined_tasks, vec![workspace_task(None, "tr-3")]);
+    assert_eq!(
+        fs::read_to_string(&solves).expect("read solves"),
+        "tr-1|\ntr-1|add a test\ntr-2|\ntr-3|\n"
+    );
+    let calls = backend.calls();
+    for expected in [
+        "set_status tr-1 closed",
+        "set_status tr-2 blocked",
+        "set_status tr-3 skipped",
+        "hook on_completed tr-1",
+        "hook on_requires_human tr-2",
+    ] {
+        assert!(calls.contains(&expected.to_string()), "got: {calls:?}");
+    }
+    let log_contents = fs::read_to_string(temp.path().join("trudger.log")).expect("read log file");
+    for expected in [
+        "review_verdict task=tr-1 verdict=retry reason=missing tests",
+        "review_loop_retry task=tr-1 loop=1 limit=3",
+        "review_follow_up task=tr-1 title=Document the flag",
+        "review_verdict task=tr-2 verdict=escalate reason=needs a product decision",
+        "review_feedback task=tr-1 source=verdict bytes=10",
+        "declined task=tr-3",
+    ] {
+        assert!(log_contents.contains(expected), "got:\n{log_contents}");
+    }
+    assert!(
+        !log_contents.contains("review_state "),
+        "a verdict replaces the status check, got:\n{log_contents}"
+    );
+
+    state.config.agent_review_command = "echo 'not json' > \"$TRUDGER_REVIEW_RESULT_PATH\"".into();
+    state.manual_tasks = vec![task("tr-4")];
+    let err = run_loop(&mut state).expect_err("invalid verdict should quit");
+    assert_eq!(err.code, 1);
+    assert!(
+        err.reason
+            .starts_with("review_result_invalid:Failed to parse review result"),
+        "got: {}",
+        err.reason
+    );
+}
+
+#[test]
+fn run_id_is_the_start_time_and_pid() {
+    let id = run_id();
+    let (stamp, pid) = id.rsplit_once('-').expect("run id has a pid");
+    assert_eq!(pid, std::process::id().to_string());
+    chrono::NaiveDateTime::parse_from_str(stamp, "%Y%m%dT%H%M%SZ").expect("run id timestamp");
+}
+
+#[test]
+fn review_transcript_feeds_the_next_solve_without_a_verdict() {
+    let _guard = ENV_MUTEX.lock().unwrap();
+    reset_test_env();
+    let temp = TempDir::new().expect("temp dir");
+    let backend = Arc::new(MockBackend::new(MockTasks {
+        next: VecDeque::from(["tr-1".to_string()]),
+        statuses: HashMap::from([(
+            "tr-1".to_string(),
+            mock_statuses(&["open", "in_progress", "closed"]),
+        )]),
+        ..MockTasks::default()
+    }));
+    let solves = temp.path().join("solves.log");
+    let mut state = mock_backend_state(&temp, backend);
+    state.agent_output_dir = Some(temp.path().join("runs"));
+    state.config.review_loop_limit = limit(3);
+    state.config.agent_command = format!(
+        "printf '%s|%s\\n' \"$TRUDGER_REVIEW_LOOP\" \"${{TRUDGER_REVIEW_FEEDBACK:-}}\" >> '{}'",
+        solves.display()
+    );
+    state.config.agent_review_command =
+        "echo 'Reviewing...'; echo \"loop $TRUDGER_REVIEW_LOOP: handle empty input\"".to_string();
+
+    let result = run_loop(&mut state).expect_err("should exit after queue drained");
+    assert_eq!(result.code, 0, "expected graceful exit");
+    assert_eq!(state.completed_tasks, vec![task("tr-1")]);
+    assert_eq!(
+        fs::read_to_string(&solves).expect("read solves"),
+        "0|\n1|Reviewing...\nloop 0: handle empty input\n"
+    );
+    let log_contents = fs::read_to_string(temp.path().join("trudger.log")).expect("read log file");
+    assert!(
+        log_contents.contains("review_feedback task=tr-1 source=transcript bytes=39"),
+        "got:\n{log_contents}"
+    );
+    assert_eq!(state.current_review_feedback, None);
+    assert_eq!(state.current_review_loop, None);
+}
+
+fn pipeline_phase(name: &str, command: String, on_failure: PhaseFailureAction) -> PipelinePhase {
+    PipelinePhase::Custom(AgentPhase {
+        name: name.to_string(),
+        invocation_id: format!("{}-runner", name),
+        command,
+        timeout: None,
+        retry: None,
+        prompt: None,
+        on_failure,
+    })
+}
+
+#[test]
+fn custom_phases_run_in_pipeline_order_around_solve_and_review() {
+    let _guard = ENV_MUTEX.lock().unwrap();
+    reset_test_env();
+    let temp = TempDir::new().expect("temp dir");
+    let backend = Arc::new(MockBackend::new(MockTasks {
+        next: VecDeque::from(["tr-1".to_string()]),
+        statuses: HashMap::from([("tr-1".to_string(), mock_statuses(&["open", "closed"]))]),
+        ..MockTasks::default()
+    }));
+    let phases = temp.path().join("phases.log");
+    let record = format!(
+        "printf '%s %s %s|%s\\n' \"$TRUDGER_AGENT_PHASE\" \"$TRUDGER_REVIEW_LOOP\" \"$TRUDGER_AGENT_PROMPT\" \"${{TRUDGER_REVIEW_FEEDBACK:-}}\" >> '{}'",
+        phases.display()
+    );
+    let mut state = mock_backend_state(&temp, backend.clone());
+    state.agent_output_dir = Some(temp.path().join("runs"));
+    state.config.review_loop_limit = limit(3);
+    state.config.agent_command = record.clone();
+    state.config.agent_review_command = record.clone();
+    state.config.phases = vec![
+        pipeline_phase("plan", record.clone(), PhaseFailureAction::Quit),
+        PipelinePhase::Solve,
+        pipeline_phase(
+            "test",
+            format!(
+                "{}; if [ \"$TRUDGER_REVIEW_LOOP\" = 0 ]; then echo '2 tests failed'; exit 1; fi",
+                record
+            ),
+            PhaseFailureAction::NextLoop,
+        ),
+    ];
+    state.prompt_trudge = "solve".to_string();
+    state.prompt_review = "review".to_string();
+    state.phase_prompts = HashMap::from([
+        ("plan".to_string(), "plan".to_string()),
+        ("test".to_string(), "test".to_string()),
+    ]);
+
+    let result = run_loop(&mut state).expect_err("should exit after queue drained");
+    assert_eq!(result.code, 0, "expected graceful exit");
+    assert_eq!(state.completed_tasks, vec![task("tr-1")]);
+    assert_eq!(
+        fs::read_to_string(&phases).expect("read phases"),
+        "plan 0 plan|\ntrudge 0 solve|\ntest 0 test|\n\
+         plan 1 plan|2 tests failed\ntrudge 1 solve|2 tests failed\ntest 1 test|2 tests failed\n\
+         trudge_review 1 review|2 tests failed\n"
+    );
+    assert_eq!(
+        backend
+            .calls()
+            .iter()
+            .filter(|call| call.as_str() == "set_status tr-1 in_progress")
+            .count(),
+        2,
+        "the first phase of each loop claims the task"
+    );
+    let log_contents = fs::read_to_string(temp.path().join("trudger.log")).expect("read log file");
+    for expected in [
+        "state=PLAN task=tr-1 loop=0",
+        "agent_start task=tr-1 label=agent_plan profile=none invocation_id=plan-runner",
+        "state=TEST task=tr-1 loop=0",
+        "test_failed task=tr-1",
+        "task_error task=tr-1 phase=test action=next_loop",
+        "review_feedback task=tr-1 source=test bytes=14",
+        "review_loop_retry task=tr-1 loop=1 limit=3",
+        "state=REVIEWING task=tr-1 loop=1",
+    ] {
+        assert!(log_contents.contains(expected), "got:\n{log_contents}");
+    }
+    assert!(temp.path().join("runs/tr-1/0-test.log").is_file());
+}
+
+#[test]
+fn resume_picks_up_at_the_journaled_pipeline_phase() {
+    let _guard = ENV_MUTEX.lock().unwrap();
+    reset_test_env();
+    let temp = TempDir::new().expect("temp dir");
+    let backend = Arc::new(MockBackend::new(MockTasks {
+        statuses: HashMap::from([("tr-1".to_string(), mock_statuses(&["open", "closed"]))]),
+        ..MockTasks::default()
+    }));
+    let phases = temp.path().join("phases.log");
+    let journal_path = temp.path().join("journal.json");
+    let record = format!("echo \"$TRUDGER_AGENT_PHASE\" >> '{}'", phases.display());
+    let mut state = mock_backend_state(&temp, backend.clone());
+    state.config.journal_path = Some(journal_path.clone());
+    state.config.agent_command = record.clone();
+    state.config.agent_review_command = record.clone();
+    state.config.phases = vec![
+        pipeline_phase("plan", record.clone(), PhaseFailureAction::Quit),
+        PipelinePhase::Solve,
+        pipeline_phase(
+            "test",
+            format!(
+                "{}; cp '{}' '{}'",
+                record,
+                journal_path.display(),
+                temp.path().join("journal-in-test.json").display()
+            ),
+            PhaseFailureAction::Quit,
+        ),
+    ];
+    let resume_point = JournalTask {
+        task_id: task("tr-1"),
+        phase: JournalPhase::Solving,
+        review_loop: 0,
+        pipeline_phase: Some("test".to_string()),
+        workspace: None,
+    };
+    state.resume = Some(resume_point.clone());
+
+    let result = run_loop(&mut state).expect_err("should exit after queue drained");
+    assert_eq!(result.code, 0, "expected graceful exit");
+    assert_eq!(state.completed_tasks, vec![task("tr-1")]);
+    assert_eq!(
+        fs::read_to_string(&phases).expect("read phases"),
+        "test\ntrudge_review\n",
+        "phases finished before the interruption are not run again"
+    );
+    assert!(
+        backend
+            .calls()
+            .contains(&"set_status tr-1 in_progress".to_string()),
+        "the resumed phase claims the task again"
+    );
+    let journal =
+        fs::read_to_string(temp.path().join("journal-in-test.json")).expect("read journal");
+    assert!(
+        journal.contains("\"pipeline_phase\": \"test\""),
+        "got:\n{journal}"
+    );
+
+    // A journal naming a phase the pipeline no longer has is refused.
+    let backend = Arc::new(MockBackend::new(MockTasks {
+        statuses: HashMap::from([("tr-1".to_string(), mock_statuses(&["open"]))]),
+        ..MockTasks::default()
+    }));
+    let mut state = mock_backend_state(&temp, backend);
+    state.config.agent_command = record.clone();
+    state.config.agent_review_command = record;
+    state.resume = Some(JournalTask {
+        pipeline_phase: Some("deploy".to_string()),
+        ..resume_point
+    });
+    let err = run_loop(&mut state).expect_err("unknown phase should quit");
+    assert_eq!(err.code, 1);
+    assert_eq!(err.reason, "resume_phase_unknown:deploy");
+}
+
+#[test]
+fn failing_invocations_fall_back_to_the_next_profile() {
+    let _guard = ENV_MUTEX.lock().unwrap();
+    reset_test_env();
+    let temp = TempDir::new().expect("temp dir");
+    let backend = Arc::new(MockBackend::new(MockTasks {
+        next: VecDeque::from(["tr-1".to_string(), "tr-2".to_string()]),
+        statuses: HashMap::from([
+            ("tr-1".to_string(), mock_statuses(&["open", "closed"])),
+            ("tr-2".to_string(), mock_statuses(&["open", "closed"])),
+        ]),
+        ..MockTasks::default()
+    }));
+    let prompt_dir = temp.path().join(".trudger/prompts");
+    fs::create_dir_all(&prompt_dir).expect("create prompt dir");
+    fs::write(prompt_dir.join("trudge.md"), "claude solve").expect("write prompt");
+    fs::write(prompt_dir.join("trudge_review.md"), "claude review").expect("write prompt");
+    let agents = temp.path().join("agents.log");
+    let record = format!(
+        "printf '%s %s %s\\n' \"$TRUDGER_PROFILE\" \"$TRUDGER_AGENT_PHASE\" \"$TRUDGER_AGENT_PROMPT\" >> '{}'",
+        agents.display()
+    );
+    let mut state = mock_backend_state(&temp, backend);
+    state.agent_context = crate::run_loop::AgentInvocationContext::new(
+        "codex".to_string(),
+        "codex".to_string(),
+        "codex".to_string(),
+    );
+    state.invocation_folder = temp.path().display().to_string();
+    state.config.agent_command = format!("{}; exit 75", record);
+    state.config.agent_review_command = record.clone();
+    state.prompt_trudge = "codex solve".to_string();
+    state.prompt_review = "codex review".to_string();
+    state.config.profile_fallback = ProfileFallback {
+        profiles: vec![crate::config::AgentProfile {
+            profile: "claude".to_string(),
+            solve_invocation_id: "claude".to_string(),
+            review_invocation_id: "claude".to_string(),
+            solve_command: record.clone(),
+            review_command: record,
+            solve_timeout: None,
+            review_timeout: None,
+            solve_retry: None,
+            review_retry: None,
+            prompts: PromptPaths::default(),
+            phases: vec![PipelinePhase::Solve],
+        }],
+        after_failures: None,
+        exit_codes: vec![75],
+    };
+
+    let result = run_loop(&mut state).expect_err("should exit after queue drained");
+    assert_eq!(result.code, 0, "expected graceful exit");
+    assert_eq!(state.completed_tasks, vec![task("tr-1"), task("tr-2")]);
+    assert_eq!(
+        fs::read_to_string(&agents).expect("read agents"),
+        "codex trudge codex solve\nclaude trudge claude solve\nclaude trudge_review claude review\n\
+         claude trudge claude solve\nclaude trudge_review claude review\n",
+        "the solve is re-run on the fallback profile, which then stays active"
+    );
+    let log_contents = fs::read_to_string(temp.path().join("trudger.log")).expect("read log file");
+    assert!(
+        log_contents.contains("profile_fallback task=tr-1 from=codex to=claude failures=1 exit=75"),
+        "got:\n{log_contents}"
+    );
+    assert!(
+        !log_contents.contains("solve_failed"),
+        "got:\n{log_contents}"
+    );
+}
+
+#[test]
+fn tasks_are_routed_to_profiles_by_label_or_task_profile_command() {
+    let _guard = ENV_MUTEX.lock().unwrap();
+    reset_test_env();
+    let temp = TempDir::new().expect("temp dir");
+    let backend = Arc::new(MockBackend::new(MockTasks {
+        next: VecDeque::from(["tr-1".to_string(), "tr-2".to_string(), "tr-3".to_string()]),
+        show: HashMap::from([
+            (
+                "tr-1".to_string(),
+                r#"[{"title":"Rework the parser","labels":["parser","hard"]}]"#.to_string(),
+            ),
+            (
+                "tr-2".to_string(),
+                r#"{"title":"Fix the docs","labels":[{"name":"hard"}]}"#.to_string(),
+            ),
+            ("tr-3".to_string(), "Plain text task".to_string()),
+        ]),
+        statuses: HashMap::from([
+            ("tr-1".to_string(), mock_statuses(&["open", "closed"])),
+            ("tr-2".to_string(), mock_statuses(&["open", "closed"])),
+            ("tr-3".to_string(), mock_statuses(&["open", "closed"])),
+        ]),
+        ..MockTasks::default()
+    }));
+    let prompt_dir = temp.path().join(".trudger/prompts");
+    fs::create_dir_all(&prompt_dir).expect("create prompt dir");
+    fs::write(prompt_dir.join("trudge.md"), "routed solve").expect("write prompt");
+    fs::write(prompt_dir.join("trudge_review.md"), "routed review").expect("write prompt");
+    let agents = temp.path().join("agents.log");
+    let payloads = temp.path().join("payloads.log");
+    let record = |name: &str| {
+        format!(
+            "printf '%s %s {} %s\\n' \"$TRUDGER_TASK_ID\" \"$TRUDGER_PROFILE\" \"$TRUDGER_AGENT_PROMPT\" >> '{}'",
+            name,
+            agents.display()
+        )
+    };
+    let profile = |name: &str| crate::config::AgentProfile {
+        profile: name.to_string(),
+        solve_invocation_id: name.to_string(),
+        review_invocation_id: name.to_string(),
+        solve_command: record(name),
+        review_command: record(name),
+        solve_timeout: None,
+        review_timeout: None,
+        solve_retry: None,
+        review_retry: None,
+        prompts: PromptPaths::default(),
+        phases: vec![PipelinePhase::Solve],
+    };
+    let mut state = mock_backend_state(&temp, backend);
+    state.agent_context = crate::run_loop::AgentInvocationContext::new(
+        "codex".to_string(),
+        "codex".to_string(),
+        "codex".to_string(),
+    );
+    state.invocation_folder = temp.path().display().to_string();
+    state.config.agent_command = record("codex");
+    state.config.agent_review_command = record("codex");
+    state.prompt_trudge = "solve".to_string();
+    state.prompt_review = "review".to_string();
+    state.config.hooks.on_notification = Some(format!(
+        "cat \"$TRUDGER_NOTIFY_PAYLOAD_PATH\" >> '{}'",
+        payloads.display()
+    ));
+    state.config.commands.task_profile =
+        Some("if [ \"$TRUDGER_TASK_ID\" = tr-2 ]; then echo haiku; fi".to_string());
+    state.config.task_routing = TaskRouting {
+        rules: vec![crate::config::ProfileRule {
+            label: "hard".to_string(),
+            profile: "opus".to_string(),
+        }],
+        profiles: HashMap::from([
+            ("codex".to_string(), profile("codex")),
+            ("opus".to_string(), profile("opus")),
+            ("haiku".to_string(), profile("haiku")),
+        ]),
+    };
+
+    let result = run_loop(&mut state).expect_err("should exit after queue drained");
+    assert_eq!(result.code, 0, "expected graceful exit");
+    assert_eq!(
+        state.completed_tasks,
+        vec![task("tr-1"), task("tr-2"), task("tr-3")]
+    );
+    assert_eq!(
+        fs::read_to_string(&agents).expect("read agents"),
+        "tr-1 opus opus routed solve\ntr-1 opus opus routed review\n\
+         tr-2 haiku haiku routed solve\ntr-2 haiku haiku routed review\n\
+         tr-3 codex codex solve\ntr-3 codex codex review\n",
+        "a routed profile lasts for its task only"
+    );
+    let log_contents = fs::read_to_string(temp.path().join("trudger.log")).expect("read log file");
+    for expected in [
+        "task_profile task=tr-1 profile=opus source=label:hard",
+        "task_profile task=tr-2 profile=haiku source=task_profile",
+        "task_profile task=tr-3 profile=codex source=default",
+    ] {
+        assert!(log_contents.contains(expected), "got:\n{log_contents}");
+    }
+    let payloads = fs::read_to_string(&payloads).expect("read payloads");
+    for expected in [
+        r#""event":"task_start","duration_ms":0"#,
+        r#""task_id":"tr-1","task_description":"Rework the parser","profile":"opus""#,
+        r#""task_id":"tr-3","task_description":"Plain text task","profile":"codex""#,
+    ] {
+        assert!(payloads.contains(expected), "got:\n{payloads}");
+    }
+}
+
+#[test]
+fn template_prompts_are_rendered_per_task_and_phase() {
+    let _guard = ENV_MUTEX.lock().unwrap();
+    reset_test_env();
+    let temp = TempDir::new().expect("temp dir");
+    let backend = Arc::new(MockBackend::new(MockTasks {
+        next: VecDeque::from(["tr-1".to_string()]),
+        show: HashMap::from([("tr-1".to_string(), "Fix the login form".to_string())]),
+        statuses: HashMap::from([("tr-1".to_string(), mock_statuses(&["open", "closed"]))]),
+        ..MockTasks::default()
+    }));
+    let prompts = temp.path().join("prompts.log");
+    let mut state = mock_backend_state(&temp, backend);
+    state.config.prompt_format = PromptFormat::Template;
+    state.prompt_trudge = "{{phase}} {{task.id}} loop {{loop}}: {{task.show}}\n{{#if review.feedback}}\nFeedback: {{review.feedback}}\n{{else}}\nFirst try.\n{{/if}}".to_string();
+    state.prompt_review = "{{phase}} {{task.id}} ({{task.description}})".to_string();
+    let record = format!(
+        "printf '%s\\n--\\n' \"$TRUDGER_AGENT_PROMPT\" >> '{}'",
+        prompts.display()
+    );
+    state.config.agent_command = record.clone();
+    state.config.agent_review_command = record;
+
+    let result = run_loop(&mut state).expect_err("should exit after queue drained");
+    assert_eq!(result.code, 0, "expected graceful exit");
+    assert_eq!(
+        fs::read_to_string(&prompts).expect("read prompts"),
+        "trudge tr-1 loop 0: Fix the login form\nFirst try.\n\n--\ntrudge_review tr-1 (Fix the login form)\n--\n"
+    );
+}
+
+#[test]
+fn manual_task_with_open_blockers_fails_fast() {
+    let _guard = ENV_MUTEX.lock().unwrap();
+    reset_test_env();
+    let temp = TempDir::new().expect("temp dir");
+    let mut state = mock_backend_state(&temp, Arc::new(MockBackend::default()));
+    state.config.commands.task_status = "printf open".to_string();
+    state.config.commands.task_dependencies = Some("printf 'tr-1\\n\\ntr-9\\n'".to_string());
+    state.task_backend = Arc::new(ShellBackend);
+    state.manual_tasks = vec![task("tr-2")];
+
+    let err = run_loop(&mut state).expect_err("blocked manual task should quit");
+    assert_eq!(err.code, 1);
+    assert_eq!(err.reason, "task_blocked_by:tr-2:tr-1");
+
+    state.config.commands.task_dependencies = Some("printf 'not valid!'".to_string());
+    state.manual_tasks = vec![task("tr-2")];
+    let err = run_loop(&mut state).expect_err("invalid dependency should quit");
+    assert!(
+        err.reason.starts_with(
+            "task_dependencies_failed:task_dependencies returned an invalid task id: not valid!"
+        ),
+        "got: {}",
+        err.reason
+    );
+}
+
+#[test]
+fn mock_backend_task_errors_quit_the_run() {
+    let _guard = ENV_MUTEX.lock().unwrap();
+    reset_test_env();
+    let temp = TempDir::new().expect("temp dir");
+    let backend = Arc::new(MockBackend::new(MockTasks {
+        next: VecDeque::from(["tr-1".to_string()]),
+        show: HashMap::new(),
+        statuses: HashMap::from([("tr-1".to_string(), mock_statuses(&["open", "in_progress"]))]),
+        ..MockTasks::default()
+    }));
+    let mut state = mock_backend_state(&temp, backend.clone());
+    state.config.agent_command = "exit 3".to_string();
+
+    let result = run_loop(&mut state);
+    reset_task_on_exit(&state, &result);
+    let err = result.expect_err("solve failure should quit");
+    assert_eq!(err.code, 1);
+    assert!(
+        err.reason.starts_with("solve_failed"),
+        "got: {}",
+        err.reason
+    );
+    assert_eq!(
+        backend.calls().last().map(String::as_str),
+        Some("set_status tr-1 open"),
+        "an interrupted task is reset to open"
+    );
+}
+
+#[test]
+fn markdown_backend_works_through_the_todo_file() {
+    let _guard = ENV_MUTEX.lock().unwrap();
+    reset_test_env();
+    let temp = TempDir::new().expect("temp dir");
+    let todo_path = temp.path().join("TODO.md");
+    fs::write(
+        &todo_path,
+        "# Tasks\n- [x] tr-1: Done already\n- [ ] tr-2: Fix the login form\n  Use the new validator.\n- [ ] tr-3: Stuck (blocked)\n- [ ] tr-4: Add dark mode\n",
+    )
+    .expect("write todo");
+
+    let mut state = agent_invocation_state(&temp, TimeoutAction::Quit);
+    state.config.backend = Backend::Markdown;
+    state.config.commands = Commands::default();
+    state.config.agent_timeout = None;
+    state.config.agent_command = "printf '%s' \"$TRUDGER_TASK_SHOW\" >> shown.txt".to_string();
+    // The reviewer closes whatever is in progress.
+    state.config.agent_review_command =
+        r"sed -i -E 's/^- \[ \] (tr-[0-9]+): (.*) \(in_progress\)$/- [x] \1: \2/' TODO.md"
+            .to_string();
+    state.config.hooks.on_completed = "true".to_string();
+    state.cwd = Some(temp.path().to_path_buf());
+    state.task_backend = Arc::new(MarkdownBackend);
+
+    validate_config(&state.config, &[]).expect("markdown config needs no commands");
+    let result = run_loop(&mut state).expect_err("should exit after the list is done");
+    assert_eq!(result.code, 0, "expected graceful exit");
+    assert_eq!(state.completed_tasks, vec![task("tr-2"), task("tr-4")]);
+    assert_eq!(
+        fs::read_to_string(&todo_path).expect("read todo"),
+        "# Tasks\n- [x] tr-1: Done already\n- [x] tr-2: Fix the login form\n  Use the new validator.\n- [ ] tr-3: Stuck (blocked)\n- [x] tr-4: Add dark mode\n"
+    );
+    assert_eq!(
+        fs::read_to_string(temp.path().join("shown.txt")).expect("read shown"),
+        "tr-2: Fix the login form\n  Use the new validator.\ntr-4: Add dark mode\n"
+    );
+}
+
+#[test]
+fn markdown_backend_moves_past_skipped_items() {
+    let _guard = ENV_MUTEX.lock().unwrap();
+    reset_test_env();
+    let temp = TempDir::new().expect("temp dir");
+    let todo_path = temp.path().join("TODO.md");
+    fs::write(
+        &todo_path,
+        "- [ ] tr-1: Breaks the agent\n- [ ] tr-2: Add dark mode\n",
+    )
+    .expect("write todo");
+
+    let mut state = agent_invocation_state(&temp, TimeoutAction::Quit);
+    state.config.backend = Backend::Markdown;
+    state.config.commands = Commands::default();
+    state.config.agent_timeout = None;
+    state.config.on_task_error = TaskErrorAction::Skip;
+    state.config.agent_command = "[ \"$TRUDGER_TASK_ID\" != tr-1 ]".to_string();
+    state.config.agent_review_command =
+        r"sed -i -E 's/^- \[ \] (tr-[0-9]+): (.*) \(in_progress\)$/- [x] \1: \2/' TODO.md"
+            .to_string();
+    state.config.hooks.on_completed = "true".to_string();
+    state.cwd = Some(temp.path().to_path_buf());
+    state.task_backend = Arc::new(MarkdownBackend);
+
+    let result = run_loop(&mut state).expect_err("should exit once only tr-1 is left");
+    assert_eq!(result.code, 0);
+    assert_eq!(result.reason, "no_task");
+    assert_eq!(state.skipped_tasks, vec![workspace_task(None, "tr-1")]);
+    assert_eq!(state.completed_tasks, vec![task("tr-2")]);
+    assert_eq!(
+        fs::read_to_string(&todo_path).expect("read todo"),
+        "- [ ] tr-1: Breaks the agent\n- [x] tr-2: Add dark mode\n"
+    );
+}
+
+#[test]
+fn review_skip_reopens_tasks_the_backend_cannot_mark_skipped() {
+    let _guard = ENV_MUTEX.lock().unwrap();
+    reset_test_env();
+    let temp = TempDir::new().expect("temp dir");
+    let todo_path = temp.path().join("TODO.md");
+    fs::write(
+        &todo_path,
+        "- [ ] tr-1: Out of scope\n- [ ] tr-2: Add dark mode\n",
+    )
+    .expect("write todo");
+
+    let mut state = agent_invocation_state(&temp, TimeoutAction::Quit);
+    state.config.backend = Backend::Markdown;
+    state.config.commands = Commands::default();
+    state.config.agent_timeout = None;
+    state.config.agent_command = "true".to_string();
+    state.config.agent_review_command = r##"if [ "$TRUDGER_TASK_ID" = tr-1 ]; then printf '%s\n' '{"verdict":"skip"}'; else printf '%s\n' '{"verdict":"complete","follow_up_tasks":["Document the flag","Add a\nchangelog entry"," "]}'; fi > "$TRUDGER_REVIEW_RESULT_PATH""##.to_string();
+    state.config.hooks.on_completed =
+        "printf '%s' \"$TRUDGER_REVIEW_FOLLOW_UPS\" > follow-ups.txt".to_string();
+    state.cwd = Some(temp.path().to_path_buf());
+    state.task_backend = Arc::new(MarkdownBackend);
+
+    let result = run_loop(&mut state).expect_err("should exit once the list is done");
+    assert_eq!(result.code, 0);
+    assert_eq!(state.declined_tasks, vec![workspace_task(None, "tr-1")]);
+    assert_eq!(state.completed_tasks, vec![task("tr-2")]);
+    assert_eq!(
+        fs::read_to_string(&todo_path).expect("read todo"),
+        "- [ ] tr-1: Out of scope\n- [x] tr-2: Add dark mode\n"
+    );
+    assert_eq!(
+        fs::read_to_string(temp.path().join("follow-ups.txt")).expect("read follow-ups"),
+        "Document the flag\nAdd a changelog entry"
+    );
+}
+
+#[test]
+fn openspec_backend_feeds_changes_and_closes_their_checklists() {
+    let _guard = ENV_MUTEX.lock().unwrap();
+    reset_test_env();
+    let temp = TempDir::new().expect("temp dir");
+    let changes = temp.path().join("openspec").join("changes");
+    for (change, tasks) in [
+        (
+            "add-dark-mode",
+            "## 1. Theme\n- [x] 1.1 Pick colors.\n- [ ] 1.2 Add the toggle.\n",
+        ),
+        ("fix-login", "- [ ] 1.1 Validate the form.\n"),
+        ("update-docs", "- [x] 1.1 Done already.\n"),
+    ] {
+        fs::create_dir_all(changes.join(change)).expect("create change");
+        fs::write(changes.join(change).join("tasks.md"), tasks).expect("write tasks");
+    }
+    fs::write(
+        changes.join("fix-login").join("proposal.md"),
+        "# Change: Fix the login form\n",
+    )
+    .expect("write proposal");
+
+    let mut state = agent_invocation_state(&temp, TimeoutAction::Quit);
+    state.config.backend = Backend::Openspec;
+    state.config.commands = Commands::default();
+    state.config.agent_timeout = None;
+    state.config.agent_command =
+        "printf '%s' \"$TRUDGER_TASK_SHOW\" | head -n 1 >> shown.txt".to_string();
+    // The reviewer checks off what is left of the change being worked on.
+    state.config.agent_review_command =
+        r#"sed -i -E 's/^- \[ \]/- [x]/' "openspec/changes/$TRUDGER_TASK_ID/tasks.md""#.to_string();
+    state.config.hooks.on_completed = "true".to_string();
+    state.cwd = Some(temp.path().to_path_buf());
+    state.task_backend = Arc::new(OpenspecBackend);
+
+    validate_config(&state.config, &[]).expect("openspec config needs no commands");
+    let result = run_loop(&mut state).expect_err("should exit after the changes are done");
+    assert_eq!(result.code, 0, "expected graceful exit");
+    assert_eq!(
+        state.completed_tasks,
+        vec![task("add-dark-mode"), task("fix-login")]
+    );
+    assert_eq!(
+        fs::read_to_string(changes.join("add-dark-mode").join("tasks.md")).expect("read tasks"),
+        "<!-- trudger: in_progress -->\n## 1. Theme\n- [x] 1.1 Pick colors.\n- [x] 1.2 Add the toggle.\n"
+    );
+    assert_eq!(
+        fs::read_to_string(temp.path().join("shown.txt")).expect("read shown"),
+        "add-dark-mode\nfix-login: Fix the login form\n"
+    );
+}
+
+#[test]
+fn statuses_map_tracker_tokens_both_ways() {
+    let _guard = ENV_MUTEX.lock().unwrap();
+    reset_test_env();
+    let temp = TempDir::new().expect("temp dir");
+    fs::create_dir(temp.path().join("statuses")).expect("create statuses dir");
+    fs::write(temp.path().join("statuses/tr-1"), "todo\n").expect("write status");
+    fs::write(temp.path().join("statuses/tr-2"), "wontfix\n").expect("write status");
+    fs::write(temp.path().join("queue"), "tr-2\ntr-1\n").expect("write queue");
+
+    let mut state = agent_invocation_state(&temp, TimeoutAction::Quit);
+    state.config.statuses = serde_yaml::from_str::<StatusMap>(
+        "todo: ready\ndoing: in_progress\ndone: done\nneeds-review: needs_human\nwontfix: skip\n",
+    )
+    .expect("statuses");
+    state.config.commands = Commands {
+        next_task: Some("head -n 1 queue; sed -i 1d queue".to_string()),
+        task_show: "echo show".to_string(),
+        task_status: "cat \"statuses/$TRUDGER_TASK_ID\"".to_string(),
+        task_update_status: "printf '%s\\n' \"$TRUDGER_TARGET_STATUS\" > \"statuses/$TRUDGER_TASK_ID\"; echo \"$TRUDGER_TASK_ID $TRUDGER_TARGET_STATUS\" >> updates.log".to_string(),
+        task_dependencies: None,
+        task_profile: None,
+    };
+    state.config.agent_command = "true".to_string();
+    state.config.agent_review_command = "echo done > \"statuses/$TRUDGER_TASK_ID\"".to_string();
+    state.config.agent_timeout = None;
+    state.config.hooks.on_completed = "true".to_string();
+    state.cwd = Some(temp.path().to_path_buf());
+
+    let result = run_loop(&mut state).expect_err("should exit after queue drained");
+    assert_eq!(result.code, 0, "expected graceful exit");
+    assert_eq!(state.completed_tasks, vec![task("tr-1")]);
+    assert_eq!(
+        fs::read_to_string(temp.path().join("updates.log")).expect("read updates"),
+        "tr-1 doing\n"
+    );
+    let log_contents = fs::read_to_string(temp.path().join("trudger.log")).expect("read log file");
+    assert!(
+        log_contents.contains("skip_not_ready task=tr-2 status=skipped"),
+        "got:\n{log_contents}"
+    );
+}
+
+#[test]
+fn manual_task_not_ready_fails_fast_without_invoking_next_task() {
+    let _guard = ENV_MUTEX.lock().unwrap();
+    reset_test_env();
+
+    let temp = TempDir::new().expect("temp dir");
+    let next_task_log = temp.path().join("next-task.log");
+    let task_status_log = temp.path().join("task-status.log");
+    let task_show_log = temp.path().join("task-show.log");
+    let task_update_log = temp.path().join("task-update.log");
+    let hook_log = temp.path().join("hook.log");
+    let codex_log = temp.path().join("codex.log");
+
+    let status_queue = temp.path().join("status-queue.txt");
+    fs::write(&status_queue, "blocked\n").expect("write status queue");
+
+    let fixtures_bin = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("fixtures")
+        .join("bin");
+    let old_path = env::var("PATH").unwrap_or_default();
+    env::set_var("PATH", format!("{}:{}", fixtures_bin.display(), old_path));
+
+    env::set_var("NEXT_TASK_LOG", &next_task_log);
+    env::set_var("TASK_STATUS_LOG", &task_status_log);
+    env::set_var("TASK_SHOW_LOG", &task_show_log);
+    env::set_var("TASK_UPDATE_LOG", &task_update_log);
+    env::set_var("HOOK_MOCK_LOG", &hook_log);
+    env::set_var("CODEX_MOCK_LOG", &codex_log);
+    env::set_var("TASK_STATUS_QUEUE", &status_queue);
+
+    let config = Config {
+        agent_command: "codex --yolo exec --default".to_string(),
+        agent_review_command: "codex --yolo exec --review \"$@\"".to_string(),
+        commands: Commands {
+            next_task: Some("next-task".to_string()),
+            task_show: "task-show \"$@\"".to_string(),
+            task_status: "task-status".to_string(),
+            task_update_status: "task-update \"$@\"".to_string(),
+            task_dependencies: None,
+            task_profile: None,
+        },
+        hooks: Hooks {
+            on_completed: "hook --done".to_string(),
+            on_requires_human: "hook --human".to_string(),
+            on_doctor_setup: None,
+            on_notification: None,
+            on_notification_scope: None,
+            on_task_error: None,
+            on_skipped: None,
+        },
+        review_loop_limit: limit(2),
+        log_path: Some(temp.path().join("trudger.log")),
+        journal_path: None,
+        agent_timeout: None,
+        agent_review_timeout: None,
+        agent_retry: None,
+        agent_review_retry: None,
+        on_task_error: TaskErrorAction::Quit,
+        watch: WatchConfig::default(),
+        workspaces: Vec::new(),
+        workspace_order: WorkspaceOrder::RoundRobin,
+        concurrency: std::num::NonZeroUsize::MIN,
+        worktree_root: None,
+        log_format: LogFormat::Text,
+        agent_output_dir: None,
+        backend: Backend::Shell,
+        br: BrConfig::default(),
+        github: GithubConfig::default(),
+        markdown: MarkdownConfig::default(),
+        openspec: OpenspecConfig::default(),
+        statuses: StatusMap::default(),
+        prompt_format: PromptFormat::Plain,
+        prompts: PromptPaths::default(),
+        phases: vec![PipelinePhase::Solve],
+        profile_fallback: ProfileFallback::default(),
+        task_routing: TaskRouting::default(),
+    };
+
+    let mut state = RuntimeState {
+        config,
+        config_path: temp.path().join("trudger.yml"),
+        invocation_folder: std::env::current_dir()
+            .ok()
+            .map(|path| path.display().to_string())
+            .unwrap_or_default(),
+        prompt_trudge: "Task context".to_string(),
+        prompt_review: "Review context".to_string(),
+        phase_prompts: HashMap::new(),
+        logger: Logger::new(None),
+        tmux: TmuxState::disabled(),
+        interrupt_flag: Arc::new(AtomicBool::new(false)),
+        manual_tasks: vec![task("tr-1")],
+        completed_tasks: Vec::new(),
+        needs_human_tasks: Vec::new(),
+        skipped_tasks: Vec::new(),
+        declined_tasks: Vec::new(),
+        workspace: None,
+        cwd: None,
+        current_task_id: None,
+        current_task_show: None,
+        current_task_status: None,
+        run_started_at: std::time::Instant::now(),
+        current_task_started_at: None,
+        run_exit_code: 0,
+        agent_output_dir: None,
+        current_agent_output: None,
+        current_review_result: None,
+        current_review_feedback: None,
+        current_review_follow_ups: Vec::new(),
+        current_review_loop: None,
+        agent_failures: 0,
+        routed_from: None,
+        agent_context: Default::default(),
+        resume: None,
+        task_backend: Arc::new(ShellBackend),
+    };
+
+    let result = run_loop(&mut state).expect_err("manual blocked task should fail fast");
+    assert_eq!(result.code, 1);
+    assert_eq!(result.reason, "task_not_ready:tr-1");
+
+    assert!(!next_task_log.exists(), "next-task should not run");
+    assert!(!task_show_log.exists(), "task-show should not run");
+    assert!(!task_update_log.exists(), "task-update should not run");
+    assert!(!hook_log.exists(), "hooks should not run");
+    assert!(!codex_log.exists(), "agent should not run");
+    assert!(
+        task_status_log.exists(),
+        "task-status should run for readiness check"
+    );
+}
+
+#[test]
+fn manual_task_runs_solve_review_and_hooks_without_invoking_next_task() {
+    let _guard = ENV_MUTEX.lock().unwrap();
+    reset_test_env();
+
+    let temp = TempDir::new().expect("temp dir");
+    let log_path = temp.path().join("trudger.log");
+    let next_task_log = temp.path().join("next-task.log");
+    let task_show_log = temp.path().join("task-show.log");
+    let task_status_log = temp.path().join("task-status.log");
+    let task_update_log = temp.path().join("task-update.log");
+    let hook_log = temp.path().join("hook.log");
+    let codex_log = temp.path().join("codex.log");
+
+    let status_queue = temp.path().join("status-queue.txt");
+    fs::write(&status_queue, "ready\nclosed\n").expect("write status queue");
+
+    let fixtures_bin = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("fixtures")
+        .join("bin");
+    let old_path = env::var("PATH").unwrap_or_default();
+    env::set_var("PATH", format!("{}:{}", fixtures_bin.display(), old_path));
+    env::set_var("NEXT_TASK_EXIT_CODE", "1");
+    env::set_var("NEXT_TASK_LOG", &next_task_log);
+    env::set_var("TASK_SHOW_LOG", &task_show_log);
+    env::set_var("TASK_STATUS_LOG", &task_status_log);
+    env::set_var("TASK_UPDATE_LOG", &task_update_log);
+    env::set_var("HOOK_MOCK_LOG", &hook_log);
+    env::set_var("CODEX_MOCK_LOG", &codex_log);
+    env::set_var("TASK_STATUS_QUEUE", &status_queue);
+    env::set_var("TASK_SHOW_OUTPUT", "SHOW_PAYLOAD");
+
+    let config = Config {
+        agent_command: "codex --yolo exec --default".to_string(),
+        agent_review_command: "codex --yolo exec --review \"$@\"".to_string(),
+        commands: Commands {
+            next_task: Some("next-task".to_string()),
+            task_show: "task-show \"$@\"".to_string(),
+            task_status: "task-status".to_string(),
+            task_update_status: "task-update \"$@\"".to_string(),
+            task_dependencies: None,
+            task_profile: None,
+        },
+        hooks: Hooks {
+            // Keep the hook running briefly so the interrupter can reliably observe its log.
+            on_completed: "hook --done; sleep 0.05".to_string(),
+            on_requires_human: "hook --human".to_string(),
+            on_doctor_setup: None,
+            on_notification: None,
+            on_notification_scope: None,
+            on_task_error: None,
+            on_skipped: None,
+        },
+        review_loop_limit: limit(2),
+        log_path: Some(log_path.clone()),
+        journal_path: None,
+        agent_timeout: None,
+        agent_review_timeout: None,
+        agent_retry: None,
+        agent_review_retry: None,
+        on_task_error: TaskErrorAction::Quit,
+        watch: WatchConfig::default(),
+        workspaces: Vec::new(),
+        workspace_order: WorkspaceOrder::RoundRobin,
+        concurrency: std::num::NonZeroUsize::MIN,
+        worktree_root: None,
+        log_format: LogFormat::Text,
+        agent_output_dir: None,
+        backend: Backend::Shell,
+        br: BrConfig::default(),
+        github: GithubConfig::default(),
+        markdown: MarkdownConfig::default(),
+        openspec: OpenspecConfig::default(),
+        statuses: StatusMap::default(),
+        prompt_format: PromptFormat::Plain,
+        prompts: PromptPaths::default(),
+        phases: vec![PipelinePhase::Solve],
+        profile_fallback: ProfileFallback::default(),
+        task_routing: TaskRouting::default(),
+    };
+
+    let interrupt_flag = Arc::new(AtomicBool::new(false));
+    let interrupter_flag = Arc::clone(&interrupt_flag);
+    let interrupter_hook_log = hook_log.clone();
+    let interrupter = thread::spawn(move || {
+        let start = Instant::now();
+        while start.elapsed() < Duration::from_secs(5) {
+            if interrupter_hook_log.exists() {
+                interrupter_flag.store(true, std::sync::atomic::Ordering::SeqCst);
+                return;
+            }
+            thread::sleep(Duration::from_millis(5));
+        }
+    });
+
+    let mut state = RuntimeState {
+        config,
+        config_path: temp.path().join("trudger.yml"),
+        invocation_folder: std::env::current_dir()
+            .ok()
+            .map(|path| path.display().to_string())
+            .unwrap_or_default(),
+        prompt_trudge: "Task context".to_string(),
+        prompt_review: "Review context".to_string(),
+        phase_prompts: HashMap::new(),
+        logger: Logger::new(Some(log_path)),
+        tmux: TmuxState::disabled(),
         interrupt_flag,
         manual_tasks: vec![task("tr-1")],
         completed_tasks: Vec::new(),
         needs_human_tasks: Vec::new(),
+        skipped_tasks: Vec::new(),
+        declined_tasks: Vec::new(),
+        workspace: None,
+        cwd: None,
+        current_task_id: None,
+        current_task_show: None,
+        current_task_status: None,
+        run_started_at: std::time::Instant::now(),
+        current_task_started_at: None,
+        run_exit_code: 0,
+        agent_output_dir: None,
+        current_agent_output: None,
+        current_review_result: None,
+        current_review_feedback: None,
+        current_review_follow_ups: Vec::new(),
+        current_review_loop: None,
+        agent_failures: 0,
+        routed_from: None,
+        agent_context: crate::run_loop::AgentInvocationContext::new(
+            "manual-profile".to_string(),
+            "manual-agent".to_string(),
+            "manual-review".to_string(),
+        ),
+        resume: None,
+        task_backend: Arc::new(ShellBackend),
+    };
+
+    let result = run_loop(&mut state).expect_err("interrupter should stop the loop");
+    interrupter.join().expect("interrupter thread");
+    assert_eq!(result.code, 130, "expected interrupt exit");
+    assert_eq!(result.reason, "interrupted");
+    assert_eq!(state.completed_tasks, vec![task("tr-1")]);
+
+    assert!(
+        !next_task_log.exists(),
+        "next-task should not run when manual tasks are provided"
+    );
+
+    assert!(
+        task_show_log.exists(),
+        "task-show should run for manual tasks"
+    );
+    assert!(
+        task_update_log.exists(),
+        "task-update should run for manual tasks"
+    );
+    assert!(
+        task_status_log.exists(),
+        "task-status should run for manual tasks"
+    );
+
+    let codex_contents = fs::read_to_string(&codex_log).expect("read codex log");
+    assert!(
+        codex_contents.contains("envset TRUDGER_AGENT_PROMPT=1"),
+        "agent solve should receive TRUDGER_AGENT_PROMPT"
+    );
+    assert!(
+        codex_contents.contains("envset TRUDGER_AGENT_PHASE=1"),
+        "agent review should receive TRUDGER_AGENT_PHASE"
+    );
+    assert!(
+        codex_contents.contains("env TRUDGER_AGENT_PHASE=trudge"),
+        "agent solve should expose trudge phase"
+    );
+    assert!(
+        codex_contents.contains("env TRUDGER_AGENT_PHASE=trudge_review"),
+        "agent review should expose trudge_review phase"
+    );
+    assert!(
+        codex_contents.contains("envset TRUDGER_PROFILE=1"),
+        "codex should receive TRUDGER_PROFILE"
+    );
+    assert!(
+        codex_contents.contains("envset TRUDGER_INVOCATION_ID=1"),
+        "codex should receive TRUDGER_INVOCATION_ID"
+    );
+    assert!(
+        codex_contents.contains("env TRUDGER_PROFILE=manual-profile"),
+        "codex should receive profile env, got:\n{codex_contents}"
+    );
+    assert!(
+        codex_contents.contains("env TRUDGER_INVOCATION_ID=manual-agent"),
+        "solve invocation env should be set to manual-agent, got:\n{codex_contents}"
+    );
+    assert!(
+        codex_contents.contains("env TRUDGER_INVOCATION_ID=manual-review"),
+        "review invocation env should be set to manual-review, got:\n{codex_contents}"
+    );
+    assert!(
+        codex_contents.contains("envset TRUDGER_PROMPT=0"),
+        "legacy TRUDGER_PROMPT should not be emitted to agents"
+    );
+    assert!(
+        codex_contents.contains("envset TRUDGER_REVIEW_PROMPT=0"),
+        "legacy TRUDGER_REVIEW_PROMPT should not be emitted to agents"
+    );
+
+    let hook_contents = fs::read_to_string(&hook_log).expect("read hook log");
+    assert!(
+        hook_contents.contains("hook args_count=1 args=--done"),
+        "expected completed hook, got:\n{hook_contents}"
+    );
+    assert!(
+        hook_contents.contains("env TRUDGER_TASK_ID=tr-1"),
+        "hook should see task id in env, got:\n{hook_contents}"
+    );
+    assert!(
+        hook_contents.contains("envset TRUDGER_PROFILE=0"),
+        "hooks should not receive TRUDGER_PROFILE"
+    );
+    assert!(
+        hook_contents.contains("envset TRUDGER_INVOCATION_ID=0"),
+        "hooks should not receive TRUDGER_INVOCATION_ID"
+    );
+}
+
+#[test]
+fn review_loop_limit_retries_until_closed() {
+    let _guard = ENV_MUTEX.lock().unwrap();
+    reset_test_env();
+    let temp = TempDir::new().expect("temp dir");
+    let log_path = temp.path().join("trudger.log");
+    let task_update_log = temp.path().join("task-update.log");
+    let hook_log = temp.path().join("hook.log");
+
+    let next_task_queue = temp.path().join("next-task-queue.txt");
+    fs::write(&next_task_queue, "tr-1\n\n").expect("write next task queue");
+    let status_queue = temp.path().join("status-queue.txt");
+    fs::write(&status_queue, "ready\nopen\nclosed\n").expect("write status queue");
+
+    let fixtures_bin = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("fixtures")
+        .join("bin");
+    let old_path = env::var("PATH").unwrap_or_default();
+    env::set_var("PATH", format!("{}:{}", fixtures_bin.display(), old_path));
+    env::set_var("NEXT_TASK_OUTPUT_QUEUE", &next_task_queue);
+    env::set_var("TASK_STATUS_QUEUE", &status_queue);
+    env::set_var("TASK_SHOW_OUTPUT", "SHOW_PAYLOAD");
+    env::set_var("TASK_UPDATE_LOG", &task_update_log);
+    env::set_var("HOOK_MOCK_LOG", &hook_log);
+
+    let config = Config {
+        agent_command: "codex --yolo exec --default".to_string(),
+        agent_review_command: "codex --yolo exec --review \"$@\"".to_string(),
+        commands: Commands {
+            next_task: Some("next-task".to_string()),
+            task_show: "task-show \"$@\"".to_string(),
+            task_status: "task-status".to_string(),
+            task_update_status: "task-update \"$@\"".to_string(),
+            task_dependencies: None,
+            task_profile: None,
+        },
+        hooks: Hooks {
+            on_completed: "hook --done".to_string(),
+            on_requires_human: "hook --human".to_string(),
+            on_doctor_setup: None,
+            on_notification: None,
+            on_notification_scope: None,
+            on_task_error: None,
+            on_skipped: None,
+        },
+        review_loop_limit: limit(2),
+        log_path: Some(log_path.clone()),
+        journal_path: None,
+        agent_timeout: None,
+        agent_review_timeout: None,
+        agent_retry: None,
+        agent_review_retry: None,
+        on_task_error: TaskErrorAction::Quit,
+        watch: WatchConfig::default(),
+        workspaces: Vec::new(),
+        workspace_order: WorkspaceOrder::RoundRobin,
+        concurrency: std::num::NonZeroUsize::MIN,
+        worktree_root: None,
+        log_format: LogFormat::Text,
+        agent_output_dir: None,
+        backend: Backend::Shell,
+        br: BrConfig::default(),
+        github: GithubConfig::default(),
+        markdown: MarkdownConfig::default(),
+        openspec: OpenspecConfig::default(),
+        statuses: StatusMap::default(),
+        prompt_format: PromptFormat::Plain,
+        prompts: PromptPaths::default(),
+        phases: vec![PipelinePhase::Solve],
+        profile_fallback: ProfileFallback::default(),
+        task_routing: TaskRouting::default(),
+    };
+
+    let mut state = RuntimeState {
+        config,
+        config_path: temp.path().join("trudger.yml"),
+        invocation_folder: std::env::current_dir()
+            .ok()
+            .map(|path| path.display().to_string())
+            .unwrap_or_default(),
+        prompt_trudge: "Task context".to_string(),
+        prompt_review: "Review context".to_string(),
+        phase_prompts: HashMap::new(),
+        logger: Logger::new(Some(log_path)),
+        tmux: TmuxState::disabled(),
+        interrupt_flag: Arc::new(AtomicBool::new(false)),
+        manual_tasks: Vec::new(),
+        completed_tasks: Vec::new(),
+        needs_human_tasks: Vec::new(),
+        skipped_tasks: Vec::new(),
+        declined_tasks: Vec::new(),
+        workspace: None,
+        cwd: None,
         current_task_id: None,
         current_task_show: None,
         current_task_status: None,
         run_started_at: std::time::Instant::now(),
         current_task_started_at: None,
         run_exit_code: 0,
+        agent_output_dir: None,
+        current_agent_output: None,
+        current_review_result: None,
+        current_review_feedback: None,
+        current_review_follow_ups: Vec::new(),
+        current_review_loop: None,
+        agent_failures: 0,
+        routed_from: None,
+        agent_context: Default::default(),
+        resume: None,
+        task_backend: Arc::new(ShellBackend),
+    };
+
+    let result = run_loop(&mut state).expect_err("should exit after queue drained");
+    assert_eq!(result.code, 0, "expected graceful exit");
+
+    let hook_contents = fs::read_to_string(&hook_log).expect("read hook log");
+    assert!(
+        hook_contents.contains("hook args_count=1 args=--done"),
+        "expected completed hook"
+    );
+    assert!(
+        !hook_contents.contains("--human"),
+        "requires-human hook should not run when closed within limit, got:\n{hook_contents}"
+    );
+
+    let update_contents = fs::read_to_string(&task_update_log).expect("read task-update log");
+    assert_eq!(
+        update_contents
+            .matches("env TRUDGER_TARGET_STATUS=in_progress")
+            .count(),
+        2,
+        "expected task_update_status to run once per solve loop, got:\n{update_contents}"
+    );
+    assert!(
+        !update_contents.contains("env TRUDGER_TARGET_STATUS=blocked"),
+        "should not block task when closed within limit, got:\n{update_contents}"
+    );
+}
+
+fn agent_invocation_state(temp: &TempDir, on_timeout: TimeoutAction) -> RuntimeState {
+    let log_path = temp.path().join("trudger.log");
+    let solve_log = temp.path().join("solve.log");
+    let config = Config {
+        agent_command: format!("echo solve >> '{}'; sleep 30", solve_log.display()),
+        agent_review_command: "codex --yolo exec --review".to_string(),
+        commands: Commands {
+            next_task: Some("next-task".to_string()),
+            task_show: "task-show \"$@\"".to_string(),
+            task_status: "task-status".to_string(),
+            task_update_status: "task-update \"$@\"".to_string(),
+            task_dependencies: None,
+            task_profile: None,
+        },
+        hooks: Hooks {
+            on_completed: "hook --done".to_string(),
+            on_requires_human: "hook --human".to_string(),
+            on_doctor_setup: None,
+            on_notification: None,
+            on_notification_scope: None,
+            on_task_error: None,
+            on_skipped: None,
+        },
+        review_loop_limit: limit(2),
+        log_path: Some(log_path.clone()),
+        journal_path: None,
+        agent_timeout: Some(AgentTimeout {
+            seconds: TimeoutSeconds::new(1).expect("timeout"),
+            on_timeout,
+        }),
+        agent_review_timeout: None,
+        agent_retry: None,
+        agent_review_retry: None,
+        on_task_error: TaskErrorAction::Quit,
+        watch: WatchConfig::default(),
+        workspaces: Vec::new(),
+        workspace_order: WorkspaceOrder::RoundRobin,
+        concurrency: std::num::NonZeroUsize::MIN,
+        worktree_root: None,
+        log_format: LogFormat::Text,
+        agent_output_dir: None,
+        backend: Backend::Shell,
+        br: BrConfig::default(),
+        github: GithubConfig::default(),
+        markdown: MarkdownConfig::default(),
+        openspec: OpenspecConfig::default(),
+        statuses: StatusMap::default(),
+        prompt_format: PromptFormat::Plain,
+        prompts: PromptPaths::default(),
+        phases: vec![PipelinePhase::Solve],
+        profile_fallback: ProfileFallback::default(),
+        task_routing: TaskRouting::default(),
     };
 
-    let result = run_loop(&mut state).expect_err("interrupter should stop the loop");
-    interrupter.join().expect("interrupter thread");
-    assert_eq!(result.code, 130, "expected interrupt exit");
-    assert_eq!(result.reason, "interrupted");
-    assert_eq!(state.completed_tasks, vec![task("tr-1")]);
+    RuntimeState {
+        config,
+        config_path: temp.path().join("trudger.yml"),
+        invocation_folder: std::env::current_dir()
+            .ok()
+            .map(|path| path.display().to_string())
+            .unwrap_or_default(),
+        prompt_trudge: "Task context".to_string(),
+        prompt_review: "Review context".to_string(),
+        phase_prompts: HashMap::new(),
+        logger: Logger::new(Some(log_path)),
+        tmux: TmuxState::disabled(),
+        interrupt_flag: Arc::new(AtomicBool::new(false)),
+        manual_tasks: Vec::new(),
+        completed_tasks: Vec::new(),
+        needs_human_tasks: Vec::new(),
+        skipped_tasks: Vec::new(),
+        declined_tasks: Vec::new(),
+        workspace: None,
+        cwd: None,
+        current_task_id: None,
+        current_task_show: None,
+        current_task_status: None,
+        run_started_at: std::time::Instant::now(),
+        current_task_started_at: None,
+        run_exit_code: 0,
+        agent_output_dir: None,
+        current_agent_output: None,
+        current_review_result: None,
+        current_review_feedback: None,
+        current_review_follow_ups: Vec::new(),
+        current_review_loop: None,
+        agent_failures: 0,
+        routed_from: None,
+        agent_context: Default::default(),
+        resume: None,
+        task_backend: Arc::new(ShellBackend),
+    }
+}
+
+#[test]
+fn agent_timeout_escalate_blocks_task_and_continues() {
+    let _guard = ENV_MUTEX.lock().unwrap();
+    reset_test_env();
+    let temp = TempDir::new().expect("temp dir");
+    let task_update_log = temp.path().join("task-update.log");
+    let hook_log = temp.path().join("hook.log");
+
+    let next_task_queue = temp.path().join("next-task-queue.txt");
+    fs::write(&next_task_queue, "tr-1\n\n").expect("write next task queue");
+    let status_queue = temp.path().join("status-queue.txt");
+    fs::write(&status_queue, "ready\n").expect("write status queue");
+
+    let fixtures_bin = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("fixtures")
+        .join("bin");
+    let old_path = env::var("PATH").unwrap_or_default();
+    env::set_var("PATH", format!("{}:{}", fixtures_bin.display(), old_path));
+    env::set_var("NEXT_TASK_OUTPUT_QUEUE", &next_task_queue);
+    env::set_var("TASK_STATUS_QUEUE", &status_queue);
+    env::set_var("TASK_SHOW_OUTPUT", "SHOW_PAYLOAD");
+    env::set_var("TASK_UPDATE_LOG", &task_update_log);
+    env::set_var("HOOK_MOCK_LOG", &hook_log);
+
+    let mut state = agent_invocation_state(&temp, TimeoutAction::Escalate);
+    let result = run_loop(&mut state).expect_err("should exit after queue drained");
+    assert_eq!(result.code, 0, "expected graceful exit after escalation");
+    assert_eq!(state.needs_human_tasks, vec![task("tr-1")]);
+
+    let update_contents = fs::read_to_string(&task_update_log).expect("read task-update log");
+    assert!(
+        update_contents.contains("env TRUDGER_TARGET_STATUS=blocked"),
+        "timed out task should be blocked, got:\n{update_contents}"
+    );
+    let hook_contents = fs::read_to_string(&hook_log).expect("read hook log");
+    assert!(
+        hook_contents.contains("hook args_count=1 args=--human"),
+        "expected requires-human hook, got:\n{hook_contents}"
+    );
+
+    let log_contents =
+        fs::read_to_string(temp.path().join("trudger.log")).expect("read trudger log");
+    assert!(
+        log_contents.contains(
+            "agent_timeout task=tr-1 label=agent_solve timeout_s=1 on_timeout=escalate retried=false"
+        ),
+        "expected agent_timeout transition, got:\n{log_contents}"
+    );
+}
+
+#[test]
+fn agent_timeout_retry_runs_once_more_then_quits() {
+    let _guard = ENV_MUTEX.lock().unwrap();
+    reset_test_env();
+    let temp = TempDir::new().expect("temp dir");
+
+    let status_queue = temp.path().join("status-queue.txt");
+    fs::write(&status_queue, "ready\n").expect("write status queue");
+
+    let fixtures_bin = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("fixtures")
+        .join("bin");
+    let old_path = env::var("PATH").unwrap_or_default();
+    env::set_var("PATH", format!("{}:{}", fixtures_bin.display(), old_path));
+    env::set_var("NEXT_TASK_OUTPUT", "tr-1");
+    env::set_var("TASK_STATUS_QUEUE", &status_queue);
+    env::set_var("TASK_SHOW_OUTPUT", "SHOW_PAYLOAD");
+
+    let mut state = agent_invocation_state(&temp, TimeoutAction::Retry);
+    let result = run_loop(&mut state).expect_err("second timeout should quit");
+    assert_eq!(result.code, 1);
+    assert_eq!(result.reason, "agent_timeout:tr-1");
+
+    let solve_contents = fs::read_to_string(temp.path().join("solve.log")).expect("read solve log");
+    assert_eq!(solve_contents.lines().count(), 2, "expected one retry");
+
+    let log_contents =
+        fs::read_to_string(temp.path().join("trudger.log")).expect("read trudger log");
+    assert!(log_contents.contains("on_timeout=retry retried=false"));
+    assert!(log_contents.contains("on_timeout=retry retried=true"));
+}
+
+#[test]
+fn agent_retry_policy_retries_failed_solve_and_notifies() {
+    let _guard = ENV_MUTEX.lock().unwrap();
+    reset_test_env();
+    let temp = TempDir::new().expect("temp dir");
+    let attempts_path = temp.path().join("attempts.txt");
+    let notify_log = temp.path().join("notify.log");
+
+    let next_task_queue = temp.path().join("next-task-queue.txt");
+    fs::write(&next_task_queue, "tr-1\n\n").expect("write next task queue");
+    let status_queue = temp.path().join("status-queue.txt");
+    fs::write(&status_queue, "ready\nclosed\n").expect("write status queue");
+
+    let fixtures_bin = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("fixtures")
+        .join("bin");
+    let old_path = env::var("PATH").unwrap_or_default();
+    env::set_var("PATH", format!("{}:{}", fixtures_bin.display(), old_path));
+    env::set_var("NEXT_TASK_OUTPUT_QUEUE", &next_task_queue);
+    env::set_var("TASK_STATUS_QUEUE", &status_queue);
+    env::set_var("TASK_SHOW_OUTPUT", "SHOW_PAYLOAD");
+
+    let mut state = agent_invocation_state(&temp, TimeoutAction::Quit);
+    state.config.agent_command = format!(
+        "n=$(cat '{path}' 2>/dev/null || echo 0); n=$((n + 1)); echo \"$n\" > '{path}'; [ \"$n\" -ge 3 ]",
+        path = attempts_path.display()
+    );
+    state.config.agent_timeout = None;
+    state.config.agent_retry = Some(RetryPolicy {
+        max_attempts: NonZeroU64::new(3).expect("non-zero"),
+        backoff_seconds: 0,
+        max_backoff_seconds: 0,
+        exit_codes: Vec::new(),
+    });
+    state.config.hooks.on_notification = Some(format!(
+        "printf '%s|%s\\n' \"$TRUDGER_NOTIFY_EVENT\" \"$TRUDGER_NOTIFY_MESSAGE\" >> '{}'",
+        notify_log.display()
+    ));
+
+    let result = run_loop(&mut state).expect_err("should exit after queue drained");
+    assert_eq!(result.code, 0, "expected graceful exit");
+    assert_eq!(state.completed_tasks, vec![task("tr-1")]);
+    assert_eq!(
+        fs::read_to_string(&attempts_path)
+            .expect("read attempts")
+            .trim(),
+        "3"
+    );
+
+    let log_contents =
+        fs::read_to_string(temp.path().join("trudger.log")).expect("read trudger log");
+    assert!(log_contents
+        .contains("agent_retry task=tr-1 label=agent_solve exit=1 attempt=2 max_attempts=3"));
+    assert!(log_contents
+        .contains("agent_retry task=tr-1 label=agent_solve exit=1 attempt=3 max_attempts=3"));
+
+    let notify_contents = fs::read_to_string(&notify_log).expect("read notify log");
+    let retry_lines: Vec<&str> = notify_contents
+        .lines()
+        .filter(|line| line.starts_with("agent_retry|"))
+        .collect();
+    assert_eq!(
+        retry_lines,
+        vec![
+            "agent_retry|agent_solve exited with code 1; retrying (attempt 2/3) in 0s",
+            "agent_retry|agent_solve exited with code 1; retrying (attempt 3/3) in 0s",
+        ]
+    );
+}
+
+#[test]
+fn agent_retry_policy_skips_exit_codes_outside_the_policy() {
+    let _guard = ENV_MUTEX.lock().unwrap();
+    reset_test_env();
+    let temp = TempDir::new().expect("temp dir");
+    let attempts_path = temp.path().join("attempts.txt");
+
+    let status_queue = temp.path().join("status-queue.txt");
+    fs::write(&status_queue, "ready\n").expect("write status queue");
+
+    let fixtures_bin = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("fixtures")
+        .join("bin");
+    let old_path = env::var("PATH").unwrap_or_default();
+    env::set_var("PATH", format!("{}:{}", fixtures_bin.display(), old_path));
+    env::set_var("NEXT_TASK_OUTPUT", "tr-1");
+    env::set_var("TASK_STATUS_QUEUE", &status_queue);
+    env::set_var("TASK_SHOW_OUTPUT", "SHOW_PAYLOAD");
+
+    let mut state = agent_invocation_state(&temp, TimeoutAction::Quit);
+    state.config.agent_command = format!("echo attempt >> '{}'; exit 2", attempts_path.display());
+    state.config.agent_timeout = None;
+    state.config.agent_retry = Some(RetryPolicy {
+        max_attempts: NonZeroU64::new(3).expect("non-zero"),
+        backoff_seconds: 0,
+        max_backoff_seconds: 0,
+        exit_codes: vec![75],
+    });
+
+    let result = run_loop(&mut state).expect_err("non-retryable exit should quit");
+    assert_eq!(result.code, 1);
+    assert_eq!(result.reason, "solve_failed:tr-1");
+    assert_eq!(
+        fs::read_to_string(&attempts_path)
+            .expect("read attempts")
+            .lines()
+            .count(),
+        1
+    );
+}
+
+#[test]
+fn agent_retry_backoff_quits_when_interrupted() {
+    let _guard = ENV_MUTEX.lock().unwrap();
+    reset_test_env();
+    let temp = TempDir::new().expect("temp dir");
+    let attempts_path = temp.path().join("attempts.txt");
+
+    let status_queue = temp.path().join("status-queue.txt");
+    fs::write(&status_queue, "ready\n").expect("write status queue");
 
-    assert!(
-        !next_task_log.exists(),
-        "next-task should not run when manual tasks are provided"
-    );
+    let fixtures_bin = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("fixtures")
+        .join("bin");
+    let old_path = env::var("PATH").unwrap_or_default();
+    env::set_var("PATH", format!("{}:{}", fixtures_bin.display(), old_path));
+    env::set_var("NEXT_TASK_OUTPUT", "tr-1");
+    env::set_var("TASK_STATUS_QUEUE", &status_queue);
+    env::set_var("TASK_SHOW_OUTPUT", "SHOW_PAYLOAD");
 
-    assert!(
-        task_show_log.exists(),
-        "task-show should run for manual tasks"
-    );
-    assert!(
-        task_update_log.exists(),
-        "task-update should run for manual tasks"
-    );
-    assert!(
-        task_status_log.exists(),
-        "task-status should run for manual tasks"
-    );
+    let mut state = agent_invocation_state(&temp, TimeoutAction::Quit);
+    state.config.agent_command = format!("echo attempt >> '{}'; exit 1", attempts_path.display());
+    state.config.agent_timeout = None;
+    state.config.agent_retry = Some(RetryPolicy {
+        max_attempts: NonZeroU64::new(3).expect("non-zero"),
+        backoff_seconds: 60,
+        max_backoff_seconds: 60,
+        exit_codes: Vec::new(),
+    });
 
-    let codex_contents = fs::read_to_string(&codex_log).expect("read codex log");
-    assert!(
-        codex_contents.contains("envset TRUDGER_AGENT_PROMPT=1"),
-        "agent solve should receive TRUDGER_AGENT_PROMPT"
-    );
-    assert!(
-        codex_contents.contains("envset TRUDGER_AGENT_PHASE=1"),
-        "agent review should receive TRUDGER_AGENT_PHASE"
-    );
-    assert!(
-        codex_contents.contains("env TRUDGER_AGENT_PHASE=trudge"),
-        "agent solve should expose trudge phase"
-    );
-    assert!(
-        codex_contents.contains("env TRUDGER_AGENT_PHASE=trudge_review"),
-        "agent review should expose trudge_review phase"
-    );
-    assert!(
-        codex_contents.contains("envset TRUDGER_PROFILE=1"),
-        "codex should receive TRUDGER_PROFILE"
-    );
-    assert!(
-        codex_contents.contains("envset TRUDGER_INVOCATION_ID=1"),
-        "codex should receive TRUDGER_INVOCATION_ID"
-    );
-    assert!(
-        codex_contents.contains("env TRUDGER_PROFILE=manual-profile"),
-        "codex should receive profile env, got:\n{codex_contents}"
-    );
-    assert!(
-        codex_contents.contains("env TRUDGER_INVOCATION_ID=manual-agent"),
-        "solve invocation env should be set to manual-agent, got:\n{codex_contents}"
-    );
-    assert!(
-        codex_contents.contains("env TRUDGER_INVOCATION_ID=manual-review"),
-        "review invocation env should be set to manual-review, got:\n{codex_contents}"
-    );
-    assert!(
-        codex_contents.contains("envset TRUDGER_PROMPT=0"),
-        "legacy TRUDGER_PROMPT should not be emitted to agents"
-    );
-    assert!(
-        codex_contents.contains("envset TRUDGER_REVIEW_PROMPT=0"),
-        "legacy TRUDGER_REVIEW_PROMPT should not be emitted to agents"
-    );
+    let interrupter_flag = Arc::clone(&state.interrupt_flag);
+    let interrupter_attempts = attempts_path.clone();
+    let interrupter = thread::spawn(move || {
+        let start = Instant::now();
+        while start.elapsed() < Duration::from_secs(5) {
+            if interrupter_attempts.exists() {
+                interrupter_flag.store(true, std::sync::atomic::Ordering::SeqCst);
+                return;
+            }
+            thread::sleep(Duration::from_millis(5));
+        }
+    });
 
-    let hook_contents = fs::read_to_string(&hook_log).expect("read hook log");
-    assert!(
-        hook_contents.contains("hook args_count=1 args=--done"),
-        "expected completed hook, got:\n{hook_contents}"
-    );
-    assert!(
-        hook_contents.contains("env TRUDGER_TASK_ID=tr-1"),
-        "hook should see task id in env, got:\n{hook_contents}"
-    );
-    assert!(
-        hook_contents.contains("envset TRUDGER_PROFILE=0"),
-        "hooks should not receive TRUDGER_PROFILE"
-    );
-    assert!(
-        hook_contents.contains("envset TRUDGER_INVOCATION_ID=0"),
-        "hooks should not receive TRUDGER_INVOCATION_ID"
+    let started_at = Instant::now();
+    let result = run_loop(&mut state).expect_err("interrupt should stop the backoff");
+    interrupter.join().expect("interrupter thread");
+    assert_eq!(result.code, 130);
+    assert_eq!(result.reason, "interrupted");
+    assert!(started_at.elapsed() < Duration::from_secs(30));
+    assert_eq!(
+        fs::read_to_string(&attempts_path)
+            .expect("read attempts")
+            .lines()
+            .count(),
+        1
     );
+    assert_eq!(state.agent_failures, 0);
 
-    crate::run_loop::reset_agent_invocation_context();
+    let log_contents =
+        fs::read_to_string(temp.path().join("trudger.log")).expect("read trudger log");
+    assert!(log_contents.contains("agent_retry task=tr-1 label=agent_solve exit=1 attempt=2"));
+    assert!(!log_contents.contains("solve_failed"));
 }
 
 #[test]
-fn review_loop_limit_retries_until_closed() {
+fn resume_mid_review_skips_solve_and_checkpoints_journal() {
     let _guard = ENV_MUTEX.lock().unwrap();
     reset_test_env();
     let temp = TempDir::new().expect("temp dir");
     let log_path = temp.path().join("trudger.log");
+    let journal_path = temp.path().join(".trudger").join("journal.json");
     let task_update_log = temp.path().join("task-update.log");
     let hook_log = temp.path().join("hook.log");
+    let codex_log = temp.path().join("codex.log");
 
-    let next_task_queue = temp.path().join("next-task-queue.txt");
-    fs::write(&next_task_queue, "tr-1\n\n").expect("write next task queue");
     let status_queue = temp.path().join("status-queue.txt");
-    fs::write(&status_queue, "ready\nopen\nclosed\n").expect("write status queue");
+    fs::write(&status_queue, "in_progress\nclosed\n").expect("write status queue");
 
     let fixtures_bin = Path::new(env!("CARGO_MANIFEST_DIR"))
         .join("tests")
@@ -841,11 +2723,12 @@ fn review_loop_limit_retries_until_closed() {
         .join("bin");
     let old_path = env::var("PATH").unwrap_or_default();
     env::set_var("PATH", format!("{}:{}", fixtures_bin.display(), old_path));
-    env::set_var("NEXT_TASK_OUTPUT_QUEUE", &next_task_queue);
+    env::set_var("NEXT_TASK_EXIT_CODE", "1");
     env::set_var("TASK_STATUS_QUEUE", &status_queue);
     env::set_var("TASK_SHOW_OUTPUT", "SHOW_PAYLOAD");
     env::set_var("TASK_UPDATE_LOG", &task_update_log);
     env::set_var("HOOK_MOCK_LOG", &hook_log);
+    env::set_var("CODEX_MOCK_LOG", &codex_log);
 
     let config = Config {
         agent_command: "codex --yolo exec --default".to_string(),
@@ -855,6 +2738,8 @@ fn review_loop_limit_retries_until_closed() {
             task_show: "task-show \"$@\"".to_string(),
             task_status: "task-status".to_string(),
             task_update_status: "task-update \"$@\"".to_string(),
+            task_dependencies: None,
+            task_profile: None,
         },
         hooks: Hooks {
             on_completed: "hook --done".to_string(),
@@ -862,9 +2747,35 @@ fn review_loop_limit_retries_until_closed() {
             on_doctor_setup: None,
             on_notification: None,
             on_notification_scope: None,
+            on_task_error: None,
+            on_skipped: None,
         },
         review_loop_limit: limit(2),
         log_path: Some(log_path.clone()),
+        journal_path: Some(journal_path.clone()),
+        agent_timeout: None,
+        agent_review_timeout: None,
+        agent_retry: None,
+        agent_review_retry: None,
+        on_task_error: TaskErrorAction::Quit,
+        watch: WatchConfig::default(),
+        workspaces: Vec::new(),
+        workspace_order: WorkspaceOrder::RoundRobin,
+        concurrency: std::num::NonZeroUsize::MIN,
+        worktree_root: None,
+        log_format: LogFormat::Text,
+        agent_output_dir: None,
+        backend: Backend::Shell,
+        br: BrConfig::default(),
+        github: GithubConfig::default(),
+        markdown: MarkdownConfig::default(),
+        openspec: OpenspecConfig::default(),
+        statuses: StatusMap::default(),
+        prompt_format: PromptFormat::Plain,
+        prompts: PromptPaths::default(),
+        phases: vec![PipelinePhase::Solve],
+        profile_fallback: ProfileFallback::default(),
+        task_routing: TaskRouting::default(),
     };
 
     let mut state = RuntimeState {
@@ -876,45 +2787,71 @@ fn review_loop_limit_retries_until_closed() {
             .unwrap_or_default(),
         prompt_trudge: "Task context".to_string(),
         prompt_review: "Review context".to_string(),
-        logger: Logger::new(Some(log_path)),
+        phase_prompts: HashMap::new(),
+        logger: Logger::new(Some(log_path.clone())),
         tmux: TmuxState::disabled(),
         interrupt_flag: Arc::new(AtomicBool::new(false)),
         manual_tasks: Vec::new(),
-        completed_tasks: Vec::new(),
+        completed_tasks: vec![task("tr-0")],
         needs_human_tasks: Vec::new(),
+        skipped_tasks: Vec::new(),
+        declined_tasks: Vec::new(),
+        workspace: None,
+        cwd: None,
         current_task_id: None,
         current_task_show: None,
         current_task_status: None,
         run_started_at: std::time::Instant::now(),
         current_task_started_at: None,
         run_exit_code: 0,
+        agent_output_dir: None,
+        current_agent_output: None,
+        current_review_result: None,
+        current_review_feedback: None,
+        current_review_follow_ups: Vec::new(),
+        current_review_loop: None,
+        agent_failures: 0,
+        routed_from: None,
+        agent_context: Default::default(),
+        resume: Some(JournalTask {
+            task_id: task("tr-1"),
+            phase: JournalPhase::Reviewing,
+            review_loop: 1,
+            pipeline_phase: None,
+            workspace: None,
+        }),
+        task_backend: Arc::new(ShellBackend),
     };
 
     let result = run_loop(&mut state).expect_err("should exit after queue drained");
     assert_eq!(result.code, 0, "expected graceful exit");
+    assert_eq!(state.completed_tasks, vec![task("tr-0"), task("tr-1")]);
 
-    let hook_contents = fs::read_to_string(&hook_log).expect("read hook log");
+    let codex_contents = fs::read_to_string(&codex_log).expect("read codex log");
     assert!(
-        hook_contents.contains("hook args_count=1 args=--done"),
-        "expected completed hook"
+        !codex_contents.contains("--default"),
+        "resumed review should not re-run solve, got:\n{codex_contents}"
     );
     assert!(
-        !hook_contents.contains("--human"),
-        "requires-human hook should not run when closed within limit, got:\n{hook_contents}"
+        codex_contents.contains("--review"),
+        "resumed review should run the review agent, got:\n{codex_contents}"
     );
-
-    let update_contents = fs::read_to_string(&task_update_log).expect("read task-update log");
-    assert_eq!(
-        update_contents
-            .matches("env TRUDGER_TARGET_STATUS=in_progress")
-            .count(),
-        2,
-        "expected task_update_status to run once per solve loop, got:\n{update_contents}"
+    assert!(
+        !task_update_log.exists(),
+        "an in_progress task should not be re-claimed on resume"
     );
+
+    let log_contents = fs::read_to_string(&log_path).expect("read log");
     assert!(
-        !update_contents.contains("env TRUDGER_TARGET_STATUS=blocked"),
-        "should not block task when closed within limit, got:\n{update_contents}"
+        log_contents.contains("resume task=tr-1 phase=reviewing loop=1"),
+        "expected resume transition, got:\n{log_contents}"
     );
+
+    let journal = read_run_journal(&journal_path)
+        .expect("read journal")
+        .expect("journal should be checkpointed");
+    assert_eq!(journal.completed_tasks, vec![task("tr-0"), task("tr-1")]);
+    assert_eq!(journal.current_task, None);
 }
 
 #[test]
@@ -951,6 +2888,8 @@ fn review_loop_limit_exhaustion_marks_blocked_and_requires_human() {
             task_show: "task-show \"$@\"".to_string(),
             task_status: "task-status".to_string(),
             task_update_status: "task-update \"$@\"".to_string(),
+            task_dependencies: None,
+            task_profile: None,
         },
         hooks: Hooks {
             on_completed: "hook --done".to_string(),
@@ -958,9 +2897,35 @@ fn review_loop_limit_exhaustion_marks_blocked_and_requires_human() {
             on_doctor_setup: None,
             on_notification: None,
             on_notification_scope: None,
+            on_task_error: None,
+            on_skipped: None,
         },
         review_loop_limit: limit(2),
         log_path: Some(log_path.clone()),
+        journal_path: None,
+        agent_timeout: None,
+        agent_review_timeout: None,
+        agent_retry: None,
+        agent_review_retry: None,
+        on_task_error: TaskErrorAction::Quit,
+        watch: WatchConfig::default(),
+        workspaces: Vec::new(),
+        workspace_order: WorkspaceOrder::RoundRobin,
+        concurrency: std::num::NonZeroUsize::MIN,
+        worktree_root: None,
+        log_format: LogFormat::Text,
+        agent_output_dir: None,
+        backend: Backend::Shell,
+        br: BrConfig::default(),
+        github: GithubConfig::default(),
+        markdown: MarkdownConfig::default(),
+        openspec: OpenspecConfig::default(),
+        statuses: StatusMap::default(),
+        prompt_format: PromptFormat::Plain,
+        prompts: PromptPaths::default(),
+        phases: vec![PipelinePhase::Solve],
+        profile_fallback: ProfileFallback::default(),
+        task_routing: TaskRouting::default(),
     };
 
     let mut state = RuntimeState {
@@ -972,18 +2937,34 @@ fn review_loop_limit_exhaustion_marks_blocked_and_requires_human() {
             .unwrap_or_default(),
         prompt_trudge: "Task context".to_string(),
         prompt_review: "Review context".to_string(),
+        phase_prompts: HashMap::new(),
         logger: Logger::new(Some(log_path)),
         tmux: TmuxState::disabled(),
         interrupt_flag: Arc::new(AtomicBool::new(false)),
         manual_tasks: Vec::new(),
         completed_tasks: Vec::new(),
         needs_human_tasks: Vec::new(),
+        skipped_tasks: Vec::new(),
+        declined_tasks: Vec::new(),
+        workspace: None,
+        cwd: None,
         current_task_id: None,
         current_task_show: None,
         current_task_status: None,
         run_started_at: std::time::Instant::now(),
         current_task_started_at: None,
         run_exit_code: 0,
+        agent_output_dir: None,
+        current_agent_output: None,
+        current_review_result: None,
+        current_review_feedback: None,
+        current_review_follow_ups: Vec::new(),
+        current_review_loop: None,
+        agent_failures: 0,
+        routed_from: None,
+        agent_context: Default::default(),
+        resume: None,
+        task_backend: Arc::new(ShellBackend),
     };
 
     let result = run_loop(&mut state).expect_err("should exit after queue drained");
@@ -1046,6 +3027,8 @@ fn next_task_exit_1_exits_zero_without_running_commands() {
             task_show: "task-show \"$@\"".to_string(),
             task_status: "task-status".to_string(),
             task_update_status: "task-update \"$@\"".to_string(),
+            task_dependencies: None,
+            task_profile: None,
         },
         hooks: Hooks {
             on_completed: "hook --done".to_string(),
@@ -1053,9 +3036,35 @@ fn next_task_exit_1_exits_zero_without_running_commands() {
             on_doctor_setup: None,
             on_notification: None,
             on_notification_scope: None,
+            on_task_error: None,
+            on_skipped: None,
         },
         review_loop_limit: limit(2),
         log_path: Some(log_path.clone()),
+        journal_path: None,
+        agent_timeout: None,
+        agent_review_timeout: None,
+        agent_retry: None,
+        agent_review_retry: None,
+        on_task_error: TaskErrorAction::Quit,
+        watch: WatchConfig::default(),
+        workspaces: Vec::new(),
+        workspace_order: WorkspaceOrder::RoundRobin,
+        concurrency: std::num::NonZeroUsize::MIN,
+        worktree_root: None,
+        log_format: LogFormat::Text,
+        agent_output_dir: None,
+        backend: Backend::Shell,
+        br: BrConfig::default(),
+        github: GithubConfig::default(),
+        markdown: MarkdownConfig::default(),
+        openspec: OpenspecConfig::default(),
+        statuses: StatusMap::default(),
+        prompt_format: PromptFormat::Plain,
+        prompts: PromptPaths::default(),
+        phases: vec![PipelinePhase::Solve],
+        profile_fallback: ProfileFallback::default(),
+        task_routing: TaskRouting::default(),
     };
 
     let mut state = RuntimeState {
@@ -1067,18 +3076,34 @@ fn next_task_exit_1_exits_zero_without_running_commands() {
             .unwrap_or_default(),
         prompt_trudge: "Task context".to_string(),
         prompt_review: "Review context".to_string(),
+        phase_prompts: HashMap::new(),
         logger: Logger::new(Some(log_path)),
         tmux: TmuxState::disabled(),
         interrupt_flag: Arc::new(AtomicBool::new(false)),
         manual_tasks: Vec::new(),
         completed_tasks: Vec::new(),
         needs_human_tasks: Vec::new(),
+        skipped_tasks: Vec::new(),
+        declined_tasks: Vec::new(),
+        workspace: None,
+        cwd: None,
         current_task_id: None,
         current_task_show: None,
         current_task_status: None,
         run_started_at: std::time::Instant::now(),
         current_task_started_at: None,
         run_exit_code: 0,
+        agent_output_dir: None,
+        current_agent_output: None,
+        current_review_result: None,
+        current_review_feedback: None,
+        current_review_follow_ups: Vec::new(),
+        current_review_loop: None,
+        agent_failures: 0,
+        routed_from: None,
+        agent_context: Default::default(),
+        resume: None,
+        task_backend: Arc::new(ShellBackend),
     };
 
     let result = run_loop(&mut state).expect_err("should exit when next-task returns exit 1");
@@ -1135,6 +3160,8 @@ fn hook_uses_env_task_id_in_shell() {
             task_show: "task-show \"$@\"".to_string(),
             task_status: "task-status".to_string(),
             task_update_status: "task-update \"$@\"".to_string(),
+            task_dependencies: None,
+            task_profile: None,
         },
         hooks: Hooks {
             on_completed: "hook --done \"$TRUDGER_TASK_ID\"".to_string(),
@@ -1142,9 +3169,35 @@ fn hook_uses_env_task_id_in_shell() {
             on_doctor_setup: None,
             on_notification: None,
             on_notification_scope: None,
+            on_task_error: None,
+            on_skipped: None,
         },
         review_loop_limit: limit(2),
         log_path: Some(log_path.clone()),
+        journal_path: None,
+        agent_timeout: None,
+        agent_review_timeout: None,
+        agent_retry: None,
+        agent_review_retry: None,
+        on_task_error: TaskErrorAction::Quit,
+        watch: WatchConfig::default(),
+        workspaces: Vec::new(),
+        workspace_order: WorkspaceOrder::RoundRobin,
+        concurrency: std::num::NonZeroUsize::MIN,
+        worktree_root: None,
+        log_format: LogFormat::Text,
+        agent_output_dir: None,
+        backend: Backend::Shell,
+        br: BrConfig::default(),
+        github: GithubConfig::default(),
+        markdown: MarkdownConfig::default(),
+        openspec: OpenspecConfig::default(),
+        statuses: StatusMap::default(),
+        prompt_format: PromptFormat::Plain,
+        prompts: PromptPaths::default(),
+        phases: vec![PipelinePhase::Solve],
+        profile_fallback: ProfileFallback::default(),
+        task_routing: TaskRouting::default(),
     };
 
     let mut state = RuntimeState {
@@ -1156,18 +3209,34 @@ fn hook_uses_env_task_id_in_shell() {
             .unwrap_or_default(),
         prompt_trudge: "Task context".to_string(),
         prompt_review: "Review context".to_string(),
+        phase_prompts: HashMap::new(),
         logger: Logger::new(Some(log_path)),
         tmux: TmuxState::disabled(),
         interrupt_flag: Arc::new(AtomicBool::new(false)),
         manual_tasks: Vec::new(),
         completed_tasks: Vec::new(),
         needs_human_tasks: Vec::new(),
+        skipped_tasks: Vec::new(),
+        declined_tasks: Vec::new(),
+        workspace: None,
+        cwd: None,
         current_task_id: None,
         current_task_show: None,
         current_task_status: None,
         run_started_at: std::time::Instant::now(),
         current_task_started_at: None,
         run_exit_code: 0,
+        agent_output_dir: None,
+        current_agent_output: None,
+        current_review_result: None,
+        current_review_feedback: None,
+        current_review_follow_ups: Vec::new(),
+        current_review_loop: None,
+        agent_failures: 0,
+        routed_from: None,
+        agent_context: Default::default(),
+        resume: None,
+        task_backend: Arc::new(ShellBackend),
     };
 
     let result = run_loop(&mut state).expect_err("should exit after queue drained");
@@ -1214,6 +3283,8 @@ fn skip_not_ready_respects_limit() {
             task_show: "task-show \"$@\"".to_string(),
             task_status: "task-status".to_string(),
             task_update_status: "task-update \"$@\"".to_string(),
+            task_dependencies: None,
+            task_profile: None,
         },
         hooks: Hooks {
             on_completed: "hook --done".to_string(),
@@ -1221,9 +3292,35 @@ fn skip_not_ready_respects_limit() {
             on_doctor_setup: None,
             on_notification: None,
             on_notification_scope: None,
+            on_task_error: None,
+            on_skipped: None,
         },
         review_loop_limit: limit(2),
         log_path: Some(log_path.clone()),
+        journal_path: None,
+        agent_timeout: None,
+        agent_review_timeout: None,
+        agent_retry: None,
+        agent_review_retry: None,
+        on_task_error: TaskErrorAction::Quit,
+        watch: WatchConfig::default(),
+        workspaces: Vec::new(),
+        workspace_order: WorkspaceOrder::RoundRobin,
+        concurrency: std::num::NonZeroUsize::MIN,
+        worktree_root: None,
+        log_format: LogFormat::Text,
+        agent_output_dir: None,
+        backend: Backend::Shell,
+        br: BrConfig::default(),
+        github: GithubConfig::default(),
+        markdown: MarkdownConfig::default(),
+        openspec: OpenspecConfig::default(),
+        statuses: StatusMap::default(),
+        prompt_format: PromptFormat::Plain,
+        prompts: PromptPaths::default(),
+        phases: vec![PipelinePhase::Solve],
+        profile_fallback: ProfileFallback::default(),
+        task_routing: TaskRouting::default(),
     };
 
     let mut state = RuntimeState {
@@ -1235,18 +3332,34 @@ fn skip_not_ready_respects_limit() {
             .unwrap_or_default(),
         prompt_trudge: "Task context".to_string(),
         prompt_review: "Review context".to_string(),
+        phase_prompts: HashMap::new(),
         logger: Logger::new(Some(log_path)),
         tmux: TmuxState::disabled(),
         interrupt_flag: Arc::new(AtomicBool::new(false)),
         manual_tasks: Vec::new(),
         completed_tasks: Vec::new(),
         needs_human_tasks: Vec::new(),
+        skipped_tasks: Vec::new(),
+        declined_tasks: Vec::new(),
+        workspace: None,
+        cwd: None,
         current_task_id: None,
         current_task_show: None,
         current_task_status: None,
         run_started_at: std::time::Instant::now(),
         current_task_started_at: None,
         run_exit_code: 0,
+        agent_output_dir: None,
+        current_agent_output: None,
+        current_review_result: None,
+        current_review_feedback: None,
+        current_review_follow_ups: Vec::new(),
+        current_review_loop: None,
+        agent_failures: 0,
+        routed_from: None,
+        agent_context: Default::default(),
+        resume: None,
+        task_backend: Arc::new(ShellBackend),
     };
 
     let result = run_loop(&mut state).expect_err("should fail-fast on unknown status");
@@ -1300,6 +3413,8 @@ fn missing_status_after_review_errors() {
             task_show: "task-show \"$@\"".to_string(),
             task_status: "task-status".to_string(),
             task_update_status: "task-update \"$@\"".to_string(),
+            task_dependencies: None,
+            task_profile: None,
         },
         hooks: Hooks {
             on_completed: "hook --done".to_string(),
@@ -1307,9 +3422,35 @@ fn missing_status_after_review_errors() {
             on_doctor_setup: None,
             on_notification: None,
             on_notification_scope: None,
+            on_task_error: None,
+            on_skipped: None,
         },
         review_loop_limit: limit(2),
         log_path: Some(log_path.clone()),
+        journal_path: None,
+        agent_timeout: None,
+        agent_review_timeout: None,
+        agent_retry: None,
+        agent_review_retry: None,
+        on_task_error: TaskErrorAction::Quit,
+        watch: WatchConfig::default(),
+        workspaces: Vec::new(),
+        workspace_order: WorkspaceOrder::RoundRobin,
+        concurrency: std::num::NonZeroUsize::MIN,
+        worktree_root: None,
+        log_format: LogFormat::Text,
+        agent_output_dir: None,
+        backend: Backend::Shell,
+        br: BrConfig::default(),
+        github: GithubConfig::default(),
+        markdown: MarkdownConfig::default(),
+        openspec: OpenspecConfig::default(),
+        statuses: StatusMap::default(),
+        prompt_format: PromptFormat::Plain,
+        prompts: PromptPaths::default(),
+        phases: vec![PipelinePhase::Solve],
+        profile_fallback: ProfileFallback::default(),
+        task_routing: TaskRouting::default(),
     };
 
     let mut state = RuntimeState {
@@ -1321,18 +3462,34 @@ fn missing_status_after_review_errors() {
             .unwrap_or_default(),
         prompt_trudge: "Task context".to_string(),
         prompt_review: "Review context".to_string(),
+        phase_prompts: HashMap::new(),
         logger: Logger::new(Some(log_path)),
         tmux: TmuxState::disabled(),
         interrupt_flag: Arc::new(AtomicBool::new(false)),
         manual_tasks: Vec::new(),
         completed_tasks: Vec::new(),
         needs_human_tasks: Vec::new(),
+        skipped_tasks: Vec::new(),
+        declined_tasks: Vec::new(),
+        workspace: None,
+        cwd: None,
         current_task_id: None,
         current_task_show: None,
         current_task_status: None,
         run_started_at: std::time::Instant::now(),
         current_task_started_at: None,
         run_exit_code: 0,
+        agent_output_dir: None,
+        current_agent_output: None,
+        current_review_result: None,
+        current_review_feedback: None,
+        current_review_follow_ups: Vec::new(),
+        current_review_loop: None,
+        agent_failures: 0,
+        routed_from: None,
+        agent_context: Default::default(),
+        resume: None,
+        task_backend: Arc::new(ShellBackend),
     };
 
     let result = run_loop(&mut state).expect_err("should error on missing status");
@@ -1372,6 +3529,8 @@ fn reset_task_runs_on_exit_with_active_task() {
             task_show: "task-show \"$@\"".to_string(),
             task_status: "task-status".to_string(),
             task_update_status: "task-update \"$@\"".to_string(),
+            task_dependencies: None,
+            task_profile: None,
         },
         hooks: Hooks {
             on_completed: "hook --done".to_string(),
@@ -1379,9 +3538,35 @@ fn reset_task_runs_on_exit_with_active_task() {
             on_doctor_setup: None,
             on_notification: None,
             on_notification_scope: None,
+            on_task_error: None,
+            on_skipped: None,
         },
         review_loop_limit: limit(2),
         log_path: Some(temp.path().join("trudger.log")),
+        journal_path: None,
+        agent_timeout: None,
+        agent_review_timeout: None,
+        agent_retry: None,
+        agent_review_retry: None,
+        on_task_error: TaskErrorAction::Quit,
+        watch: WatchConfig::default(),
+        workspaces: Vec::new(),
+        workspace_order: WorkspaceOrder::RoundRobin,
+        concurrency: std::num::NonZeroUsize::MIN,
+        worktree_root: None,
+        log_format: LogFormat::Text,
+        agent_output_dir: None,
+        backend: Backend::Shell,
+        br: BrConfig::default(),
+        github: GithubConfig::default(),
+        markdown: MarkdownConfig::default(),
+        openspec: OpenspecConfig::default(),
+        statuses: StatusMap::default(),
+        prompt_format: PromptFormat::Plain,
+        prompts: PromptPaths::default(),
+        phases: vec![PipelinePhase::Solve],
+        profile_fallback: ProfileFallback::default(),
+        task_routing: TaskRouting::default(),
     };
 
     let state = RuntimeState {
@@ -1393,18 +3578,34 @@ fn reset_task_runs_on_exit_with_active_task() {
             .unwrap_or_default(),
         prompt_trudge: "Task context".to_string(),
         prompt_review: "Review context".to_string(),
+        phase_prompts: HashMap::new(),
         logger: Logger::new(None),
         tmux: TmuxState::disabled(),
         interrupt_flag: Arc::new(AtomicBool::new(false)),
         manual_tasks: Vec::new(),
         completed_tasks: Vec::new(),
         needs_human_tasks: Vec::new(),
+        skipped_tasks: Vec::new(),
+        declined_tasks: Vec::new(),
+        workspace: None,
+        cwd: None,
         current_task_id: Some(task("tr-1")),
         current_task_show: None,
         current_task_status: None,
         run_started_at: std::time::Instant::now(),
         current_task_started_at: None,
         run_exit_code: 0,
+        agent_output_dir: None,
+        current_agent_output: None,
+        current_review_result: None,
+        current_review_feedback: None,
+        current_review_follow_ups: Vec::new(),
+        current_review_loop: None,
+        agent_failures: 0,
+        routed_from: None,
+        agent_context: Default::default(),
+        resume: None,
+        task_backend: Arc::new(ShellBackend),
     };
 
     let result = Err(Quit {
@@ -1488,6 +3689,60 @@ fn clap_parses_doctor_subcommand_and_positional_args() {
     assert_eq!(cli.positional, vec!["tr-1"]);
 }
 
+#[test]
+fn clap_parses_status_subcommand_flags() {
+    let cli = Cli::try_parse_from(["trudger", "status"]).expect("parse status");
+    assert!(matches!(
+        cli.command,
+        Some(CliCommand::Status {
+            json: false,
+            all: false
+        })
+    ));
+
+    let cli = Cli::try_parse_from(["trudger", "status", "--json", "--all"]).expect("parse status");
+    assert!(matches!(
+        cli.command,
+        Some(CliCommand::Status {
+            json: true,
+            all: true
+        })
+    ));
+}
+
+#[test]
+fn status_rejects_task_and_watch_flags() {
+    let status = || {
+        Some(CliCommand::Status {
+            json: false,
+            all: false,
+        })
+    };
+    let err = run_with_cli(Cli {
+        profile: None,
+        config: None,
+        task: vec!["tr-1".to_string()],
+        positional: Vec::new(),
+        watch: false,
+        command: status(),
+    })
+    .expect_err("expected status task-flag rejection");
+    assert_eq!(err.code, 1);
+    assert!(err.reason.contains("-t/--task"), "got: {}", err.reason);
+
+    let err = run_with_cli(Cli {
+        profile: None,
+        config: None,
+        task: Vec::new(),
+        positional: Vec::new(),
+        watch: true,
+        command: status(),
+    })
+    .expect_err("expected status watch-flag rejection");
+    assert_eq!(err.code, 1);
+    assert!(err.reason.contains("--watch"), "got: {}", err.reason);
+}
+
 #[test]
 fn clap_parses_profile_flag() {
     let cli = Cli::try_parse_from(["trudger", "-p", "review"]).expect("parse profile");
@@ -1503,6 +3758,7 @@ fn doctor_rejects_profile_flag_with_clear_error() {
         config: None,
         task: Vec::new(),
         positional: Vec::new(),
+        watch: false,
         command: Some(CliCommand::Doctor),
     })
     .expect_err("expected doctor profile-flag rejection");
@@ -1521,6 +3777,7 @@ fn doctor_rejects_task_flag_with_clear_error() {
         config: None,
         task: vec!["tr-1".to_string()],
         positional: Vec::new(),
+        watch: false,
         command: Some(CliCommand::Doctor),
     })
     .expect_err("expected doctor task-flag rejection");
@@ -1539,6 +3796,7 @@ fn positional_task_ids_are_rejected_with_migration_hint() {
         config: None,
         task: Vec::new(),
         positional: vec!["tr-1".to_string()],
+        watch: false,
         command: None,
     })
     .expect_err("expected positional task id rejection");
@@ -1553,6 +3811,7 @@ fn wizard_rejects_profile_flag_with_clear_error() {
         config: None,
         task: Vec::new(),
         positional: Vec::new(),
+        watch: false,
         command: Some(CliCommand::Wizard),
     })
     .expect_err("expected wizard profile-flag rejection");
@@ -1657,6 +3916,7 @@ hooks:
         config: Some(config_path.clone()),
         task: Vec::new(),
         positional: Vec::new(),
+        watch: false,
         command: Some(CliCommand::Doctor),
     };
 
@@ -1821,6 +4081,8 @@ fn doctor_cleanup_failure_is_an_error() {
             task_show: "task-show".to_string(),
             task_status: "task-status".to_string(),
             task_update_status: "task-update".to_string(),
+            task_dependencies: None,
+            task_profile: None,
         },
         hooks: Hooks {
             on_completed: "true".to_string(),
@@ -1828,9 +4090,35 @@ fn doctor_cleanup_failure_is_an_error() {
             on_doctor_setup: Some(hook),
             on_notification: None,
             on_notification_scope: None,
+            on_task_error: None,
+            on_skipped: None,
         },
         review_loop_limit: limit(2),
         log_path: Some(temp.path().join("trudger.log")),
+        journal_path: None,
+        agent_timeout: None,
+        agent_review_timeout: None,
+        agent_retry: None,
+        agent_review_retry: None,
+        on_task_error: TaskErrorAction::Quit,
+        watch: WatchConfig::default(),
+        workspaces: Vec::new(),
+        workspace_order: WorkspaceOrder::RoundRobin,
+        concurrency: std::num::NonZeroUsize::MIN,
+        worktree_root: None,
+        log_format: LogFormat::Text,
+        agent_output_dir: None,
+        backend: Backend::Shell,
+        br: BrConfig::default(),
+        github: GithubConfig::default(),
+        markdown: MarkdownConfig::default(),
+        openspec: OpenspecConfig::default(),
+        statuses: StatusMap::default(),
+        prompt_format: PromptFormat::Plain,
+        prompts: PromptPaths::default(),
+        phases: vec![PipelinePhase::Solve],
+        profile_fallback: ProfileFallback::default(),
+        task_routing: TaskRouting::default(),
     };
     let logger = Logger::new(None);
 
@@ -1984,8 +4272,8 @@ fn log_transition_disables_after_write_error() {
     let logger = Logger::new(Some(std::path::PathBuf::from("/dev/full")));
 
     let stderr = capture_stderr(|| {
-        logger.log_transition("first");
-        logger.log_transition("second");
+        logger.log_transition(Transition::new("first"));
+        logger.log_transition(Transition::new("second"));
     });
 
     let lines: Vec<&str> = stderr
@@ -2079,6 +4367,8 @@ fn run_shell_command_noops_when_command_is_empty() {
         agent_phase: None,
         completed: None,
         needs_human: None,
+        skipped: None,
+        declined: None,
         notify_event: None,
         notify_duration_ms: None,
         notify_folder: None,
@@ -2087,6 +4377,11 @@ fn run_shell_command_noops_when_command_is_empty() {
         notify_task_description: None,
         notify_message: None,
         notify_payload_path: None,
+        agent_output: None,
+        review_result_path: None,
+        review_feedback: None,
+        review_follow_ups: None,
+        review_loop: None,
         agent_profile: None,
         agent_invocation_id: None,
     };
@@ -2136,6 +4431,8 @@ fn command_env_truncates_oversized_values_and_warns() {
         agent_phase: None,
         completed: None,
         needs_human: None,
+        skipped: None,
+        declined: None,
         notify_event: None,
         notify_duration_ms: None,
         notify_folder: None,
@@ -2144,6 +4441,11 @@ fn command_env_truncates_oversized_values_and_warns() {
         notify_task_description: None,
         notify_message: None,
         notify_payload_path: None,
+        agent_output: None,
+        review_result_path: None,
+        review_feedback: None,
+        review_follow_ups: None,
+        review_loop: None,
         agent_profile: None,
         agent_invocation_id: None,
     };
@@ -2201,6 +4503,8 @@ fn command_env_truncates_total_trudger_payload_and_warns() {
         agent_phase: None,
         completed: None,
         needs_human: None,
+        skipped: None,
+        declined: None,
         notify_event: None,
         notify_duration_ms: None,
         notify_folder: None,
@@ -2209,6 +4513,11 @@ fn command_env_truncates_total_trudger_payload_and_warns() {
         notify_task_description: None,
         notify_message: None,
         notify_payload_path: None,
+        agent_output: None,
+        review_result_path: None,
+        review_feedback: None,
+        review_follow_ups: None,
+        review_loop: None,
         agent_profile: None,
         agent_invocation_id: None,
     };
@@ -2297,6 +4606,8 @@ fn command_env_truncates_oversized_notify_payload_and_warns() {
         agent_phase: None,
         completed: None,
         needs_human: None,
+        skipped: None,
+        declined: None,
         notify_event: Some("log".to_string()),
         notify_duration_ms: Some("123".to_string()),
         notify_folder: Some("/tmp".to_string()),
@@ -2305,6 +4616,11 @@ fn command_env_truncates_oversized_notify_payload_and_warns() {
         notify_task_description: Some(String::new()),
         notify_message: Some(large_notify),
         notify_payload_path: None,
+        agent_output: None,
+        review_result_path: None,
+        review_feedback: None,
+        review_follow_ups: None,
+        review_loop: None,
         agent_profile: None,
         agent_invocation_id: None,
     };
@@ -2359,6 +4675,8 @@ fn run_shell_command_errors_when_bash_is_missing() {
         agent_phase: None,
         completed: None,
         needs_human: None,
+        skipped: None,
+        declined: None,
         notify_event: None,
         notify_duration_ms: None,
         notify_folder: None,
@@ -2367,6 +4685,11 @@ fn run_shell_command_errors_when_bash_is_missing() {
         notify_task_description: None,
         notify_message: None,
         notify_payload_path: None,
+        agent_output: None,
+        review_result_path: None,
+        review_feedback: None,
+        review_follow_ups: None,
+        review_loop: None,
         agent_profile: None,
         agent_invocation_id: None,
     };
@@ -2405,6 +4728,8 @@ fn validate_config_rejects_missing_and_empty_values() {
             task_show: "task-show".to_string(),
             task_status: "task-status".to_string(),
             task_update_status: "task-update".to_string(),
+            task_dependencies: None,
+            task_profile: None,
         },
         hooks: Hooks {
             on_completed: "hook --done".to_string(),
@@ -2412,9 +4737,35 @@ fn validate_config_rejects_missing_and_empty_values() {
             on_doctor_setup: None,
             on_notification: None,
             on_notification_scope: None,
+            on_task_error: None,
+            on_skipped: None,
         },
         review_loop_limit: limit(2),
         log_path: None,
+        journal_path: None,
+        agent_timeout: None,
+        agent_review_timeout: None,
+        agent_retry: None,
+        agent_review_retry: None,
+        on_task_error: TaskErrorAction::Quit,
+        watch: WatchConfig::default(),
+        workspaces: Vec::new(),
+        workspace_order: WorkspaceOrder::RoundRobin,
+        concurrency: std::num::NonZeroUsize::MIN,
+        worktree_root: None,
+        log_format: LogFormat::Text,
+        agent_output_dir: None,
+        backend: Backend::Shell,
+        br: BrConfig::default(),
+        github: GithubConfig::default(),
+        markdown: MarkdownConfig::default(),
+        openspec: OpenspecConfig::default(),
+        statuses: StatusMap::default(),
+        prompt_format: PromptFormat::Plain,
+        prompts: PromptPaths::default(),
+        phases: vec![PipelinePhase::Solve],
+        profile_fallback: ProfileFallback::default(),
+        task_routing: TaskRouting::default(),
     };
 
     let mut config = base.clone();
@@ -2476,6 +4827,8 @@ fn run_loop_errors_when_next_task_command_missing() {
             task_show: "task-show".to_string(),
             task_status: "task-status".to_string(),
             task_update_status: "task-update".to_string(),
+            task_dependencies: None,
+            task_profile: None,
         },
         hooks: Hooks {
             on_completed: "true".to_string(),
@@ -2483,9 +4836,35 @@ fn run_loop_errors_when_next_task_command_missing() {
             on_doctor_setup: None,
             on_notification: None,
             on_notification_scope: None,
+            on_task_error: None,
+            on_skipped: None,
         },
         review_loop_limit: limit(2),
         log_path: None,
+        journal_path: None,
+        agent_timeout: None,
+        agent_review_timeout: None,
+        agent_retry: None,
+        agent_review_retry: None,
+        on_task_error: TaskErrorAction::Quit,
+        watch: WatchConfig::default(),
+        workspaces: Vec::new(),
+        workspace_order: WorkspaceOrder::RoundRobin,
+        concurrency: std::num::NonZeroUsize::MIN,
+        worktree_root: None,
+        log_format: LogFormat::Text,
+        agent_output_dir: None,
+        backend: Backend::Shell,
+        br: BrConfig::default(),
+        github: GithubConfig::default(),
+        markdown: MarkdownConfig::default(),
+        openspec: OpenspecConfig::default(),
+        statuses: StatusMap::default(),
+        prompt_format: PromptFormat::Plain,
+        prompts: PromptPaths::default(),
+        phases: vec![PipelinePhase::Solve],
+        profile_fallback: ProfileFallback::default(),
+        task_routing: TaskRouting::default(),
     };
 
     let mut state = RuntimeState {
@@ -2497,18 +4876,34 @@ fn run_loop_errors_when_next_task_command_missing() {
             .unwrap_or_default(),
         prompt_trudge: "Task context".to_string(),
         prompt_review: "Review context".to_string(),
+        phase_prompts: HashMap::new(),
         logger: Logger::new(None),
         tmux: TmuxState::disabled(),
         interrupt_flag: Arc::new(AtomicBool::new(false)),
         manual_tasks: Vec::new(),
         completed_tasks: Vec::new(),
         needs_human_tasks: Vec::new(),
+        skipped_tasks: Vec::new(),
+        declined_tasks: Vec::new(),
+        workspace: None,
+        cwd: None,
         current_task_id: None,
         current_task_show: None,
         current_task_status: None,
         run_started_at: std::time::Instant::now(),
         current_task_started_at: None,
         run_exit_code: 0,
+        agent_output_dir: None,
+        current_agent_output: None,
+        current_review_result: None,
+        current_review_feedback: None,
+        current_review_follow_ups: Vec::new(),
+        current_review_loop: None,
+        agent_failures: 0,
+        routed_from: None,
+        agent_context: Default::default(),
+        resume: None,
+        task_backend: Arc::new(ShellBackend),
     };
 
     let err = run_loop(&mut state).expect_err("expected idle");
@@ -2539,6 +4934,8 @@ fn run_loop_propagates_next_task_exit_code_other_than_1() {
             task_show: "task-show".to_string(),
             task_status: "task-status".to_string(),
             task_update_status: "task-update".to_string(),
+            task_dependencies: None,
+            task_profile: None,
         },
         hooks: Hooks {
             on_completed: "true".to_string(),
@@ -2546,9 +4943,35 @@ fn run_loop_propagates_next_task_exit_code_other_than_1() {
             on_doctor_setup: None,
             on_notification: None,
             on_notification_scope: None,
+            on_task_error: None,
+            on_skipped: None,
         },
         review_loop_limit: limit(2),
         log_path: None,
+        journal_path: None,
+        agent_timeout: None,
+        agent_review_timeout: None,
+        agent_retry: None,
+        agent_review_retry: None,
+        on_task_error: TaskErrorAction::Quit,
+        watch: WatchConfig::default(),
+        workspaces: Vec::new(),
+        workspace_order: WorkspaceOrder::RoundRobin,
+        concurrency: std::num::NonZeroUsize::MIN,
+        worktree_root: None,
+        log_format: LogFormat::Text,
+        agent_output_dir: None,
+        backend: Backend::Shell,
+        br: BrConfig::default(),
+        github: GithubConfig::default(),
+        markdown: MarkdownConfig::default(),
+        openspec: OpenspecConfig::default(),
+        statuses: StatusMap::default(),
+        prompt_format: PromptFormat::Plain,
+        prompts: PromptPaths::default(),
+        phases: vec![PipelinePhase::Solve],
+        profile_fallback: ProfileFallback::default(),
+        task_routing: TaskRouting::default(),
     };
 
     let mut state = RuntimeState {
@@ -2560,18 +4983,34 @@ fn run_loop_propagates_next_task_exit_code_other_than_1() {
             .unwrap_or_default(),
         prompt_trudge: "Task context".to_string(),
         prompt_review: "Review context".to_string(),
+        phase_prompts: HashMap::new(),
         logger: Logger::new(None),
         tmux: TmuxState::disabled(),
         interrupt_flag: Arc::new(AtomicBool::new(false)),
         manual_tasks: Vec::new(),
         completed_tasks: Vec::new(),
         needs_human_tasks: Vec::new(),
+        skipped_tasks: Vec::new(),
+        declined_tasks: Vec::new(),
+        workspace: None,
+        cwd: None,
         current_task_id: None,
         current_task_show: None,
         current_task_status: None,
         run_started_at: std::time::Instant::now(),
         current_task_started_at: None,
         run_exit_code: 0,
+        agent_output_dir: None,
+        current_agent_output: None,
+        current_review_result: None,
+        current_review_feedback: None,
+        current_review_follow_ups: Vec::new(),
+        current_review_loop: None,
+        agent_failures: 0,
+        routed_from: None,
+        agent_context: Default::default(),
+        resume: None,
+        task_backend: Arc::new(ShellBackend),
     };
 
     let err = run_loop(&mut state).expect_err("expected next_task failure");
@@ -2604,6 +5043,8 @@ fn run_loop_errors_when_selected_task_has_empty_status() {
             task_show: "task-show".to_string(),
             task_status: "task-status".to_string(),
             task_update_status: "task-update".to_string(),
+            task_dependencies: None,
+            task_profile: None,
         },
         hooks: Hooks {
             on_completed: "true".to_string(),
@@ -2611,9 +5052,35 @@ fn run_loop_errors_when_selected_task_has_empty_status() {
             on_doctor_setup: None,
             on_notification: None,
             on_notification_scope: None,
+            on_task_error: None,
+            on_skipped: None,
         },
         review_loop_limit: limit(2),
         log_path: None,
+        journal_path: None,
+        agent_timeout: None,
+        agent_review_timeout: None,
+        agent_retry: None,
+        agent_review_retry: None,
+        on_task_error: TaskErrorAction::Quit,
+        watch: WatchConfig::default(),
+        workspaces: Vec::new(),
+        workspace_order: WorkspaceOrder::RoundRobin,
+        concurrency: std::num::NonZeroUsize::MIN,
+        worktree_root: None,
+        log_format: LogFormat::Text,
+        agent_output_dir: None,
+        backend: Backend::Shell,
+        br: BrConfig::default(),
+        github: GithubConfig::default(),
+        markdown: MarkdownConfig::default(),
+        openspec: OpenspecConfig::default(),
+        statuses: StatusMap::default(),
+        prompt_format: PromptFormat::Plain,
+        prompts: PromptPaths::default(),
+        phases: vec![PipelinePhase::Solve],
+        profile_fallback: ProfileFallback::default(),
+        task_routing: TaskRouting::default(),
     };
 
     let mut state = RuntimeState {
@@ -2625,18 +5092,34 @@ fn run_loop_errors_when_selected_task_has_empty_status() {
             .unwrap_or_default(),
         prompt_trudge: "Task context".to_string(),
         prompt_review: "Review context".to_string(),
+        phase_prompts: HashMap::new(),
         logger: Logger::new(None),
         tmux: TmuxState::disabled(),
         interrupt_flag: Arc::new(AtomicBool::new(false)),
         manual_tasks: Vec::new(),
         completed_tasks: Vec::new(),
         needs_human_tasks: Vec::new(),
+        skipped_tasks: Vec::new(),
+        declined_tasks: Vec::new(),
+        workspace: None,
+        cwd: None,
         current_task_id: None,
         current_task_show: None,
         current_task_status: None,
         run_started_at: std::time::Instant::now(),
         current_task_started_at: None,
         run_exit_code: 0,
+        agent_output_dir: None,
+        current_agent_output: None,
+        current_review_result: None,
+        current_review_feedback: None,
+        current_review_follow_ups: Vec::new(),
+        current_review_loop: None,
+        agent_failures: 0,
+        routed_from: None,
+        agent_context: Default::default(),
+        resume: None,
+        task_backend: Arc::new(ShellBackend),
     };
 
     let err = run_loop(&mut state).expect_err("expected missing status");
@@ -2681,6 +5164,8 @@ fn run_loop_errors_when_update_in_progress_fails() {
             task_show: "task-show".to_string(),
             task_status: "printf 'open\\n'".to_string(),
             task_update_status: "exit 1".to_string(),
+            task_dependencies: None,
+            task_profile: None,
         },
         hooks: Hooks {
             on_completed: "true".to_string(),
@@ -2688,9 +5173,35 @@ fn run_loop_errors_when_update_in_progress_fails() {
             on_doctor_setup: None,
             on_notification: None,
             on_notification_scope: None,
+            on_task_error: None,
+            on_skipped: None,
         },
         review_loop_limit: limit(2),
         log_path: None,
+        journal_path: None,
+        agent_timeout: None,
+        agent_review_timeout: None,
+        agent_retry: None,
+        agent_review_retry: None,
+        on_task_error: TaskErrorAction::Quit,
+        watch: WatchConfig::default(),
+        workspaces: Vec::new(),
+        workspace_order: WorkspaceOrder::RoundRobin,
+        concurrency: std::num::NonZeroUsize::MIN,
+        worktree_root: None,
+        log_format: LogFormat::Text,
+        agent_output_dir: None,
+        backend: Backend::Shell,
+        br: BrConfig::default(),
+        github: GithubConfig::default(),
+        markdown: MarkdownConfig::default(),
+        openspec: OpenspecConfig::default(),
+        statuses: StatusMap::default(),
+        prompt_format: PromptFormat::Plain,
+        prompts: PromptPaths::default(),
+        phases: vec![PipelinePhase::Solve],
+        profile_fallback: ProfileFallback::default(),
+        task_routing: TaskRouting::default(),
     };
 
     let mut state = RuntimeState {
@@ -2702,18 +5213,34 @@ fn run_loop_errors_when_update_in_progress_fails() {
             .unwrap_or_default(),
         prompt_trudge: "Task context".to_string(),
         prompt_review: "Review context".to_string(),
+        phase_prompts: HashMap::new(),
         logger: Logger::new(None),
         tmux: TmuxState::disabled(),
         interrupt_flag: Arc::new(AtomicBool::new(false)),
         manual_tasks: Vec::new(),
         completed_tasks: Vec::new(),
         needs_human_tasks: Vec::new(),
+        skipped_tasks: Vec::new(),
+        declined_tasks: Vec::new(),
+        workspace: None,
+        cwd: None,
         current_task_id: None,
         current_task_show: None,
         current_task_status: None,
         run_started_at: std::time::Instant::now(),
         current_task_started_at: None,
         run_exit_code: 0,
+        agent_output_dir: None,
+        current_agent_output: None,
+        current_review_result: None,
+        current_review_feedback: None,
+        current_review_follow_ups: Vec::new(),
+        current_review_loop: None,
+        agent_failures: 0,
+        routed_from: None,
+        agent_context: Default::default(),
+        resume: None,
+        task_backend: Arc::new(ShellBackend),
     };
 
     let err = run_loop(&mut state).expect_err("expected update failure");
@@ -2734,6 +5261,8 @@ fn run_loop_errors_when_task_show_fails_during_solve() {
             task_show: "exit 1".to_string(),
             task_status: "printf 'open\\n'".to_string(),
             task_update_status: "true".to_string(),
+            task_dependencies: None,
+            task_profile: None,
         },
         hooks: Hooks {
             on_completed: "true".to_string(),
@@ -2741,9 +5270,35 @@ fn run_loop_errors_when_task_show_fails_during_solve() {
             on_doctor_setup: None,
             on_notification: None,
             on_notification_scope: None,
+            on_task_error: None,
+            on_skipped: None,
         },
         review_loop_limit: limit(2),
         log_path: None,
+        journal_path: None,
+        agent_timeout: None,
+        agent_review_timeout: None,
+        agent_retry: None,
+        agent_review_retry: None,
+        on_task_error: TaskErrorAction::Quit,
+        watch: WatchConfig::default(),
+        workspaces: Vec::new(),
+        workspace_order: WorkspaceOrder::RoundRobin,
+        concurrency: std::num::NonZeroUsize::MIN,
+        worktree_root: None,
+        log_format: LogFormat::Text,
+        agent_output_dir: None,
+        backend: Backend::Shell,
+        br: BrConfig::default(),
+        github: GithubConfig::default(),
+        markdown: MarkdownConfig::default(),
+        openspec: OpenspecConfig::default(),
+        statuses: StatusMap::default(),
+        prompt_format: PromptFormat::Plain,
+        prompts: PromptPaths::default(),
+        phases: vec![PipelinePhase::Solve],
+        profile_fallback: ProfileFallback::default(),
+        task_routing: TaskRouting::default(),
     };
 
     let mut state = RuntimeState {
@@ -2755,18 +5310,34 @@ fn run_loop_errors_when_task_show_fails_during_solve() {
             .unwrap_or_default(),
         prompt_trudge: "Task context".to_string(),
         prompt_review: "Review context".to_string(),
+        phase_prompts: HashMap::new(),
         logger: Logger::new(None),
         tmux: TmuxState::disabled(),
         interrupt_flag: Arc::new(AtomicBool::new(false)),
         manual_tasks: Vec::new(),
         completed_tasks: Vec::new(),
         needs_human_tasks: Vec::new(),
+        skipped_tasks: Vec::new(),
+        declined_tasks: Vec::new(),
+        workspace: None,
+        cwd: None,
         current_task_id: None,
         current_task_show: None,
         current_task_status: None,
         run_started_at: std::time::Instant::now(),
         current_task_started_at: None,
         run_exit_code: 0,
+        agent_output_dir: None,
+        current_agent_output: None,
+        current_review_result: None,
+        current_review_feedback: None,
+        current_review_follow_ups: Vec::new(),
+        current_review_loop: None,
+        agent_failures: 0,
+        routed_from: None,
+        agent_context: Default::default(),
+        resume: None,
+        task_backend: Arc::new(ShellBackend),
     };
 
     let err = run_loop(&mut state).expect_err("expected show failure");
@@ -2787,6 +5358,8 @@ fn run_loop_errors_when_agent_solve_fails() {
             task_show: "printf 'SHOW\\n'".to_string(),
             task_status: "printf 'open\\n'".to_string(),
             task_update_status: "true".to_string(),
+            task_dependencies: None,
+            task_profile: None,
         },
         hooks: Hooks {
             on_completed: "true".to_string(),
@@ -2794,9 +5367,35 @@ fn run_loop_errors_when_agent_solve_fails() {
             on_doctor_setup: None,
             on_notification: None,
             on_notification_scope: None,
+            on_task_error: None,
+            on_skipped: None,
         },
         review_loop_limit: limit(2),
         log_path: None,
+        journal_path: None,
+        agent_timeout: None,
+        agent_review_timeout: None,
+        agent_retry: None,
+        agent_review_retry: None,
+        on_task_error: TaskErrorAction::Quit,
+        watch: WatchConfig::default(),
+        workspaces: Vec::new(),
+        workspace_order: WorkspaceOrder::RoundRobin,
+        concurrency: std::num::NonZeroUsize::MIN,
+        worktree_root: None,
+        log_format: LogFormat::Text,
+        agent_output_dir: None,
+        backend: Backend::Shell,
+        br: BrConfig::default(),
+        github: GithubConfig::default(),
+        markdown: MarkdownConfig::default(),
+        openspec: OpenspecConfig::default(),
+        statuses: StatusMap::default(),
+        prompt_format: PromptFormat::Plain,
+        prompts: PromptPaths::default(),
+        phases: vec![PipelinePhase::Solve],
+        profile_fallback: ProfileFallback::default(),
+        task_routing: TaskRouting::default(),
     };
 
     let mut state = RuntimeState {
@@ -2808,18 +5407,34 @@ fn run_loop_errors_when_agent_solve_fails() {
             .unwrap_or_default(),
         prompt_trudge: "Task context".to_string(),
         prompt_review: "Review context".to_string(),
+        phase_prompts: HashMap::new(),
         logger: Logger::new(None),
         tmux: TmuxState::disabled(),
         interrupt_flag: Arc::new(AtomicBool::new(false)),
         manual_tasks: Vec::new(),
         completed_tasks: Vec::new(),
         needs_human_tasks: Vec::new(),
+        skipped_tasks: Vec::new(),
+        declined_tasks: Vec::new(),
+        workspace: None,
+        cwd: None,
         current_task_id: None,
         current_task_show: None,
         current_task_status: None,
         run_started_at: std::time::Instant::now(),
         current_task_started_at: None,
         run_exit_code: 0,
+        agent_output_dir: None,
+        current_agent_output: None,
+        current_review_result: None,
+        current_review_feedback: None,
+        current_review_follow_ups: Vec::new(),
+        current_review_loop: None,
+        agent_failures: 0,
+        routed_from: None,
+        agent_context: Default::default(),
+        resume: None,
+        task_backend: Arc::new(ShellBackend),
     };
 
     let err = run_loop(&mut state).expect_err("expected solve failure");
@@ -2855,6 +5470,8 @@ fn run_loop_errors_when_task_show_fails_during_review() {
             task_show: task_show.display().to_string(),
             task_status: "printf 'open\\n'".to_string(),
             task_update_status: "true".to_string(),
+            task_dependencies: None,
+            task_profile: None,
         },
         hooks: Hooks {
             on_completed: "true".to_string(),
@@ -2862,9 +5479,35 @@ fn run_loop_errors_when_task_show_fails_during_review() {
             on_doctor_setup: None,
             on_notification: None,
             on_notification_scope: None,
+            on_task_error: None,
+            on_skipped: None,
         },
         review_loop_limit: limit(2),
         log_path: None,
+        journal_path: None,
+        agent_timeout: None,
+        agent_review_timeout: None,
+        agent_retry: None,
+        agent_review_retry: None,
+        on_task_error: TaskErrorAction::Quit,
+        watch: WatchConfig::default(),
+        workspaces: Vec::new(),
+        workspace_order: WorkspaceOrder::RoundRobin,
+        concurrency: std::num::NonZeroUsize::MIN,
+        worktree_root: None,
+        log_format: LogFormat::Text,
+        agent_output_dir: None,
+        backend: Backend::Shell,
+        br: BrConfig::default(),
+        github: GithubConfig::default(),
+        markdown: MarkdownConfig::default(),
+        openspec: OpenspecConfig::default(),
+        statuses: StatusMap::default(),
+        prompt_format: PromptFormat::Plain,
+        prompts: PromptPaths::default(),
+        phases: vec![PipelinePhase::Solve],
+        profile_fallback: ProfileFallback::default(),
+        task_routing: TaskRouting::default(),
     };
 
     let mut state = RuntimeState {
@@ -2876,18 +5519,34 @@ fn run_loop_errors_when_task_show_fails_during_review() {
             .unwrap_or_default(),
         prompt_trudge: "Task context".to_string(),
         prompt_review: "Review context".to_string(),
+        phase_prompts: HashMap::new(),
         logger: Logger::new(None),
         tmux: TmuxState::disabled(),
         interrupt_flag: Arc::new(AtomicBool::new(false)),
         manual_tasks: Vec::new(),
         completed_tasks: Vec::new(),
         needs_human_tasks: Vec::new(),
+        skipped_tasks: Vec::new(),
+        declined_tasks: Vec::new(),
+        workspace: None,
+        cwd: None,
         current_task_id: None,
         current_task_show: None,
         current_task_status: None,
         run_started_at: std::time::Instant::now(),
         current_task_started_at: None,
         run_exit_code: 0,
+        agent_output_dir: None,
+        current_agent_output: None,
+        current_review_result: None,
+        current_review_feedback: None,
+        current_review_follow_ups: Vec::new(),
+        current_review_loop: None,
+        agent_failures: 0,
+        routed_from: None,
+        agent_context: Default::default(),
+        resume: None,
+        task_backend: Arc::new(ShellBackend),
     };
 
     let err = run_loop(&mut state).expect_err("expected review show failure");
@@ -2908,6 +5567,8 @@ fn run_loop_errors_when_agent_review_fails() {
             task_show: "printf 'SHOW\\n'".to_string(),
             task_status: "printf 'open\\n'".to_string(),
             task_update_status: "true".to_string(),
+            task_dependencies: None,
+            task_profile: None,
         },
         hooks: Hooks {
             on_completed: "true".to_string(),
@@ -2915,9 +5576,35 @@ fn run_loop_errors_when_agent_review_fails() {
             on_doctor_setup: None,
             on_notification: None,
             on_notification_scope: None,
+            on_task_error: None,
+            on_skipped: None,
         },
         review_loop_limit: limit(2),
         log_path: None,
+        journal_path: None,
+        agent_timeout: None,
+        agent_review_timeout: None,
+        agent_retry: None,
+        agent_review_retry: None,
+        on_task_error: TaskErrorAction::Quit,
+        watch: WatchConfig::default(),
+        workspaces: Vec::new(),
+        workspace_order: WorkspaceOrder::RoundRobin,
+        concurrency: std::num::NonZeroUsize::MIN,
+        worktree_root: None,
+        log_format: LogFormat::Text,
+        agent_output_dir: None,
+        backend: Backend::Shell,
+        br: BrConfig::default(),
+        github: GithubConfig::default(),
+        markdown: MarkdownConfig::default(),
+        openspec: OpenspecConfig::default(),
+        statuses: StatusMap::default(),
+        prompt_format: PromptFormat::Plain,
+        prompts: PromptPaths::default(),
+        phases: vec![PipelinePhase::Solve],
+        profile_fallback: ProfileFallback::default(),
+        task_routing: TaskRouting::default(),
     };
 
     let mut state = RuntimeState {
@@ -2929,18 +5616,34 @@ fn run_loop_errors_when_agent_review_fails() {
             .unwrap_or_default(),
         prompt_trudge: "Task context".to_string(),
         prompt_review: "Review context".to_string(),
+        phase_prompts: HashMap::new(),
         logger: Logger::new(None),
         tmux: TmuxState::disabled(),
         interrupt_flag: Arc::new(AtomicBool::new(false)),
         manual_tasks: Vec::new(),
         completed_tasks: Vec::new(),
         needs_human_tasks: Vec::new(),
+        skipped_tasks: Vec::new(),
+        declined_tasks: Vec::new(),
+        workspace: None,
+        cwd: None,
         current_task_id: None,
         current_task_show: None,
         current_task_status: None,
         run_started_at: std::time::Instant::now(),
         current_task_started_at: None,
         run_exit_code: 0,
+        agent_output_dir: None,
+        current_agent_output: None,
+        current_review_result: None,
+        current_review_feedback: None,
+        current_review_follow_ups: Vec::new(),
+        current_review_loop: None,
+        agent_failures: 0,
+        routed_from: None,
+        agent_context: Default::default(),
+        resume: None,
+        task_backend: Arc::new(ShellBackend),
     };
 
     let err = run_loop(&mut state).expect_err("expected review failure");
@@ -2973,6 +5676,8 @@ fn run_loop_errors_when_on_completed_hook_fails() {
             task_show: "task-show".to_string(),
             task_status: "task-status".to_string(),
             task_update_status: "true".to_string(),
+            task_dependencies: None,
+            task_profile: None,
         },
         hooks: Hooks {
             on_completed: "exit 1".to_string(),
@@ -2980,9 +5685,35 @@ fn run_loop_errors_when_on_completed_hook_fails() {
             on_doctor_setup: None,
             on_notification: None,
             on_notification_scope: None,
+            on_task_error: None,
+            on_skipped: None,
         },
         review_loop_limit: limit(2),
         log_path: None,
+        journal_path: None,
+        agent_timeout: None,
+        agent_review_timeout: None,
+        agent_retry: None,
+        agent_review_retry: None,
+        on_task_error: TaskErrorAction::Quit,
+        watch: WatchConfig::default(),
+        workspaces: Vec::new(),
+        workspace_order: WorkspaceOrder::RoundRobin,
+        concurrency: std::num::NonZeroUsize::MIN,
+        worktree_root: None,
+        log_format: LogFormat::Text,
+        agent_output_dir: None,
+        backend: Backend::Shell,
+        br: BrConfig::default(),
+        github: GithubConfig::default(),
+        markdown: MarkdownConfig::default(),
+        openspec: OpenspecConfig::default(),
+        statuses: StatusMap::default(),
+        prompt_format: PromptFormat::Plain,
+        prompts: PromptPaths::default(),
+        phases: vec![PipelinePhase::Solve],
+        profile_fallback: ProfileFallback::default(),
+        task_routing: TaskRouting::default(),
     };
 
     let mut state = RuntimeState {
@@ -2994,18 +5725,34 @@ fn run_loop_errors_when_on_completed_hook_fails() {
             .unwrap_or_default(),
         prompt_trudge: "Task context".to_string(),
         prompt_review: "Review context".to_string(),
+        phase_prompts: HashMap::new(),
         logger: Logger::new(None),
         tmux: TmuxState::disabled(),
         interrupt_flag: Arc::new(AtomicBool::new(false)),
         manual_tasks: Vec::new(),
         completed_tasks: Vec::new(),
         needs_human_tasks: Vec::new(),
+        skipped_tasks: Vec::new(),
+        declined_tasks: Vec::new(),
+        workspace: None,
+        cwd: None,
         current_task_id: None,
         current_task_show: None,
         current_task_status: None,
         run_started_at: std::time::Instant::now(),
         current_task_started_at: None,
         run_exit_code: 0,
+        agent_output_dir: None,
+        current_agent_output: None,
+        current_review_result: None,
+        current_review_feedback: None,
+        current_review_follow_ups: Vec::new(),
+        current_review_loop: None,
+        agent_failures: 0,
+        routed_from: None,
+        agent_context: Default::default(),
+        resume: None,
+        task_backend: Arc::new(ShellBackend),
     };
 
     let err = run_loop(&mut state).expect_err("expected hook failure");
@@ -3038,6 +5785,8 @@ fn run_loop_errors_when_on_requires_human_hook_fails_on_blocked_status() {
             task_show: "task-show".to_string(),
             task_status: "task-status".to_string(),
             task_update_status: "true".to_string(),
+            task_dependencies: None,
+            task_profile: None,
         },
         hooks: Hooks {
             on_completed: "true".to_string(),
@@ -3045,9 +5794,35 @@ fn run_loop_errors_when_on_requires_human_hook_fails_on_blocked_status() {
             on_doctor_setup: None,
             on_notification: None,
             on_notification_scope: None,
+            on_task_error: None,
+            on_skipped: None,
         },
         review_loop_limit: limit(2),
         log_path: None,
+        journal_path: None,
+        agent_timeout: None,
+        agent_review_timeout: None,
+        agent_retry: None,
+        agent_review_retry: None,
+        on_task_error: TaskErrorAction::Quit,
+        watch: WatchConfig::default(),
+        workspaces: Vec::new(),
+        workspace_order: WorkspaceOrder::RoundRobin,
+        concurrency: std::num::NonZeroUsize::MIN,
+        worktree_root: None,
+        log_format: LogFormat::Text,
+        agent_output_dir: None,
+        backend: Backend::Shell,
+        br: BrConfig::default(),
+        github: GithubConfig::default(),
+        markdown: MarkdownConfig::default(),
+        openspec: OpenspecConfig::default(),
+        statuses: StatusMap::default(),
+        prompt_format: PromptFormat::Plain,
+        prompts: PromptPaths::default(),
+        phases: vec![PipelinePhase::Solve],
+        profile_fallback: ProfileFallback::default(),
+        task_routing: TaskRouting::default(),
     };
 
     let mut state = RuntimeState {
@@ -3059,18 +5834,34 @@ fn run_loop_errors_when_on_requires_human_hook_fails_on_blocked_status() {
             .unwrap_or_default(),
         prompt_trudge: "Task context".to_string(),
         prompt_review: "Review context".to_string(),
+        phase_prompts: HashMap::new(),
         logger: Logger::new(None),
         tmux: TmuxState::disabled(),
         interrupt_flag: Arc::new(AtomicBool::new(false)),
         manual_tasks: Vec::new(),
         completed_tasks: Vec::new(),
         needs_human_tasks: Vec::new(),
+        skipped_tasks: Vec::new(),
+        declined_tasks: Vec::new(),
+        workspace: None,
+        cwd: None,
         current_task_id: None,
         current_task_show: None,
         current_task_status: None,
         run_started_at: std::time::Instant::now(),
         current_task_started_at: None,
         run_exit_code: 0,
+        agent_output_dir: None,
+        current_agent_output: None,
+        current_review_result: None,
+        current_review_feedback: None,
+        current_review_follow_ups: Vec::new(),
+        current_review_loop: None,
+        agent_failures: 0,
+        routed_from: None,
+        agent_context: Default::default(),
+        resume: None,
+        task_backend: Arc::new(ShellBackend),
     };
 
     let err = run_loop(&mut state).expect_err("expected hook failure");
@@ -3105,6 +5896,8 @@ fn run_loop_errors_when_blocked_status_update_fails_after_exhausting_review_loop
             task_update_status:
                 "if [[ \"${TRUDGER_TARGET_STATUS:-}\" == \"blocked\" ]]; then exit 1; fi; exit 0"
                     .to_string(),
+            task_dependencies: None,
+            task_profile: None,
         },
         hooks: Hooks {
             on_completed: "true".to_string(),
@@ -3112,9 +5905,35 @@ fn run_loop_errors_when_blocked_status_update_fails_after_exhausting_review_loop
             on_doctor_setup: None,
             on_notification: None,
             on_notification_scope: None,
+            on_task_error: None,
+            on_skipped: None,
         },
         review_loop_limit: limit(1),
         log_path: None,
+        journal_path: None,
+        agent_timeout: None,
+        agent_review_timeout: None,
+        agent_retry: None,
+        agent_review_retry: None,
+        on_task_error: TaskErrorAction::Quit,
+        watch: WatchConfig::default(),
+        workspaces: Vec::new(),
+        workspace_order: WorkspaceOrder::RoundRobin,
+        concurrency: std::num::NonZeroUsize::MIN,
+        worktree_root: None,
+        log_format: LogFormat::Text,
+        agent_output_dir: None,
+        backend: Backend::Shell,
+        br: BrConfig::default(),
+        github: GithubConfig::default(),
+        markdown: MarkdownConfig::default(),
+        openspec: OpenspecConfig::default(),
+        statuses: StatusMap::default(),
+        prompt_format: PromptFormat::Plain,
+        prompts: PromptPaths::default(),
+        phases: vec![PipelinePhase::Solve],
+        profile_fallback: ProfileFallback::default(),
+        task_routing: TaskRouting::default(),
     };
 
     let mut state = RuntimeState {
@@ -3126,18 +5945,34 @@ fn run_loop_errors_when_blocked_status_update_fails_after_exhausting_review_loop
             .unwrap_or_default(),
         prompt_trudge: "Task context".to_string(),
         prompt_review: "Review context".to_string(),
+        phase_prompts: HashMap::new(),
         logger: Logger::new(None),
         tmux: TmuxState::disabled(),
         interrupt_flag: Arc::new(AtomicBool::new(false)),
         manual_tasks: Vec::new(),
         completed_tasks: Vec::new(),
         needs_human_tasks: Vec::new(),
+        skipped_tasks: Vec::new(),
+        declined_tasks: Vec::new(),
+        workspace: None,
+        cwd: None,
         current_task_id: None,
         current_task_show: None,
         current_task_status: None,
         run_started_at: std::time::Instant::now(),
         current_task_started_at: None,
         run_exit_code: 0,
+        agent_output_dir: None,
+        current_agent_output: None,
+        current_review_result: None,
+        current_review_feedback: None,
+        current_review_follow_ups: Vec::new(),
+        current_review_loop: None,
+        agent_failures: 0,
+        routed_from: None,
+        agent_context: Default::default(),
+        resume: None,
+        task_backend: Arc::new(ShellBackend),
     };
 
     let err = run_loop(&mut state).expect_err("expected blocked update failure");
@@ -3170,6 +6005,8 @@ fn run_loop_errors_when_on_requires_human_hook_fails_after_exhausting_review_loo
             task_show: "task-show".to_string(),
             task_status: "task-status".to_string(),
             task_update_status: "true".to_string(),
+            task_dependencies: None,
+            task_profile: None,
         },
         hooks: Hooks {
             on_completed: "true".to_string(),
@@ -3177,9 +6014,35 @@ fn run_loop_errors_when_on_requires_human_hook_fails_after_exhausting_review_loo
             on_doctor_setup: None,
             on_notification: None,
             on_notification_scope: None,
+            on_task_error: None,
+            on_skipped: None,
         },
         review_loop_limit: limit(1),
         log_path: None,
+        journal_path: None,
+        agent_timeout: None,
+        agent_review_timeout: None,
+        agent_retry: None,
+        agent_review_retry: None,
+        on_task_error: TaskErrorAction::Quit,
+        watch: WatchConfig::default(),
+        workspaces: Vec::new(),
+        workspace_order: WorkspaceOrder::RoundRobin,
+        concurrency: std::num::NonZeroUsize::MIN,
+        worktree_root: None,
+        log_format: LogFormat::Text,
+        agent_output_dir: None,
+        backend: Backend::Shell,
+        br: BrConfig::default(),
+        github: GithubConfig::default(),
+        markdown: MarkdownConfig::default(),
+        openspec: OpenspecConfig::default(),
+        statuses: StatusMap::default(),
+        prompt_format: PromptFormat::Plain,
+        prompts: PromptPaths::default(),
+        phases: vec![PipelinePhase::Solve],
+        profile_fallback: ProfileFallback::default(),
+        task_routing: TaskRouting::default(),
     };
 
     let mut state = RuntimeState {
@@ -3191,18 +6054,34 @@ fn run_loop_errors_when_on_requires_human_hook_fails_after_exhausting_review_loo
             .unwrap_or_default(),
         prompt_trudge: "Task context".to_string(),
         prompt_review: "Review context".to_string(),
+        phase_prompts: HashMap::new(),
         logger: Logger::new(None),
         tmux: TmuxState::disabled(),
         interrupt_flag: Arc::new(AtomicBool::new(false)),
         manual_tasks: Vec::new(),
         completed_tasks: Vec::new(),
         needs_human_tasks: Vec::new(),
+        skipped_tasks: Vec::new(),
+        declined_tasks: Vec::new(),
+        workspace: None,
+        cwd: None,
         current_task_id: None,
         current_task_show: None,
         current_task_status: None,
         run_started_at: std::time::Instant::now(),
         current_task_started_at: None,
         run_exit_code: 0,
+        agent_output_dir: None,
+        current_agent_output: None,
+        current_review_result: None,
+        current_review_feedback: None,
+        current_review_follow_ups: Vec::new(),
+        current_review_loop: None,
+        agent_failures: 0,
+        routed_from: None,
+        agent_context: Default::default(),
+        resume: None,
+        task_backend: Arc::new(ShellBackend),
     };
 
     let err = run_loop(&mut state).expect_err("expected hook failure");
@@ -3225,6 +6104,8 @@ fn reset_task_on_exit_is_noop_for_ok_or_missing_task_id() {
                 task_show: "task-show".to_string(),
                 task_status: "task-status".to_string(),
                 task_update_status: "task-update".to_string(),
+                task_dependencies: None,
+                task_profile: None,
             },
             hooks: Hooks {
                 on_completed: "true".to_string(),
@@ -3232,9 +6113,35 @@ fn reset_task_on_exit_is_noop_for_ok_or_missing_task_id() {
                 on_doctor_setup: None,
                 on_notification: None,
                 on_notification_scope: None,
+                on_task_error: None,
+                on_skipped: None,
             },
             review_loop_limit: limit(2),
             log_path: None,
+            journal_path: None,
+            agent_timeout: None,
+            agent_review_timeout: None,
+            agent_retry: None,
+            agent_review_retry: None,
+            on_task_error: TaskErrorAction::Quit,
+            watch: WatchConfig::default(),
+            workspaces: Vec::new(),
+            workspace_order: WorkspaceOrder::RoundRobin,
+            concurrency: std::num::NonZeroUsize::MIN,
+            worktree_root: None,
+            log_format: LogFormat::Text,
+            agent_output_dir: None,
+            backend: Backend::Shell,
+            br: BrConfig::default(),
+            github: GithubConfig::default(),
+            markdown: MarkdownConfig::default(),
+            openspec: OpenspecConfig::default(),
+            statuses: StatusMap::default(),
+            prompt_format: PromptFormat::Plain,
+            prompts: PromptPaths::default(),
+            phases: vec![PipelinePhase::Solve],
+            profile_fallback: ProfileFallback::default(),
+            task_routing: TaskRouting::default(),
         },
         config_path: temp.path().join("trudger.yml"),
         invocation_folder: std::env::current_dir()
@@ -3243,18 +6150,34 @@ fn reset_task_on_exit_is_noop_for_ok_or_missing_task_id() {
             .unwrap_or_default(),
         prompt_trudge: "Task context".to_string(),
         prompt_review: "Review context".to_string(),
+        phase_prompts: HashMap::new(),
         logger,
         tmux: TmuxState::disabled(),
         interrupt_flag: Arc::new(AtomicBool::new(false)),
         manual_tasks: Vec::new(),
         completed_tasks: Vec::new(),
         needs_human_tasks: Vec::new(),
+        skipped_tasks: Vec::new(),
+        declined_tasks: Vec::new(),
+        workspace: None,
+        cwd: None,
         current_task_id: None,
         current_task_show: None,
         current_task_status: None,
         run_started_at: std::time::Instant::now(),
         current_task_started_at: None,
         run_exit_code: 0,
+        agent_output_dir: None,
+        current_agent_output: None,
+        current_review_result: None,
+        current_review_feedback: None,
+        current_review_follow_ups: Vec::new(),
+        current_review_loop: None,
+        agent_failures: 0,
+        routed_from: None,
+        agent_context: Default::default(),
+        resume: None,
+        task_backend: Arc::new(ShellBackend),
     };
 
     reset_task_on_exit(&state, &Ok(()));
@@ -3282,6 +6205,8 @@ fn reset_task_on_exit_logs_failure_when_reset_task_fails() {
                 task_show: "task-show".to_string(),
                 task_status: "printf 'in_progress\\n'".to_string(),
                 task_update_status: "task-update".to_string(),
+                task_dependencies: None,
+                task_profile: None,
             },
             hooks: Hooks {
                 on_completed: "true".to_string(),
@@ -3289,9 +6214,35 @@ fn reset_task_on_exit_logs_failure_when_reset_task_fails() {
                 on_doctor_setup: None,
                 on_notification: None,
                 on_notification_scope: None,
+                on_task_error: None,
+                on_skipped: None,
             },
             review_loop_limit: limit(2),
             log_path: None,
+            journal_path: None,
+            agent_timeout: None,
+            agent_review_timeout: None,
+            agent_retry: None,
+            agent_review_retry: None,
+            on_task_error: TaskErrorAction::Quit,
+            watch: WatchConfig::default(),
+            workspaces: Vec::new(),
+            workspace_order: WorkspaceOrder::RoundRobin,
+            concurrency: std::num::NonZeroUsize::MIN,
+            worktree_root: None,
+            log_format: LogFormat::Text,
+            agent_output_dir: None,
+            backend: Backend::Shell,
+            br: BrConfig::default(),
+            github: GithubConfig::default(),
+            markdown: MarkdownConfig::default(),
+            openspec: OpenspecConfig::default(),
+            statuses: StatusMap::default(),
+            prompt_format: PromptFormat::Plain,
+            prompts: PromptPaths::default(),
+            phases: vec![PipelinePhase::Solve],
+            profile_fallback: ProfileFallback::default(),
+            task_routing: TaskRouting::default(),
         },
         config_path: temp.path().join("trudger.yml"),
         invocation_folder: std::env::current_dir()
@@ -3300,18 +6251,34 @@ fn reset_task_on_exit_logs_failure_when_reset_task_fails() {
             .unwrap_or_default(),
         prompt_trudge: "Task context".to_string(),
         prompt_review: "Review context".to_string(),
+        phase_prompts: HashMap::new(),
         logger: Logger::new(None),
         tmux: TmuxState::disabled(),
         interrupt_flag: Arc::new(AtomicBool::new(false)),
         manual_tasks: Vec::new(),
         completed_tasks: Vec::new(),
         needs_human_tasks: Vec::new(),
+        skipped_tasks: Vec::new(),
+        declined_tasks: Vec::new(),
+        workspace: None,
+        cwd: None,
         current_task_id: Some(task("tr-1")),
         current_task_show: None,
         current_task_status: None,
         run_started_at: std::time::Instant::now(),
         current_task_started_at: None,
         run_exit_code: 0,
+        agent_output_dir: None,
+        current_agent_output: None,
+        current_review_result: None,
+        current_review_feedback: None,
+        current_review_follow_ups: Vec::new(),
+        current_review_loop: None,
+        agent_failures: 0,
+        routed_from: None,
+        agent_context: Default::default(),
+        resume: None,
+        task_backend: Arc::new(ShellBackend),
     };
 
     reset_task_on_exit(
@@ -3348,6 +6315,8 @@ fn reset_task_on_exit_skips_reset_when_task_status_is_empty() {
                 task_show: "task-show".to_string(),
                 task_status: "printf ''".to_string(),
                 task_update_status: "task-update".to_string(),
+                task_dependencies: None,
+                task_profile: None,
             },
             hooks: Hooks {
                 on_completed: "true".to_string(),
@@ -3355,9 +6324,35 @@ fn reset_task_on_exit_skips_reset_when_task_status_is_empty() {
                 on_doctor_setup: None,
                 on_notification: None,
                 on_notification_scope: None,
+                on_task_error: None,
+                on_skipped: None,
             },
             review_loop_limit: limit(2),
             log_path: None,
+            journal_path: None,
+            agent_timeout: None,
+            agent_review_timeout: None,
+            agent_retry: None,
+            agent_review_retry: None,
+            on_task_error: TaskErrorAction::Quit,
+            watch: WatchConfig::default(),
+            workspaces: Vec::new(),
+            workspace_order: WorkspaceOrder::RoundRobin,
+            concurrency: std::num::NonZeroUsize::MIN,
+            worktree_root: None,
+            log_format: LogFormat::Text,
+            agent_output_dir: None,
+            backend: Backend::Shell,
+            br: BrConfig::default(),
+            github: GithubConfig::default(),
+            markdown: MarkdownConfig::default(),
+            openspec: OpenspecConfig::default(),
+            statuses: StatusMap::default(),
+            prompt_format: PromptFormat::Plain,
+            prompts: PromptPaths::default(),
+            phases: vec![PipelinePhase::Solve],
+            profile_fallback: ProfileFallback::default(),
+            task_routing: TaskRouting::default(),
         },
         config_path: temp.path().join("trudger.yml"),
         invocation_folder: std::env::current_dir()
@@ -3366,18 +6361,34 @@ fn reset_task_on_exit_skips_reset_when_task_status_is_empty() {
             .unwrap_or_default(),
         prompt_trudge: "Task context".to_string(),
         prompt_review: "Review context".to_string(),
+        phase_prompts: HashMap::new(),
         logger: Logger::new(None),
         tmux: TmuxState::disabled(),
         interrupt_flag: Arc::new(AtomicBool::new(false)),
         manual_tasks: Vec::new(),
         completed_tasks: Vec::new(),
         needs_human_tasks: Vec::new(),
+        skipped_tasks: Vec::new(),
+        declined_tasks: Vec::new(),
+        workspace: None,
+        cwd: None,
         current_task_id: Some(task("tr-1")),
         current_task_show: None,
         current_task_status: None,
         run_started_at: std::time::Instant::now(),
         current_task_started_at: None,
         run_exit_code: 0,
+        agent_output_dir: None,
+        current_agent_output: None,
+        current_review_result: None,
+        current_review_feedback: None,
+        current_review_follow_ups: Vec::new(),
+        current_review_loop: None,
+        agent_failures: 0,
+        routed_from: None,
+        agent_context: Default::default(),
+        resume: None,
+        task_backend: Arc::new(ShellBackend),
     };
 
     reset_task_on_exit(
@@ -3412,6 +6423,8 @@ fn reset_task_on_exit_skips_reset_when_task_status_command_fails_to_spawn() {
                 task_show: "task-show".to_string(),
                 task_status: "task-status".to_string(),
                 task_update_status: "task-update".to_string(),
+                task_dependencies: None,
+                task_profile: None,
             },
             hooks: Hooks {
                 on_completed: "true".to_string(),
@@ -3419,9 +6432,35 @@ fn reset_task_on_exit_skips_reset_when_task_status_command_fails_to_spawn() {
                 on_doctor_setup: None,
                 on_notification: None,
                 on_notification_scope: None,
+                on_task_error: None,
+                on_skipped: None,
             },
             review_loop_limit: limit(2),
             log_path: None,
+            journal_path: None,
+            agent_timeout: None,
+            agent_review_timeout: None,
+            agent_retry: None,
+            agent_review_retry: None,
+            on_task_error: TaskErrorAction::Quit,
+            watch: WatchConfig::default(),
+            workspaces: Vec::new(),
+            workspace_order: WorkspaceOrder::RoundRobin,
+            concurrency: std::num::NonZeroUsize::MIN,
+            worktree_root: None,
+            log_format: LogFormat::Text,
+            agent_output_dir: None,
+            backend: Backend::Shell,
+            br: BrConfig::default(),
+            github: GithubConfig::default(),
+            markdown: MarkdownConfig::default(),
+            openspec: OpenspecConfig::default(),
+            statuses: StatusMap::default(),
+            prompt_format: PromptFormat::Plain,
+            prompts: PromptPaths::default(),
+            phases: vec![PipelinePhase::Solve],
+            profile_fallback: ProfileFallback::default(),
+            task_routing: TaskRouting::default(),
         },
         config_path: temp.path().join("trudger.yml"),
         invocation_folder: std::env::current_dir()
@@ -3430,18 +6469,34 @@ fn reset_task_on_exit_skips_reset_when_task_status_command_fails_to_spawn() {
             .unwrap_or_default(),
         prompt_trudge: "Task context".to_string(),
         prompt_review: "Review context".to_string(),
+        phase_prompts: HashMap::new(),
         logger: Logger::new(None),
         tmux: TmuxState::disabled(),
         interrupt_flag: Arc::new(AtomicBool::new(false)),
         manual_tasks: Vec::new(),
         completed_tasks: Vec::new(),
         needs_human_tasks: Vec::new(),
+        skipped_tasks: Vec::new(),
+        declined_tasks: Vec::new(),
+        workspace: None,
+        cwd: None,
         current_task_id: Some(task("tr-1")),
         current_task_show: None,
         current_task_status: None,
         run_started_at: std::time::Instant::now(),
         current_task_started_at: None,
         run_exit_code: 0,
+        agent_output_dir: None,
+        current_agent_output: None,
+        current_review_result: None,
+        current_review_feedback: None,
+        current_review_follow_ups: Vec::new(),
+        current_review_loop: None,
+        agent_failures: 0,
+        routed_from: None,
+        agent_context: Default::default(),
+        resume: None,
+        task_backend: Arc::new(ShellBackend),
     };
 
     reset_task_on_exit(
@@ -3488,6 +6543,8 @@ fn hook_failure_after_closed_does_not_reset_task_on_exit() {
                 task_show: "task-show".to_string(),
                 task_status: "task-status".to_string(),
                 task_update_status: "task-update".to_string(),
+                task_dependencies: None,
+                task_profile: None,
             },
             hooks: Hooks {
                 on_completed: "true".to_string(),
@@ -3495,9 +6552,35 @@ fn hook_failure_after_closed_does_not_reset_task_on_exit() {
                 on_doctor_setup: None,
                 on_notification: None,
                 on_notification_scope: None,
+                on_task_error: None,
+                on_skipped: None,
             },
             review_loop_limit: limit(1),
             log_path: None,
+            journal_path: None,
+            agent_timeout: None,
+            agent_review_timeout: None,
+            agent_retry: None,
+            agent_review_retry: None,
+            on_task_error: TaskErrorAction::Quit,
+            watch: WatchConfig::default(),
+            workspaces: Vec::new(),
+            workspace_order: WorkspaceOrder::RoundRobin,
+            concurrency: std::num::NonZeroUsize::MIN,
+            worktree_root: None,
+            log_format: LogFormat::Text,
+            agent_output_dir: None,
+            backend: Backend::Shell,
+            br: BrConfig::default(),
+            github: GithubConfig::default(),
+            markdown: MarkdownConfig::default(),
+            openspec: OpenspecConfig::default(),
+            statuses: StatusMap::default(),
+            prompt_format: PromptFormat::Plain,
+            prompts: PromptPaths::default(),
+            phases: vec![PipelinePhase::Solve],
+            profile_fallback: ProfileFallback::default(),
+            task_routing: TaskRouting::default(),
         },
         config_path: temp.path().join("trudger.yml"),
         invocation_folder: std::env::current_dir()
@@ -3506,18 +6589,34 @@ fn hook_failure_after_closed_does_not_reset_task_on_exit() {
             .unwrap_or_default(),
         prompt_trudge: "Task context".to_string(),
         prompt_review: "Review context".to_string(),
+        phase_prompts: HashMap::new(),
         logger: Logger::new(None),
         tmux: TmuxState::disabled(),
         interrupt_flag: Arc::new(AtomicBool::new(false)),
         manual_tasks: Vec::new(),
         completed_tasks: Vec::new(),
         needs_human_tasks: Vec::new(),
+        skipped_tasks: Vec::new(),
+        declined_tasks: Vec::new(),
+        workspace: None,
+        cwd: None,
         current_task_id: Some(task("tr-1")),
         current_task_show: None,
         current_task_status: None,
         run_started_at: std::time::Instant::now(),
         current_task_started_at: None,
         run_exit_code: 0,
+        agent_output_dir: None,
+        current_agent_output: None,
+        current_review_result: None,
+        current_review_feedback: None,
+        current_review_follow_ups: Vec::new(),
+        current_review_loop: None,
+        agent_failures: 0,
+        routed_from: None,
+        agent_context: Default::default(),
+        resume: None,
+        task_backend: Arc::new(ShellBackend),
     };
 
     reset_task_on_exit(
@@ -3565,6 +6664,8 @@ fn hook_failure_after_blocked_does_not_reset_task_on_exit() {
                 task_show: "task-show".to_string(),
                 task_status: "task-status".to_string(),
                 task_update_status: "task-update".to_string(),
+                task_dependencies: None,
+                task_profile: None,
             },
             hooks: Hooks {
                 on_completed: "true".to_string(),
@@ -3572,9 +6673,35 @@ fn hook_failure_after_blocked_does_not_reset_task_on_exit() {
                 on_doctor_setup: None,
                 on_notification: None,
                 on_notification_scope: None,
+                on_task_error: None,
+                on_skipped: None,
             },
             review_loop_limit: limit(1),
             log_path: None,
+            journal_path: None,
+            agent_timeout: None,
+            agent_review_timeout: None,
+            agent_retry: None,
+            agent_review_retry: None,
+            on_task_error: TaskErrorAction::Quit,
+            watch: WatchConfig::default(),
+            workspaces: Vec::new(),
+            workspace_order: WorkspaceOrder::RoundRobin,
+            concurrency: std::num::NonZeroUsize::MIN,
+            worktree_root: None,
+            log_format: LogFormat::Text,
+            agent_output_dir: None,
+            backend: Backend::Shell,
+            br: BrConfig::default(),
+            github: GithubConfig::default(),
+            markdown: MarkdownConfig::default(),
+            openspec: OpenspecConfig::default(),
+            statuses: StatusMap::default(),
+            prompt_format: PromptFormat::Plain,
+            prompts: PromptPaths::default(),
+            phases: vec![PipelinePhase::Solve],
+            profile_fallback: ProfileFallback::default(),
+            task_routing: TaskRouting::default(),
         },
         config_path: temp.path().join("trudger.yml"),
         invocation_folder: std::env::current_dir()
@@ -3583,18 +6710,34 @@ fn hook_failure_after_blocked_does_not_reset_task_on_exit() {
             .unwrap_or_default(),
         prompt_trudge: "Task context".to_string(),
         prompt_review: "Review context".to_string(),
+        phase_prompts: HashMap::new(),
         logger: Logger::new(None),
         tmux: TmuxState::disabled(),
         interrupt_flag: Arc::new(AtomicBool::new(false)),
         manual_tasks: Vec::new(),
         completed_tasks: Vec::new(),
         needs_human_tasks: Vec::new(),
+        skipped_tasks: Vec::new(),
+        declined_tasks: Vec::new(),
+        workspace: None,
+        cwd: None,
         current_task_id: Some(task("tr-1")),
         current_task_show: None,
         current_task_status: None,
         run_started_at: std::time::Instant::now(),
         current_task_started_at: None,
         run_exit_code: 0,
+        agent_output_dir: None,
+        current_agent_output: None,
+        current_review_result: None,
+        current_review_feedback: None,
+        current_review_follow_ups: Vec::new(),
+        current_review_loop: None,
+        agent_failures: 0,
+        routed_from: None,
+        agent_context: Default::default(),
+        resume: None,
+        task_backend: Arc::new(ShellBackend),
     };
 
     reset_task_on_exit(
@@ -3642,6 +6785,8 @@ fn solve_failure_while_in_progress_invokes_reset_task_on_exit() {
                 task_show: "task-show".to_string(),
                 task_status: "task-status".to_string(),
                 task_update_status: "task-update".to_string(),
+                task_dependencies: None,
+                task_profile: None,
             },
             hooks: Hooks {
                 on_completed: "true".to_string(),
@@ -3649,9 +6794,35 @@ fn solve_failure_while_in_progress_invokes_reset_task_on_exit() {
                 on_doctor_setup: None,
                 on_notification: None,
                 on_notification_scope: None,
+                on_task_error: None,
+                on_skipped: None,
             },
             review_loop_limit: limit(1),
             log_path: None,
+            journal_path: None,
+            agent_timeout: None,
+            agent_review_timeout: None,
+            agent_retry: None,
+            agent_review_retry: None,
+            on_task_error: TaskErrorAction::Quit,
+            watch: WatchConfig::default(),
+            workspaces: Vec::new(),
+            workspace_order: WorkspaceOrder::RoundRobin,
+            concurrency: std::num::NonZeroUsize::MIN,
+            worktree_root: None,
+            log_format: LogFormat::Text,
+            agent_output_dir: None,
+            backend: Backend::Shell,
+            br: BrConfig::default(),
+            github: GithubConfig::default(),
+            markdown: MarkdownConfig::default(),
+            openspec: OpenspecConfig::default(),
+            statuses: StatusMap::default(),
+            prompt_format: PromptFormat::Plain,
+            prompts: PromptPaths::default(),
+            phases: vec![PipelinePhase::Solve],
+            profile_fallback: ProfileFallback::default(),
+            task_routing: TaskRouting::default(),
         },
         config_path: temp.path().join("trudger.yml"),
         invocation_folder: std::env::current_dir()
@@ -3660,18 +6831,34 @@ fn solve_failure_while_in_progress_invokes_reset_task_on_exit() {
             .unwrap_or_default(),
         prompt_trudge: "Task context".to_string(),
         prompt_review: "Review context".to_string(),
+        phase_prompts: HashMap::new(),
         logger: Logger::new(None),
         tmux: TmuxState::disabled(),
         interrupt_flag: Arc::new(AtomicBool::new(false)),
         manual_tasks: Vec::new(),
         completed_tasks: Vec::new(),
         needs_human_tasks: Vec::new(),
+        skipped_tasks: Vec::new(),
+        declined_tasks: Vec::new(),
+        workspace: None,
+        cwd: None,
         current_task_id: Some(task("tr-1")),
         current_task_show: None,
         current_task_status: None,
         run_started_at: std::time::Instant::now(),
         current_task_started_at: None,
         run_exit_code: 0,
+        agent_output_dir: None,
+        current_agent_output: None,
+        current_review_result: None,
+        current_review_feedback: None,
+        current_review_follow_ups: Vec::new(),
+        current_review_loop: None,
+        agent_failures: 0,
+        routed_from: None,
+        agent_context: Default::default(),
+        resume: None,
+        task_backend: Arc::new(ShellBackend),
     };
 
     reset_task_on_exit(
@@ -3719,6 +6906,8 @@ fn sigint_while_in_progress_invokes_reset_task_on_exit() {
                 task_show: "task-show".to_string(),
                 task_status: "task-status".to_string(),
                 task_update_status: "task-update".to_string(),
+                task_dependencies: None,
+                task_profile: None,
             },
             hooks: Hooks {
                 on_completed: "true".to_string(),
@@ -3726,9 +6915,35 @@ fn sigint_while_in_progress_invokes_reset_task_on_exit() {
                 on_doctor_setup: None,
                 on_notification: None,
                 on_notification_scope: None,
+                on_task_error: None,
+                on_skipped: None,
             },
             review_loop_limit: limit(1),
             log_path: None,
+            journal_path: None,
+            agent_timeout: None,
+            agent_review_timeout: None,
+            agent_retry: None,
+            agent_review_retry: None,
+            on_task_error: TaskErrorAction::Quit,
+            watch: WatchConfig::default(),
+            workspaces: Vec::new(),
+            workspace_order: WorkspaceOrder::RoundRobin,
+            concurrency: std::num::NonZeroUsize::MIN,
+            worktree_root: None,
+            log_format: LogFormat::Text,
+            agent_output_dir: None,
+            backend: Backend::Shell,
+            br: BrConfig::default(),
+            github: GithubConfig::default(),
+            markdown: MarkdownConfig::default(),
+            openspec: OpenspecConfig::default(),
+            statuses: StatusMap::default(),
+            prompt_format: PromptFormat::Plain,
+            prompts: PromptPaths::default(),
+            phases: vec![PipelinePhase::Solve],
+            profile_fallback: ProfileFallback::default(),
+            task_routing: TaskRouting::default(),
         },
         config_path: temp.path().join("trudger.yml"),
         invocation_folder: std::env::current_dir()
@@ -3737,18 +6952,34 @@ fn sigint_while_in_progress_invokes_reset_task_on_exit() {
             .unwrap_or_default(),
         prompt_trudge: "Task context".to_string(),
         prompt_review: "Review context".to_string(),
+        phase_prompts: HashMap::new(),
         logger: Logger::new(None),
         tmux: TmuxState::disabled(),
         interrupt_flag: Arc::new(AtomicBool::new(false)),
         manual_tasks: Vec::new(),
         completed_tasks: Vec::new(),
         needs_human_tasks: Vec::new(),
+        skipped_tasks: Vec::new(),
+        declined_tasks: Vec::new(),
+        workspace: None,
+        cwd: None,
         current_task_id: Some(task("tr-1")),
         current_task_show: None,
         current_task_status: None,
         run_started_at: std::time::Instant::now(),
         current_task_started_at: None,
         run_exit_code: 0,
+        agent_output_dir: None,
+        current_agent_output: None,
+        current_review_result: None,
+        current_review_feedback: None,
+        current_review_follow_ups: Vec::new(),
+        current_review_loop: None,
+        agent_failures: 0,
+        routed_from: None,
+        agent_context: Default::default(),
+        resume: None,
+        task_backend: Arc::new(ShellBackend),
     };
 
     reset_task_on_exit(
@@ -3797,6 +7028,8 @@ fn status_check_failure_at_exit_does_not_invoke_reset_task() {
                 task_show: "task-show".to_string(),
                 task_status: "task-status".to_string(),
                 task_update_status: "task-update".to_string(),
+                task_dependencies: None,
+                task_profile: None,
             },
             hooks: Hooks {
                 on_completed: "true".to_string(),
@@ -3804,9 +7037,35 @@ fn status_check_failure_at_exit_does_not_invoke_reset_task() {
                 on_doctor_setup: None,
                 on_notification: None,
                 on_notification_scope: None,
+                on_task_error: None,
+                on_skipped: None,
             },
             review_loop_limit: limit(1),
             log_path: None,
+            journal_path: None,
+            agent_timeout: None,
+            agent_review_timeout: None,
+            agent_retry: None,
+            agent_review_retry: None,
+            on_task_error: TaskErrorAction::Quit,
+            watch: WatchConfig::default(),
+            workspaces: Vec::new(),
+            workspace_order: WorkspaceOrder::RoundRobin,
+            concurrency: std::num::NonZeroUsize::MIN,
+            worktree_root: None,
+            log_format: LogFormat::Text,
+            agent_output_dir: None,
+            backend: Backend::Shell,
+            br: BrConfig::default(),
+            github: GithubConfig::default(),
+            markdown: MarkdownConfig::default(),
+            openspec: OpenspecConfig::default(),
+            statuses: StatusMap::default(),
+            prompt_format: PromptFormat::Plain,
+            prompts: PromptPaths::default(),
+            phases: vec![PipelinePhase::Solve],
+            profile_fallback: ProfileFallback::default(),
+            task_routing: TaskRouting::default(),
         },
         config_path: temp.path().join("trudger.yml"),
         invocation_folder: std::env::current_dir()
@@ -3815,18 +7074,34 @@ fn status_check_failure_at_exit_does_not_invoke_reset_task() {
             .unwrap_or_default(),
         prompt_trudge: "Task context".to_string(),
         prompt_review: "Review context".to_string(),
+        phase_prompts: HashMap::new(),
         logger: Logger::new(None),
         tmux: TmuxState::disabled(),
         interrupt_flag: Arc::new(AtomicBool::new(false)),
         manual_tasks: Vec::new(),
         completed_tasks: Vec::new(),
         needs_human_tasks: Vec::new(),
+        skipped_tasks: Vec::new(),
+        declined_tasks: Vec::new(),
+        workspace: None,
+        cwd: None,
         current_task_id: Some(task("tr-1")),
         current_task_show: None,
         current_task_status: None,
         run_started_at: std::time::Instant::now(),
         current_task_started_at: None,
         run_exit_code: 0,
+        agent_output_dir: None,
+        current_agent_output: None,
+        current_review_result: None,
+        current_review_feedback: None,
+        current_review_follow_ups: Vec::new(),
+        current_review_loop: None,
+        agent_failures: 0,
+        routed_from: None,
+        agent_context: Default::default(),
+        resume: None,
+        task_backend: Arc::new(ShellBackend),
     };
 
     reset_task_on_exit(
@@ -3859,6 +7134,7 @@ fn run_with_cli_bootstraps_when_default_config_missing() {
         config: None,
         task: Vec::new(),
         positional: Vec::new(),
+        watch: false,
         command: None,
     })
     .expect_err("expected bootstrap missing config error");
@@ -3880,6 +7156,7 @@ fn run_with_cli_rejects_invalid_manual_task_values() {
         config: None,
         task: vec!["tr-1,,tr-2".to_string()],
         positional: Vec::new(),
+        watch: false,
         command: None,
     })
     .expect_err("expected manual task parse error");
@@ -3928,6 +7205,7 @@ hooks:
         config: Some(config_path),
         task: Vec::new(),
         positional: Vec::new(),
+        watch: false,
         command: None,
     })
     .expect_err("expected unknown profile error");
@@ -4007,6 +7285,7 @@ log_path: ""
         config: Some(config_path),
         task: vec!["tr-1".to_string()],
         positional: Vec::new(),
+        watch: false,
         command: None,
     })
     .expect_err("expected manual task run termination");
@@ -4105,6 +7384,7 @@ log_path: ""
         config: Some(config_path),
         task: vec!["tr-1".to_string()],
         positional: Vec::new(),
+        watch: false,
         command: None,
     })
     .expect_err("expected manual task run termination");
@@ -4146,6 +7426,7 @@ fn run_with_cli_rejects_wizard_positional_args() {
         config: None,
         task: Vec::new(),
         positional: vec!["extra".to_string()],
+        watch: false,
         command: Some(CliCommand::Wizard),
     })
     .expect_err("expected positional args error");
@@ -4163,6 +7444,7 @@ fn run_with_cli_rejects_wizard_task_flag() {
         config: None,
         task: vec!["tr-1".to_string()],
         positional: Vec::new(),
+        watch: false,
         command: Some(CliCommand::Wizard),
     })
     .expect_err("expected wizard -t error");
@@ -4188,6 +7470,7 @@ fn run_with_cli_invokes_wizard_runner_without_tty() {
             config: None,
             task: Vec::new(),
             positional: Vec::new(),
+            watch: false,
             command: Some(CliCommand::Wizard),
         },
         |_| {
@@ -4226,6 +7509,7 @@ fn run_with_cli_errors_when_home_missing() {
         config: None,
         task: Vec::new(),
         positional: Vec::new(),
+        watch: false,
         command: None,
     })
     .expect_err("expected missing HOME error");
@@ -4253,6 +7537,7 @@ fn run_with_cli_errors_when_explicit_config_is_missing() {
         config: Some(missing.clone()),
         task: Vec::new(),
         positional: Vec::new(),
+        watch: false,
         command: None,
     })
     .expect_err("expected missing config error");
@@ -4283,6 +7568,7 @@ fn run_with_cli_errors_when_config_is_invalid_yaml() {
         config: Some(config_path),
         task: Vec::new(),
         positional: Vec::new(),
+        watch: false,
         command: None,
     })
     .expect_err("expected invalid config error");
@@ -4327,6 +7613,7 @@ hooks:
         config: Some(config_path),
         task: Vec::new(),
         positional: Vec::new(),
+        watch: false,
         command: None,
     })
     .expect_err("expected validate_config failure");
@@ -4380,6 +7667,7 @@ hooks:
         config: Some(config_path.clone()),
         task: Vec::new(),
         positional: Vec::new(),
+        watch: false,
         command: None,
     })
     .expect_err("expected prompt missing error");
@@ -4394,6 +7682,7 @@ hooks:
         config: Some(config_path),
         task: Vec::new(),
         positional: Vec::new(),
+        watch: false,
         command: None,
     })
     .expect_err("expected review prompt missing error");
@@ -4447,26 +7736,97 @@ hooks:
 
     let prompts_dir = temp.path().join(".codex").join("prompts");
     fs::create_dir_all(&prompts_dir).expect("create prompts dir");
-    let trudge_prompt = prompts_dir.join("trudge.md");
-    let review_prompt = prompts_dir.join("trudge_review.md");
-    fs::write(&trudge_prompt, "hello").expect("write trudge.md");
-    fs::write(&review_prompt, "review").expect("write trudge_review.md");
-
-    fs::set_permissions(&trudge_prompt, fs::Permissions::from_mode(0o000))
-        .expect("chmod trudge.md");
+    let trudge_prompt = prompts_dir.join("trudge.md");
+    let review_prompt = prompts_dir.join("trudge_review.md");
+    fs::write(&trudge_prompt, "hello").expect("write trudge.md");
+    fs::write(&review_prompt, "review").expect("write trudge_review.md");
+
+    fs::set_permissions(&trudge_prompt, fs::Permissions::from_mode(0o000))
+        .expect("chmod trudge.md");
+
+    let err = run_with_cli(Cli {
+        profile: None,
+        config: Some(config_path),
+        task: Vec::new(),
+        positional: Vec::new(),
+        watch: false,
+        command: None,
+    })
+    .expect_err("expected trudge prompt read error");
+    assert_eq!(err.code, 1);
+    assert!(
+        err.reason.contains("Failed to read prompt"),
+        "expected prompt read error, got: {}",
+        err.reason
+    );
+
+    match old_home {
+        Some(value) => env::set_var("HOME", value),
+        None => env::remove_var("HOME"),
+    };
+}
+
+#[test]
+fn run_with_cli_rejects_invalid_prompt_templates() {
+    let _guard = ENV_MUTEX.lock().unwrap();
+    reset_test_env();
+
+    env::remove_var("TMUX");
+    let old_home = env::var_os("HOME");
+    let temp = TempDir::new().expect("temp dir");
+    env::set_var("HOME", temp.path());
+
+    let config_path = temp.path().join("trudger.yml");
+    fs::write(
+        &config_path,
+        r#"
+default_profile: codex
+profiles:
+  codex:
+    trudge: agent
+    trudge_review: agent-review
+invocations:
+  agent:
+    command: "agent"
+  agent-review:
+    command: "agent-review"
+commands:
+  next_task: "next-task"
+  task_show: "task-show"
+  task_status: "task-status"
+  task_update_status: "task-update"
+review_loop_limit: 2
+prompt_format: template
+hooks:
+  on_completed: "true"
+  on_requires_human: "true"
+"#,
+    )
+    .expect("write config");
+
+    let prompts_dir = temp.path().join(".codex").join("prompts");
+    fs::create_dir_all(&prompts_dir).expect("create prompts dir");
+    fs::write(prompts_dir.join("trudge.md"), "Solve {{task.id}}").expect("write trudge.md");
+    fs::write(
+        prompts_dir.join("trudge_review.md"),
+        "Review {{task.id}}\n{{#if review.feedback}}",
+    )
+    .expect("write trudge_review.md");
 
     let err = run_with_cli(Cli {
         profile: None,
         config: Some(config_path),
         task: Vec::new(),
         positional: Vec::new(),
+        watch: false,
         command: None,
     })
-    .expect_err("expected trudge prompt read error");
+    .expect_err("expected prompt template error");
     assert_eq!(err.code, 1);
     assert!(
-        err.reason.contains("Failed to read prompt"),
-        "expected prompt read error, got: {}",
+        err.reason
+            .contains("trudge_review.md: prompt template line 2:"),
+        "got: {}",
         err.reason
     );
 
@@ -4531,6 +7891,7 @@ hooks:
         config: Some(config_path),
         task: Vec::new(),
         positional: Vec::new(),
+        watch: false,
         command: None,
     })
     .expect_err("expected review prompt read error");
@@ -4604,6 +7965,7 @@ log_path: ""
         config: Some(config_path.clone()),
         task: Vec::new(),
         positional: Vec::new(),
+        watch: false,
         command: None,
     };
 
@@ -4615,6 +7977,7 @@ log_path: ""
         config: Some(config_path),
         task: Vec::new(),
         positional: Vec::new(),
+        watch: false,
         command: None,
     })
     .expect_err("expected forced error");
@@ -4678,6 +8041,7 @@ log_path: ""
         config: Some(config_path),
         task: Vec::new(),
         positional: Vec::new(),
+        watch: false,
         command: None,
     });
 
@@ -4756,6 +8120,7 @@ log_path: ""
         config: Some(config_path),
         task: Vec::new(),
         positional: Vec::new(),
+        watch: false,
         command: None,
     })
     .expect_err("expected idle exit");
@@ -4844,6 +8209,7 @@ log_path: ""
         config: Some(config_path),
         task: Vec::new(),
         positional: Vec::new(),
+        watch: false,
         command: None,
     })
     .expect_err("expected on_completed failure");
@@ -4923,6 +8289,7 @@ log_path: ""
         config: Some(config_path),
         task: Vec::new(),
         positional: Vec::new(),
+        watch: false,
         command: None,
     })
     .expect_err("expected on_requires_human failure");
@@ -5005,6 +8372,7 @@ log_path: ""
         config: Some(config_path),
         task: Vec::new(),
         positional: Vec::new(),
+        watch: false,
         command: None,
     })
     .expect_err("expected idle exit");
@@ -5088,6 +8456,7 @@ log_path: ""
         config: Some(config_path),
         task: Vec::new(),
         positional: Vec::new(),
+        watch: false,
         command: None,
     })
     .expect_err("expected idle exit");
@@ -5203,6 +8572,7 @@ log_path: ""
         config: Some(config_path),
         task: Vec::new(),
         positional: Vec::new(),
+        watch: false,
         command: None,
     })
     .expect_err("expected idle exit");
@@ -5255,9 +8625,9 @@ fn all_logs_duration_baseline_starts_at_run_start() {
     );
 
     thread::sleep(Duration::from_millis(20));
-    logger.log_transition("before_run_start");
+    logger.log_transition(Transition::new("before_run_start"));
     logger.mark_all_logs_run_started_at(Instant::now() - Duration::from_millis(30));
-    logger.log_transition("after_run_start");
+    logger.log_transition(Transition::new("after_run_start"));
 
     let hook_contents = fs::read_to_string(&hook_log).expect("read hook log");
     let durations: Vec<u128> = hook_contents
@@ -5297,7 +8667,7 @@ fn all_logs_notification_failure_does_not_recurse() {
             .map(|path| path.display().to_string())
             .unwrap_or_default(),
     );
-    logger.log_transition("first");
+    logger.log_transition(Transition::new("first"));
 
     let contents = fs::read_to_string(&log_path).expect("read log");
     assert_eq!(
@@ -5342,7 +8712,7 @@ fn all_logs_notification_uses_invocation_folder_not_current_dir() {
     logger.configure_all_logs_notification(Some("hook"), &config_path, invocation_folder.clone());
 
     env::set_current_dir(&other).expect("chdir");
-    logger.log_transition("hello");
+    logger.log_transition(Transition::new("hello"));
 
     let hook_contents = fs::read_to_string(&hook_log).expect("read hook log");
     assert!(
@@ -5410,6 +8780,7 @@ log_path: ""
         config: Some(config_path),
         task: Vec::new(),
         positional: Vec::new(),
+        watch: false,
         command: None,
     })
     .expect_err("expected solve failure");
@@ -5471,7 +8842,14 @@ fn tmux_state_enabled_reads_title_and_updates_pane_name() {
     env::remove_var("TRUDGER_TMUX_ORIGINAL_PANE_TITLE");
 
     let state = TmuxState::new();
-    state.update_name(Phase::Solving, &task("tr-9"), &[task("tr-1")], &[]);
+    state.update_name(
+        Phase::Solving,
+        &task("tr-9"),
+        &[task("tr-1")],
+        &[],
+        &[],
+        &[],
+    );
     state.restore();
 
     let log_contents = fs::read_to_string(&tmux_log).unwrap_or_default();
@@ -5727,6 +9105,493 @@ fn tmux_state_tolerates_tmux_spawn_failures() {
     env::set_var("TRUDGER_TMUX_ORIGINAL_PANE_TITLE", " ");
 
     let state = TmuxState::new();
-    state.update_name(Phase::Solving, &task("tr-1"), &[], &[]);
+    state.update_name(Phase::Solving, &task("tr-1"), &[], &[], &[], &[]);
     state.restore();
 }
+
+#[test]
+fn on_task_error_escalate_runs_task_error_hook_and_continues() {
+    let _guard = ENV_MUTEX.lock().unwrap();
+    reset_test_env();
+    let temp = TempDir::new().expect("temp dir");
+    let attempts_path = temp.path().join("attempts.txt");
+    let task_update_log = temp.path().join("task-update.log");
+    let hook_log = temp.path().join("hook.log");
+
+    let next_task_queue = temp.path().join("next-task-queue.txt");
+    fs::write(&next_task_queue, "tr-1\ntr-2\n\n").expect("write next task queue");
+    let status_queue = temp.path().join("status-queue.txt");
+    fs::write(&status_queue, "ready\nready\nclosed\n").expect("write status queue");
+
+    let fixtures_bin = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("fixtures")
+        .join("bin");
+    let old_path = env::var("PATH").unwrap_or_default();
+    env::set_var("PATH", format!("{}:{}", fixtures_bin.display(), old_path));
+    env::set_var("NEXT_TASK_OUTPUT_QUEUE", &next_task_queue);
+    env::set_var("TASK_STATUS_QUEUE", &status_queue);
+    env::set_var("TASK_SHOW_OUTPUT", "SHOW_PAYLOAD");
+    env::set_var("TASK_UPDATE_LOG", &task_update_log);
+    env::set_var("HOOK_MOCK_LOG", &hook_log);
+
+    let mut state = agent_invocation_state(&temp, TimeoutAction::Quit);
+    state.config.agent_command = format!(
+        "n=$(cat '{path}' 2>/dev/null || echo 0); n=$((n + 1)); echo \"$n\" > '{path}'; [ \"$n\" -ge 2 ]",
+        path = attempts_path.display()
+    );
+    state.config.agent_timeout = None;
+    state.config.on_task_error = TaskErrorAction::Escalate;
+    state.config.hooks.on_task_error = Some("hook --task-error".to_string());
+
+    let result = run_loop(&mut state).expect_err("should exit after queue drained");
+    assert_eq!(result.code, 0, "expected graceful exit after escalation");
+    assert_eq!(state.needs_human_tasks, vec![task("tr-1")]);
+    assert_eq!(state.completed_tasks, vec![task("tr-2")]);
+
+    let update_contents = fs::read_to_string(&task_update_log).expect("read task-update log");
+    assert!(
+        update_contents.contains("env TRUDGER_TARGET_STATUS=blocked"),
+        "failed task should be blocked, got:\n{update_contents}"
+    );
+    let hook_contents = fs::read_to_string(&hook_log).expect("read hook log");
+    assert!(
+        hook_contents.contains("hook args_count=1 args=--task-error"),
+        "expected task-error hook, got:\n{hook_contents}"
+    );
+    assert!(
+        !hook_contents.contains("args=--human"),
+        "requires-human hook should be replaced by on_task_error, got:\n{hook_contents}"
+    );
+
+    let log_contents =
+        fs::read_to_string(temp.path().join("trudger.log")).expect("read trudger log");
+    assert!(log_contents.contains("solve_failed task=tr-1"));
+    assert!(log_contents.contains("task_error task=tr-1 phase=solve action=escalate"));
+}
+
+#[test]
+fn on_task_error_skip_reopens_task_and_ignores_it_for_the_run() {
+    let _guard = ENV_MUTEX.lock().unwrap();
+    reset_test_env();
+    let temp = TempDir::new().expect("temp dir");
+    let task_update_log = temp.path().join("task-update.log");
+    let hook_log = temp.path().join("hook.log");
+
+    let status_queue = temp.path().join("status-queue.txt");
+    fs::write(&status_queue, "ready\n").expect("write status queue");
+
+    let fixtures_bin = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("fixtures")
+        .join("bin");
+    let old_path = env::var("PATH").unwrap_or_default();
+    env::set_var("PATH", format!("{}:{}", fixtures_bin.display(), old_path));
+    env::set_var("NEXT_TASK_OUTPUT", "tr-1");
+    env::set_var("TASK_STATUS_QUEUE", &status_queue);
+    env::set_var("TASK_SHOW_OUTPUT", "SHOW_PAYLOAD");
+    env::set_var("TASK_UPDATE_LOG", &task_update_log);
+    env::set_var("HOOK_MOCK_LOG", &hook_log);
+    env::set_var("TRUDGER_SKIP_NOT_READY_LIMIT", "2");
+
+    let mut state = agent_invocation_state(&temp, TimeoutAction::Quit);
+    state.config.agent_command = "exit 1".to_string();
+    state.config.agent_timeout = None;
+    state.config.on_task_error = TaskErrorAction::Skip;
+
+    let result = run_loop(&mut state).expect_err("should go idle once only skipped tasks remain");
+    assert_eq!(result.code, 0);
+    assert_eq!(result.reason, "no_ready_task");
+    assert_eq!(state.skipped_tasks, vec![workspace_task(None, "tr-1")]);
+    assert!(state.needs_human_tasks.is_empty());
+
+    let update_contents = fs::read_to_string(&task_update_log).expect("read task-update log");
+    assert!(
+        update_contents.contains("env TRUDGER_TARGET_STATUS=open"),
+        "skipped task should be reopened, got:\n{update_contents}"
+    );
+    assert!(!update_contents.contains("TRUDGER_TARGET_STATUS=blocked"));
+    assert!(!hook_log.exists(), "skip should not run escalation hooks");
+
+    let log_contents =
+        fs::read_to_string(temp.path().join("trudger.log")).expect("read trudger log");
+    assert!(log_contents.contains("task_error task=tr-1 phase=solve action=skip"));
+    assert!(log_contents.contains("skipped task=tr-1"));
+    assert_eq!(log_contents.matches("skip_errored task=tr-1").count(), 2);
+}
+
+#[test]
+fn watch_loop_polls_after_idle_and_reloads_on_request() {
+    let _guard = ENV_MUTEX.lock().unwrap();
+    reset_test_env();
+    let temp = TempDir::new().expect("temp dir");
+
+    let next_task_queue = temp.path().join("next-task-queue.txt");
+    fs::write(&next_task_queue, "tr-1\n\ntr-2\n").expect("write next task queue");
+    let status_queue = temp.path().join("status-queue.txt");
+    fs::write(&status_queue, "ready\nclosed\nready\n").expect("write status queue");
+
+    let fixtures_bin = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("fixtures")
+        .join("bin");
+    let old_path = env::var("PATH").unwrap_or_default();
+    env::set_var("PATH", format!("{}:{}", fixtures_bin.display(), old_path));
+    env::set_var("NEXT_TASK_OUTPUT_QUEUE", &next_task_queue);
+    env::set_var("TASK_STATUS_QUEUE", &status_queue);
+    env::set_var("TASK_SHOW_OUTPUT", "SHOW_PAYLOAD");
+
+    let mut state = agent_invocation_state(&temp, TimeoutAction::Quit);
+    state.config.agent_command = "[ \"$TRUDGER_TASK_ID\" != tr-2 ]".to_string();
+    state.config.agent_timeout = None;
+    state.config.watch = WatchConfig {
+        poll_interval_seconds: NonZeroU64::new(1).expect("non-zero"),
+        max_poll_interval_seconds: NonZeroU64::new(1).expect("non-zero"),
+    };
+
+    let reload_requested = AtomicBool::new(true);
+    let mut reloads = 0;
+    let result = run_watch_loop(&mut state, &reload_requested, |state| {
+        reloads += 1;
+        state.prompt_trudge = "Reloaded context".to_string();
+        Ok(())
+    })
+    .expect_err("failing solve should end the watch loop");
+    assert_eq!(result.code, 1);
+    assert_eq!(result.reason, "solve_failed:tr-2");
+    assert_eq!(reloads, 1);
+    assert_eq!(state.prompt_trudge, "Reloaded context");
+    assert!(
+        state.completed_tasks.is_empty(),
+        "watch_idle should clear completed tasks"
+    );
+
+    let log_contents =
+        fs::read_to_string(temp.path().join("trudger.log")).expect("read trudger log");
+    assert!(log_contents.contains("watch_reload status=ok"));
+    assert!(log_contents.contains("completed task=tr-1"));
+    assert!(log_contents.contains("watch_idle reason=no_task sleep_s=1"));
+    assert!(
+        !log_contents.contains("quit reason=no_task"),
+        "idle polls should not quit, got:\n{log_contents}"
+    );
+}
+
+#[test]
+fn watch_loop_retries_skipped_tasks_after_idle() {
+    let _guard = ENV_MUTEX.lock().unwrap();
+    reset_test_env();
+    let temp = TempDir::new().expect("temp dir");
+    let attempts_path = temp.path().join("attempts.txt");
+
+    let fixtures_bin = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("fixtures")
+        .join("bin");
+    let old_path = env::var("PATH").unwrap_or_default();
+    env::set_var("PATH", format!("{}:{}", fixtures_bin.display(), old_path));
+    env::set_var("NEXT_TASK_OUTPUT", "tr-1");
+    env::set_var("TASK_STATUS_OUTPUT", "ready");
+    env::set_var("TASK_SHOW_OUTPUT", "SHOW_PAYLOAD");
+    env::set_var("TRUDGER_SKIP_NOT_READY_LIMIT", "1");
+
+    let mut state = agent_invocation_state(&temp, TimeoutAction::Quit);
+    state.config.agent_command = format!("echo attempt >> '{}'; exit 1", attempts_path.display());
+    state.config.agent_timeout = None;
+    state.config.on_task_error = TaskErrorAction::Skip;
+    state.config.watch = WatchConfig {
+        poll_interval_seconds: NonZeroU64::new(1).expect("non-zero"),
+        max_poll_interval_seconds: NonZeroU64::new(1).expect("non-zero"),
+    };
+
+    let interrupter_flag = Arc::clone(&state.interrupt_flag);
+    let interrupter_attempts = attempts_path.clone();
+    let interrupter = thread::spawn(move || {
+        let start = Instant::now();
+        while start.elapsed() < Duration::from_secs(10) {
+            let attempts = fs::read_to_string(&interrupter_attempts).unwrap_or_default();
+            if attempts.lines().count() >= 2 {
+                break;
+            }
+            thread::sleep(Duration::from_millis(5));
+        }
+        interrupter_flag.store(true, std::sync::atomic::Ordering::SeqCst);
+    });
+
+    let result = run_watch_loop(&mut state, &AtomicBool::new(false), |_state| Ok(()))
+        .expect_err("interrupt should end the watch loop");
+    interrupter.join().expect("interrupter thread");
+    assert_eq!(result.code, 130);
+    assert!(
+        fs::read_to_string(&attempts_path)
+            .expect("read attempts")
+            .lines()
+            .count()
+            >= 2,
+        "skipped task should be tried again after watch_idle"
+    );
+
+    let log_contents =
+        fs::read_to_string(temp.path().join("trudger.log")).expect("read trudger log");
+    let idle_at = log_contents
+        .find("watch_idle reason=no_ready_task")
+        .expect("watch_idle after skip");
+    assert!(log_contents[..idle_at].contains("skip_errored task=tr-1"));
+    assert!(log_contents[idle_at..].contains("skipped task=tr-1"));
+}
+
+#[test]
+fn watch_loop_treats_next_task_exit_1_as_idle() {
+    let _guard = ENV_MUTEX.lock().unwrap();
+    reset_test_env();
+    let temp = TempDir::new().expect("temp dir");
+    let calls_path = temp.path().join("next-task-calls.txt");
+
+    let status_queue = temp.path().join("status-queue.txt");
+    fs::write(&status_queue, "ready\n").expect("write status queue");
+
+    let fixtures_bin = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("fixtures")
+        .join("bin");
+    let old_path = env::var("PATH").unwrap_or_default();
+    env::set_var("PATH", format!("{}:{}", fixtures_bin.display(), old_path));
+    env::set_var("TASK_STATUS_QUEUE", &status_queue);
+    env::set_var("TASK_SHOW_OUTPUT", "SHOW_PAYLOAD");
+
+    let mut state = agent_invocation_state(&temp, TimeoutAction::Quit);
+    state.config.commands.next_task = Some(format!(
+        "n=$(cat '{path}' 2>/dev/null || echo 0); n=$((n + 1)); echo \"$n\" > '{path}'; [ \"$n\" -ge 2 ] || exit 1; printf tr-1",
+        path = calls_path.display()
+    ));
+    state.config.agent_command = "exit 1".to_string();
+    state.config.agent_timeout = None;
+    state.config.watch = WatchConfig {
+        poll_interval_seconds: NonZeroU64::new(1).expect("non-zero"),
+        max_poll_interval_seconds: NonZeroU64::new(1).expect("non-zero"),
+    };
+
+    let result = run_watch_loop(&mut state, &AtomicBool::new(false), |_state| Ok(()))
+        .expect_err("failing solve should end the watch loop");
+    assert_eq!(result.reason, "solve_failed:tr-1");
+
+    let log_contents =
+        fs::read_to_string(temp.path().join("trudger.log")).expect("read trudger log");
+    assert!(log_contents.contains("idle next_task_exit=1"));
+    assert!(log_contents.contains("watch_idle reason=no_next_task sleep_s=1"));
+}
+
+#[test]
+fn watch_loop_keeps_previous_config_when_reload_fails() {
+    let _guard = ENV_MUTEX.lock().unwrap();
+    reset_test_env();
+    let temp = TempDir::new().expect("temp dir");
+
+    let fixtures_bin = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("fixtures")
+        .join("bin");
+    let old_path = env::var("PATH").unwrap_or_default();
+    env::set_var("PATH", format!("{}:{}", fixtures_bin.display(), old_path));
+    env::set_var("NEXT_TASK_OUTPUT", "");
+
+    let mut state = agent_invocation_state(&temp, TimeoutAction::Quit);
+    let reload_requested = AtomicBool::new(true);
+    let interrupt_flag = Arc::clone(&state.interrupt_flag);
+    let result = run_watch_loop(&mut state, &reload_requested, |_state| {
+        // Stop the loop at the next interruption check.
+        interrupt_flag.store(true, Ordering::SeqCst);
+        Err("bad config".to_string())
+    })
+    .expect_err("interrupt should end the watch loop");
+    assert_eq!(result.code, 130);
+    assert_eq!(state.prompt_trudge, "Task context");
+
+    let log_contents =
+        fs::read_to_string(temp.path().join("trudger.log")).expect("read trudger log");
+    assert!(log_contents.contains("watch_reload status=failed"));
+}
+
+#[test]
+fn doctor_rejects_watch_flag_with_clear_error() {
+    let err = run_with_cli(Cli {
+        profile: None,
+        config: None,
+        task: Vec::new(),
+        positional: Vec::new(),
+        watch: true,
+        command: Some(CliCommand::Doctor),
+    })
+    .expect_err("expected doctor watch-flag rejection");
+    assert_eq!(err.code, 1);
+    assert!(
+        err.reason.contains("--watch"),
+        "expected --watch error, got: {}",
+        err.reason
+    );
+}
+
+fn workspace_run_state(temp: &TempDir, workspace_order: &str) -> RuntimeState {
+    for (name, queue) in [("api", "a-1\na-2\n"), ("web", "w-1\n")] {
+        let dir = temp.path().join(name);
+        fs::create_dir_all(&dir).expect("create workspace dir");
+        fs::write(dir.join("queue.txt"), queue).expect("write workspace queue");
+    }
+
+    let order_log = temp.path().join("order.log");
+    let content = format!(
+        r#"
+default_profile: codex
+profiles:
+  codex:
+    trudge: solve
+    trudge_review: review
+invocations:
+  solve:
+    command: 'echo "$TRUDGER_TASK_ID $(basename "$PWD")" >> "{order_log}"'
+  review:
+    command: 'echo closed > "status-$TRUDGER_TASK_ID"'
+commands:
+  next_task: 'if [ -s queue.txt ]; then head -n 1 queue.txt; tail -n +2 queue.txt > queue.tmp; mv queue.tmp queue.txt; fi'
+  task_show: 'echo show'
+  task_status: 'cat "status-$TRUDGER_TASK_ID" 2>/dev/null || echo ready'
+  task_update_status: 'true'
+review_loop_limit: 2
+hooks:
+  on_completed: 'true'
+  on_requires_human: 'true'
+workspace_order: {workspace_order}
+workspaces:
+  - name: api
+    path: "{api}"
+  - name: web
+    path: "{web}"
+"#,
+        order_log = order_log.display(),
+        workspace_order = workspace_order,
+        api = temp.path().join("api").display(),
+        web = temp.path().join("web").display(),
+    );
+    let loaded = crate::config::load_config_from_str("test", &content).expect("load config");
+
+    let mut state = agent_invocation_state(temp, TimeoutAction::Quit);
+    let log_path = state.config.log_path.clone();
+    state.config = Config {
+        log_path,
+        ..loaded.config
+    };
+    state
+}
+
+#[test]
+fn workspaces_round_robin_runs_tasks_in_their_directories() {
+    let _guard = ENV_MUTEX.lock().unwrap();
+    reset_test_env();
+    let temp = TempDir::new().expect("temp dir");
+
+    let mut state = workspace_run_state(&temp, "round_robin");
+    validate_config(&state.config, &[]).expect("workspace config should validate");
+    let result = run_loop(&mut state).expect_err("should exit once every workspace is idle");
+    assert_eq!(result.code, 0);
+    assert_eq!(result.reason, "no_task");
+    assert_eq!(
+        state.completed_tasks,
+        vec![task("a-1"), task("w-1"), task("a-2")]
+    );
+    assert_eq!(state.config.workspaces.len(), 2, "base config is restored");
+    assert_eq!(state.cwd, None);
+
+    let order = fs::read_to_string(temp.path().join("order.log")).expect("read order log");
+    assert_eq!(order, "a-1 api\nw-1 web\na-2 api\n");
+    assert!(temp.path().join("web").join("status-w-1").is_file());
+
+    let log_contents =
+        fs::read_to_string(temp.path().join("trudger.log")).expect("read trudger log");
+    assert!(log_contents.contains(&format!(
+        "workspace name=web path={}",
+        temp.path().join("web").display()
+    )));
+    assert!(log_contents.contains("idle workspaces"));
+}
+
+#[test]
+fn workspaces_priority_drains_the_first_workspace_first() {
+    let _guard = ENV_MUTEX.lock().unwrap();
+    reset_test_env();
+    let temp = TempDir::new().expect("temp dir");
+
+    let mut state = workspace_run_state(&temp, "priority");
+    run_loop(&mut state).expect_err("should exit once every workspace is idle");
+    assert_eq!(
+        state.completed_tasks,
+        vec![task("a-1"), task("a-2"), task("w-1")]
+    );
+}
+
+#[test]
+fn workspaces_skip_tasks_only_in_the_workspace_that_skipped_them() {
+    let _guard = ENV_MUTEX.lock().unwrap();
+    reset_test_env();
+    let temp = TempDir::new().expect("temp dir");
+
+    let mut state = workspace_run_state(&temp, "round_robin");
+    for workspace in &mut state.config.workspaces {
+        fs::write(
+            temp.path().join(&workspace.name).join("queue.txt"),
+            "t-1\nt-1\n",
+        )
+        .expect("write workspace queue");
+        workspace.config.agent_command = "[ \"$(basename \"$PWD\")\" != api ]".to_string();
+        workspace.config.on_task_error = TaskErrorAction::Skip;
+    }
+
+    let result = run_loop(&mut state).expect_err("should exit once every workspace is idle");
+    assert_eq!(result.code, 0);
+    assert_eq!(state.completed_tasks, vec![task("t-1")]);
+    assert_eq!(
+        state.skipped_tasks,
+        vec![workspace_task(Some("api"), "t-1")]
+    );
+
+    let log_contents =
+        fs::read_to_string(temp.path().join("trudger.log")).expect("read trudger log");
+    assert_eq!(log_contents.matches("skip_errored task=t-1").count(), 1);
+}
+
+#[test]
+fn workspaces_restore_the_base_config_when_a_turn_quits() {
+    let _guard = ENV_MUTEX.lock().unwrap();
+    reset_test_env();
+    let temp = TempDir::new().expect("temp dir");
+
+    let mut state = workspace_run_state(&temp, "round_robin");
+    for workspace in &mut state.config.workspaces {
+        workspace.config.agent_command = "exit 1".to_string();
+    }
+    let base_folder = state.invocation_folder.clone();
+
+    let result = run_loop(&mut state).expect_err("failing solve should quit");
+    assert_eq!(result.code, 1);
+    assert_eq!(result.reason, "solve_failed:a-1");
+    assert_eq!(state.config.workspaces.len(), 2, "base config is restored");
+    assert_eq!(state.workspace, None);
+    assert_eq!(state.cwd, None);
+    assert_eq!(state.invocation_folder, base_folder);
+    assert_eq!(
+        state.current_task_id, None,
+        "task is wound down in its workspace"
+    );
+}
+
+#[test]
+fn workspaces_reject_manual_tasks_and_missing_directories() {
+    let temp = TempDir::new().expect("temp dir");
+    let mut state = workspace_run_state(&temp, "round_robin");
+
+    let err = validate_config(&state.config, &[task("a-1")]).expect_err("manual tasks rejected");
+    assert!(err.contains("-t/--task is not supported with workspaces"));
+
+    state.config.workspaces[1].path = temp.path().join("missing");
+    let err = validate_config(&state.config, &[]).expect_err("missing dir rejected");
+    assert!(err.starts_with("workspace web: path is not a directory"));
+}
@@ -4,7 +4,11 @@ use std::env;
 use std::path::{Path, PathBuf};
 
 use crate::config::load_config_from_str;
-use crate::config::{Commands, Config, Hooks};
+use crate::config::{
+    Backend, BrConfig, Commands, Config, GithubConfig, Hooks, LogFormat, MarkdownConfig,
+    OpenspecConfig, PipelinePhase, ProfileFallback, PromptFormat, PromptPaths, StatusMap,
+    TaskErrorAction, TaskRouting, WatchConfig, WorkspaceOrder,
+};
 use crate::prompt_defaults::default_prompts;
 use crate::prompt_install::{
     detect_prompt_state, overwrite_prompt_with_backup, write_prompt_if_missing, PromptState,
@@ -385,6 +389,30 @@ fn validate_generated_config_with_legacy_schema(yaml: &str) -> Result<(), String
             .log_path
             .filter(|value| !value.trim().is_empty())
             .map(PathBuf::from),
+        journal_path: None,
+        agent_timeout: None,
+        agent_review_timeout: None,
+        agent_retry: None,
+        agent_review_retry: None,
+        on_task_error: TaskErrorAction::Quit,
+        watch: WatchConfig::default(),
+        workspaces: Vec::new(),
+        workspace_order: WorkspaceOrder::RoundRobin,
+        concurrency: std::num::NonZeroUsize::MIN,
+        worktree_root: None,
+        log_format: LogFormat::Text,
+        agent_output_dir: None,
+        backend: Backend::Shell,
+        br: BrConfig::default(),
+        github: GithubConfig::default(),
+        markdown: MarkdownConfig::default(),
+        openspec: OpenspecConfig::default(),
+        statuses: StatusMap::default(),
+        prompt_format: PromptFormat::Plain,
+        prompts: PromptPaths::default(),
+        phases: vec![PipelinePhase::Solve],
+        profile_fallback: ProfileFallback::default(),
+        task_routing: TaskRouting::default(),
     };
     validate_config(&config, &[])?;
     Ok(())
@@ -0,0 +1,161 @@
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+use crate::task_types::TaskId;
+
+/// The top level of the git repository containing `dir`.
+pub(crate) fn repository_root(dir: &Path) -> Result<PathBuf, String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(["rev-parse", "--show-toplevel"])
+        .output()
+        .map_err(|err| format!("failed to run git: {}", err))?;
+    if !output.status.success() {
+        return Err(git_error(&output));
+    }
+    Ok(PathBuf::from(
+        String::from_utf8_lossy(&output.stdout).trim_end(),
+    ))
+}
+
+/// Directory holding the per-task worktrees: `worktree_root`, or `<repo>.worktrees` next to the
+/// repository. A relative `worktree_root` is resolved against the repository's top level.
+pub(crate) fn worktree_root(repo: &Path, configured: Option<&Path>) -> PathBuf {
+    if let Some(root) = configured {
+        return repo.join(root);
+    }
+    let name = repo
+        .file_name()
+        .map(|value| value.to_string_lossy().to_string())
+        .unwrap_or_else(|| "trudger".to_string());
+    repo.parent()
+        .unwrap_or(repo)
+        .join(format!("{}.worktrees", name))
+}
+
+/// Task ids may contain `:`, which git rejects in branch names and some filesystems in paths.
+pub(crate) fn path_safe_task_id(task_id: &TaskId) -> String {
+    task_id.as_str().replace(':', "-")
+}
+
+fn task_branch(task_id: &TaskId) -> String {
+    format!("trudger/{}", path_safe_task_id(task_id))
+}
+
+/// Checks out `trudger/<task>` in its own worktree under `root`, creating the branch from `HEAD`
+/// on first use. An existing worktree directory (for example one kept for a human) is reused.
+pub(crate) fn create_worktree(
+    repo: &Path,
+    root: &Path,
+    task_id: &TaskId,
+) -> Result<PathBuf, String> {
+    let path = root.join(path_safe_task_id(task_id));
+    if path.is_dir() {
+        return Ok(path);
+    }
+
+    let branch = task_branch(task_id);
+    let branch_exists = Command::new("git")
+        .arg("-C")
+        .arg(repo)
+        .args(["rev-parse", "--verify", "--quiet"])
+        .arg(format!("refs/heads/{}", branch))
+        .output()
+        .map_err(|err| format!("failed to run git: {}", err))?
+        .status
+        .success();
+
+    let mut command = Command::new("git");
+    command.arg("-C").arg(repo).args(["worktree", "add"]);
+    if branch_exists {
+        command.arg(&path).arg(&branch);
+    } else {
+        command.arg("-b").arg(&branch).arg(&path);
+    }
+    run_git(&mut command)?;
+    Ok(path)
+}
+
+/// Removes a finished task's worktree. Its branch is kept, and git refuses to drop a worktree
+/// with uncommitted changes.
+pub(crate) fn remove_worktree(repo: &Path, path: &Path) -> Result<(), String> {
+    let mut command = Command::new("git");
+    command
+        .arg("-C")
+        .arg(repo)
+        .args(["worktree", "remove"])
+        .arg(path);
+    run_git(&mut command)
+}
+
+fn run_git(command: &mut Command) -> Result<(), String> {
+    let output = command
+        .output()
+        .map_err(|err| format!("failed to run git: {}", err))?;
+    if output.status.success() {
+        return Ok(());
+    }
+    Err(git_error(&output))
+}
+
+fn git_error(output: &Output) -> String {
+    let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+    format!(
+        "git exited with {}: {}",
+        output.status.code().unwrap_or(-1),
+        stderr
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn worktree_root_defaults_next_to_the_repository() {
+        assert_eq!(
+            worktree_root(Path::new("/src/app"), None),
+            PathBuf::from("/src/app.worktrees")
+        );
+        assert_eq!(
+            worktree_root(Path::new("/src/app"), Some(Path::new("../trees"))),
+            PathBuf::from("/src/app/../trees")
+        );
+        assert_eq!(
+            worktree_root(Path::new("/src/app"), Some(Path::new("/tmp/trees"))),
+            PathBuf::from("/tmp/trees")
+        );
+    }
+
+    #[test]
+    fn repository_root_finds_the_top_level_from_a_subdirectory() {
+        let temp = TempDir::new().expect("temp dir");
+        let status = Command::new("git")
+            .arg("init")
+            .arg("--quiet")
+            .arg(temp.path())
+            .status()
+            .expect("run git init");
+        assert!(status.success());
+        let nested = temp.path().join("src").join("nested");
+        std::fs::create_dir_all(&nested).expect("create nested dir");
+
+        let root = repository_root(&nested).expect("repository root");
+        assert_eq!(
+            root.canonicalize().expect("canonical root"),
+            temp.path().canonicalize().expect("canonical temp")
+        );
+
+        let outside = TempDir::new().expect("temp dir");
+        let err = repository_root(outside.path()).expect_err("not a repository");
+        assert!(err.starts_with("git exited with"), "got: {}", err);
+    }
+
+    #[test]
+    fn task_branch_replaces_colons() {
+        let task_id = TaskId::try_from("gh:12").expect("task id");
+        assert_eq!(task_branch(&task_id), "trudger/gh-12");
+    }
+}